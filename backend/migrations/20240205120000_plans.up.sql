@@ -0,0 +1,22 @@
+-- Add up migration script here
+
+DROP TABLE IF EXISTS production_plans CASCADE;
+
+CREATE TABLE production_plans (
+    id BIGSERIAL PRIMARY KEY,
+    organization_id BIGINT NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+    product_id BIGINT NOT NULL REFERENCES products (id) ON DELETE CASCADE,
+    user_id BIGINT NULL REFERENCES users (id) ON DELETE SET NULL,
+    date_one DATE NOT NULL,
+    date_two DATE NOT NULL,
+    cnt BIGINT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NULL,
+    CHECK (date_one <= date_two),
+    CHECK (cnt > 0)
+);
+
+CREATE INDEX ON production_plans (organization_id);
+CREATE INDEX ON production_plans (product_id);
+CREATE INDEX ON production_plans (user_id);
+CREATE INDEX ON production_plans (date_one, date_two);
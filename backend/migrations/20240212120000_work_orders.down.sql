@@ -0,0 +1,5 @@
+-- Add down migration script here
+
+ALTER TABLE produced_goods DROP COLUMN IF EXISTS work_order_id;
+
+DROP TABLE IF EXISTS work_orders cascade;
@@ -0,0 +1,26 @@
+-- Add up migration script here
+
+DROP TABLE IF EXISTS work_orders CASCADE;
+
+CREATE TABLE work_orders (
+    id BIGSERIAL PRIMARY KEY,
+    organization_id BIGINT NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+    product_id BIGINT NOT NULL REFERENCES products (id) ON DELETE CASCADE,
+    user_id BIGINT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    cnt BIGINT NOT NULL,
+    due_date DATE NOT NULL,
+    status VARCHAR(255) NOT NULL DEFAULT 'Draft',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NULL,
+    CHECK (cnt > 0)
+);
+
+CREATE INDEX ON work_orders (organization_id);
+CREATE INDEX ON work_orders (product_id);
+CREATE INDEX ON work_orders (status);
+
+
+ALTER TABLE produced_goods
+    ADD COLUMN work_order_id BIGINT NULL REFERENCES work_orders (id) ON DELETE SET NULL;
+
+CREATE INDEX ON produced_goods (work_order_id);
@@ -0,0 +1,20 @@
+-- Add up migration script here
+
+DROP TABLE IF EXISTS bom_items CASCADE;
+
+CREATE TABLE bom_items (
+    id BIGSERIAL PRIMARY KEY,
+    organization_id BIGINT NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+    product_id BIGINT NOT NULL REFERENCES products (id) ON DELETE CASCADE,
+    component_id BIGINT NOT NULL REFERENCES products (id) ON DELETE CASCADE,
+    cnt DOUBLE PRECISION NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NULL,
+    UNIQUE (product_id, component_id),
+    CHECK (product_id <> component_id),
+    CHECK (cnt > 0)
+);
+
+CREATE INDEX ON bom_items (organization_id);
+CREATE INDEX ON bom_items (product_id);
+CREATE INDEX ON bom_items (component_id);
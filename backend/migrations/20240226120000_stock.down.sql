@@ -0,0 +1,7 @@
+-- Add down migration script here
+
+DROP TRIGGER IF EXISTS stock_movements_append_only ON stock_movements;
+DROP FUNCTION IF EXISTS stock_movements_append_only;
+
+DROP TABLE IF EXISTS stock_movements cascade;
+DROP TABLE IF EXISTS warehouses cascade;
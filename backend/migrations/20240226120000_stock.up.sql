@@ -0,0 +1,59 @@
+-- Add up migration script here
+
+DROP TABLE IF EXISTS warehouses CASCADE;
+
+CREATE TABLE warehouses (
+    id BIGSERIAL PRIMARY KEY,
+    organization_id BIGINT NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    is_default BOOLEAN NOT NULL DEFAULT 'F',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NULL
+);
+
+CREATE INDEX ON warehouses (organization_id);
+-- Склад по умолчанию (куда приходует производство) у организации один
+CREATE UNIQUE INDEX ON warehouses (organization_id) WHERE is_default;
+
+
+DROP TABLE IF EXISTS stock_movements CASCADE;
+
+CREATE TABLE stock_movements (
+    id BIGSERIAL PRIMARY KEY,
+    organization_id BIGINT NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+    warehouse_id BIGINT NOT NULL REFERENCES warehouses (id) ON DELETE CASCADE,
+    product_id BIGINT NOT NULL REFERENCES products (id) ON DELETE CASCADE,
+    -- Пользователя с движениями удалить нельзя, история не должна теряться
+    user_id BIGINT NOT NULL REFERENCES users (id) ON DELETE RESTRICT,
+    kind VARCHAR(255) NOT NULL,
+    cnt BIGINT NOT NULL,
+    -- Ссылки на документы-основания хранятся без внешних ключей:
+    -- журнал не должен меняться при удалении исходных записей.
+    produced_good_id BIGINT NULL,
+    produced_good_adjustment_id BIGINT NULL,
+    counterpart_warehouse_id BIGINT NULL,
+    comment TEXT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX ON stock_movements (organization_id);
+CREATE INDEX ON stock_movements (warehouse_id, product_id);
+CREATE INDEX ON stock_movements (product_id);
+CREATE INDEX ON stock_movements (produced_good_id);
+CREATE INDEX ON stock_movements (created_at);
+
+
+-- Журнал движений только на добавление.
+-- Удаление разрешено лишь каскадом (удаление организации, склада, продукта).
+CREATE OR REPLACE FUNCTION stock_movements_append_only() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'UPDATE' OR pg_trigger_depth() <= 1 THEN
+        RAISE EXCEPTION 'stock_movements is append-only';
+    END IF;
+    RETURN OLD;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER stock_movements_append_only
+    BEFORE UPDATE OR DELETE ON stock_movements
+    FOR EACH ROW EXECUTE FUNCTION stock_movements_append_only();
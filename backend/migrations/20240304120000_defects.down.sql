@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+DROP TABLE IF EXISTS produced_good_defects cascade;
+DROP TABLE IF EXISTS defect_reasons cascade;
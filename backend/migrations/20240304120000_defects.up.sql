@@ -0,0 +1,29 @@
+-- Add up migration script here
+
+DROP TABLE IF EXISTS defect_reasons CASCADE;
+
+CREATE TABLE defect_reasons (
+    id BIGSERIAL PRIMARY KEY,
+    organization_id BIGINT NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NULL
+);
+
+CREATE INDEX ON defect_reasons (organization_id);
+
+
+DROP TABLE IF EXISTS produced_good_defects CASCADE;
+
+CREATE TABLE produced_good_defects (
+    id BIGSERIAL PRIMARY KEY,
+    produced_good_id BIGINT NOT NULL REFERENCES produced_goods (id) ON DELETE CASCADE,
+    defect_reason_id BIGINT NOT NULL REFERENCES defect_reasons (id) ON DELETE RESTRICT,
+    cnt BIGINT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (produced_good_id, defect_reason_id),
+    CHECK (cnt > 0)
+);
+
+CREATE INDEX ON produced_good_defects (produced_good_id);
+CREATE INDEX ON produced_good_defects (defect_reason_id);
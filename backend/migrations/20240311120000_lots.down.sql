@@ -0,0 +1,7 @@
+-- Add down migration script here
+
+ALTER TABLE produced_goods DROP COLUMN IF EXISTS shift_id;
+ALTER TABLE produced_goods DROP COLUMN IF EXISTS lot;
+ALTER TABLE products DROP COLUMN IF EXISTS lot_pattern;
+
+DROP TABLE IF EXISTS shifts cascade;
@@ -0,0 +1,27 @@
+-- Add up migration script here
+
+DROP TABLE IF EXISTS shifts CASCADE;
+
+CREATE TABLE shifts (
+    id BIGSERIAL PRIMARY KEY,
+    organization_id BIGINT NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    start_time TIME NOT NULL,
+    end_time TIME NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NULL,
+    CHECK (start_time <> end_time)
+);
+
+CREATE INDEX ON shifts (organization_id);
+
+
+-- Шаблон номера партии, например {YYYY}{MM}{DD}-{SEQ}
+ALTER TABLE products ADD COLUMN lot_pattern VARCHAR(255) NULL;
+
+ALTER TABLE produced_goods ADD COLUMN lot VARCHAR(255) NULL;
+ALTER TABLE produced_goods
+    ADD COLUMN shift_id BIGINT NULL REFERENCES shifts (id) ON DELETE SET NULL;
+
+CREATE INDEX ON produced_goods (lot);
+CREATE INDEX ON produced_goods (shift_id);
@@ -0,0 +1,13 @@
+-- Add down migration script here
+
+ALTER TABLE produced_good_adjustments
+    DROP COLUMN IF EXISTS review_comment,
+    DROP COLUMN IF EXISTS reviewed_at,
+    DROP COLUMN IF EXISTS reviewed_by,
+    DROP COLUMN IF EXISTS status,
+    DROP COLUMN IF EXISTS comment,
+    DROP COLUMN IF EXISTS reason_id;
+
+ALTER TABLE organizations DROP COLUMN IF EXISTS adjustment_threshold;
+
+DROP TABLE IF EXISTS adjustment_reasons cascade;
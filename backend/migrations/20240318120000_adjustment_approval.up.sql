@@ -0,0 +1,31 @@
+-- Add up migration script here
+
+DROP TABLE IF EXISTS adjustment_reasons CASCADE;
+
+CREATE TABLE adjustment_reasons (
+    id BIGSERIAL PRIMARY KEY,
+    organization_id BIGINT NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NULL
+);
+
+CREATE INDEX ON adjustment_reasons (organization_id);
+
+
+-- Корректировки больше порога (по модулю) требуют утверждения Директором.
+-- NULL - правило выключено.
+ALTER TABLE organizations ADD COLUMN adjustment_threshold BIGINT NULL;
+
+
+-- Старые корректировки считаются утвержденными
+ALTER TABLE produced_good_adjustments
+    ADD COLUMN reason_id BIGINT NULL REFERENCES adjustment_reasons (id) ON DELETE RESTRICT,
+    ADD COLUMN comment TEXT NULL,
+    ADD COLUMN status VARCHAR(255) NOT NULL DEFAULT 'Approved',
+    ADD COLUMN reviewed_by BIGINT NULL REFERENCES users (id) ON DELETE SET NULL,
+    ADD COLUMN reviewed_at TIMESTAMP WITH TIME ZONE NULL,
+    ADD COLUMN review_comment TEXT NULL;
+
+CREATE INDEX ON produced_good_adjustments (status);
+CREATE INDEX ON produced_good_adjustments (reason_id);
@@ -0,0 +1,7 @@
+-- Add down migration script here
+
+ALTER TABLE produced_goods
+    DROP COLUMN IF EXISTS status,
+    DROP COLUMN IF EXISTS reviewed_by,
+    DROP COLUMN IF EXISTS reviewed_at,
+    DROP COLUMN IF EXISTS review_comment;
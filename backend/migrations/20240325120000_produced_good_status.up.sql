@@ -0,0 +1,13 @@
+-- Add up migration script here
+
+-- Старые записи считаются подтвержденными, новые записи сотрудников
+-- ждут подтверждения Директором.
+ALTER TABLE produced_goods
+    ADD COLUMN status VARCHAR(255) NOT NULL DEFAULT 'Confirmed',
+    ADD COLUMN reviewed_by BIGINT NULL REFERENCES users (id) ON DELETE SET NULL,
+    ADD COLUMN reviewed_at TIMESTAMP WITH TIME ZONE NULL,
+    ADD COLUMN review_comment TEXT NULL;
+
+ALTER TABLE produced_goods ALTER COLUMN status SET DEFAULT 'Submitted';
+
+CREATE INDEX ON produced_goods (status);
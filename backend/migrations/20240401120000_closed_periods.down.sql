@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+DROP TABLE IF EXISTS audit_log CASCADE;
+DROP TABLE IF EXISTS closed_periods CASCADE;
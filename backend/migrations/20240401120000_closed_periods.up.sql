@@ -0,0 +1,31 @@
+-- Add up migration script here
+
+DROP TABLE IF EXISTS closed_periods CASCADE;
+
+-- Закрытый месяц организации: period - первый день месяца
+CREATE TABLE closed_periods (
+    id BIGSERIAL PRIMARY KEY,
+    organization_id BIGINT NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+    period DATE NOT NULL,
+    user_id BIGINT NOT NULL REFERENCES users (id),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (organization_id, period),
+    CHECK (period = DATE_TRUNC('month', period)::date)
+);
+
+
+DROP TABLE IF EXISTS audit_log CASCADE;
+
+CREATE TABLE audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    organization_id BIGINT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+    user_id BIGINT NOT NULL REFERENCES users (id),
+    action VARCHAR(255) NOT NULL,
+    entity VARCHAR(255) NOT NULL,
+    entity_id BIGINT NULL,
+    details JSONB NOT NULL DEFAULT '{}'::jsonb,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX ON audit_log (organization_id);
+CREATE INDEX ON audit_log (entity, entity_id);
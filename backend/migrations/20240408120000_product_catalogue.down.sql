@@ -0,0 +1,10 @@
+-- Add down migration script here
+
+ALTER TABLE products
+    DROP COLUMN IF EXISTS category_id,
+    DROP COLUMN IF EXISTS description,
+    DROP COLUMN IF EXISTS barcode_type,
+    DROP COLUMN IF EXISTS barcode,
+    DROP COLUMN IF EXISTS sku;
+
+DROP TABLE IF EXISTS product_categories CASCADE;
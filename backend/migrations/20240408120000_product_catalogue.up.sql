@@ -0,0 +1,30 @@
+-- Add up migration script here
+
+DROP TABLE IF EXISTS product_categories CASCADE;
+
+CREATE TABLE product_categories (
+    id BIGSERIAL PRIMARY KEY,
+    organization_id BIGINT NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+    parent_id BIGINT NULL REFERENCES product_categories (id) ON DELETE RESTRICT,
+    name VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NULL,
+    CHECK (parent_id <> id)
+);
+
+CREATE INDEX ON product_categories (organization_id);
+CREATE INDEX ON product_categories (parent_id);
+
+
+-- Штрихкод хранится без контрольного символа Code128, для EAN-13 - все 13 цифр
+ALTER TABLE products
+    ADD COLUMN sku VARCHAR(255) NULL,
+    ADD COLUMN barcode VARCHAR(255) NULL,
+    ADD COLUMN barcode_type VARCHAR(255) NULL,
+    ADD COLUMN description TEXT NULL,
+    ADD COLUMN category_id BIGINT NULL REFERENCES product_categories (id) ON DELETE SET NULL,
+    ADD CHECK ((barcode IS NULL) = (barcode_type IS NULL));
+
+CREATE UNIQUE INDEX ON products (organization_id, sku) WHERE sku IS NOT NULL;
+CREATE UNIQUE INDEX ON products (organization_id, barcode) WHERE barcode IS NOT NULL;
+CREATE INDEX ON products (category_id);
@@ -0,0 +1,23 @@
+-- Add up migration script here
+
+DROP TABLE IF EXISTS attachments CASCADE;
+
+-- Файлы, прикрепленные к продукту (изображения) или к записи производства
+-- (фото и документы). Сами файлы лежат в хранилище под ключом storage_key.
+CREATE TABLE attachments (
+    id BIGSERIAL PRIMARY KEY,
+    organization_id BIGINT NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+    product_id BIGINT NULL REFERENCES products (id) ON DELETE CASCADE,
+    produced_good_id BIGINT NULL REFERENCES produced_goods (id) ON DELETE CASCADE,
+    file_name VARCHAR(255) NOT NULL,
+    content_type VARCHAR(255) NOT NULL,
+    size BIGINT NOT NULL,
+    storage_key VARCHAR(255) NOT NULL UNIQUE,
+    thumbnail_key VARCHAR(255) NULL,
+    user_id BIGINT NOT NULL REFERENCES users (id),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CHECK ((product_id IS NULL) <> (produced_good_id IS NULL))
+);
+
+CREATE INDEX ON attachments (product_id);
+CREATE INDEX ON attachments (produced_good_id);
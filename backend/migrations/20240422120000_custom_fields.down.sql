@@ -0,0 +1,9 @@
+-- Add down migration script here
+
+ALTER TABLE produced_goods
+    DROP COLUMN IF EXISTS custom_fields;
+
+ALTER TABLE products
+    DROP COLUMN IF EXISTS custom_fields;
+
+DROP TABLE IF EXISTS custom_fields CASCADE;
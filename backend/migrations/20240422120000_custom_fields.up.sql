@@ -0,0 +1,33 @@
+-- Add up migration script here
+
+DROP TABLE IF EXISTS custom_fields CASCADE;
+
+-- Дополнительные поля организации для продуктов и записей производства.
+-- Значения хранятся в JSONB-колонке custom_fields записи под ключом code.
+CREATE TABLE custom_fields (
+    id BIGSERIAL PRIMARY KEY,
+    organization_id BIGINT NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+    entity VARCHAR(255) NOT NULL,
+    code VARCHAR(63) NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    kind VARCHAR(255) NOT NULL,
+    -- Допустимые значения для списка
+    options JSONB NOT NULL DEFAULT '[]'::jsonb,
+    required BOOLEAN NOT NULL DEFAULT FALSE,
+    position INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NULL,
+    UNIQUE (organization_id, entity, code),
+    CHECK (entity IN ('Product', 'ProducedGood')),
+    CHECK (kind IN ('Text', 'Number', 'Enum', 'Date', 'Boolean')),
+    CHECK (code ~ '^[a-z][a-z0-9_]*$')
+);
+
+ALTER TABLE products
+    ADD COLUMN custom_fields JSONB NOT NULL DEFAULT '{}'::jsonb;
+
+ALTER TABLE produced_goods
+    ADD COLUMN custom_fields JSONB NOT NULL DEFAULT '{}'::jsonb;
+
+CREATE INDEX ON products USING GIN (custom_fields);
+CREATE INDEX ON produced_goods USING GIN (custom_fields);
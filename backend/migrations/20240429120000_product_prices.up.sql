@@ -0,0 +1,22 @@
+-- Add up migration script here
+
+DROP TABLE IF EXISTS product_prices CASCADE;
+
+CREATE TABLE product_prices (
+    id BIGSERIAL PRIMARY KEY,
+    organization_id BIGINT NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+    product_id BIGINT NOT NULL REFERENCES products (id) ON DELETE CASCADE,
+    -- Себестоимость и цена единицы продукта, действуют с valid_from до следующей записи
+    cost NUMERIC(14, 2) NOT NULL DEFAULT 0,
+    price NUMERIC(14, 2) NOT NULL DEFAULT 0,
+    valid_from DATE NOT NULL,
+    user_id BIGINT NULL REFERENCES users (id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NULL,
+    UNIQUE (product_id, valid_from),
+    CHECK (cost >= 0),
+    CHECK (price >= 0)
+);
+
+CREATE INDEX ON product_prices (organization_id);
+CREATE INDEX ON product_prices (product_id, valid_from DESC);
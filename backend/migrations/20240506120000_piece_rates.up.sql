@@ -0,0 +1,24 @@
+-- Add up migration script here
+
+DROP TABLE IF EXISTS piece_rates CASCADE;
+
+-- Сдельные расценки за единицу продукта. Расценка может уточняться ролью
+-- работника и сменой, при расчете выбирается наиболее точная из действующих на дату выпуска.
+CREATE TABLE piece_rates (
+    id BIGSERIAL PRIMARY KEY,
+    organization_id BIGINT NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+    product_id BIGINT NOT NULL REFERENCES products (id) ON DELETE CASCADE,
+    role VARCHAR(255) NULL,
+    shift_id BIGINT NULL REFERENCES shifts (id) ON DELETE CASCADE,
+    rate NUMERIC(14, 2) NOT NULL,
+    valid_from DATE NOT NULL,
+    user_id BIGINT NULL REFERENCES users (id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NULL,
+    CHECK (rate >= 0),
+    CHECK (role IS NULL OR role IN ('Developer', 'Admin', 'Director', 'User'))
+);
+
+CREATE UNIQUE INDEX ON piece_rates (product_id, COALESCE(role, ''), COALESCE(shift_id, 0), valid_from);
+CREATE INDEX ON piece_rates (organization_id);
+CREATE INDEX ON piece_rates (product_id, valid_from DESC);
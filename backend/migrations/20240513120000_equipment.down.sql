@@ -0,0 +1,8 @@
+-- Add down migration script here
+
+ALTER TABLE produced_goods
+    DROP COLUMN IF EXISTS machine_id;
+
+DROP TABLE IF EXISTS downtimes CASCADE;
+DROP TABLE IF EXISTS downtime_reasons CASCADE;
+DROP TABLE IF EXISTS machines CASCADE;
@@ -0,0 +1,62 @@
+-- Add up migration script here
+
+DROP TABLE IF EXISTS machines CASCADE;
+
+CREATE TABLE machines (
+    id BIGSERIAL PRIMARY KEY,
+    organization_id BIGINT NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    code VARCHAR(50) NULL,
+    -- Идеальная производительность, единиц продукта в час, используется в расчете OEE
+    ideal_rate DOUBLE PRECISION NULL,
+    active BOOLEAN NOT NULL DEFAULT 'T',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NULL,
+    UNIQUE (organization_id, code),
+    CHECK (ideal_rate IS NULL OR ideal_rate > 0)
+);
+
+CREATE INDEX ON machines (organization_id);
+
+
+DROP TABLE IF EXISTS downtime_reasons CASCADE;
+
+CREATE TABLE downtime_reasons (
+    id BIGSERIAL PRIMARY KEY,
+    organization_id BIGINT NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+    code VARCHAR(50) NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NULL,
+    UNIQUE (organization_id, code)
+);
+
+CREATE INDEX ON downtime_reasons (organization_id);
+
+
+DROP TABLE IF EXISTS downtimes CASCADE;
+
+CREATE TABLE downtimes (
+    id BIGSERIAL PRIMARY KEY,
+    organization_id BIGINT NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+    machine_id BIGINT NOT NULL REFERENCES machines (id) ON DELETE CASCADE,
+    downtime_reason_id BIGINT NOT NULL REFERENCES downtime_reasons (id) ON DELETE RESTRICT,
+    -- Открытый простой (без окончания) считается до текущего момента
+    started_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    ended_at TIMESTAMP WITH TIME ZONE NULL,
+    comment TEXT NULL,
+    user_id BIGINT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NULL,
+    CHECK (ended_at IS NULL OR ended_at > started_at)
+);
+
+CREATE INDEX ON downtimes (organization_id);
+CREATE INDEX ON downtimes (machine_id, started_at);
+CREATE INDEX ON downtimes (downtime_reason_id);
+
+
+ALTER TABLE produced_goods
+    ADD COLUMN machine_id BIGINT NULL REFERENCES machines (id) ON DELETE SET NULL;
+
+CREATE INDEX ON produced_goods (machine_id);
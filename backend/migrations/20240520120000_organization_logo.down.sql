@@ -0,0 +1,3 @@
+-- Add down migration script here
+
+ALTER TABLE organizations DROP COLUMN IF EXISTS logo_key;
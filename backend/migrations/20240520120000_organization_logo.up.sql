@@ -0,0 +1,4 @@
+-- Add up migration script here
+
+-- Логотип организации для шапки печатных отчетов, файл лежит в хранилище вложений
+ALTER TABLE organizations ADD COLUMN logo_key VARCHAR(255) NULL;
@@ -0,0 +1,48 @@
+-- Add up migration script here
+
+DROP TABLE IF EXISTS report_subscriptions CASCADE;
+
+-- Подписка на регулярную отправку отчета по почте.
+-- Отчет строится от имени владельца подписки с его правами доступа.
+CREATE TABLE report_subscriptions (
+    id BIGSERIAL PRIMARY KEY,
+    organization_id BIGINT NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+    user_id BIGINT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    report_type VARCHAR(50) NOT NULL,
+    -- Фильтры отчета в формате параметров аналитики
+    filters JSONB NOT NULL DEFAULT '{}'::jsonb,
+    -- За какой прошедший период строится отчет: day, week, month
+    period VARCHAR(20) NOT NULL,
+    -- Расписание cron из пяти полей: минута час день месяц день_недели
+    schedule VARCHAR(100) NOT NULL,
+    time_zone VARCHAR(100) NOT NULL,
+    recipients TEXT[] NOT NULL,
+    active BOOLEAN NOT NULL DEFAULT 'T',
+    next_run_at TIMESTAMP WITH TIME ZONE NULL,
+    last_run_at TIMESTAMP WITH TIME ZONE NULL,
+    -- Неудачные попытки отправки текущего запуска
+    attempts INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NULL
+);
+
+CREATE INDEX ON report_subscriptions (organization_id);
+CREATE INDEX ON report_subscriptions (next_run_at) WHERE active;
+
+
+DROP TABLE IF EXISTS report_deliveries CASCADE;
+
+-- История отправок по подпискам
+CREATE TABLE report_deliveries (
+    id BIGSERIAL PRIMARY KEY,
+    subscription_id BIGINT NOT NULL REFERENCES report_subscriptions (id) ON DELETE CASCADE,
+    status VARCHAR(20) NOT NULL,
+    date_one DATE NOT NULL,
+    date_two DATE NOT NULL,
+    recipients TEXT[] NOT NULL,
+    error TEXT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX ON report_deliveries (subscription_id, created_at);
@@ -0,0 +1,5 @@
+-- Add down migration script here
+
+ALTER TABLE produced_goods DROP COLUMN IF EXISTS lot_generated;
+
+DROP TABLE IF EXISTS lot_sequences CASCADE;
@@ -0,0 +1,19 @@
+-- Add up migration script here
+
+DROP TABLE IF EXISTS lot_sequences CASCADE;
+
+-- Счетчик номеров партий продукта. Ключ - номер партии по шаблону без {SEQ},
+-- для шаблона с датой счетчик начинается заново каждый день.
+-- Номер не переиспользуется после удаления записи.
+CREATE TABLE lot_sequences (
+    product_id BIGINT NOT NULL REFERENCES products (id) ON DELETE CASCADE,
+    prefix VARCHAR(255) NOT NULL,
+    seq BIGINT NOT NULL,
+    PRIMARY KEY (product_id, prefix)
+);
+
+-- Сгенерированные номера партий уникальны в пределах продукта,
+-- введенный вручную номер может объединять несколько записей
+ALTER TABLE produced_goods ADD COLUMN lot_generated BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE UNIQUE INDEX ON produced_goods (product_id, lot) WHERE lot_generated;
@@ -0,0 +1,176 @@
+use crate::services::attachments::attachment::{self as serv, Entity, File, Item};
+use crate::services::attachments::storage::Storage;
+use crate::{AppError, CurrentUser};
+use axum::body::Body;
+use axum::extract::{Multipart, Path};
+use axum::http::{Response, StatusCode};
+use axum::Extension;
+use axum::{extract::State, Json};
+use sqlx::PgPool;
+use std::collections::HashMap;
+
+pub async fn upload_product_attachment(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(storage): Extension<Storage>,
+    Path(id): Path<i64>,
+    multipart: Multipart,
+) -> Result<Json<HashMap<String, i64>>, AppError> {
+    // Метод загрузки изображения продукта
+
+    let insert_id = serv::upload_attachment(
+        State(pool),
+        Extension(current_user),
+        Extension(storage),
+        Entity::Product,
+        Path(id),
+        multipart,
+    )
+    .await?;
+
+    Ok(Json(HashMap::from([("id".to_string(), insert_id)])))
+}
+
+pub async fn upload_produced_good_attachment(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(storage): Extension<Storage>,
+    Path(id): Path<i64>,
+    multipart: Multipart,
+) -> Result<Json<HashMap<String, i64>>, AppError> {
+    // Метод загрузки фото или документа к записи производства
+
+    let insert_id = serv::upload_attachment(
+        State(pool),
+        Extension(current_user),
+        Extension(storage),
+        Entity::ProducedGood,
+        Path(id),
+        multipart,
+    )
+    .await?;
+
+    Ok(Json(HashMap::from([("id".to_string(), insert_id)])))
+}
+
+pub async fn get_product_attachments(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<Json<Vec<Item>>, AppError> {
+    // Метод получения изображений продукта
+
+    let items = serv::get_attachments(
+        State(pool),
+        Extension(current_user),
+        Entity::Product,
+        Path(id),
+    )
+    .await?;
+
+    Ok(Json(items))
+}
+
+pub async fn get_produced_good_attachments(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<Json<Vec<Item>>, AppError> {
+    // Метод получения вложений записи производства
+
+    let items = serv::get_attachments(
+        State(pool),
+        Extension(current_user),
+        Entity::ProducedGood,
+        Path(id),
+    )
+    .await?;
+
+    Ok(Json(items))
+}
+
+fn content_disposition(file_name: &str) -> String {
+    // Имя файла может быть на кириллице, поэтому кодируется по RFC 5987
+
+    let encoded: String = file_name
+        .bytes()
+        .map(|b| match b {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'.' | b'-' | b'_' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect();
+
+    format!("attachment; filename*=UTF-8''{}", encoded)
+}
+
+fn file_response(file: File) -> Response<Body> {
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Disposition", content_disposition(&file.file_name))
+        .header("Content-Type", file.content_type)
+        .header("Cache-Control", "private, max-age=86400")
+        .body(Body::from(file.data))
+        .unwrap()
+}
+
+pub async fn download_attachment(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(storage): Extension<Storage>,
+    Path(id): Path<i64>,
+) -> Result<Response<Body>, AppError> {
+    // Метод скачивания файла
+
+    let file = serv::download_attachment(
+        State(pool),
+        Extension(current_user),
+        Extension(storage),
+        Path(id),
+        false,
+    )
+    .await?;
+
+    Ok(file_response(file))
+}
+
+pub async fn download_attachment_thumbnail(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(storage): Extension<Storage>,
+    Path(id): Path<i64>,
+) -> Result<Response<Body>, AppError> {
+    // Метод получения превью изображения
+
+    let file = serv::download_attachment(
+        State(pool),
+        Extension(current_user),
+        Extension(storage),
+        Path(id),
+        true,
+    )
+    .await?;
+
+    Ok(file_response(file))
+}
+
+pub async fn delete_attachment(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(storage): Extension<Storage>,
+    Path(id): Path<i64>,
+) -> Result<Json<HashMap<String, String>>, AppError> {
+    // Метод удаления вложения
+
+    serv::delete_attachment(
+        State(pool),
+        Extension(current_user),
+        Extension(storage),
+        Path(id),
+    )
+    .await?;
+
+    Ok(Json(HashMap::from([(
+        "detail".to_string(),
+        "OK".to_string(),
+    )])))
+}
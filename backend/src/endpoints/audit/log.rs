@@ -0,0 +1,20 @@
+use crate::services::audit::log::{self as serv, Item, Q};
+use crate::services::Items;
+
+use crate::{AppError, CurrentUser};
+use axum::extract::Query;
+use axum::Extension;
+use axum::{extract::State, Json};
+use sqlx::PgPool;
+
+pub async fn get_audit_log(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Json<Items<Item>>, AppError> {
+    // Метод получения журнала аудита
+
+    let items = serv::get_audit_log(State(pool), Extension(current_user), Query(q)).await?;
+
+    Ok(Json(items))
+}
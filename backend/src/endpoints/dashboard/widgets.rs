@@ -0,0 +1,19 @@
+use crate::services::dashboard::widgets::{self as serv, Dashboard, Q};
+
+use crate::{AppError, CurrentUser};
+use axum::extract::Query;
+use axum::Extension;
+use axum::{extract::State, Json};
+use sqlx::PgPool;
+
+pub async fn get_dashboard(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Json<Dashboard>, AppError> {
+    // Метод получения сводки для главной страницы
+
+    let dashboard = serv::get_dashboard(State(pool), Extension(current_user), Query(q)).await?;
+
+    Ok(Json(dashboard))
+}
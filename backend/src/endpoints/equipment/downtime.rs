@@ -0,0 +1,63 @@
+use crate::services::equipment::downtime::{self as serv, Item, RequestBody, Q};
+use crate::services::Items;
+use crate::{AppError, CurrentUser};
+use axum::extract::{Path, Query};
+use axum::Extension;
+use axum::{extract::State, Json};
+use sqlx::PgPool;
+use std::collections::HashMap;
+
+pub async fn create_downtime(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(body): Json<RequestBody>,
+) -> Result<Json<HashMap<String, i64>>, AppError> {
+    // Метод регистрации простоя
+
+    let insert_id = serv::create_downtime(State(pool), Extension(current_user), Json(body)).await?;
+
+    Ok(Json(HashMap::from([("id".to_string(), insert_id)])))
+}
+
+pub async fn edit_downtime(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+    Json(body): Json<RequestBody>,
+) -> Result<Json<HashMap<String, String>>, AppError> {
+    // Метод редактирования простоя
+
+    let _ = serv::edit_downtime(State(pool), Extension(current_user), Path(id), Json(body)).await?;
+
+    Ok(Json(HashMap::from([(
+        "detail".to_string(),
+        "OK".to_string(),
+    )])))
+}
+
+pub async fn get_downtimes(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Json<Items<Item>>, AppError> {
+    // Метод получения журнала простоев
+
+    let items = serv::get_downtimes(State(pool), Extension(current_user), Query(q)).await?;
+
+    Ok(Json(items))
+}
+
+pub async fn delete_downtime(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<Json<HashMap<String, String>>, AppError> {
+    // Метод удаления простоя
+
+    serv::delete_downtime(State(pool), Extension(current_user), Path(id)).await?;
+
+    Ok(Json(HashMap::from([(
+        "detail".to_string(),
+        "OK".to_string(),
+    )])))
+}
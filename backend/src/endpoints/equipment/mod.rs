@@ -0,0 +1,3 @@
+pub mod downtime;
+pub mod machine;
+pub mod oee;
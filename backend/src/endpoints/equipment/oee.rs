@@ -0,0 +1,19 @@
+use crate::services::equipment::oee::{self as serv, Item, Q};
+
+use crate::{AppError, CurrentUser};
+use axum::extract::Query;
+use axum::Extension;
+use axum::{extract::State, Json};
+use sqlx::PgPool;
+
+pub async fn get_oee(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Json<Vec<Item>>, AppError> {
+    // Метод получения доступности и OEE оборудования за период
+
+    let items = serv::get_oee(State(pool), Extension(current_user), Query(q)).await?;
+
+    Ok(Json(items))
+}
@@ -0,0 +1,89 @@
+use crate::services::export::format::{self as serv, ExportFormat, FormatQ};
+use crate::services::export::table::{self as tables, ProducedGoodsQ, ProductsQ};
+use crate::services::produced_goods::analitic::Q as AnaliticQ;
+
+use crate::{AppError, CurrentUser};
+use axum::body::Body;
+use axum::extract::{Query, State};
+use axum::http::{HeaderMap, Response};
+use axum::Extension;
+use sqlx::PgPool;
+
+pub async fn export_produced_goods(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    headers: HeaderMap,
+    Query(f): Query<FormatQ>,
+    Query(q): Query<ProducedGoodsQ>,
+) -> Result<Response<Body>, AppError> {
+    // Метод выгрузки выпуска продукции
+
+    let format = ExportFormat::negotiate(f.format, &headers);
+    let table =
+        tables::produced_goods_table(State(pool), Extension(current_user), Query(q)).await?;
+
+    serv::export(format, "produced_goods", table).await
+}
+
+pub async fn export_products(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    headers: HeaderMap,
+    Query(f): Query<FormatQ>,
+    Query(q): Query<ProductsQ>,
+) -> Result<Response<Body>, AppError> {
+    // Метод выгрузки справочника продуктов
+
+    let format = ExportFormat::negotiate(f.format, &headers);
+    let table = tables::products_table(State(pool), Extension(current_user), Query(q)).await?;
+
+    serv::export(format, "products", table).await
+}
+
+pub async fn export_measures(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    headers: HeaderMap,
+    Query(f): Query<FormatQ>,
+) -> Result<Response<Body>, AppError> {
+    // Метод выгрузки единиц измерения
+
+    let format = ExportFormat::negotiate(f.format, &headers);
+    let table = tables::measures_table(State(pool), Extension(current_user)).await?;
+
+    serv::export(format, "measure_units", table).await
+}
+
+pub async fn export_users(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    headers: HeaderMap,
+    Query(f): Query<FormatQ>,
+) -> Result<Response<Body>, AppError> {
+    // Метод выгрузки пользователей
+
+    let format = ExportFormat::negotiate(f.format, &headers);
+    let table = tables::users_table(State(pool), Extension(current_user)).await?;
+
+    serv::export(format, "users", table).await
+}
+
+pub async fn export_analitics(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    headers: HeaderMap,
+    Query(f): Query<FormatQ>,
+    Query(q): Query<AnaliticQ>,
+) -> Result<Response<Body>, AppError> {
+    // Метод выгрузки аналитики за период
+
+    let format = ExportFormat::negotiate(f.format, &headers);
+    let filename = format!(
+        "analitics_{}_{}",
+        q.date_one.format("%d.%m.%Y"),
+        q.date_two.format("%d.%m.%Y")
+    );
+    let table = tables::analitics_table(State(pool), Extension(current_user), Query(q)).await?;
+
+    serv::export(format, &filename, table).await
+}
@@ -1,4 +1,15 @@
+pub mod attachments;
+pub mod audit;
+pub mod dashboard;
+pub mod equipment;
+pub mod export;
 pub mod init;
+pub mod payroll;
+pub mod periods;
+pub mod plans;
 pub mod produced_goods;
 pub mod rbs;
+pub mod reports;
+pub mod stock;
 pub mod users;
+pub mod work_orders;
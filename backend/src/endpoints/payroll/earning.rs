@@ -0,0 +1,71 @@
+use crate::services::payroll::earning::{self as serv, Item, ReportFormat, Q};
+
+use crate::{AppError, CurrentUser};
+use axum::body::Body;
+use axum::extract::Query;
+use axum::http::{Response, StatusCode};
+use axum::Extension;
+use axum::{extract::State, Json};
+use sqlx::PgPool;
+
+pub async fn get_earnings(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Json<Vec<Item>>, AppError> {
+    // Метод получения сдельной оплаты работников за период
+
+    let items = serv::get_earnings(State(pool), Extension(current_user), Query(q)).await?;
+
+    Ok(Json(items))
+}
+
+pub async fn get_my_earnings(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Json<Vec<Item>>, AppError> {
+    // Метод получения сдельной оплаты текущего пользователя за период
+
+    let items = serv::get_my_earnings(State(pool), Extension(current_user), Query(q)).await?;
+
+    Ok(Json(items))
+}
+
+pub async fn upload_payroll_report(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Response<Body>, AppError> {
+    // Метод выгрузки ведомости сдельной оплаты в excel или csv
+
+    let items = serv::get_earnings(State(pool), Extension(current_user), Query(q.clone())).await?;
+
+    let (buffer, extension, content_type) = match q.format {
+        ReportFormat::Xlsx => (
+            serv::generate_excel(items, q.date_one, q.date_two).await?,
+            "xlsx",
+            "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        ),
+        ReportFormat::Csv => (serv::generate_csv(items), "csv", "text/csv; charset=utf-8"),
+    };
+
+    let filename = format!(
+        "payroll_report_{}_{}.{}",
+        q.date_one.format("%d.%m.%Y"),
+        q.date_two.format("%d.%m.%Y"),
+        extension
+    );
+
+    let resp = Response::builder()
+        .status(StatusCode::OK)
+        .header(
+            "Content-Disposition",
+            format!("attachment; filename={}", filename),
+        )
+        .header("Content-Type", content_type)
+        .body(Body::from(buffer))
+        .unwrap();
+
+    Ok(resp)
+}
@@ -0,0 +1,65 @@
+use crate::services::payroll::piece_rate::{self as serv, Item, RequestBody, Q};
+use crate::services::Items;
+use crate::{AppError, CurrentUser};
+use axum::extract::{Path, Query};
+use axum::Extension;
+use axum::{extract::State, Json};
+use sqlx::PgPool;
+use std::collections::HashMap;
+
+pub async fn create_piece_rate(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(body): Json<RequestBody>,
+) -> Result<Json<HashMap<String, i64>>, AppError> {
+    // Метод создания сдельной расценки
+
+    let insert_id =
+        serv::create_piece_rate(State(pool), Extension(current_user), Json(body)).await?;
+
+    Ok(Json(HashMap::from([("id".to_string(), insert_id)])))
+}
+
+pub async fn edit_piece_rate(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+    Json(body): Json<RequestBody>,
+) -> Result<Json<HashMap<String, String>>, AppError> {
+    // Метод редактирования сдельной расценки
+
+    let _ =
+        serv::edit_piece_rate(State(pool), Extension(current_user), Path(id), Json(body)).await?;
+
+    Ok(Json(HashMap::from([(
+        "detail".to_string(),
+        "OK".to_string(),
+    )])))
+}
+
+pub async fn get_piece_rates(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Json<Items<Item>>, AppError> {
+    // Метод получения списка сдельных расценок
+
+    let items = serv::get_piece_rates(State(pool), Extension(current_user), Query(q)).await?;
+
+    Ok(Json(items))
+}
+
+pub async fn delete_piece_rate(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<Json<HashMap<String, String>>, AppError> {
+    // Метод удаления сдельной расценки
+
+    serv::delete_piece_rate(State(pool), Extension(current_user), Path(id)).await?;
+
+    Ok(Json(HashMap::from([(
+        "detail".to_string(),
+        "OK".to_string(),
+    )])))
+}
@@ -0,0 +1,46 @@
+use crate::services::periods::closed_period::{self as serv, Item, RequestBody};
+
+use crate::{AppError, CurrentUser};
+use axum::extract::Path;
+use axum::Extension;
+use axum::{extract::State, Json};
+use sqlx::PgPool;
+use std::collections::HashMap;
+
+pub async fn close_period(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(body): Json<RequestBody>,
+) -> Result<Json<HashMap<String, i64>>, AppError> {
+    // Метод закрытия периода
+
+    let insert_id = serv::close_period(State(pool), Extension(current_user), Json(body)).await?;
+
+    Ok(Json(HashMap::from([("id".to_string(), insert_id)])))
+}
+
+pub async fn reopen_period(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<Json<HashMap<String, String>>, AppError> {
+    // Метод открытия закрытого периода
+
+    serv::reopen_period(State(pool), Extension(current_user), Path(id)).await?;
+
+    Ok(Json(HashMap::from([(
+        "detail".to_string(),
+        "OK".to_string(),
+    )])))
+}
+
+pub async fn get_closed_periods(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<Json<Vec<Item>>, AppError> {
+    // Метод получения закрытых периодов
+
+    let items = serv::get_closed_periods(State(pool), Extension(current_user)).await?;
+
+    Ok(Json(items))
+}
@@ -0,0 +1 @@
+pub mod closed_period;
@@ -0,0 +1,121 @@
+use crate::services::plans::plan::{self as serv, Item, ItemFact, QFact, RequestBody, Q};
+use crate::services::Items;
+use crate::{AppError, CurrentUser};
+use axum::body::Body;
+use axum::extract::{Path, Query};
+use axum::http::{Response, StatusCode};
+use axum::Extension;
+use axum::{extract::State, Json};
+use sqlx::PgPool;
+use std::collections::HashMap;
+
+pub async fn create_plan(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(body): Json<RequestBody>,
+) -> Result<Json<HashMap<String, i64>>, AppError> {
+    // Метод создания плана
+
+    let insert_id = serv::create_plan(State(pool), Extension(current_user), Json(body)).await?;
+
+    Ok(Json(HashMap::from([("id".to_string(), insert_id)])))
+}
+
+pub async fn edit_plan(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+    Json(body): Json<RequestBody>,
+) -> Result<Json<HashMap<String, String>>, AppError> {
+    // Метод редактирования плана
+
+    let _ = serv::edit_plan(State(pool), Extension(current_user), Path(id), Json(body)).await?;
+
+    Ok(Json(HashMap::from([(
+        "detail".to_string(),
+        "OK".to_string(),
+    )])))
+}
+
+pub async fn get_plans(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Json<Items<Item>>, AppError> {
+    // Метод получения списка планов
+
+    let items = serv::get_plans(State(pool), Extension(current_user), Query(q)).await?;
+
+    Ok(Json(items))
+}
+
+pub async fn detail_plan(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<Json<Item>, AppError> {
+    // Метод получения плана
+
+    let item = serv::detail_plan(State(pool), Extension(current_user), Path(id)).await?;
+
+    Ok(Json(item))
+}
+
+pub async fn delete_plan(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<Json<HashMap<String, String>>, AppError> {
+    // Метод удаления плана
+
+    serv::delete_plan(State(pool), Extension(current_user), Path(id)).await?;
+
+    Ok(Json(HashMap::from([(
+        "detail".to_string(),
+        "OK".to_string(),
+    )])))
+}
+
+pub async fn get_plan_fact(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<QFact>,
+) -> Result<Json<Vec<ItemFact>>, AppError> {
+    // Метод получения отчета план/факт
+
+    let items = serv::get_plan_fact(State(pool), Extension(current_user), Query(q)).await?;
+
+    Ok(Json(items))
+}
+
+pub async fn upload_plan_report_in_excel(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<QFact>,
+) -> Result<Response<Body>, AppError> {
+    // Метод выгрузки отчета план/факт в excel
+
+    let items = serv::get_plan_fact(State(pool), Extension(current_user), Query(q.clone())).await?;
+    let buffer = serv::generate_excel(items, q.date_one, q.date_two).await?;
+
+    let filename = format!(
+        "plan_report_{}_{}.xlsx",
+        q.date_one.format("%d.%m.%Y"),
+        q.date_two.format("%d.%m.%Y")
+    );
+
+    let resp = Response::builder()
+        .status(StatusCode::OK)
+        .header(
+            "Content-Disposition",
+            format!("attachment; filename={}", filename),
+        )
+        .header(
+            "Content-Type",
+            "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        )
+        .body(Body::from(buffer))
+        .unwrap();
+
+    Ok(resp)
+}
@@ -0,0 +1,38 @@
+use crate::services::produced_goods::adjustment::{self as serv, Item, RequestBodyStatus, Q};
+use crate::services::Items;
+
+use crate::{AppError, CurrentUser};
+use axum::extract::{Path, Query};
+use axum::Extension;
+use axum::{extract::State, Json};
+use sqlx::PgPool;
+use std::collections::HashMap;
+
+pub async fn get_adjustments(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Json<Items<Item>>, AppError> {
+    // Метод получения списка корректировок
+
+    let items = serv::get_adjustments(State(pool), Extension(current_user), Query(q)).await?;
+
+    Ok(Json(items))
+}
+
+pub async fn review_adjustment(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+    Json(body): Json<RequestBodyStatus>,
+) -> Result<Json<HashMap<String, String>>, AppError> {
+    // Метод утверждения или отклонения корректировки
+
+    let _ =
+        serv::review_adjustment(State(pool), Extension(current_user), Path(id), Json(body)).await?;
+
+    Ok(Json(HashMap::from([(
+        "detail".to_string(),
+        "OK".to_string(),
+    )])))
+}
@@ -1,4 +1,4 @@
-use crate::services::produced_goods::analitic::{self as serv, Item, Q};
+use crate::services::produced_goods::analitic::{self as serv, Item, ItemCategory, Period, Q};
 
 use crate::{AppError, CurrentUser};
 use axum::body::Body;
@@ -20,6 +20,19 @@ pub async fn get_analitics(
     Ok(Json(items))
 }
 
+pub async fn get_category_analitics(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Json<Vec<ItemCategory>>, AppError> {
+    // Метод получения выпуска по категориям продуктов
+
+    let items =
+        serv::get_category_analitics(State(pool), Extension(current_user), Query(q)).await?;
+
+    Ok(Json(items))
+}
+
 // Content-Disposition": "attachment; filename='test.xlsx;"
 pub async fn upload_report_in_excel(
     State(pool): State<PgPool>,
@@ -28,8 +41,24 @@ pub async fn upload_report_in_excel(
 ) -> Result<Response<Body>, AppError> {
     // Метод получения списка единиц измерения
 
-    let items = serv::get_analitics(State(pool), Extension(current_user), Query(q.clone())).await?;
-    let buffer = serv::generate_excel(items, q.date_one, q.date_two).await?;
+    let items = serv::get_analitics(
+        State(pool.clone()),
+        Extension(current_user.clone()),
+        Query(q.clone()),
+    )
+    .await?;
+    // Для листа по дням тот же отчет с группировкой по дням
+    let daily = serv::get_analitics(
+        State(pool),
+        Extension(current_user),
+        Query(Q {
+            group: Some(Period::Day),
+            compare: None,
+            ..q.clone()
+        }),
+    )
+    .await?;
+    let buffer = serv::generate_excel(items, daily, q.date_one, q.date_two).await?;
 
     let filename = format!(
         "period_report_{}_{}.xlsx",
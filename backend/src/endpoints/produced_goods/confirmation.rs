@@ -0,0 +1,40 @@
+use crate::services::produced_goods::confirmation::{
+    self as serv, RequestBodyConfirm, RequestBodyStatus,
+};
+
+use crate::{AppError, CurrentUser};
+use axum::extract::Path;
+use axum::Extension;
+use axum::{extract::State, Json};
+use sqlx::PgPool;
+use std::collections::HashMap;
+
+pub async fn review_produced_good(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+    Json(body): Json<RequestBodyStatus>,
+) -> Result<Json<HashMap<String, String>>, AppError> {
+    // Метод подтверждения или отклонения записи производства
+
+    let _ = serv::review_produced_good(State(pool), Extension(current_user), Path(id), Json(body))
+        .await?;
+
+    Ok(Json(HashMap::from([(
+        "detail".to_string(),
+        "OK".to_string(),
+    )])))
+}
+
+pub async fn confirm_produced_goods(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(body): Json<RequestBodyConfirm>,
+) -> Result<Json<HashMap<String, i64>>, AppError> {
+    // Метод массового подтверждения записей производства
+
+    let cnt =
+        serv::confirm_produced_goods(State(pool), Extension(current_user), Json(body)).await?;
+
+    Ok(Json(HashMap::from([("cnt".to_string(), cnt)])))
+}
@@ -0,0 +1,54 @@
+use crate::services::produced_goods::consumption::{self as serv, Item, Q};
+
+use crate::{AppError, CurrentUser};
+use axum::body::Body;
+use axum::extract::Query;
+use axum::http::{Response, StatusCode};
+use axum::Extension;
+use axum::{extract::State, Json};
+use sqlx::PgPool;
+
+pub async fn get_consumption(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Json<Vec<Item>>, AppError> {
+    // Метод получения расхода материалов
+
+    let items = serv::get_consumption(State(pool), Extension(current_user), Query(q)).await?;
+
+    Ok(Json(items))
+}
+
+pub async fn upload_consumption_report_in_excel(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Response<Body>, AppError> {
+    // Метод выгрузки расхода материалов в excel
+
+    let items =
+        serv::get_consumption(State(pool), Extension(current_user), Query(q.clone())).await?;
+    let buffer = serv::generate_excel(items, q.date_one, q.date_two).await?;
+
+    let filename = format!(
+        "consumption_report_{}_{}.xlsx",
+        q.date_one.format("%d.%m.%Y"),
+        q.date_two.format("%d.%m.%Y")
+    );
+
+    let resp = Response::builder()
+        .status(StatusCode::OK)
+        .header(
+            "Content-Disposition",
+            format!("attachment; filename={}", filename),
+        )
+        .header(
+            "Content-Type",
+            "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        )
+        .body(Body::from(buffer))
+        .unwrap();
+
+    Ok(resp)
+}
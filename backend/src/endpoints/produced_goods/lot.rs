@@ -0,0 +1,19 @@
+use crate::services::produced_goods::lot::{self as serv, Item};
+
+use crate::{AppError, CurrentUser};
+use axum::extract::Path;
+use axum::Extension;
+use axum::{extract::State, Json};
+use sqlx::PgPool;
+
+pub async fn get_lot(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(lot): Path<String>,
+) -> Result<Json<Vec<Item>>, AppError> {
+    // Метод получения прослеживаемости партии
+
+    let items = serv::get_lot(State(pool), Extension(current_user), Path(lot)).await?;
+
+    Ok(Json(items))
+}
@@ -1,2 +1,8 @@
+pub mod adjustment;
 pub mod analitic;
+pub mod confirmation;
+pub mod consumption;
+pub mod lot;
+pub mod pivot;
 pub mod produced_good;
+pub mod quality;
@@ -0,0 +1,71 @@
+use crate::services::produced_goods::{
+    analitic::Q as AnaliticQ,
+    pivot::{self as serv, Pivot, Q},
+};
+
+use crate::{AppError, CurrentUser};
+use axum::body::Body;
+use axum::extract::Query;
+use axum::http::{Response, StatusCode};
+use axum::Extension;
+use axum::{extract::State, Json};
+use sqlx::PgPool;
+
+pub async fn get_pivot(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+    Query(filter): Query<AnaliticQ>,
+) -> Result<Json<Pivot>, AppError> {
+    // Метод получения сводной таблицы выпуска
+
+    let pivot = serv::get_pivot(
+        State(pool),
+        Extension(current_user),
+        Query(q),
+        Query(filter),
+    )
+    .await?;
+
+    Ok(Json(pivot))
+}
+
+pub async fn upload_pivot_report_in_excel(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+    Query(filter): Query<AnaliticQ>,
+) -> Result<Response<Body>, AppError> {
+    // Метод выгрузки сводной таблицы выпуска в Excel
+
+    let (date_one, date_two) = (filter.date_one, filter.date_two);
+    let pivot = serv::get_pivot(
+        State(pool),
+        Extension(current_user),
+        Query(q.clone()),
+        Query(filter),
+    )
+    .await?;
+    let buffer = serv::generate_excel(&pivot, &q, date_one, date_two)?;
+
+    let filename = format!(
+        "pivot_report_{}_{}.xlsx",
+        date_one.format("%d.%m.%Y"),
+        date_two.format("%d.%m.%Y")
+    );
+
+    let resp = Response::builder()
+        .status(StatusCode::OK)
+        .header(
+            "Content-Disposition",
+            format!("attachment; filename={}", filename),
+        )
+        .header(
+            "Content-Type",
+            "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        )
+        .body(Body::from(buffer))
+        .unwrap();
+
+    Ok(resp)
+}
@@ -1,5 +1,6 @@
+use crate::services::attachments::storage::Storage;
 use crate::services::produced_goods::produced_good::{
-    self as serv, Item, RequestBody, RequestBodyAdj, Q,
+    self as serv, Created, Item, RequestBody, RequestBodyAdj, Q,
 };
 use crate::services::Items;
 use crate::{AppError, CurrentUser};
@@ -13,13 +14,13 @@ pub async fn create_produced_good(
     State(pool): State<PgPool>,
     Extension(current_user): Extension<CurrentUser>,
     Json(body): Json<RequestBody>,
-) -> Result<Json<HashMap<String, i64>>, AppError> {
+) -> Result<Json<Created>, AppError> {
     // Метод создания единицы измерения
 
-    let insert_id =
+    let created =
         serv::create_produced_good(State(pool), Extension(current_user), Json(body)).await?;
 
-    Ok(Json(HashMap::from([("id".to_string(), insert_id)])))
+    Ok(Json(created))
 }
 
 pub async fn edit_produced_good(
@@ -66,11 +67,18 @@ pub async fn detail_produced_good(
 pub async fn delete_produced_good(
     State(pool): State<PgPool>,
     Extension(current_user): Extension<CurrentUser>,
+    Extension(storage): Extension<Storage>,
     Path(id): Path<i64>,
 ) -> Result<Json<HashMap<String, String>>, AppError> {
     // Метод удаления производства
 
-    serv::delete_produced_good(State(pool), Extension(current_user), Path(id)).await?;
+    serv::delete_produced_good(
+        State(pool),
+        Extension(current_user),
+        Extension(storage),
+        Path(id),
+    )
+    .await?;
 
     Ok(Json(HashMap::from([(
         "detail".to_string(),
@@ -0,0 +1,31 @@
+use crate::services::produced_goods::quality::{self as serv, Item, ItemReason, Q};
+
+use crate::{AppError, CurrentUser};
+use axum::extract::Query;
+use axum::Extension;
+use axum::{extract::State, Json};
+use sqlx::PgPool;
+
+pub async fn get_quality(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Json<Vec<Item>>, AppError> {
+    // Метод получения выхода годного и доли брака
+
+    let items = serv::get_quality(State(pool), Extension(current_user), Query(q)).await?;
+
+    Ok(Json(items))
+}
+
+pub async fn get_defect_pareto(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Json<Vec<ItemReason>>, AppError> {
+    // Метод получения Парето причин брака
+
+    let items = serv::get_defect_pareto(State(pool), Extension(current_user), Query(q)).await?;
+
+    Ok(Json(items))
+}
@@ -1,2 +1,10 @@
+pub mod adjustment_reason;
+pub mod bom;
+pub mod custom_field;
+pub mod defect_reason;
+pub mod downtime_reason;
 pub mod measure;
 pub mod product;
+pub mod product_category;
+pub mod product_price;
+pub mod shift;
@@ -1,3 +1,4 @@
+use crate::services::attachments::storage::Storage;
 use crate::services::rbs::product::{self as serv, Item, RequestBody, Q};
 use crate::services::Items;
 use crate::{AppError, CurrentUser};
@@ -62,11 +63,18 @@ pub async fn detail_product(
 pub async fn delete_product(
     State(pool): State<PgPool>,
     Extension(current_user): Extension<CurrentUser>,
+    Extension(storage): Extension<Storage>,
     Path(id): Path<i64>,
 ) -> Result<Json<HashMap<String, String>>, AppError> {
     // Метод удаления единицы измерения
 
-    serv::delete_product(State(pool), Extension(current_user), Path(id)).await?;
+    serv::delete_product(
+        State(pool),
+        Extension(current_user),
+        Extension(storage),
+        Path(id),
+    )
+    .await?;
 
     Ok(Json(HashMap::from([(
         "detail".to_string(),
@@ -0,0 +1,71 @@
+use crate::services::rbs::product_price::{self as serv, Item, RequestBody};
+use crate::{AppError, CurrentUser};
+use axum::extract::Path;
+use axum::Extension;
+use axum::{extract::State, Json};
+use sqlx::PgPool;
+use std::collections::HashMap;
+
+pub async fn create_product_price(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(product_id): Path<i64>,
+    Json(body): Json<RequestBody>,
+) -> Result<Json<HashMap<String, i64>>, AppError> {
+    // Метод добавления себестоимости и цены продукта
+
+    let insert_id = serv::create_product_price(
+        State(pool),
+        Extension(current_user),
+        Path(product_id),
+        Json(body),
+    )
+    .await?;
+
+    Ok(Json(HashMap::from([("id".to_string(), insert_id)])))
+}
+
+pub async fn edit_product_price(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+    Json(body): Json<RequestBody>,
+) -> Result<Json<HashMap<String, String>>, AppError> {
+    // Метод редактирования себестоимости и цены продукта
+
+    let _ = serv::edit_product_price(State(pool), Extension(current_user), Path(id), Json(body))
+        .await?;
+
+    Ok(Json(HashMap::from([(
+        "detail".to_string(),
+        "OK".to_string(),
+    )])))
+}
+
+pub async fn get_product_prices(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(product_id): Path<i64>,
+) -> Result<Json<Vec<Item>>, AppError> {
+    // Метод получения истории себестоимости и цен продукта
+
+    let items =
+        serv::get_product_prices(State(pool), Extension(current_user), Path(product_id)).await?;
+
+    Ok(Json(items))
+}
+
+pub async fn delete_product_price(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<Json<HashMap<String, String>>, AppError> {
+    // Метод удаления себестоимости и цены продукта
+
+    serv::delete_product_price(State(pool), Extension(current_user), Path(id)).await?;
+
+    Ok(Json(HashMap::from([(
+        "detail".to_string(),
+        "OK".to_string(),
+    )])))
+}
@@ -0,0 +1,67 @@
+use crate::services::attachments::storage::Storage;
+use crate::services::produced_goods::analitic::Q;
+use crate::services::reports::report::{self as serv, ShiftQ};
+
+use crate::{AppError, CurrentUser};
+use axum::body::Body;
+use axum::extract::Query;
+use axum::extract::State;
+use axum::http::{Response, StatusCode};
+use axum::Extension;
+use sqlx::PgPool;
+
+fn pdf_response(buffer: Vec<u8>, filename: String) -> Response<Body> {
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(
+            "Content-Disposition",
+            format!("attachment; filename={}", filename),
+        )
+        .header("Content-Type", "application/pdf")
+        .body(Body::from(buffer))
+        .unwrap()
+}
+
+pub async fn upload_report_in_pdf(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(storage): Extension<Storage>,
+    Query(q): Query<Q>,
+) -> Result<Response<Body>, AppError> {
+    // Метод получения печатного отчета по производству за период
+
+    let filename = format!(
+        "period_report_{}_{}.pdf",
+        q.date_one.format("%d.%m.%Y"),
+        q.date_two.format("%d.%m.%Y")
+    );
+    let buffer = serv::analitics_pdf(
+        State(pool),
+        Extension(current_user),
+        Extension(storage),
+        Query(q),
+    )
+    .await?;
+
+    Ok(pdf_response(buffer, filename))
+}
+
+pub async fn upload_shift_act(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(storage): Extension<Storage>,
+    Query(q): Query<ShiftQ>,
+) -> Result<Response<Body>, AppError> {
+    // Метод получения акта выпуска продукции за смену
+
+    let filename = format!("shift_act_{}_{}.pdf", q.shift_id, q.date.format("%d.%m.%Y"));
+    let buffer = serv::shift_act_pdf(
+        State(pool),
+        Extension(current_user),
+        Extension(storage),
+        Query(q),
+    )
+    .await?;
+
+    Ok(pdf_response(buffer, filename))
+}
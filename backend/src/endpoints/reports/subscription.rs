@@ -0,0 +1,99 @@
+use crate::services::reports::mailer::Mailer;
+use crate::services::reports::subscription::{self as serv, Delivery, Item, RequestBody, Q};
+use crate::services::Items;
+use crate::{AppError, CurrentUser};
+use axum::extract::{Path, Query};
+use axum::Extension;
+use axum::{extract::State, Json};
+use sqlx::PgPool;
+use std::collections::HashMap;
+
+pub async fn create_subscription(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(body): Json<RequestBody>,
+) -> Result<Json<HashMap<String, i64>>, AppError> {
+    // Метод создания подписки на отчет
+
+    let insert_id =
+        serv::create_subscription(State(pool), Extension(current_user), Json(body)).await?;
+
+    Ok(Json(HashMap::from([("id".to_string(), insert_id)])))
+}
+
+pub async fn edit_subscription(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+    Json(body): Json<RequestBody>,
+) -> Result<Json<HashMap<String, String>>, AppError> {
+    // Метод редактирования подписки на отчет
+
+    let _ =
+        serv::edit_subscription(State(pool), Extension(current_user), Path(id), Json(body)).await?;
+
+    Ok(Json(HashMap::from([(
+        "detail".to_string(),
+        "OK".to_string(),
+    )])))
+}
+
+pub async fn get_subscriptions(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Json<Items<Item>>, AppError> {
+    // Метод получения списка подписок на отчеты
+
+    let items = serv::get_subscriptions(State(pool), Extension(current_user), Query(q)).await?;
+
+    Ok(Json(items))
+}
+
+pub async fn delete_subscription(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<Json<HashMap<String, String>>, AppError> {
+    // Метод удаления подписки на отчет
+
+    serv::delete_subscription(State(pool), Extension(current_user), Path(id)).await?;
+
+    Ok(Json(HashMap::from([(
+        "detail".to_string(),
+        "OK".to_string(),
+    )])))
+}
+
+pub async fn get_deliveries(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+    Query(q): Query<Q>,
+) -> Result<Json<Items<Delivery>>, AppError> {
+    // Метод получения истории отправок подписки
+
+    let items =
+        serv::get_deliveries(State(pool), Extension(current_user), Path(id), Query(q)).await?;
+
+    Ok(Json(items))
+}
+
+pub async fn send_subscription(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(mailer): Extension<Mailer>,
+    Path(id): Path<i64>,
+) -> Result<Json<Delivery>, AppError> {
+    // Метод внеочередной отправки отчета по подписке
+
+    let delivery = serv::send_subscription(
+        State(pool),
+        Extension(current_user),
+        Extension(mailer),
+        Path(id),
+    )
+    .await?;
+
+    Ok(Json(delivery))
+}
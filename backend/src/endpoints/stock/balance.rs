@@ -0,0 +1,30 @@
+use crate::services::stock::balance::{self as serv, Item, QAsOf, Q};
+use crate::{AppError, CurrentUser};
+use axum::extract::Query;
+use axum::Extension;
+use axum::{extract::State, Json};
+use sqlx::PgPool;
+
+pub async fn get_stock(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Json<Vec<Item>>, AppError> {
+    // Метод получения текущих остатков
+
+    let items = serv::get_stock(State(pool), Extension(current_user), Query(q)).await?;
+
+    Ok(Json(items))
+}
+
+pub async fn get_stock_as_of(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<QAsOf>,
+) -> Result<Json<Vec<Item>>, AppError> {
+    // Метод получения остатков на дату
+
+    let items = serv::get_stock_as_of(State(pool), Extension(current_user), Query(q)).await?;
+
+    Ok(Json(items))
+}
@@ -0,0 +1,3 @@
+pub mod balance;
+pub mod movement;
+pub mod warehouse;
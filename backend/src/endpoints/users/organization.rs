@@ -1,7 +1,10 @@
-use crate::services::users::organization::{self as serv, Item, RequestBody, Q};
+use crate::services::attachments::storage::Storage;
+use crate::services::users::organization::{
+    self as serv, Item, RequestBody, RequestBodySettings, Q,
+};
 use crate::services::Items;
 use crate::{AppError, CurrentUser};
-use axum::extract::{Path, Query};
+use axum::extract::{Multipart, Path, Query};
 use axum::Extension;
 use axum::{extract::State, Json};
 use sqlx::PgPool;
@@ -75,3 +78,49 @@ pub async fn delete_organization(
         "OK".to_string(),
     )])))
 }
+
+pub async fn edit_organization_settings(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+    Json(body): Json<RequestBodySettings>,
+) -> Result<Json<HashMap<String, String>>, AppError> {
+    // Метод изменения настроек организации
+
+    let _ = serv::edit_organization_settings(
+        State(pool),
+        Extension(current_user),
+        Path(id),
+        Json(body),
+    )
+    .await?;
+
+    Ok(Json(HashMap::from([(
+        "detail".to_string(),
+        "OK".to_string(),
+    )])))
+}
+
+pub async fn upload_organization_logo(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(storage): Extension<Storage>,
+    Path(id): Path<i64>,
+    multipart: Multipart,
+) -> Result<Json<HashMap<String, String>>, AppError> {
+    // Метод загрузки логотипа организации
+
+    let _ = serv::upload_organization_logo(
+        State(pool),
+        Extension(current_user),
+        Extension(storage),
+        Path(id),
+        multipart,
+    )
+    .await?;
+
+    Ok(Json(HashMap::from([(
+        "detail".to_string(),
+        "OK".to_string(),
+    )])))
+}
@@ -0,0 +1 @@
+pub mod work_order;
@@ -1,32 +1,110 @@
 use axum::{
-    extract::{Request, State},
+    extract::{DefaultBodyLimit, Request, State},
     http::{self, StatusCode},
     middleware::{self, Next},
     response::Response,
     routing::{get, patch, post},
-    Router,
+    Extension, Router,
 };
 use backend::{
     endpoints::{
+        attachments::attachment::{
+            delete_attachment, download_attachment, download_attachment_thumbnail,
+            get_produced_good_attachments, get_product_attachments,
+            upload_produced_good_attachment, upload_product_attachment,
+        },
+        audit::log::get_audit_log,
+        dashboard::widgets::get_dashboard,
+        equipment::{
+            downtime::{create_downtime, delete_downtime, edit_downtime, get_downtimes},
+            machine::{create_machine, delete_machine, detail_machine, edit_machine, get_machines},
+            oee::get_oee,
+        },
+        export::table::{
+            export_analitics, export_measures, export_produced_goods, export_products, export_users,
+        },
         init::{authorization, logout},
+        payroll::{
+            earning::{get_earnings, get_my_earnings, upload_payroll_report},
+            piece_rate::{create_piece_rate, delete_piece_rate, edit_piece_rate, get_piece_rates},
+        },
+        periods::closed_period::{close_period, get_closed_periods, reopen_period},
+        plans::plan::{
+            create_plan, delete_plan, detail_plan, edit_plan, get_plan_fact, get_plans,
+            upload_plan_report_in_excel,
+        },
         produced_goods::{
-            analitic::{get_analitics, upload_report_in_excel},
+            adjustment::{get_adjustments, review_adjustment},
+            analitic::{get_analitics, get_category_analitics, upload_report_in_excel},
+            confirmation::{confirm_produced_goods, review_produced_good},
+            consumption::{get_consumption, upload_consumption_report_in_excel},
+            lot::get_lot,
+            pivot::{get_pivot, upload_pivot_report_in_excel},
             produced_good::{
                 add_adj_produced_goods, create_produced_good, delete_produced_good,
                 detail_produced_good, edit_produced_good, get_produced_goods,
             },
+            quality::{get_defect_pareto, get_quality},
         },
         rbs::{
+            adjustment_reason::{
+                create_adjustment_reason, delete_adjustment_reason, detail_adjustment_reason,
+                edit_adjustment_reason, get_adjustment_reasons,
+            },
+            bom::{create_bom_item, delete_bom_item, edit_bom_item, get_bom},
+            custom_field::{
+                create_custom_field, delete_custom_field, detail_custom_field, edit_custom_field,
+                get_custom_fields,
+            },
+            defect_reason::{
+                create_defect_reason, delete_defect_reason, detail_defect_reason,
+                edit_defect_reason, get_defect_reasons,
+            },
+            downtime_reason::{
+                create_downtime_reason, delete_downtime_reason, detail_downtime_reason,
+                edit_downtime_reason, get_downtime_reasons,
+            },
             measure::{create_measure, delete_measure, detail_measure, edit_measure, get_measures},
             product::{create_product, delete_product, detail_product, edit_product, get_products},
+            product_category::{
+                create_product_category, delete_product_category, detail_product_category,
+                edit_product_category, get_product_categories,
+            },
+            product_price::{
+                create_product_price, delete_product_price, edit_product_price, get_product_prices,
+            },
+            shift::{create_shift, delete_shift, detail_shift, edit_shift, get_shifts},
+        },
+        reports::{
+            report::{upload_report_in_pdf, upload_shift_act},
+            subscription::{
+                create_subscription, delete_subscription, edit_subscription, get_deliveries,
+                get_subscriptions, send_subscription,
+            },
+        },
+        stock::{
+            balance::{get_stock, get_stock_as_of},
+            movement::{create_movement, get_movements},
+            warehouse::{
+                create_warehouse, delete_warehouse, detail_warehouse, edit_warehouse,
+                get_warehouses,
+            },
         },
         users::{
             organization::{
                 create_organization, delete_organization, detail_organization, edit_organization,
-                get_organizations,
+                edit_organization_settings, get_organizations, upload_organization_logo,
             },
             user::{create_user, current_user, detail_user, edit_passwd, edit_user, get_users},
         },
+        work_orders::work_order::{
+            change_status_work_order, create_work_order, delete_work_order, detail_work_order,
+            edit_work_order, get_work_orders,
+        },
+    },
+    services::{
+        attachments::{attachment::MAX_FILE_SIZE, storage::Storage},
+        reports::{mailer::Mailer, scheduler},
     },
     CurrentUser,
 };
@@ -59,6 +137,12 @@ async fn main() {
         .await
         .expect("can't connect to database");
 
+    let storage = Storage::from_env().expect("can't init attachments storage");
+    let mailer = Mailer::from_env().expect("can't init mailer");
+
+    // Отправка отчетов по расписанию подписок
+    tokio::spawn(scheduler::run(pool.clone(), mailer.clone()));
+
     let api = Router::new()
         // Check Auth
         .route("/logout", post(logout))
@@ -72,6 +156,15 @@ async fn main() {
                 .patch(edit_organization)
                 .delete(delete_organization),
         )
+        .route(
+            "/organizations/:id/settings",
+            patch(edit_organization_settings),
+        )
+        .route(
+            "/organizations/:id/logo",
+            post(upload_organization_logo)
+                .layer(DefaultBodyLimit::max(MAX_FILE_SIZE + 1024 * 1024)),
+        )
         .route("/current", get(current_user))
         .route("/users", get(get_users).post(create_user))
         .route("/users/:id", get(detail_user).patch(edit_user))
@@ -83,6 +176,65 @@ async fn main() {
                 .patch(edit_measure)
                 .delete(delete_measure),
         )
+        .route(
+            "/defect-reasons",
+            get(get_defect_reasons).post(create_defect_reason),
+        )
+        .route(
+            "/defect-reasons/:id",
+            get(detail_defect_reason)
+                .patch(edit_defect_reason)
+                .delete(delete_defect_reason),
+        )
+        .route(
+            "/downtime-reasons",
+            get(get_downtime_reasons).post(create_downtime_reason),
+        )
+        .route(
+            "/downtime-reasons/:id",
+            get(detail_downtime_reason)
+                .patch(edit_downtime_reason)
+                .delete(delete_downtime_reason),
+        )
+        .route("/machines", get(get_machines).post(create_machine))
+        .route(
+            "/machines/:id",
+            get(detail_machine)
+                .patch(edit_machine)
+                .delete(delete_machine),
+        )
+        .route("/downtimes", get(get_downtimes).post(create_downtime))
+        .route(
+            "/downtimes/:id",
+            patch(edit_downtime).delete(delete_downtime),
+        )
+        .route("/oee", get(get_oee))
+        .route("/dashboard", get(get_dashboard))
+        .route(
+            "/adjustment-reasons",
+            get(get_adjustment_reasons).post(create_adjustment_reason),
+        )
+        .route(
+            "/adjustment-reasons/:id",
+            get(detail_adjustment_reason)
+                .patch(edit_adjustment_reason)
+                .delete(delete_adjustment_reason),
+        )
+        .route(
+            "/custom-fields",
+            get(get_custom_fields).post(create_custom_field),
+        )
+        .route(
+            "/custom-fields/:id",
+            get(detail_custom_field)
+                .patch(edit_custom_field)
+                .delete(delete_custom_field),
+        )
+        .route("/shifts", get(get_shifts).post(create_shift))
+        .route(
+            "/shifts/:id",
+            get(detail_shift).patch(edit_shift).delete(delete_shift),
+        )
         .route("/products", get(get_products).post(create_product))
         .route(
             "/products/:id",
@@ -90,6 +242,35 @@ async fn main() {
                 .patch(edit_product)
                 .delete(delete_product),
         )
+        .route(
+            "/product-categories",
+            get(get_product_categories).post(create_product_category),
+        )
+        .route(
+            "/product-categories/:id",
+            get(detail_product_category)
+                .patch(edit_product_category)
+                .delete(delete_product_category),
+        )
+        .route(
+            "/products/:id/attachments",
+            get(get_product_attachments)
+                .post(upload_product_attachment)
+                .layer(DefaultBodyLimit::max(MAX_FILE_SIZE + 1024 * 1024)),
+        )
+        .route("/products/:id/bom", get(get_bom).post(create_bom_item))
+        .route(
+            "/bom-items/:id",
+            patch(edit_bom_item).delete(delete_bom_item),
+        )
+        .route(
+            "/products/:id/prices",
+            get(get_product_prices).post(create_product_price),
+        )
+        .route(
+            "/product-prices/:id",
+            patch(edit_product_price).delete(delete_product_price),
+        )
         .route(
             "/produced-goods",
             get(get_produced_goods).post(create_produced_good),
@@ -100,9 +281,94 @@ async fn main() {
                 .patch(edit_produced_good)
                 .delete(delete_produced_good),
         )
+        .route("/produced-goods/confirm", post(confirm_produced_goods))
+        .route("/produced-goods/:id/status", patch(review_produced_good))
         .route("/produced-goods/:id/adj", post(add_adj_produced_goods))
+        .route(
+            "/produced-goods/:id/attachments",
+            get(get_produced_good_attachments)
+                .post(upload_produced_good_attachment)
+                .layer(DefaultBodyLimit::max(MAX_FILE_SIZE + 1024 * 1024)),
+        )
+        .route(
+            "/attachments/:id",
+            get(download_attachment).delete(delete_attachment),
+        )
+        .route(
+            "/attachments/:id/thumbnail",
+            get(download_attachment_thumbnail),
+        )
+        .route("/adjustments", get(get_adjustments))
+        .route("/adjustments/:id/status", patch(review_adjustment))
+        .route("/lots/:lot", get(get_lot))
         .route("/analitics", get(get_analitics))
+        .route("/analitics/categories", get(get_category_analitics))
+        .route("/analitics/pivot", get(get_pivot))
         .route("/upload-report", post(upload_report_in_excel))
+        .route("/upload-report-pdf", post(upload_report_in_pdf))
+        .route("/upload-pivot-report", post(upload_pivot_report_in_excel))
+        .route("/upload-shift-act", post(upload_shift_act))
+        .route(
+            "/report-subscriptions",
+            get(get_subscriptions).post(create_subscription),
+        )
+        .route(
+            "/report-subscriptions/:id",
+            patch(edit_subscription).delete(delete_subscription),
+        )
+        .route("/report-subscriptions/:id/deliveries", get(get_deliveries))
+        .route("/report-subscriptions/:id/send", post(send_subscription))
+        .route("/quality", get(get_quality))
+        .route("/quality/pareto", get(get_defect_pareto))
+        .route("/consumption", get(get_consumption))
+        .route(
+            "/upload-consumption-report",
+            post(upload_consumption_report_in_excel),
+        )
+        .route(
+            "/closed-periods",
+            get(get_closed_periods).post(close_period),
+        )
+        .route("/closed-periods/:id/reopen", post(reopen_period))
+        .route("/audit-log", get(get_audit_log))
+        .route("/piece-rates", get(get_piece_rates).post(create_piece_rate))
+        .route(
+            "/piece-rates/:id",
+            patch(edit_piece_rate).delete(delete_piece_rate),
+        )
+        .route("/payroll", get(get_earnings))
+        .route("/payroll/my", get(get_my_earnings))
+        .route("/upload-payroll-report", post(upload_payroll_report))
+        .route("/plans", get(get_plans).post(create_plan))
+        .route("/plans/fact", get(get_plan_fact))
+        .route(
+            "/plans/:id",
+            get(detail_plan).patch(edit_plan).delete(delete_plan),
+        )
+        .route("/upload-plan-report", post(upload_plan_report_in_excel))
+        .route("/work-orders", get(get_work_orders).post(create_work_order))
+        .route(
+            "/work-orders/:id",
+            get(detail_work_order)
+                .patch(edit_work_order)
+                .delete(delete_work_order),
+        )
+        .route("/work-orders/:id/status", patch(change_status_work_order))
+        .route("/warehouses", get(get_warehouses).post(create_warehouse))
+        .route(
+            "/warehouses/:id",
+            get(detail_warehouse)
+                .patch(edit_warehouse)
+                .delete(delete_warehouse),
+        )
+        .route("/stock-movements", get(get_movements).post(create_movement))
+        .route("/stock", get(get_stock))
+        .route("/stock/as-of", get(get_stock_as_of))
+        .route("/export/produced-goods", get(export_produced_goods))
+        .route("/export/products", get(export_products))
+        .route("/export/measure-units", get(export_measures))
+        .route("/export/users", get(export_users))
+        .route("/export/analitics", get(export_analitics))
         .route_layer(middleware::from_fn_with_state(pool.clone(), authenticate))
         // Not Check Auth
         .route("/auth", post(authorization));
@@ -118,6 +384,8 @@ async fn main() {
                 .allow_methods(Any),
         )
         .layer(CompressionLayer::new())
+        .layer(Extension(storage))
+        .layer(Extension(mailer))
         .with_state(pool);
 
     // run it with hyper
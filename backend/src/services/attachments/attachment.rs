@@ -0,0 +1,518 @@
+use axum::{
+    body::Bytes,
+    extract::{Multipart, Path, State},
+    http::StatusCode,
+    Extension,
+};
+
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    check_access,
+    services::{periods::closed_period::check_open_period, Select},
+    AppError, CurrentUser,
+};
+
+use super::storage::Storage;
+
+// Максимальный размер одного файла
+pub const MAX_FILE_SIZE: usize = 10 * 1024 * 1024;
+// Превью вписывается в квадрат со стороной THUMBNAIL_SIZE
+const THUMBNAIL_SIZE: u32 = 320;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Entity {
+    Product,
+    ProducedGood,
+}
+
+impl Entity {
+    fn ids(self, id: i64) -> (Option<i64>, Option<i64>) {
+        // (product_id, produced_good_id) для записи вложения
+        match self {
+            Self::Product => (Some(id), None),
+            Self::ProducedGood => (None, Some(id)),
+        }
+    }
+}
+
+pub fn detect_content_type(data: &[u8]) -> Option<&'static str> {
+    // Тип определяется по сигнатуре файла, заявленному клиентом типу не доверяем
+
+    match data {
+        [0xFF, 0xD8, 0xFF, ..] => Some("image/jpeg"),
+        [0x89, b'P', b'N', b'G', ..] => Some("image/png"),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some("image/webp"),
+        [b'%', b'P', b'D', b'F', b'-', ..] => Some("application/pdf"),
+        _ => None,
+    }
+}
+
+pub fn extension(content_type: &str) -> &'static str {
+    match content_type {
+        "image/jpeg" => "jpg",
+        "image/png" => "png",
+        "image/webp" => "webp",
+        _ => "pdf",
+    }
+}
+
+fn make_thumbnail(data: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
+    // Уменьшенная копия изображения в JPEG
+
+    let img = image::load_from_memory(data)?;
+    let thumbnail =
+        image::DynamicImage::from(img.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE).to_rgb8());
+
+    let mut buffer = std::io::Cursor::new(Vec::new());
+    thumbnail.write_to(&mut buffer, image::ImageOutputFormat::Jpeg(80))?;
+
+    Ok(buffer.into_inner())
+}
+
+async fn check_target(
+    pool: &PgPool,
+    current_user: &CurrentUser,
+    entity: Entity,
+    id: i64,
+    write: bool,
+) -> Result<i64, AppError> {
+    // Проверка доступа к записи, к которой относятся вложения. Возвращает организацию записи.
+    // Изображения продукта видят все пользователи организации, меняют Директор и администраторы.
+    // Вложения записи производства доступны ее автору, Директору и администраторам.
+
+    let row: Option<(Option<i64>, Option<i64>)> = match entity {
+        Entity::Product => {
+            sqlx::query_as(
+                "SELECT organization_id, NULL::bigint
+                FROM products
+                WHERE id = $1
+                AND CASE
+                    WHEN $2::bigint IS NOT NULL AND $3 not in ('Admin', 'Developer') THEN
+                        organization_id = $2
+                    ELSE TRUE
+                END",
+            )
+            .bind(id)
+            .bind(current_user.organization_id)
+            .bind(current_user.role.to_string())
+            .fetch_optional(pool)
+            .await?
+        }
+        Entity::ProducedGood => {
+            sqlx::query_as(
+                "SELECT organization_id, user_id
+                FROM produced_goods
+                WHERE id = $1
+                AND CASE
+                    WHEN $2::bigint IS NOT NULL AND $3 not in ('Admin', 'Developer') THEN
+                        organization_id = $2
+                    ELSE TRUE
+                END",
+            )
+            .bind(id)
+            .bind(current_user.organization_id)
+            .bind(current_user.role.to_string())
+            .fetch_optional(pool)
+            .await?
+        }
+    };
+
+    let Some((organization_id, author_id)) = row else {
+        return Err(AppError(
+            StatusCode::NOT_FOUND,
+            anyhow::anyhow!("Такой записи не существует"),
+        ));
+    };
+
+    let allowed = match entity {
+        Entity::Product => !write || check_access(current_user.role),
+        Entity::ProducedGood => {
+            check_access(current_user.role) || author_id == Some(current_user.id)
+        }
+    };
+
+    if !allowed {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    organization_id.ok_or(AppError(
+        StatusCode::BAD_REQUEST,
+        anyhow::anyhow!("Запись не относится к организации!"),
+    ))
+}
+
+pub async fn upload_attachment(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(storage): Extension<Storage>,
+    entity: Entity,
+    Path(id): Path<i64>,
+    mut multipart: Multipart,
+) -> Result<i64, AppError> {
+    // Бизнес логика загрузки файла. Ожидается поле формы "file".
+    // К продукту прикрепляются только изображения, к записи производства также PDF.
+
+    let organization_id = check_target(&pool, &current_user, entity, id, true).await?;
+
+    let mut file: Option<(String, Bytes)> = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError(e.status(), e.into()))?
+    {
+        if field.name() == Some("file") {
+            let file_name = field.file_name().unwrap_or("file").to_string();
+            let data = field
+                .bytes()
+                .await
+                .map_err(|e| AppError(e.status(), e.into()))?;
+
+            file = Some((file_name, data));
+        }
+    }
+
+    let Some((file_name, data)) = file else {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Файл не передан!"),
+        ));
+    };
+
+    if data.len() > MAX_FILE_SIZE {
+        return Err(AppError(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            anyhow::anyhow!("Размер файла превышает {} МБ!", MAX_FILE_SIZE / 1024 / 1024),
+        ));
+    }
+
+    let content_type = match (entity, detect_content_type(&data)) {
+        (Entity::ProducedGood, Some(content_type)) => content_type,
+        (Entity::Product, Some(content_type)) if content_type.starts_with("image/") => content_type,
+        _ => {
+            return Err(AppError(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                anyhow::anyhow!("Недопустимый тип файла!"),
+            ))
+        }
+    };
+
+    let thumbnail = if content_type.starts_with("image/") {
+        let cloned_data = data.clone();
+        let thumbnail = tokio::task::spawn_blocking(move || make_thumbnail(&cloned_data))
+            .await?
+            .map_err(|_| {
+                AppError(
+                    StatusCode::BAD_REQUEST,
+                    anyhow::anyhow!("Не удалось прочитать изображение!"),
+                )
+            })?;
+
+        Some(thumbnail)
+    } else {
+        None
+    };
+
+    let mut tx = pool.begin().await?;
+
+    if entity == Entity::ProducedGood {
+        check_open_period(&mut tx, &current_user, id, "attachment_upload").await?;
+    }
+
+    let name = Uuid::new_v4();
+    let storage_key = format!("{}/{}.{}", organization_id, name, extension(content_type));
+    let thumbnail_key = thumbnail
+        .as_ref()
+        .map(|_| format!("{}/{}_thumb.jpg", organization_id, name));
+
+    storage.put(&storage_key, data.clone()).await?;
+    if let (Some(key), Some(thumbnail)) = (&thumbnail_key, thumbnail) {
+        storage.put(key, Bytes::from(thumbnail)).await?;
+    }
+
+    let (product_id, produced_good_id) = entity.ids(id);
+    let row: (i64,) = sqlx::query_as(
+        "INSERT
+        INTO attachments (
+          organization_id, product_id, produced_good_id, file_name,
+          content_type, size, storage_key, thumbnail_key, user_id
+        ) VALUES
+        ($1, $2, $3, $4, $5, $6, $7, $8, $9) RETURNING id",
+    )
+    .bind(organization_id)
+    .bind(product_id)
+    .bind(produced_good_id)
+    .bind(file_name.chars().take(255).collect::<String>())
+    .bind(content_type)
+    .bind(data.len() as i64)
+    .bind(&storage_key)
+    .bind(&thumbnail_key)
+    .bind(current_user.id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(row.0)
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Item {
+    pub id: i64,
+    pub file_name: String,
+    pub content_type: String,
+    pub size: i64,
+    pub has_thumbnail: bool,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+
+    user: Select,
+}
+
+pub async fn get_attachments(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    entity: Entity,
+    Path(id): Path<i64>,
+) -> Result<Vec<Item>, AppError> {
+    // Бизнес логика получения списка вложений записи
+
+    check_target(&pool, &current_user, entity, id, false).await?;
+
+    let (product_id, produced_good_id) = entity.ids(id);
+    let rows = sqlx::query!(
+        "SELECT
+            a.id,
+            a.file_name,
+            a.content_type,
+            a.size,
+            a.thumbnail_key IS NOT NULL AS \"has_thumbnail!\",
+            a.created_at,
+            JSONB_BUILD_OBJECT(
+                'id', u.id,
+                'name', u.fio
+            ) AS author
+        FROM attachments AS a
+        INNER JOIN users AS u ON u.id = a.user_id
+        WHERE
+            ($1::bigint IS NULL OR a.product_id = $1)
+            AND ($2::bigint IS NULL OR a.produced_good_id = $2)
+        ORDER BY a.id;",
+        product_id,
+        produced_good_id,
+    )
+    .map(|row| Item {
+        id: row.id,
+        file_name: row.file_name,
+        content_type: row.content_type,
+        size: row.size,
+        has_thumbnail: row.has_thumbnail,
+        created_at: row.created_at,
+        user: row.author.into(),
+    })
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(rows)
+}
+
+struct Row {
+    entity: Entity,
+    entity_id: i64,
+    storage_key: String,
+    thumbnail_key: Option<String>,
+    file_name: String,
+    content_type: String,
+}
+
+async fn get_row(pool: &PgPool, id: i64) -> Result<Row, AppError> {
+    // Получение вложения и записи, к которой оно относится
+
+    let row = sqlx::query!(
+        "SELECT product_id, produced_good_id, storage_key, thumbnail_key, file_name, content_type
+            FROM attachments
+            WHERE id = $1",
+        id,
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    let target = row
+        .as_ref()
+        .and_then(|row| match (row.product_id, row.produced_good_id) {
+            (Some(product_id), _) => Some((Entity::Product, product_id)),
+            (_, Some(produced_good_id)) => Some((Entity::ProducedGood, produced_good_id)),
+            _ => None,
+        });
+
+    match (row, target) {
+        (Some(row), Some((entity, entity_id))) => Ok(Row {
+            entity,
+            entity_id,
+            storage_key: row.storage_key,
+            thumbnail_key: row.thumbnail_key,
+            file_name: row.file_name,
+            content_type: row.content_type,
+        }),
+        _ => Err(AppError(
+            StatusCode::NOT_FOUND,
+            anyhow::anyhow!("Такой записи не существует"),
+        )),
+    }
+}
+
+pub struct File {
+    pub file_name: String,
+    pub content_type: String,
+    pub data: Bytes,
+}
+
+pub async fn download_attachment(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(storage): Extension<Storage>,
+    Path(id): Path<i64>,
+    thumbnail: bool,
+) -> Result<File, AppError> {
+    // Бизнес логика скачивания файла или его превью
+
+    let row = get_row(&pool, id).await?;
+
+    check_target(&pool, &current_user, row.entity, row.entity_id, false).await?;
+
+    let file = if thumbnail {
+        let Some(key) = row.thumbnail_key else {
+            return Err(AppError(
+                StatusCode::NOT_FOUND,
+                anyhow::anyhow!("У файла нет превью"),
+            ));
+        };
+
+        File {
+            file_name: format!("thumb_{}.jpg", id),
+            content_type: "image/jpeg".to_string(),
+            data: storage.get(&key).await?,
+        }
+    } else {
+        File {
+            data: storage.get(&row.storage_key).await?,
+            file_name: row.file_name,
+            content_type: row.content_type,
+        }
+    };
+
+    Ok(file)
+}
+
+pub async fn delete_attachment(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(storage): Extension<Storage>,
+    Path(id): Path<i64>,
+) -> Result<(), AppError> {
+    // Бизнес логика удаления вложения вместе с файлами в хранилище
+
+    let row = get_row(&pool, id).await?;
+
+    check_target(&pool, &current_user, row.entity, row.entity_id, true).await?;
+
+    let mut tx = pool.begin().await?;
+
+    if row.entity == Entity::ProducedGood {
+        check_open_period(&mut tx, &current_user, row.entity_id, "attachment_delete").await?;
+    }
+
+    let _ = sqlx::query("DELETE FROM attachments WHERE id = $1")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    remove_files(
+        &storage,
+        [Some(row.storage_key), row.thumbnail_key]
+            .into_iter()
+            .flatten()
+            .collect(),
+    )
+    .await;
+
+    Ok(())
+}
+
+pub async fn take_storage_keys(
+    tx: &mut Transaction<'_, Postgres>,
+    entity: Entity,
+    id: i64,
+) -> Result<Vec<String>, anyhow::Error> {
+    // Ключи файлов записи. Строки вложений удаляются каскадно вместе с записью,
+    // а файлы нужно удалить из хранилища после фиксации транзакции.
+
+    let (product_id, produced_good_id) = entity.ids(id);
+    let keys: Vec<String> = sqlx::query_scalar(
+        "SELECT key
+        FROM attachments,
+        LATERAL (VALUES (storage_key), (thumbnail_key)) AS k(key)
+        WHERE key IS NOT NULL
+        AND ($1::bigint IS NULL OR product_id = $1)
+        AND ($2::bigint IS NULL OR produced_good_id = $2)",
+    )
+    .bind(product_id)
+    .bind(produced_good_id)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    Ok(keys)
+}
+
+pub async fn remove_files(storage: &Storage, keys: Vec<String>) {
+    // Ошибка удаления файла не отменяет удаление записи, файл останется в хранилище
+
+    for key in keys {
+        if let Err(e) = storage.delete(&key).await {
+            tracing::warn!("can't delete attachment file {}: {}", key, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_by_signature() {
+        assert_eq!(
+            detect_content_type(&[0xFF, 0xD8, 0xFF, 0xE0, 0x00]),
+            Some("image/jpeg")
+        );
+        assert_eq!(detect_content_type(b"\x89PNG\r\n\x1a\n"), Some("image/png"));
+        assert_eq!(
+            detect_content_type(b"RIFF\x24\x00\x00\x00WEBPVP8 "),
+            Some("image/webp")
+        );
+        assert_eq!(detect_content_type(b"%PDF-1.7\n"), Some("application/pdf"));
+    }
+
+    #[test]
+    fn rejects_unknown_and_truncated() {
+        assert_eq!(detect_content_type(b""), None);
+        assert_eq!(detect_content_type(&[0xFF, 0xD8]), None);
+        assert_eq!(detect_content_type(b"RIFF\x24\x00\x00\x00WAVE"), None);
+        assert_eq!(detect_content_type(b"%PDF"), None);
+        assert_eq!(detect_content_type(b"<svg xmlns=\"\"/>"), None);
+        assert_eq!(detect_content_type(b"GIF89a"), None);
+    }
+
+    #[test]
+    fn extension_by_type() {
+        assert_eq!(extension("image/jpeg"), "jpg");
+        assert_eq!(extension("image/png"), "png");
+        assert_eq!(extension("image/webp"), "webp");
+        assert_eq!(extension("application/pdf"), "pdf");
+    }
+}
@@ -0,0 +1,2 @@
+pub mod attachment;
+pub mod storage;
@@ -0,0 +1,60 @@
+use std::sync::Arc;
+
+use axum::body::Bytes;
+use object_store::{aws::AmazonS3Builder, local::LocalFileSystem, path::Path, ObjectStore};
+
+// Хранилище файлов вложений.
+// Тип задается переменной ATTACHMENTS_STORAGE:
+//   local (по умолчанию) - каталог ATTACHMENTS_DIR (./media);
+//   s3 - бакет S3_BUCKET, доступ и адрес берутся из AWS_ACCESS_KEY_ID,
+//        AWS_SECRET_ACCESS_KEY, AWS_DEFAULT_REGION, AWS_ENDPOINT и AWS_ALLOW_HTTP
+//        (для локального MinIO: AWS_ENDPOINT=http://127.0.0.1:9000, AWS_ALLOW_HTTP=true).
+#[derive(Clone)]
+pub struct Storage(Arc<dyn ObjectStore>);
+
+impl Storage {
+    pub fn from_env() -> Result<Self, anyhow::Error> {
+        let kind = std::env::var("ATTACHMENTS_STORAGE").unwrap_or_else(|_| "local".to_string());
+
+        let store: Arc<dyn ObjectStore> = match kind.as_str() {
+            "s3" => {
+                let bucket = std::env::var("S3_BUCKET")
+                    .map_err(|_| anyhow::anyhow!("S3_BUCKET is not set"))?;
+
+                Arc::new(
+                    AmazonS3Builder::from_env()
+                        .with_bucket_name(bucket)
+                        .build()?,
+                )
+            }
+            "local" => {
+                let dir =
+                    std::env::var("ATTACHMENTS_DIR").unwrap_or_else(|_| "./media".to_string());
+                std::fs::create_dir_all(&dir)?;
+
+                Arc::new(LocalFileSystem::new_with_prefix(dir)?)
+            }
+            _ => anyhow::bail!("unknown ATTACHMENTS_STORAGE: {}", kind),
+        };
+
+        Ok(Self(store))
+    }
+
+    pub async fn put(&self, key: &str, data: Bytes) -> Result<(), anyhow::Error> {
+        self.0.put(&Path::from(key), data).await?;
+
+        Ok(())
+    }
+
+    pub async fn get(&self, key: &str) -> Result<Bytes, anyhow::Error> {
+        let data = self.0.get(&Path::from(key)).await?.bytes().await?;
+
+        Ok(data)
+    }
+
+    pub async fn delete(&self, key: &str) -> Result<(), anyhow::Error> {
+        self.0.delete(&Path::from(key)).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Extension,
+};
+
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Transaction};
+
+use crate::{
+    check_access,
+    services::{Items, Select},
+    AppError, CurrentUser,
+};
+
+pub async fn write_audit(
+    tx: &mut Transaction<'_, Postgres>,
+    user_id: i64,
+    organization_id: Option<i64>,
+    action: &str,
+    entity: &str,
+    entity_id: Option<i64>,
+    details: serde_json::Value,
+) -> Result<(), anyhow::Error> {
+    // Запись действия в журнал аудита в рамках транзакции изменения
+
+    let _ = sqlx::query(
+        "INSERT INTO
+          audit_log (organization_id, user_id, action, entity, entity_id, details)
+        VALUES
+          ($1, $2, $3, $4, $5, $6)",
+    )
+    .bind(organization_id)
+    .bind(user_id)
+    .bind(action)
+    .bind(entity)
+    .bind(entity_id)
+    .bind(details)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Q {
+    #[serde(default = "page")]
+    pub page: i64,
+    #[serde(default = "per_page")]
+    pub per_page: i64,
+    pub entity: Option<String>,
+    pub entity_id: Option<i64>,
+}
+
+fn per_page() -> i64 {
+    15
+}
+
+fn page() -> i64 {
+    1
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Item {
+    pub id: i64,
+    pub action: String,
+    pub entity: String,
+    pub entity_id: Option<i64>,
+    pub details: serde_json::Value,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+
+    user: Select,
+    organization: Select,
+}
+
+pub async fn get_audit_log(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Items<Item>, AppError> {
+    // Бизнес логика получения журнала аудита
+
+    if !check_access(current_user.role) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    let rows = sqlx::query!(
+        "SELECT
+            al.id,
+            al.action,
+            al.entity,
+            al.entity_id,
+            al.details,
+            al.created_at,
+            JSONB_BUILD_OBJECT(
+                'id', u.id,
+                'name', u.fio
+            ) AS author,
+            JSONB_BUILD_OBJECT(
+                'id', o.id,
+                'name', o.name
+            ) AS organization
+        FROM audit_log AS al
+        INNER JOIN users AS u ON u.id = al.user_id
+        LEFT JOIN organizations AS o ON o.id = al.organization_id
+        WHERE
+            CASE
+                WHEN $1::bigint IS NOT NULL AND $2 not in ('Admin', 'Developer') THEN
+                    al.organization_id = $1
+                ELSE TRUE
+            END
+            AND ($3::text IS NULL OR al.entity = $3)
+            AND ($4::bigint IS NULL OR al.entity_id = $4)
+        ORDER BY al.id DESC
+        OFFSET $5 LIMIT $6;",
+        current_user.organization_id,
+        current_user.role.to_string(),
+        q.entity,
+        q.entity_id,
+        (q.page - 1) * q.per_page,
+        q.per_page,
+    )
+    .map(|row| Item {
+        id: row.id,
+        action: row.action,
+        entity: row.entity,
+        entity_id: row.entity_id,
+        details: row.details,
+        created_at: row.created_at,
+        user: row.author.into(),
+        organization: row.organization.into(),
+    })
+    .fetch_all(&pool)
+    .await?;
+
+    // Подсчет данных для пагинации
+    let cnt: i64 = sqlx::query_scalar(
+        "SELECT COUNT(id) FROM audit_log AS al WHERE CASE
+        WHEN $1::bigint IS NOT NULL AND $2 not in ('Admin', 'Developer') THEN
+            al.organization_id = $1
+        ELSE TRUE
+    END
+    AND ($3::text IS NULL OR al.entity = $3)
+    AND ($4::bigint IS NULL OR al.entity_id = $4)",
+    )
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .bind(&q.entity)
+    .bind(q.entity_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap_or(0);
+
+    let cnt = (cnt as f64 / q.per_page as f64).ceil() as i64;
+
+    Ok(Items { items: rows, cnt })
+}
@@ -0,0 +1,395 @@
+use axum::{
+    extract::{Query, State},
+    Extension,
+};
+
+use chrono::Datelike;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{
+    check_access,
+    services::{produced_goods::analitic::time_zone, Select},
+    AppError, CurrentUser,
+};
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Q {
+    // Часовой пояс, в котором считаются сегодняшний день и неделя
+    pub tz: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Point {
+    pub date: chrono::NaiveDate,
+    pub cnt: i64,
+}
+
+// Выпуск в разных единицах измерения не складывается, показатели считаются по каждой
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Output {
+    pub measure: String,
+    pub today: i64,
+    pub yesterday: i64,
+    // Неделя с понедельника по сегодня и те же дни прошлой недели
+    pub week: i64,
+    pub previous_week: i64,
+    // Выпуск по дням за последние две недели
+    pub daily: Vec<Point>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Top {
+    pub item: Select,
+    pub measure: String,
+    pub cnt: i64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Attention {
+    // Записи, ожидающие подтверждения Директором
+    pub submitted: i64,
+    pub rejected: i64,
+    // Корректировки, ожидающие утверждения
+    pub pending_adjustments: i64,
+    // Простои без окончания
+    pub open_downtimes: i64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Dashboard {
+    pub output: Vec<Output>,
+    pub top_products: Vec<Top>,
+    pub top_workers: Vec<Top>,
+    pub attention: Attention,
+    // Одобренные корректировки за неделю: количество и объем по модулю
+    pub adjustments_cnt: i64,
+    pub adjustments_volume: i64,
+}
+
+#[derive(sqlx::FromRow)]
+struct DailyRow {
+    day: chrono::NaiveDate,
+    measure: String,
+    cnt: i64,
+}
+
+#[derive(sqlx::FromRow)]
+struct LeaderRow {
+    kind: String,
+    id: i64,
+    name: String,
+    measure: String,
+    cnt: i64,
+}
+
+#[derive(sqlx::FromRow)]
+struct Counters {
+    submitted: i64,
+    rejected: i64,
+    pending_adjustments: i64,
+    open_downtimes: i64,
+    adjustments_cnt: i64,
+    adjustments_volume: i64,
+}
+
+pub async fn get_dashboard(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Dashboard, AppError> {
+    // Бизнес логика сводки на главной странице.
+    // Директор видит свою организацию, администратор - все организации,
+    // работник - только свой выпуск.
+
+    let tz = time_zone(&pool, &q.tz).await?;
+    let user_id = if check_access(current_user.role) {
+        None
+    } else {
+        Some(current_user.id)
+    };
+
+    let today: chrono::NaiveDate = sqlx::query_scalar("SELECT (NOW() AT TIME ZONE $1)::date")
+        .bind(&tz)
+        .fetch_one(&pool)
+        .await?;
+
+    // По каждой единице измерения, встречавшейся за две недели, выводятся все дни
+    let daily: Vec<DailyRow> = sqlx::query_as(
+        "WITH base AS (
+          SELECT
+            (pg.created_at AT TIME ZONE $3)::date AS day,
+            mu.name AS measure,
+            pg.cnt + COALESCE(pa.adjustment_cnt, 0) AS qty
+          FROM
+            produced_goods AS pg
+            JOIN products AS p ON p.id = pg.product_id
+            JOIN measure_units AS mu ON mu.id = p.measure_unit_id
+            LEFT JOIN (
+              SELECT
+                produced_good_id,
+                SUM(cnt::bigint) AS adjustment_cnt
+              FROM
+                produced_good_adjustments
+              WHERE
+                status = 'Approved'
+              GROUP BY
+                produced_good_id
+            ) pa ON pa.produced_good_id = pg.id
+          WHERE (pg.created_at AT TIME ZONE $3)::date >= (NOW() AT TIME ZONE $3)::date - 13
+          AND pg.status <> 'Rejected'
+          AND CASE
+              WHEN $2 not in ('Admin', 'Developer') THEN pg.organization_id = $1::bigint
+              ELSE TRUE
+            END
+          AND ($4::bigint IS NULL OR pg.user_id = $4)
+        ),
+        measures AS (
+          SELECT DISTINCT measure FROM base
+        )
+        SELECT
+          d::date AS day,
+          m.measure,
+          COALESCE(SUM(b.qty), 0)::bigint AS cnt
+        FROM
+          GENERATE_SERIES(
+            (NOW() AT TIME ZONE $3)::date - 13,
+            (NOW() AT TIME ZONE $3)::date,
+            INTERVAL '1 day'
+          ) AS d
+          CROSS JOIN measures AS m
+          LEFT JOIN base AS b ON b.day = d::date AND b.measure = m.measure
+        GROUP BY
+          d,
+          m.measure
+        ORDER BY
+          m.measure,
+          d",
+    )
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .bind(&tz)
+    .bind(user_id)
+    .fetch_all(&pool)
+    .await?;
+
+    let yesterday = today - chrono::Duration::days(1);
+    let week_start = today - chrono::Duration::days(today.weekday().num_days_from_monday() as i64);
+    let previous_week = (
+        week_start - chrono::Duration::days(7),
+        today - chrono::Duration::days(7),
+    );
+
+    let mut output: Vec<Output> = Vec::new();
+    for row in &daily {
+        let index = match output.iter().position(|o| o.measure == row.measure) {
+            Some(index) => index,
+            None => {
+                output.push(Output {
+                    measure: row.measure.clone(),
+                    today: 0,
+                    yesterday: 0,
+                    week: 0,
+                    previous_week: 0,
+                    daily: Vec::new(),
+                });
+                output.len() - 1
+            }
+        };
+        let o = &mut output[index];
+
+        if row.day == today {
+            o.today += row.cnt;
+        }
+        if row.day == yesterday {
+            o.yesterday += row.cnt;
+        }
+        if row.day >= week_start && row.day <= today {
+            o.week += row.cnt;
+        }
+        if row.day >= previous_week.0 && row.day <= previous_week.1 {
+            o.previous_week += row.cnt;
+        }
+        o.daily.push(Point {
+            date: row.day,
+            cnt: row.cnt,
+        });
+    }
+
+    // Лидеры недели: продукты и работники по выпуску, работники - в каждой единице измерения
+    let leaders: Vec<LeaderRow> = sqlx::query_as(
+        "WITH base AS (
+          SELECT
+            pg.product_id,
+            pg.user_id,
+            p.measure_unit_id,
+            pg.cnt + COALESCE(pa.adjustment_cnt, 0) AS qty
+          FROM
+            produced_goods AS pg
+            JOIN products AS p ON p.id = pg.product_id
+            LEFT JOIN (
+              SELECT
+                produced_good_id,
+                SUM(cnt::bigint) AS adjustment_cnt
+              FROM
+                produced_good_adjustments
+              WHERE
+                status = 'Approved'
+              GROUP BY
+                produced_good_id
+            ) pa ON pa.produced_good_id = pg.id
+          WHERE (pg.created_at AT TIME ZONE $3)::date BETWEEN $5 AND $6
+          AND pg.status <> 'Rejected'
+          AND CASE
+              WHEN $2 not in ('Admin', 'Developer') THEN pg.organization_id = $1::bigint
+              ELSE TRUE
+            END
+          AND ($4::bigint IS NULL OR pg.user_id = $4)
+        )
+        (
+          SELECT
+            'product' AS kind,
+            p.id,
+            p.name,
+            mu.name AS measure,
+            SUM(b.qty)::bigint AS cnt
+          FROM
+            base AS b
+            JOIN products AS p ON p.id = b.product_id
+            JOIN measure_units AS mu ON mu.id = b.measure_unit_id
+          GROUP BY
+            p.id,
+            mu.name
+          ORDER BY
+            cnt DESC
+          LIMIT 5
+        )
+        UNION ALL
+        (
+          SELECT
+            'user',
+            u.id,
+            u.fio,
+            mu.name,
+            SUM(b.qty)::bigint AS cnt
+          FROM
+            base AS b
+            JOIN users AS u ON u.id = b.user_id
+            JOIN measure_units AS mu ON mu.id = b.measure_unit_id
+          GROUP BY
+            u.id,
+            mu.name
+          ORDER BY
+            cnt DESC
+          LIMIT 5
+        )",
+    )
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .bind(&tz)
+    .bind(user_id)
+    .bind(week_start)
+    .bind(today)
+    .fetch_all(&pool)
+    .await?;
+
+    let top = |kind: &str| {
+        leaders
+            .iter()
+            .filter(|row| row.kind == kind)
+            .map(|row| Top {
+                item: Select {
+                    id: row.id,
+                    name: row.name.clone(),
+                },
+                measure: row.measure.clone(),
+                cnt: row.cnt,
+            })
+            .collect::<Vec<Top>>()
+    };
+
+    let counters: Counters = sqlx::query_as(
+        "SELECT
+          (
+            SELECT COUNT(*) FROM produced_goods AS pg
+            WHERE pg.status = 'Submitted'
+            AND CASE
+                WHEN $2 not in ('Admin', 'Developer') THEN pg.organization_id = $1::bigint
+                ELSE TRUE
+              END
+            AND ($4::bigint IS NULL OR pg.user_id = $4)
+          ) AS submitted,
+          (
+            SELECT COUNT(*) FROM produced_goods AS pg
+            WHERE pg.status = 'Rejected'
+            AND CASE
+                WHEN $2 not in ('Admin', 'Developer') THEN pg.organization_id = $1::bigint
+                ELSE TRUE
+              END
+            AND ($4::bigint IS NULL OR pg.user_id = $4)
+          ) AS rejected,
+          (
+            SELECT COUNT(*) FROM produced_good_adjustments AS pga
+            JOIN produced_goods AS pg ON pg.id = pga.produced_good_id
+            WHERE pga.status = 'Pending'
+            AND CASE
+                WHEN $2 not in ('Admin', 'Developer') THEN pg.organization_id = $1::bigint
+                ELSE TRUE
+              END
+            AND ($4::bigint IS NULL OR pga.user_id = $4)
+          ) AS pending_adjustments,
+          (
+            SELECT COUNT(*) FROM downtimes AS d
+            WHERE d.ended_at IS NULL
+            AND CASE
+                WHEN $2 not in ('Admin', 'Developer') THEN d.organization_id = $1::bigint
+                ELSE TRUE
+              END
+            AND ($4::bigint IS NULL OR d.user_id = $4)
+          ) AS open_downtimes,
+          (
+            SELECT COUNT(*) FROM produced_good_adjustments AS pga
+            JOIN produced_goods AS pg ON pg.id = pga.produced_good_id
+            WHERE pga.status = 'Approved'
+            AND (pga.created_at AT TIME ZONE $3)::date BETWEEN $5 AND $6
+            AND CASE
+                WHEN $2 not in ('Admin', 'Developer') THEN pg.organization_id = $1::bigint
+                ELSE TRUE
+              END
+            AND ($4::bigint IS NULL OR pg.user_id = $4)
+          ) AS adjustments_cnt,
+          (
+            SELECT COALESCE(SUM(ABS(pga.cnt)), 0)::bigint FROM produced_good_adjustments AS pga
+            JOIN produced_goods AS pg ON pg.id = pga.produced_good_id
+            WHERE pga.status = 'Approved'
+            AND (pga.created_at AT TIME ZONE $3)::date BETWEEN $5 AND $6
+            AND CASE
+                WHEN $2 not in ('Admin', 'Developer') THEN pg.organization_id = $1::bigint
+                ELSE TRUE
+              END
+            AND ($4::bigint IS NULL OR pg.user_id = $4)
+          ) AS adjustments_volume",
+    )
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .bind(&tz)
+    .bind(user_id)
+    .bind(week_start)
+    .bind(today)
+    .fetch_one(&pool)
+    .await?;
+
+    Ok(Dashboard {
+        output,
+        top_products: top("product"),
+        top_workers: top("user"),
+        attention: Attention {
+            submitted: counters.submitted,
+            rejected: counters.rejected,
+            pending_adjustments: counters.pending_adjustments,
+            open_downtimes: counters.open_downtimes,
+        },
+        adjustments_cnt: counters.adjustments_cnt,
+        adjustments_volume: counters.adjustments_volume,
+    })
+}
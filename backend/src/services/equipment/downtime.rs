@@ -0,0 +1,329 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{
+    check_access,
+    services::{Items, Select},
+    AppError, CurrentUser,
+};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RequestBody {
+    machine_id: i64,
+    downtime_reason_id: i64,
+    started_at: chrono::DateTime<chrono::Utc>,
+    // Простой без окончания считается продолжающимся
+    ended_at: Option<chrono::DateTime<chrono::Utc>>,
+    comment: Option<String>,
+}
+
+async fn validate(
+    pool: &PgPool,
+    current_user: &CurrentUser,
+    id: Option<i64>,
+    body: &RequestBody,
+) -> Result<i64, AppError> {
+    // Проверка простоя: интервал, оборудование и причина одной организации,
+    // отсутствие пересечения с другими простоями оборудования.
+    // Возвращает организацию оборудования.
+
+    if body
+        .ended_at
+        .is_some_and(|ended_at| ended_at <= body.started_at)
+    {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Окончание простоя должно быть позже начала!"),
+        ));
+    }
+
+    let organization_id: Option<i64> = sqlx::query_scalar(
+        "SELECT m.organization_id
+        FROM machines AS m
+        INNER JOIN downtime_reasons AS dr ON dr.organization_id = m.organization_id
+        WHERE m.id = $1
+        AND dr.id = $2
+        AND CASE
+            WHEN $3::bigint IS NOT NULL AND $4 not in ('Admin', 'Developer') THEN
+                m.organization_id = $3
+            ELSE TRUE
+        END",
+    )
+    .bind(body.machine_id)
+    .bind(body.downtime_reason_id)
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(organization_id) = organization_id else {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!(
+                "Оборудование и причина простоя должны принадлежать вашей организации!"
+            ),
+        ));
+    };
+
+    let is_overlap: bool = sqlx::query_scalar(
+        "SELECT EXISTS (
+          SELECT 1 FROM downtimes
+          WHERE machine_id = $1
+          AND ($4::bigint IS NULL OR id <> $4)
+          AND TSTZRANGE(started_at, COALESCE(ended_at, 'infinity'))
+            && TSTZRANGE($2, COALESCE($3, 'infinity'))
+        )",
+    )
+    .bind(body.machine_id)
+    .bind(body.started_at)
+    .bind(body.ended_at)
+    .bind(id)
+    .fetch_one(pool)
+    .await?;
+
+    if is_overlap {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Простой пересекается с другим простоем этого оборудования!"),
+        ));
+    }
+
+    Ok(organization_id)
+}
+
+pub async fn create_downtime(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(body): Json<RequestBody>,
+) -> Result<i64, AppError> {
+    // Бизнес логика регистрации простоя, сообщить о простое может любой работник организации
+
+    let organization_id = validate(&pool, &current_user, None, &body).await?;
+
+    let row: (i64,) = sqlx::query_as(
+        "INSERT INTO
+          downtimes (
+            organization_id, machine_id, downtime_reason_id, started_at, ended_at, comment, user_id
+          )
+        VALUES
+          ($1, $2, $3, $4, $5, NULLIF(TRIM($6), ''), $7)
+        RETURNING
+          id",
+    )
+    .bind(organization_id)
+    .bind(body.machine_id)
+    .bind(body.downtime_reason_id)
+    .bind(body.started_at)
+    .bind(body.ended_at)
+    .bind(&body.comment)
+    .bind(current_user.id)
+    .fetch_one(&pool)
+    .await?;
+
+    Ok(row.0)
+}
+
+pub async fn edit_downtime(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+    Json(body): Json<RequestBody>,
+) -> Result<i64, AppError> {
+    // Бизнес логика редактирования простоя.
+    // Работник может изменить (например, закрыть) только свой простой.
+
+    let organization_id = validate(&pool, &current_user, Some(id), &body).await?;
+
+    let result = sqlx::query(
+        "UPDATE downtimes
+        SET machine_id=$1, downtime_reason_id=$2, started_at=$3, ended_at=$4,
+            comment=NULLIF(TRIM($5), ''), organization_id=$6, updated_at=NOW()
+        WHERE id = $7
+        AND ($8 OR user_id = $9)
+        AND CASE
+            WHEN $10::bigint IS NOT NULL AND $11 not in ('Admin', 'Developer') THEN
+                organization_id = $10
+            ELSE TRUE
+        END",
+    )
+    .bind(body.machine_id)
+    .bind(body.downtime_reason_id)
+    .bind(body.started_at)
+    .bind(body.ended_at)
+    .bind(&body.comment)
+    .bind(organization_id)
+    .bind(id)
+    .bind(check_access(current_user.role))
+    .bind(current_user.id)
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .execute(&pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError(
+            StatusCode::NOT_FOUND,
+            anyhow::anyhow!("Такой записи не существует"),
+        ));
+    }
+
+    Ok(id)
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Q {
+    pub date_one: Option<chrono::NaiveDate>,
+    pub date_two: Option<chrono::NaiveDate>,
+    pub machine_id: Option<i64>,
+    #[serde(default = "page")]
+    pub page: i64,
+    #[serde(default = "per_page")]
+    pub per_page: i64,
+}
+
+fn per_page() -> i64 {
+    15
+}
+
+fn page() -> i64 {
+    1
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Item {
+    pub id: i64,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    pub ended_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub comment: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+
+    machine: Select,
+    reason: Select,
+    user: Select,
+}
+
+pub async fn get_downtimes(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Items<Item>, AppError> {
+    // Бизнес логика получения журнала простоев, новые первыми.
+    // В период попадают простои, пересекающиеся с ним.
+
+    let rows = sqlx::query!(
+        "SELECT
+            d.id,
+            d.started_at,
+            d.ended_at,
+            d.comment,
+            d.created_at,
+            JSONB_BUILD_OBJECT(
+                'id', m.id,
+                'name', m.name
+            ) AS machine,
+            JSONB_BUILD_OBJECT(
+                'id', dr.id,
+                'name', dr.code || ' ' || dr.name
+            ) AS reason,
+            JSONB_BUILD_OBJECT(
+                'id', author.id,
+                'name', author.fio
+            ) AS user
+        FROM downtimes AS d
+        INNER JOIN machines AS m ON m.id = d.machine_id
+        INNER JOIN downtime_reasons AS dr ON dr.id = d.downtime_reason_id
+        INNER JOIN users AS author ON author.id = d.user_id
+        WHERE
+            CASE
+                WHEN $1::bigint IS NOT NULL AND $2 not in ('Admin', 'Developer') THEN
+                    d.organization_id = $1
+                ELSE TRUE
+            END
+            AND ($3::date IS NULL OR COALESCE(d.ended_at, NOW()) >= $3::date)
+            AND ($4::date IS NULL OR d.started_at < $4::date + 1)
+            AND ($5::bigint IS NULL OR d.machine_id = $5)
+        ORDER BY d.started_at DESC, d.id DESC
+        OFFSET $6 LIMIT $7;",
+        current_user.organization_id,
+        current_user.role.to_string(),
+        q.date_one,
+        q.date_two,
+        q.machine_id,
+        (q.page - 1) * q.per_page,
+        q.per_page,
+    )
+    .map(|row| Item {
+        id: row.id,
+        started_at: row.started_at,
+        ended_at: row.ended_at,
+        comment: row.comment,
+        created_at: row.created_at,
+        machine: row.machine.into(),
+        reason: row.reason.into(),
+        user: row.user.into(),
+    })
+    .fetch_all(&pool)
+    .await?;
+
+    // Подсчет данных для пагинации
+    let cnt: i64 = sqlx::query_scalar(
+        "SELECT COUNT(id) FROM downtimes AS d WHERE CASE
+        WHEN $1::bigint IS NOT NULL AND $2 not in ('Admin', 'Developer') THEN
+            d.organization_id = $1
+        ELSE TRUE
+    END
+    AND ($3::date IS NULL OR COALESCE(d.ended_at, NOW()) >= $3::date)
+    AND ($4::date IS NULL OR d.started_at < $4::date + 1)
+    AND ($5::bigint IS NULL OR d.machine_id = $5)",
+    )
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .bind(q.date_one)
+    .bind(q.date_two)
+    .bind(q.machine_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap_or(0);
+
+    let cnt = (cnt as f64 / q.per_page as f64).ceil() as i64;
+
+    Ok(Items { items: rows, cnt })
+}
+
+pub async fn delete_downtime(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<(), AppError> {
+    // Бизнес логика удаления простоя
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        let _ = sqlx::query(
+            "DELETE
+        FROM downtimes
+        WHERE id = $1
+        AND CASE
+            WHEN $2::bigint IS NOT NULL AND $3 not in ('Admin', 'Developer') THEN
+                organization_id = $2
+            ELSE TRUE
+        END;",
+        )
+        .bind(id)
+        .bind(current_user.organization_id)
+        .bind(current_user.role.to_string())
+        .execute(&pool)
+        .await?;
+
+        Ok(())
+    }
+}
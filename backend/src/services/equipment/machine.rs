@@ -0,0 +1,385 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{
+    check_access, check_is_admin,
+    services::{Items, Select},
+    AppError, CurrentUser,
+};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RequestBody {
+    organization_id: Option<i64>,
+    name: String,
+    code: Option<String>,
+    // Идеальная производительность, единиц в час
+    ideal_rate: Option<f64>,
+    #[serde(default = "active")]
+    active: bool,
+}
+
+fn active() -> bool {
+    true
+}
+
+fn validate(body: &RequestBody) -> Result<(), AppError> {
+    // Проверка идеальной производительности оборудования
+
+    if body
+        .ideal_rate
+        .is_some_and(|rate| !rate.is_finite() || rate <= 0.0)
+    {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Идеальная производительность должна быть больше нуля!"),
+        ));
+    }
+
+    Ok(())
+}
+
+fn code(body: &RequestBody) -> Option<String> {
+    body.code
+        .as_deref()
+        .map(str::trim)
+        .filter(|code| !code.is_empty())
+        .map(str::to_string)
+}
+
+async fn check_code(
+    pool: &PgPool,
+    organization_id: i64,
+    id: Option<i64>,
+    code: Option<String>,
+) -> Result<(), AppError> {
+    // Инвентарный код оборудования уникален в организации
+
+    let Some(code) = code else {
+        return Ok(());
+    };
+
+    let exists: bool = sqlx::query_scalar(
+        "SELECT EXISTS (
+          SELECT 1 FROM machines
+          WHERE organization_id = $1 AND code = $2 AND ($3::bigint IS NULL OR id <> $3)
+        )",
+    )
+    .bind(organization_id)
+    .bind(&code)
+    .bind(id)
+    .fetch_one(pool)
+    .await?;
+
+    if exists {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Оборудование с кодом {} уже существует!", code),
+        ));
+    }
+
+    Ok(())
+}
+
+pub async fn create_machine(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(body): Json<RequestBody>,
+) -> Result<i64, AppError> {
+    // Бизнес логика создания оборудования
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        validate(&body)?;
+
+        let organization_id = if check_is_admin(current_user.role) {
+            body.organization_id
+        } else {
+            current_user.organization_id
+        };
+
+        match organization_id {
+            Some(organization_id) => {
+                check_code(&pool, organization_id, None, code(&body)).await?;
+
+                let row: (i64,) = sqlx::query_as(
+                    "INSERT
+                    INTO machines (name, code, ideal_rate, active, organization_id) VALUES
+                    ($1, $2, $3, $4, $5) RETURNING id",
+                )
+                .bind(&body.name)
+                .bind(code(&body))
+                .bind(body.ideal_rate)
+                .bind(body.active)
+                .bind(organization_id)
+                .fetch_one(&pool)
+                .await?;
+
+                Ok(row.0)
+            }
+            _ => Err(AppError(
+                StatusCode::BAD_REQUEST,
+                anyhow::anyhow!("Невозможно создать запись без организации!"),
+            )),
+        }
+    }
+}
+
+pub async fn edit_machine(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+    Json(body): Json<RequestBody>,
+) -> Result<i64, AppError> {
+    // Бизнес логика редактирования оборудования
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        validate(&body)?;
+
+        let organization_id = if check_is_admin(current_user.role) {
+            body.organization_id
+        } else {
+            current_user.organization_id
+        };
+
+        match organization_id {
+            Some(organization_id) => {
+                check_code(&pool, organization_id, Some(id), code(&body)).await?;
+
+                let _ = sqlx::query(
+                    "UPDATE machines
+                    SET name=$1, code=$2, ideal_rate=$3, active=$4, organization_id=$5,
+                        updated_at=NOW()
+                    WHERE id = $6",
+                )
+                .bind(&body.name)
+                .bind(code(&body))
+                .bind(body.ideal_rate)
+                .bind(body.active)
+                .bind(organization_id)
+                .bind(id)
+                .execute(&pool)
+                .await?;
+
+                Ok(id)
+            }
+            _ => Err(AppError(
+                StatusCode::BAD_REQUEST,
+                anyhow::anyhow!("Невозможно отредактировать запись без организации!"),
+            )),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Q {
+    #[serde(default = "page")]
+    pub page: i64,
+    #[serde(default = "per_page")]
+    pub per_page: i64,
+}
+
+fn per_page() -> i64 {
+    15
+}
+
+fn page() -> i64 {
+    1
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Item {
+    pub id: i64,
+    pub name: String,
+    pub code: Option<String>,
+    pub ideal_rate: Option<f64>,
+    pub active: bool,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+
+    organization: Select,
+}
+
+pub async fn get_machines(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Items<Item>, anyhow::Error> {
+    // Бизнес логика получения списка оборудования
+
+    let rows = sqlx::query!(
+        "SELECT
+            m.id,
+            m.name,
+            m.code,
+            m.ideal_rate,
+            m.active,
+            m.created_at,
+            JSONB_BUILD_OBJECT(
+                'id', o.id,
+                'name', o.name
+            ) AS organization
+        FROM machines AS m
+        LEFT JOIN organizations AS o ON o.id = m.organization_id
+        WHERE
+            CASE
+                WHEN $1::bigint IS NOT NULL AND $2 not in ('Admin', 'Developer') THEN
+                    m.organization_id = $1
+                ELSE TRUE
+            END
+        ORDER BY m.active DESC, m.name, m.id DESC
+        OFFSET $3 LIMIT $4;",
+        current_user.organization_id,
+        current_user.role.to_string(),
+        (q.page - 1) * q.per_page,
+        q.per_page,
+    )
+    .map(|row| Item {
+        id: row.id,
+        name: row.name,
+        code: row.code,
+        ideal_rate: row.ideal_rate,
+        active: row.active,
+        created_at: row.created_at,
+        organization: row.organization.into(),
+    })
+    .fetch_all(&pool)
+    .await?;
+
+    // Подсчет данных для пагинации
+    let cnt: i64 = sqlx::query_scalar(
+        "SELECT COUNT(id) FROM machines AS m WHERE CASE
+        WHEN $1::bigint IS NOT NULL AND $2 not in ('Admin', 'Developer') THEN
+            m.organization_id = $1
+        ELSE TRUE
+    END",
+    )
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .fetch_one(&pool)
+    .await
+    .unwrap_or(0);
+
+    let cnt = (cnt as f64 / q.per_page as f64).ceil() as i64;
+
+    Ok(Items { items: rows, cnt })
+}
+
+pub async fn detail_machine(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<Item, AppError> {
+    // Бизнес логика получения оборудования
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        let row = sqlx::query!(
+            "SELECT
+            m.id,
+            m.name,
+            m.code,
+            m.ideal_rate,
+            m.active,
+            m.created_at,
+            JSONB_BUILD_OBJECT(
+                'id', o.id,
+                'name', o.name
+            ) AS organization
+        FROM machines AS m
+        LEFT JOIN organizations AS o ON o.id = m.organization_id
+        WHERE m.id = $1
+        AND CASE
+            WHEN $2::bigint IS NOT NULL AND $3 not in ('Admin', 'Developer') THEN
+                m.organization_id = $2
+            ELSE TRUE
+        END;",
+            id,
+            current_user.organization_id,
+            current_user.role.to_string(),
+        )
+        .fetch_optional(&pool)
+        .await?;
+
+        match row {
+            Some(row) => Ok(Item {
+                id: row.id,
+                name: row.name,
+                code: row.code,
+                ideal_rate: row.ideal_rate,
+                active: row.active,
+                created_at: row.created_at,
+                organization: row.organization.into(),
+            }),
+            None => Err(AppError(
+                StatusCode::FORBIDDEN,
+                anyhow::anyhow!("Такой записи не существует"),
+            )),
+        }
+    }
+}
+
+pub async fn delete_machine(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<(), AppError> {
+    // Бизнес логика удаления оборудования
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        let is_used: bool = sqlx::query_scalar(
+            "SELECT
+              EXISTS (SELECT 1 FROM produced_goods WHERE machine_id = $1)
+              OR EXISTS (SELECT 1 FROM downtimes WHERE machine_id = $1)",
+        )
+        .bind(id)
+        .fetch_one(&pool)
+        .await?;
+
+        if is_used {
+            return Err(AppError(
+                StatusCode::BAD_REQUEST,
+                anyhow::anyhow!(
+                    "Нельзя удалить оборудование, по которому уже есть записи, отключите его!"
+                ),
+            ));
+        }
+
+        let _ = sqlx::query(
+            "DELETE
+        FROM machines
+        WHERE id = $1
+        AND CASE
+            WHEN $2::bigint IS NOT NULL THEN organization_id = $2
+            ELSE TRUE
+        END;",
+        )
+        .bind(id)
+        .bind(current_user.organization_id)
+        .execute(&pool)
+        .await?;
+
+        Ok(())
+    }
+}
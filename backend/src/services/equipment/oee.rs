@@ -0,0 +1,263 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Extension,
+};
+
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{check_access, services::Select, AppError, CurrentUser};
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Q {
+    pub date_one: chrono::NaiveDate,
+    pub date_two: chrono::NaiveDate,
+    pub machine_id: Option<i64>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Item {
+    pub machine: Select,
+    // Время в минутах
+    pub planned: f64,
+    pub downtime: f64,
+    pub run: f64,
+    pub good: i64,
+    pub defect: i64,
+    // Показатели в процентах, производительность и OEE не считаются без идеальной производительности
+    pub availability: f64,
+    pub performance: Option<f64>,
+    pub quality: f64,
+    pub oee: Option<f64>,
+}
+
+fn percent(value: f64) -> f64 {
+    (value * 10000.0).round() / 100.0
+}
+
+#[derive(sqlx::FromRow)]
+struct Row {
+    id: i64,
+    name: String,
+    ideal_rate: Option<f64>,
+    planned: f64,
+    downtime: f64,
+    good: i64,
+    defect: i64,
+}
+
+impl From<Row> for Item {
+    fn from(row: Row) -> Self {
+        // Простой вне смен не уменьшает плановое время ниже нуля
+        let downtime = row.downtime.min(row.planned);
+        let run = row.planned - downtime;
+        let total = row.good + row.defect;
+
+        let availability = if row.planned > 0.0 {
+            run / row.planned
+        } else {
+            0.0
+        };
+        let performance = row.ideal_rate.map(|rate| {
+            if run > 0.0 {
+                total as f64 / (rate * run / 60.0)
+            } else {
+                0.0
+            }
+        });
+        let quality = if total > 0 {
+            row.good as f64 / total as f64
+        } else {
+            0.0
+        };
+
+        Item {
+            machine: Select {
+                id: row.id,
+                name: row.name,
+            },
+            planned: row.planned.round(),
+            downtime: downtime.round(),
+            run: run.round(),
+            good: row.good,
+            defect: row.defect,
+            availability: percent(availability),
+            performance: performance.map(percent),
+            quality: percent(quality),
+            oee: performance.map(|performance| percent(availability * performance * quality)),
+        }
+    }
+}
+
+pub async fn get_oee(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Vec<Item>, AppError> {
+    // Бизнес логика расчета доступности и OEE оборудования за период.
+    // Плановое время - длительность смен организации за каждый день периода
+    // (круглые сутки, если смены не заданы). Доступность - доля планового времени без простоев,
+    // производительность - выпуск относительно идеальной производительности за время работы,
+    // качество - доля годного в выпуске с браком.
+
+    if !check_access(current_user.role) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    let rows: Vec<Row> = sqlx::query_as(
+        "WITH shift_minutes AS (
+          SELECT
+            s.organization_id,
+            SUM(EXTRACT(EPOCH FROM CASE
+              WHEN s.end_time > s.start_time THEN s.end_time - s.start_time
+              ELSE s.end_time - s.start_time + INTERVAL '24 hours'
+            END) / 60)::double precision AS minutes
+          FROM
+            shifts AS s
+          GROUP BY
+            s.organization_id
+        ),
+        output AS (
+          SELECT
+            pg.machine_id,
+            SUM(pg.cnt + COALESCE(pa.adjustment_cnt, 0))::bigint AS good,
+            SUM(COALESCE(pd.defect_cnt, 0))::bigint AS defect
+          FROM
+            produced_goods AS pg
+            LEFT JOIN (
+              SELECT
+                produced_good_id,
+                SUM(cnt::bigint) AS adjustment_cnt
+              FROM
+                produced_good_adjustments
+              WHERE
+                status = 'Approved'
+              GROUP BY
+                produced_good_id
+            ) pa ON pa.produced_good_id = pg.id
+            LEFT JOIN (
+              SELECT
+                produced_good_id,
+                SUM(cnt) AS defect_cnt
+              FROM
+                produced_good_defects
+              GROUP BY
+                produced_good_id
+            ) pd ON pd.produced_good_id = pg.id
+          WHERE pg.machine_id IS NOT NULL
+          AND pg.status <> 'Rejected'
+          AND pg.created_at::date BETWEEN $1 AND $2
+          GROUP BY
+            pg.machine_id
+        ),
+        stops AS (
+          SELECT
+            d.machine_id,
+            SUM(EXTRACT(EPOCH FROM
+              LEAST(COALESCE(d.ended_at, NOW()), ($2::date + 1)::timestamptz)
+              - GREATEST(d.started_at, $1::date::timestamptz)
+            ) / 60)::double precision AS minutes
+          FROM
+            downtimes AS d
+          WHERE d.started_at < ($2::date + 1)::timestamptz
+          AND COALESCE(d.ended_at, NOW()) > $1::date::timestamptz
+          GROUP BY
+            d.machine_id
+        )
+        SELECT
+          m.id,
+          m.name,
+          m.ideal_rate,
+          (($2::date - $1::date + 1) * COALESCE(sm.minutes, 1440))::double precision AS planned,
+          COALESCE(st.minutes, 0)::double precision AS downtime,
+          COALESCE(o.good, 0)::bigint AS good,
+          COALESCE(o.defect, 0)::bigint AS defect
+        FROM
+          machines AS m
+          LEFT JOIN shift_minutes AS sm ON sm.organization_id = m.organization_id
+          LEFT JOIN output AS o ON o.machine_id = m.id
+          LEFT JOIN stops AS st ON st.machine_id = m.id
+        WHERE CASE
+            WHEN $3::bigint IS NOT NULL AND $4 not in ('Admin', 'Developer') THEN
+              m.organization_id = $3
+            ELSE TRUE
+          END
+        AND ($5::bigint IS NULL OR m.id = $5)
+        AND (m.active OR o.machine_id IS NOT NULL OR st.machine_id IS NOT NULL)
+        ORDER BY
+          m.name,
+          m.id",
+    )
+    .bind(q.date_one)
+    .bind(q.date_two)
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .bind(q.machine_id)
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(rows.into_iter().map(Item::from).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(ideal_rate: Option<f64>, planned: f64, downtime: f64, good: i64, defect: i64) -> Row {
+        Row {
+            id: 1,
+            name: "Станок".to_string(),
+            ideal_rate,
+            planned,
+            downtime,
+            good,
+            defect,
+        }
+    }
+
+    #[test]
+    fn oee_components() {
+        // 480 мин по плану, 60 мин простоя, 60 шт/ч идеально: 420 мин работы -> 420 шт
+        let item = Item::from(row(Some(60.0), 480.0, 60.0, 380, 20));
+
+        assert_eq!(item.run, 420.0);
+        assert_eq!(item.availability, 87.5);
+        assert_eq!(item.performance, Some(95.24));
+        assert_eq!(item.quality, 95.0);
+        assert_eq!(item.oee, Some(79.17));
+    }
+
+    #[test]
+    fn without_ideal_rate() {
+        let item = Item::from(row(None, 480.0, 0.0, 100, 0));
+
+        assert_eq!(item.availability, 100.0);
+        assert_eq!(item.performance, None);
+        assert_eq!(item.quality, 100.0);
+        assert_eq!(item.oee, None);
+    }
+
+    #[test]
+    fn downtime_longer_than_plan() {
+        let item = Item::from(row(Some(60.0), 480.0, 600.0, 0, 0));
+
+        assert_eq!(item.downtime, 480.0);
+        assert_eq!(item.run, 0.0);
+        assert_eq!(item.availability, 0.0);
+        assert_eq!(item.performance, Some(0.0));
+        assert_eq!(item.quality, 0.0);
+        assert_eq!(item.oee, Some(0.0));
+    }
+
+    #[test]
+    fn empty_plan() {
+        let item = Item::from(row(Some(60.0), 0.0, 0.0, 0, 0));
+
+        assert_eq!(item.availability, 0.0);
+        assert_eq!(item.oee, Some(0.0));
+    }
+}
@@ -0,0 +1,538 @@
+use std::io::{Seek, Write};
+
+use axum::{
+    body::Body,
+    http::{header, HeaderMap, Response, StatusCode},
+};
+
+use futures::{stream, Stream, StreamExt};
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use rust_xlsxwriter::*;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio_util::io::ReaderStream;
+
+use crate::AppError;
+
+// Сколько строк может ждать в очереди, пока клиент читает ответ
+const BUFFER: usize = 256;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Xlsx,
+    Ods,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn negotiate(format: Option<ExportFormat>, headers: &HeaderMap) -> ExportFormat {
+        // Параметр format важнее заголовка Accept, по умолчанию CSV
+
+        if let Some(format) = format {
+            return format;
+        }
+
+        let accept = headers
+            .get(header::ACCEPT)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("");
+
+        [Self::Xlsx, Self::Ods, Self::Json, Self::Csv]
+            .into_iter()
+            .find(|format| accept.contains(format.content_type()))
+            .unwrap_or(Self::Csv)
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv",
+            Self::Xlsx => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+            Self::Ods => "application/vnd.oasis.opendocument.spreadsheet",
+            Self::Json => "application/json",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Xlsx => "xlsx",
+            Self::Ods => "ods",
+            Self::Json => "json",
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct FormatQ {
+    pub format: Option<ExportFormat>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Cell {
+    Text(String),
+    Int(i64),
+    Float(f64),
+    Empty,
+}
+
+impl From<String> for Cell {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<&str> for Cell {
+    fn from(value: &str) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+impl From<i64> for Cell {
+    fn from(value: i64) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<f64> for Cell {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<Decimal> for Cell {
+    fn from(value: Decimal) -> Self {
+        Self::Float(value.to_f64().unwrap_or_default())
+    }
+}
+
+impl From<bool> for Cell {
+    fn from(value: bool) -> Self {
+        Self::Text(if value { "Да" } else { "Нет" }.to_string())
+    }
+}
+
+impl From<chrono::DateTime<chrono::Utc>> for Cell {
+    fn from(value: chrono::DateTime<chrono::Utc>) -> Self {
+        Self::Text(value.format("%d.%m.%Y %H:%M:%S").to_string())
+    }
+}
+
+impl<T: Into<Cell>> From<Option<T>> for Cell {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Empty, Into::into)
+    }
+}
+
+impl Cell {
+    fn text(&self) -> String {
+        match self {
+            Self::Text(value) => value.clone(),
+            Self::Int(value) => value.to_string(),
+            Self::Float(value) => value.to_string(),
+            Self::Empty => String::new(),
+        }
+    }
+}
+
+pub type Row = Vec<Cell>;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Column {
+    // Ключ поля в JSON
+    pub key: &'static str,
+    // Заголовок колонки в таблицах
+    pub title: &'static str,
+}
+
+pub struct Table {
+    pub name: &'static str,
+    pub columns: Vec<Column>,
+    // Строки приходят из фоновой задачи по мере чтения из БД
+    pub rows: mpsc::Receiver<Result<Row, sqlx::Error>>,
+}
+
+impl Table {
+    pub fn channel(
+        name: &'static str,
+        columns: Vec<Column>,
+    ) -> (mpsc::Sender<Result<Row, sqlx::Error>>, Table) {
+        let (tx, rows) = mpsc::channel(BUFFER);
+
+        (
+            tx,
+            Table {
+                name,
+                columns,
+                rows,
+            },
+        )
+    }
+
+    pub fn from_rows(name: &'static str, columns: Vec<Column>, rows: Vec<Row>) -> Table {
+        // Таблица из уже загруженных строк, например из аналитики
+
+        let (tx, table) = Self::channel(name, columns);
+        tokio::spawn(async move {
+            for row in rows {
+                if tx.send(Ok(row)).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        table
+    }
+}
+
+pub async fn forward<T, S>(
+    mut rows: S,
+    tx: mpsc::Sender<Result<Row, sqlx::Error>>,
+    cells: impl Fn(T) -> Row,
+) where
+    S: Stream<Item = Result<T, sqlx::Error>> + Unpin,
+{
+    // Передача строк из курсора БД в очередь выгрузки
+
+    while let Some(row) = rows.next().await {
+        // Клиент закрыл соединение, дальше читать не нужно
+        if tx.send(row.map(&cells)).await.is_err() {
+            break;
+        }
+    }
+}
+
+fn receive(
+    rows: mpsc::Receiver<Result<Row, sqlx::Error>>,
+) -> impl Stream<Item = Result<Row, sqlx::Error>> {
+    stream::unfold(rows, |mut rows| async move {
+        rows.recv().await.map(|row| (row, rows))
+    })
+}
+
+fn csv_line(cells: impl Iterator<Item = String>) -> String {
+    // Разделитель ; - его ожидает Excel с русской локалью
+
+    let mut line = cells
+        .map(|value| {
+            if value.contains([';', '"', '\r', '\n']) {
+                format!("\"{}\"", value.replace('"', "\"\""))
+            } else {
+                value
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(";");
+    line.push_str("\r\n");
+
+    line
+}
+
+fn csv(table: Table) -> impl Stream<Item = Result<String, sqlx::Error>> {
+    // BOM, чтобы Excel открыл файл в UTF-8 без мастера импорта
+
+    let header = format!(
+        "\u{feff}{}",
+        csv_line(table.columns.iter().map(|c| c.title.to_string()))
+    );
+
+    stream::once(async move { Ok(header) })
+        .chain(receive(table.rows).map(|row| row.map(|row| csv_line(row.iter().map(Cell::text)))))
+}
+
+fn json(table: Table) -> impl Stream<Item = Result<String, sqlx::Error>> {
+    // Массив объектов, ключи - поля колонок
+
+    let columns = table.columns;
+    let rows = receive(table.rows).enumerate().map(move |(i, row)| {
+        row.map(|row| {
+            let object = columns
+                .iter()
+                .zip(row)
+                .map(|(column, cell)| {
+                    (
+                        column.key.to_string(),
+                        serde_json::to_value(cell).unwrap_or_default(),
+                    )
+                })
+                .collect::<serde_json::Map<String, serde_json::Value>>();
+
+            let separator = if i == 0 { "" } else { "," };
+            format!("{}{}", separator, serde_json::Value::Object(object))
+        })
+    });
+
+    stream::once(async { Ok("[".to_string()) })
+        .chain(rows)
+        .chain(stream::once(async { Ok("]".to_string()) }))
+}
+
+async fn build<F>(table: Table, write: F) -> Result<std::fs::File, AppError>
+where
+    F: FnOnce(Table) -> Result<std::fs::File, AppError> + Send + 'static,
+{
+    // Запись файла синхронная, поэтому файл собирается в отдельном потоке,
+    // строки принимаются там же по мере чтения из БД
+
+    let file = tokio::task::spawn_blocking(move || write(table)).await??;
+
+    Ok(file)
+}
+
+async fn xlsx(table: Table) -> Result<std::fs::File, AppError> {
+    build(table, write_xlsx).await
+}
+
+async fn ods(table: Table) -> Result<std::fs::File, AppError> {
+    build(table, write_ods).await
+}
+
+fn write_xlsx(mut table: Table) -> Result<std::fs::File, AppError> {
+    // XLSX - zip-архив с оглавлением в конце, поэтому файл собирается во временном файле.
+    // Лист в режиме постоянной памяти сбрасывает строки на диск по мере записи.
+
+    let header_fmt = Format::new()
+        .set_bold()
+        .set_align(FormatAlign::Center)
+        .set_background_color(Color::RGB(0xC6C6C6))
+        .set_border(FormatBorder::Thin);
+    let money_fmt = Format::new().set_num_format("#,##0.00");
+
+    let mut wookbook = Workbook::new();
+    let worksheet = wookbook.add_worksheet_with_constant_memory();
+    worksheet.set_name(table.name)?;
+
+    for (i, column) in table.columns.iter().enumerate() {
+        worksheet.set_column_width(i as u16, 20)?;
+        worksheet.write_with_format(0, i as u16, column.title, &header_fmt)?;
+    }
+    worksheet.set_freeze_panes(1, 0)?;
+
+    let mut i = 0;
+    while let Some(row) = table.rows.blocking_recv() {
+        i += 1;
+        for (j, cell) in row?.iter().enumerate() {
+            let j = j as u16;
+            match cell {
+                Cell::Text(value) => {
+                    worksheet.write(i, j, value)?;
+                }
+                Cell::Int(value) => {
+                    worksheet.write(i, j, *value)?;
+                }
+                Cell::Float(value) => {
+                    worksheet.write_with_format(i, j, *value, &money_fmt)?;
+                }
+                Cell::Empty => {}
+            }
+        }
+    }
+
+    if i > 0 && !table.columns.is_empty() {
+        worksheet.autofilter(0, 0, i, table.columns.len() as u16 - 1)?;
+    }
+
+    let mut file = tempfile::tempfile()?;
+    wookbook.save_to_writer(&mut file)?;
+    file.rewind()?;
+
+    Ok(file)
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn ods_cell(cell: &Cell) -> String {
+    match cell {
+        Cell::Text(value) => format!(
+            "<table:table-cell office:value-type=\"string\"><text:p>{}</text:p></table:table-cell>",
+            xml_escape(value)
+        ),
+        Cell::Int(_) | Cell::Float(_) => format!(
+            "<table:table-cell office:value-type=\"float\" office:value=\"{0}\"><text:p>{0}</text:p></table:table-cell>",
+            cell.text()
+        ),
+        Cell::Empty => "<table:table-cell/>".to_string(),
+    }
+}
+
+fn ods_row(cells: impl Iterator<Item = Cell>) -> String {
+    format!(
+        "<table:table-row>{}</table:table-row>",
+        cells.map(|cell| ods_cell(&cell)).collect::<String>()
+    )
+}
+
+fn write_ods(mut table: Table) -> Result<std::fs::File, AppError> {
+    // ODS - zip-архив с content.xml, первым в архиве идет несжатый mimetype.
+    // Строки дописываются в content.xml по мере чтения, архив собирается во временном файле.
+
+    let manifest = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+        <manifest:manifest \
+        xmlns:manifest=\"urn:oasis:names:tc:opendocument:xmlns:manifest:1.0\" \
+        manifest:version=\"1.2\">\
+        <manifest:file-entry manifest:full-path=\"/\" manifest:media-type=\"{}\"/>\
+        <manifest:file-entry manifest:full-path=\"content.xml\" manifest:media-type=\"text/xml\"/>\
+        </manifest:manifest>",
+        ExportFormat::Ods.content_type()
+    );
+
+    let stored =
+        zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    let deflated =
+        zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    let mut zip = zip::ZipWriter::new(tempfile::tempfile()?);
+    zip.start_file("mimetype", stored)?;
+    zip.write_all(ExportFormat::Ods.content_type().as_bytes())?;
+    zip.start_file("META-INF/manifest.xml", deflated)?;
+    zip.write_all(manifest.as_bytes())?;
+    zip.start_file("content.xml", deflated)?;
+
+    zip.write_all(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+        <office:document-content \
+        xmlns:office=\"urn:oasis:names:tc:opendocument:xmlns:office:1.0\" \
+        xmlns:table=\"urn:oasis:names:tc:opendocument:xmlns:table:1.0\" \
+        xmlns:text=\"urn:oasis:names:tc:opendocument:xmlns:text:1.0\" \
+        office:version=\"1.2\"><office:body><office:spreadsheet>"
+            .as_bytes(),
+    )?;
+    zip.write_all(
+        format!(
+            "<table:table table:name=\"{}\">{}",
+            xml_escape(table.name),
+            ods_row(table.columns.iter().map(|column| Cell::from(column.title)))
+        )
+        .as_bytes(),
+    )?;
+    while let Some(row) = table.rows.blocking_recv() {
+        zip.write_all(ods_row(row?.into_iter()).as_bytes())?;
+    }
+    zip.write_all(
+        "</table:table></office:spreadsheet></office:body></office:document-content>".as_bytes(),
+    )?;
+
+    let mut file = zip.finish()?;
+    file.rewind()?;
+
+    Ok(file)
+}
+
+pub async fn export(
+    format: ExportFormat,
+    filename: &str,
+    table: Table,
+) -> Result<Response<Body>, AppError> {
+    // Бизнес логика выгрузки таблицы в выбранном формате.
+    // CSV и JSON отдаются потоком по мере чтения строк из БД,
+    // XLSX и ODS собираются во временном файле и отдаются потоком из него.
+
+    let body = match format {
+        ExportFormat::Csv => Body::from_stream(csv(table)),
+        ExportFormat::Json => Body::from_stream(json(table)),
+        ExportFormat::Xlsx => Body::from_stream(ReaderStream::new(tokio::fs::File::from_std(
+            xlsx(table).await?,
+        ))),
+        ExportFormat::Ods => Body::from_stream(ReaderStream::new(tokio::fs::File::from_std(
+            ods(table).await?,
+        ))),
+    };
+
+    let resp = Response::builder()
+        .status(StatusCode::OK)
+        .header(
+            "Content-Disposition",
+            format!("attachment; filename={}.{}", filename, format.extension()),
+        )
+        .header("Content-Type", format.content_type())
+        .body(body)
+        .unwrap();
+
+    Ok(resp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn table(rows: Vec<Row>) -> Table {
+        Table::from_rows(
+            "Тест",
+            vec![
+                Column {
+                    key: "name",
+                    title: "Название",
+                },
+                Column {
+                    key: "cnt",
+                    title: "Кол-во",
+                },
+            ],
+            rows,
+        )
+    }
+
+    fn rows(n: i64) -> Vec<Row> {
+        (0..n)
+            .map(|i| vec![format!("Продукт <{}> & Ко", i).into(), i.into()])
+            .collect()
+    }
+
+    fn unzip(file: std::fs::File, name: &str) -> String {
+        let mut archive = zip::ZipArchive::new(file).unwrap();
+        let mut content = String::new();
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+
+        content
+    }
+
+    #[tokio::test]
+    async fn ods_streams_rows() {
+        let file = ods(table(rows(1000))).await.ok().unwrap();
+
+        let mut archive = zip::ZipArchive::new(file.try_clone().unwrap()).unwrap();
+        assert_eq!(archive.by_index(0).unwrap().name(), "mimetype");
+
+        let content = unzip(file, "content.xml");
+        assert_eq!(content.matches("<table:table-row>").count(), 1001);
+        assert!(content.contains("<text:p>Продукт &lt;999&gt; &amp; Ко</text:p>"));
+        assert!(content.ends_with("</office:document-content>"));
+    }
+
+    #[tokio::test]
+    async fn xlsx_streams_rows() {
+        let file = xlsx(table(rows(1000))).await.ok().unwrap();
+
+        let sheet = unzip(file, "xl/worksheets/sheet1.xml");
+        assert_eq!(sheet.matches("<row ").count(), 1001);
+        assert!(sheet.contains("<autoFilter ref=\"A1:B1001\"/>"));
+    }
+
+    #[tokio::test]
+    async fn empty_table() {
+        assert!(xlsx(table(vec![])).await.is_ok());
+        assert!(ods(table(vec![])).await.is_ok());
+    }
+
+    #[test]
+    fn csv_escapes_separators() {
+        assert_eq!(
+            csv_line(["a;b", "c\"d", "e"].into_iter().map(String::from)),
+            "\"a;b\";\"c\"\"d\";e\r\n"
+        );
+    }
+}
@@ -0,0 +1,423 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Extension,
+};
+
+use serde::Deserialize;
+use sqlx::PgPool;
+
+use crate::{
+    check_access,
+    services::{
+        export::format::{forward, Cell, Column, Table},
+        produced_goods::{analitic, confirmation::Status},
+    },
+    AppError, CurrentUser,
+};
+
+fn columns(items: &[(&'static str, &'static str)]) -> Vec<Column> {
+    items
+        .iter()
+        .map(|&(key, title)| Column { key, title })
+        .collect()
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ProducedGoodsQ {
+    pub status: Option<Status>,
+    pub date_one: Option<chrono::NaiveDate>,
+    pub date_two: Option<chrono::NaiveDate>,
+}
+
+#[derive(sqlx::FromRow)]
+struct ProducedGoodRow {
+    id: i64,
+    created_at: chrono::DateTime<chrono::Utc>,
+    product: String,
+    measure: String,
+    fio: String,
+    organization: Option<String>,
+    cnt: i64,
+    adjustment_cnt: i64,
+    lot: Option<String>,
+    status: String,
+}
+
+pub async fn produced_goods_table(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<ProducedGoodsQ>,
+) -> Result<Table, AppError> {
+    // Бизнес логика выгрузки выпуска: работник видит только свои записи,
+    // Директор - свою организацию, администратор - все организации
+
+    let user_id = if check_access(current_user.role) {
+        None
+    } else {
+        Some(current_user.id)
+    };
+
+    let (tx, table) = Table::channel(
+        "Выпуск",
+        columns(&[
+            ("id", "#"),
+            ("created_at", "Дата"),
+            ("product", "Продукт"),
+            ("measure", "Ед.измерения"),
+            ("user", "Пользователь"),
+            ("organization", "Организация"),
+            ("cnt", "Кол-во"),
+            ("adj", "Корректировка"),
+            ("lot", "Партия"),
+            ("status", "Статус"),
+        ]),
+    );
+
+    tokio::spawn(async move {
+        let rows = sqlx::query_as::<_, ProducedGoodRow>(
+            "SELECT
+              pg.id,
+              pg.created_at,
+              p.name AS product,
+              mu.name AS measure,
+              u.fio,
+              o.name AS organization,
+              pg.cnt,
+              COALESCE(pa.adjustment_cnt, 0)::bigint AS adjustment_cnt,
+              pg.lot,
+              pg.status
+            FROM
+              produced_goods AS pg
+              JOIN users AS u ON u.id = pg.user_id
+              JOIN products AS p ON p.id = pg.product_id
+              JOIN measure_units AS mu ON mu.id = p.measure_unit_id
+              LEFT JOIN organizations AS o ON o.id = pg.organization_id
+              LEFT JOIN (
+                SELECT
+                  produced_good_id,
+                  SUM(cnt::bigint) AS adjustment_cnt
+                FROM
+                  produced_good_adjustments
+                WHERE
+                  status = 'Approved'
+                GROUP BY
+                  produced_good_id
+              ) pa ON pa.produced_good_id = pg.id
+            WHERE
+              CASE
+                WHEN $1::bigint IS NOT NULL THEN pg.user_id = $1
+                WHEN $3 not in ('Admin', 'Developer') THEN pg.organization_id = $2::bigint
+                ELSE TRUE
+              END
+              AND ($4::text IS NULL OR pg.status = $4)
+              AND ($5::date IS NULL OR pg.created_at::date >= $5)
+              AND ($6::date IS NULL OR pg.created_at::date <= $6)
+            ORDER BY
+              pg.id DESC",
+        )
+        .bind(user_id)
+        .bind(current_user.organization_id)
+        .bind(current_user.role.to_string())
+        .bind(q.status.map(|s| s.to_string()))
+        .bind(q.date_one)
+        .bind(q.date_two)
+        .fetch(&pool);
+
+        forward(rows, tx, |row: ProducedGoodRow| {
+            vec![
+                row.id.into(),
+                row.created_at.into(),
+                row.product.into(),
+                row.measure.into(),
+                row.fio.into(),
+                row.organization.into(),
+                row.cnt.into(),
+                row.adjustment_cnt.into(),
+                row.lot.into(),
+                row.status.into(),
+            ]
+        })
+        .await;
+    });
+
+    Ok(table)
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ProductsQ {
+    pub category_id: Option<i64>,
+}
+
+#[derive(sqlx::FromRow)]
+struct ProductRow {
+    id: i64,
+    name: String,
+    sku: Option<String>,
+    barcode: Option<String>,
+    measure: Option<String>,
+    category: Option<String>,
+    organization: Option<String>,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+pub async fn products_table(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<ProductsQ>,
+) -> Result<Table, AppError> {
+    // Бизнес логика выгрузки справочника продуктов
+
+    let (tx, table) = Table::channel(
+        "Продукты",
+        columns(&[
+            ("id", "#"),
+            ("name", "Наименование"),
+            ("sku", "Артикул"),
+            ("barcode", "Штрихкод"),
+            ("measure", "Ед.измерения"),
+            ("category", "Категория"),
+            ("organization", "Организация"),
+            ("created_at", "Создан"),
+        ]),
+    );
+
+    tokio::spawn(async move {
+        let rows = sqlx::query_as::<_, ProductRow>(
+            "WITH RECURSIVE category_tree AS (
+                SELECT id FROM product_categories WHERE id = $3
+                UNION ALL
+                SELECT pc.id FROM product_categories AS pc
+                JOIN category_tree AS ct ON pc.parent_id = ct.id
+            )
+            SELECT
+                p.id,
+                p.name,
+                p.sku,
+                p.barcode,
+                mu.name AS measure,
+                pc.name AS category,
+                o.name AS organization,
+                p.created_at
+            FROM products AS p
+            LEFT JOIN measure_units AS mu on mu.id = p.measure_unit_id
+            LEFT JOIN organizations AS o ON o.id = p.organization_id
+            LEFT JOIN product_categories AS pc ON pc.id = p.category_id
+            WHERE
+                CASE
+                    WHEN $1::bigint IS NOT NULL AND $2 not in ('Admin', 'Developer') THEN
+                        p.organization_id = $1
+                    ELSE TRUE
+                END
+                AND ($3::bigint IS NULL OR p.category_id IN (SELECT id FROM category_tree))
+            ORDER BY p.id DESC",
+        )
+        .bind(current_user.organization_id)
+        .bind(current_user.role.to_string())
+        .bind(q.category_id)
+        .fetch(&pool);
+
+        forward(rows, tx, |row: ProductRow| {
+            vec![
+                row.id.into(),
+                row.name.into(),
+                row.sku.into(),
+                row.barcode.into(),
+                row.measure.into(),
+                row.category.into(),
+                row.organization.into(),
+                row.created_at.into(),
+            ]
+        })
+        .await;
+    });
+
+    Ok(table)
+}
+
+#[derive(sqlx::FromRow)]
+struct MeasureRow {
+    id: i64,
+    name: String,
+    organization: Option<String>,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+pub async fn measures_table(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<Table, AppError> {
+    // Бизнес логика выгрузки единиц измерения
+
+    let (tx, table) = Table::channel(
+        "Единицы измерения",
+        columns(&[
+            ("id", "#"),
+            ("name", "Наименование"),
+            ("organization", "Организация"),
+            ("created_at", "Создана"),
+        ]),
+    );
+
+    tokio::spawn(async move {
+        let rows = sqlx::query_as::<_, MeasureRow>(
+            "SELECT
+                mu.id,
+                mu.name,
+                o.name AS organization,
+                mu.created_at
+            FROM measure_units AS mu
+            LEFT JOIN organizations AS o ON o.id = mu.organization_id
+            WHERE
+                CASE
+                    WHEN $1::bigint IS NOT NULL AND $2 not in ('Admin', 'Developer') THEN
+                        mu.organization_id = $1
+                    ELSE TRUE
+                END
+            ORDER BY mu.id DESC",
+        )
+        .bind(current_user.organization_id)
+        .bind(current_user.role.to_string())
+        .fetch(&pool);
+
+        forward(rows, tx, |row: MeasureRow| {
+            vec![
+                row.id.into(),
+                row.name.into(),
+                row.organization.into(),
+                row.created_at.into(),
+            ]
+        })
+        .await;
+    });
+
+    Ok(table)
+}
+
+#[derive(sqlx::FromRow)]
+struct UserRow {
+    id: i64,
+    fio: String,
+    email: String,
+    role: String,
+    blocked: bool,
+    organization: Option<String>,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+pub async fn users_table(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<Table, AppError> {
+    // Бизнес логика выгрузки пользователей, доступна администратору и Директору
+
+    if !check_access(current_user.role) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    let (tx, table) = Table::channel(
+        "Пользователи",
+        columns(&[
+            ("id", "#"),
+            ("fio", "ФИО"),
+            ("email", "Email"),
+            ("role", "Роль"),
+            ("blocked", "Заблокирован"),
+            ("organization", "Организация"),
+            ("created_at", "Создан"),
+        ]),
+    );
+
+    tokio::spawn(async move {
+        let rows = sqlx::query_as::<_, UserRow>(
+            "SELECT
+              u.id,
+              u.fio,
+              u.email,
+              u.role,
+              u.blocked,
+              o.name AS organization,
+              u.created_at
+            FROM
+              users AS u
+              LEFT JOIN organizations AS o ON o.id = u.organization_id
+            WHERE
+              CASE
+                WHEN $1::bigint IS NOT NULL AND $2 = 'Director' THEN
+                    u.organization_id = $1
+                ELSE TRUE
+              END
+            ORDER BY u.id DESC",
+        )
+        .bind(current_user.organization_id)
+        .bind(current_user.role.to_string())
+        .fetch(&pool);
+
+        forward(rows, tx, |row: UserRow| {
+            vec![
+                row.id.into(),
+                row.fio.into(),
+                row.email.into(),
+                row.role.into(),
+                row.blocked.into(),
+                row.organization.into(),
+                row.created_at.into(),
+            ]
+        })
+        .await;
+    });
+
+    Ok(table)
+}
+
+pub async fn analitics_table(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<analitic::Q>,
+) -> Result<Table, AppError> {
+    // Бизнес логика выгрузки аналитики. Отчет агрегированный,
+    // поэтому строится целиком теми же правилами, что и на странице аналитики.
+    // В отличие от списков, все строки отчета держатся в памяти до выгрузки: их число
+    // ограничено парами продукт - пользователь (при группировке - на число периодов),
+    // а не количеством записей выпуска.
+
+    let items = analitic::get_analitics(State(pool), Extension(current_user), Query(q)).await?;
+    let grouped = items.iter().any(|item| item.period.is_some());
+
+    let mut titles = vec![("id", "#")];
+    if grouped {
+        titles.push(("period", "Период"));
+    }
+    titles.extend([
+        ("product", "Продукт"),
+        ("user", "Пользователь"),
+        ("measure", "Ед.измерения"),
+        ("cnt", "Кол-во"),
+        ("cost_amount", "Себестоимость, руб."),
+        ("price_amount", "Стоимость, руб."),
+    ]);
+
+    let rows = items
+        .into_iter()
+        .map(|item| {
+            let mut row: Vec<Cell> = vec![item.id.into()];
+            if grouped {
+                row.push(item.period.into());
+            }
+            row.extend([
+                item.name.into(),
+                item.fio.into(),
+                item.measure.into(),
+                item.cnt.into(),
+                item.cost_amount.into(),
+                item.price_amount.into(),
+            ]);
+            row
+        })
+        .collect();
+
+    Ok(Table::from_rows("Аналитика", columns(&titles), rows))
+}
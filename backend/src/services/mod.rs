@@ -1,9 +1,27 @@
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
+pub mod attachments;
+pub mod audit;
+pub mod dashboard;
+pub mod equipment;
+pub mod export;
 pub mod init;
+pub mod payroll;
+pub mod periods;
+pub mod plans;
 pub mod produced_goods;
 pub mod rbs;
+pub mod reports;
+pub mod stock;
 pub mod users;
+pub mod work_orders;
+
+pub fn check_money(value: Decimal) -> bool {
+    // Денежные суммы хранятся в NUMERIC(14, 2): неотрицательные, до 12 знаков в целой части
+
+    !value.is_sign_negative() && value < Decimal::new(1_000_000_000_000, 0)
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Items<T> {
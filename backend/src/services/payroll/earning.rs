@@ -0,0 +1,405 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Extension,
+};
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{
+    check_access,
+    services::{produced_goods::analitic::time_zone, Select},
+    AppError, CurrentUser,
+};
+use rust_xlsxwriter::*;
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    #[default]
+    Xlsx,
+    Csv,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Q {
+    pub date_one: chrono::NaiveDate,
+    pub date_two: chrono::NaiveDate,
+
+    // Работники через ;
+    pub user: Option<String>,
+    // Учитывать только подтвержденные Директором записи
+    #[serde(default)]
+    pub confirmed_only: bool,
+    // Границы дней и дата действия расценки считаются в часовом поясе tz, как в аналитике
+    pub tz: Option<String>,
+    #[serde(default)]
+    pub format: ReportFormat,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Item {
+    pub measure: String,
+    // Расценка не задана, если на дату выпуска нет подходящей записи
+    pub rate: Option<Decimal>,
+    pub cnt: i64,
+    pub amount: Decimal,
+
+    pub user: Select,
+    pub product: Select,
+}
+
+async fn earnings(
+    pool: &PgPool,
+    current_user: &CurrentUser,
+    q: &Q,
+    user_ids: Option<Vec<i64>>,
+) -> Result<Vec<Item>, AppError> {
+    // Начисления по сдельным расценкам: выпуск работника с учетом одобренных корректировок.
+    // Для каждой записи выбирается расценка, действующая на дату выпуска;
+    // расценка для смены и роли работника приоритетнее общей расценки продукта.
+    // Отклоненные Директором записи не оплачиваются.
+
+    let tz = time_zone(pool, &q.tz).await?;
+
+    let rows = sqlx::query!(
+        "SELECT
+          u.id AS user_id,
+          u.fio,
+          p.id AS product_id,
+          p.name AS product,
+          mu.name AS measure,
+          r.rate AS \"rate?\",
+          SUM(pg.cnt + COALESCE(pa.adjustment_cnt, 0))::bigint AS cnt,
+          SUM((pg.cnt + COALESCE(pa.adjustment_cnt, 0)) * COALESCE(r.rate, 0)) AS amount
+        FROM
+          produced_goods AS pg
+          JOIN users AS u ON u.id = pg.user_id
+          JOIN products AS p ON p.id = pg.product_id
+          JOIN measure_units AS mu ON mu.id = p.measure_unit_id
+          LEFT JOIN (
+            SELECT
+              produced_good_id,
+              SUM(cnt::bigint) AS adjustment_cnt
+            FROM
+              produced_good_adjustments
+            WHERE
+              status = 'Approved'
+            GROUP BY
+              produced_good_id
+          ) pa ON pa.produced_good_id = pg.id
+          LEFT JOIN LATERAL (
+            SELECT
+              pr.rate
+            FROM
+              piece_rates AS pr
+            WHERE
+              pr.product_id = pg.product_id
+              AND pr.valid_from <= (pg.created_at AT TIME ZONE $7)::date
+              AND (pr.role IS NULL OR pr.role = u.role)
+              AND (pr.shift_id IS NULL OR pr.shift_id = pg.shift_id)
+            ORDER BY
+              pr.shift_id IS NOT NULL DESC,
+              pr.role IS NOT NULL DESC,
+              pr.valid_from DESC
+            LIMIT 1
+          ) r ON TRUE
+        WHERE (pg.created_at AT TIME ZONE $7)::date BETWEEN $1 AND $2
+        AND pg.status <> 'Rejected'
+        AND CASE
+            WHEN $3::bigint IS NOT NULL AND $4 not in ('Admin', 'Developer') THEN
+              pg.organization_id = $3
+            ELSE TRUE
+          END
+        AND ($5::bigint[] IS NULL OR u.id = ANY($5))
+        AND ($6::bool IS NOT TRUE OR pg.status = 'Confirmed')
+        GROUP BY
+          u.id,
+          p.id,
+          mu.name,
+          r.rate
+        ORDER BY
+          u.fio,
+          u.id,
+          p.name,
+          r.rate;",
+        q.date_one,
+        q.date_two,
+        current_user.organization_id,
+        current_user.role.to_string(),
+        user_ids.as_deref(),
+        q.confirmed_only,
+        tz,
+    )
+    .map(|row| Item {
+        measure: row.measure,
+        rate: row.rate,
+        cnt: row.cnt.map_or(0, |cnt| cnt),
+        amount: row.amount.unwrap_or_default(),
+        user: Select {
+            id: row.user_id,
+            name: row.fio,
+        },
+        product: Select {
+            id: row.product_id,
+            name: row.product,
+        },
+    })
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+pub async fn get_earnings(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Vec<Item>, AppError> {
+    // Бизнес логика расчета сдельной оплаты работников за период
+
+    if !check_access(current_user.role) {
+        return Err(AppError(
+            StatusCode::NOT_FOUND,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    let user_ids = q.user.as_ref().filter(|u| !u.is_empty()).map(|u| {
+        u.split(';')
+            .filter_map(|s| s.parse::<i64>().ok())
+            .collect::<Vec<i64>>()
+    });
+
+    earnings(&pool, &current_user, &q, user_ids).await
+}
+
+pub async fn get_my_earnings(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Vec<Item>, AppError> {
+    // Бизнес логика расчета сдельной оплаты текущего пользователя за период
+
+    earnings(&pool, &current_user, &q, Some(vec![current_user.id])).await
+}
+
+fn user_total(items: &[Item], user_id: i64) -> Decimal {
+    // Сумма начислений работника по всем продуктам и расценкам
+
+    items
+        .iter()
+        .filter(|item| item.user.id == user_id)
+        .map(|item| item.amount)
+        .sum()
+}
+
+pub async fn generate_excel(
+    items: Vec<Item>,
+    date_one: chrono::NaiveDate,
+    date_two: chrono::NaiveDate,
+) -> Result<Vec<u8>, AppError> {
+    let mut wookbook = Workbook::new();
+
+    // Суммы хранятся точно и переводятся в число Excel только при записи в ячейку
+    let money = |value: Decimal| value.to_f64().unwrap_or_default();
+
+    // formats
+    let text_fmt = Format::new().set_border(FormatBorder::Thin);
+    let money_fmt = Format::new()
+        .set_num_format("#,##0.00")
+        .set_border(FormatBorder::Thin);
+    let total_fmt = Format::new()
+        .set_bold()
+        .set_num_format("#,##0.00")
+        .set_background_color(Color::RGB(0xEDEDED))
+        .set_border(FormatBorder::Thin);
+
+    let worksheet = wookbook.add_worksheet();
+
+    worksheet.set_column_width(0, 30)?;
+    worksheet.set_column_width(1, 30)?;
+    worksheet.set_column_width(2, 15)?;
+    worksheet.set_column_width(3, 12)?;
+    worksheet.set_column_width(4, 12)?;
+    worksheet.set_column_width(5, 15)?;
+
+    let _ = worksheet.merge_range(
+        0,
+        0,
+        0,
+        5,
+        &format!(
+            "Ведомость сдельной оплаты за период: {} - {}",
+            date_one.format("%d.%m.%Y"),
+            date_two.format("%d.%m.%Y")
+        ),
+        &Format::new()
+            .set_bold()
+            .set_align(FormatAlign::Center)
+            .set_border(FormatBorder::Thin),
+    );
+    let _ = worksheet.set_row_height(0, 30);
+    let _ = worksheet.set_row_height(1, 20);
+
+    for (i, title) in [
+        "Работник",
+        "Продукт",
+        "Ед.измерения",
+        "Кол-во",
+        "Расценка, руб.",
+        "Сумма, руб.",
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let _ = worksheet.write_with_format(
+            1,
+            i as u16,
+            title,
+            &Format::new()
+                .set_bold()
+                .set_align(FormatAlign::Center)
+                .set_background_color(Color::RGB(0xC6C6C6))
+                .set_border(FormatBorder::Thin),
+        );
+    }
+
+    let mut i = 2;
+    // Итог по работнику выводится после его последней строки
+    for (n, item) in items.iter().enumerate() {
+        let _ = worksheet.write_with_format(i, 0, item.user.name.clone(), &text_fmt);
+        let _ = worksheet.write_with_format(i, 1, item.product.name.clone(), &text_fmt);
+        let _ = worksheet.write_with_format(i, 2, item.measure.clone(), &text_fmt);
+        let _ = worksheet.write_with_format(i, 3, item.cnt, &text_fmt);
+        let _ = match item.rate {
+            Some(rate) => worksheet.write_with_format(i, 4, money(rate), &money_fmt),
+            None => worksheet.write_with_format(i, 4, "не задана", &text_fmt),
+        };
+        let _ = worksheet.write_with_format(i, 5, money(item.amount), &money_fmt);
+        i += 1;
+
+        if items.get(n + 1).map(|next| next.user.id) != Some(item.user.id) {
+            let total = user_total(&items, item.user.id);
+
+            let _ = worksheet.merge_range(
+                i,
+                0,
+                i,
+                4,
+                &format!("Итого: {}", item.user.name),
+                &total_fmt,
+            );
+            let _ = worksheet.write_with_format(i, 5, money(total), &total_fmt);
+            i += 1;
+        }
+    }
+
+    let _ = worksheet.merge_range(i, 0, i, 4, "Итого по ведомости", &total_fmt);
+    let _ = worksheet.write_with_format(
+        i,
+        5,
+        money(items.iter().map(|item| item.amount).sum()),
+        &total_fmt,
+    );
+
+    let buffer = wookbook.save_to_buffer()?;
+
+    Ok(buffer)
+}
+
+fn csv_field(value: &str) -> String {
+    // Экранирование значения для CSV
+
+    if value.contains([';', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn generate_csv(items: Vec<Item>) -> Vec<u8> {
+    // Ведомость в CSV с разделителем ; и BOM, чтобы Excel корректно открыл кириллицу
+
+    let mut csv =
+        String::from("\u{feff}user_id;Работник;Продукт;Ед.измерения;Кол-во;Расценка;Сумма\r\n");
+
+    for item in items {
+        csv.push_str(&format!(
+            "{};{};{};{};{};{};{:.2}\r\n",
+            item.user.id,
+            csv_field(&item.user.name),
+            csv_field(&item.product.name),
+            csv_field(&item.measure),
+            item.cnt,
+            item.rate
+                .map(|rate| format!("{:.2}", rate))
+                .unwrap_or_default(),
+            item.amount,
+        ));
+    }
+
+    csv.into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(user_id: i64, fio: &str, product: &str, rate: Option<Decimal>, cnt: i64) -> Item {
+        Item {
+            measure: "шт".to_string(),
+            rate,
+            cnt,
+            amount: rate.map_or(Decimal::ZERO, |rate| rate * Decimal::from(cnt)),
+            user: Select {
+                id: user_id,
+                name: fio.to_string(),
+            },
+            product: Select {
+                id: 1,
+                name: product.to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn totals_by_user() {
+        let items = vec![
+            item(1, "Иванов", "Болт", Some(Decimal::new(15, 1)), 10),
+            item(1, "Иванов", "Гайка", Some(Decimal::new(25, 2)), 4),
+            item(1, "Иванов", "Шайба", None, 100),
+            item(2, "Петров", "Болт", Some(Decimal::new(15, 1)), 2),
+            item(2, "Петров", "Гайка", Some(Decimal::new(10, 2)), 3),
+        ];
+
+        assert_eq!(user_total(&items, 1), Decimal::new(16, 0));
+        // 0.1 * 3 без ошибки округления двоичной дроби
+        assert_eq!(user_total(&items, 2), Decimal::new(33, 1));
+        assert_eq!(user_total(&items, 3), Decimal::ZERO);
+    }
+
+    #[test]
+    fn csv_escapes_fields() {
+        assert_eq!(csv_field("Болт М8"), "Болт М8");
+        assert_eq!(csv_field("Болт; М8"), "\"Болт; М8\"");
+        assert_eq!(csv_field("Болт \"М8\""), "\"Болт \"\"М8\"\"\"");
+        assert_eq!(csv_field("Болт\nМ8"), "\"Болт\nМ8\"");
+    }
+
+    #[test]
+    fn csv_report() {
+        let csv = generate_csv(vec![
+            item(1, "Иванов; И.И.", "Болт", Some(Decimal::new(15, 1)), 10),
+            item(2, "Петров", "Шайба", None, 3),
+        ]);
+        let csv = String::from_utf8(csv).unwrap();
+        let lines = csv.split("\r\n").collect::<Vec<&str>>();
+
+        assert!(lines[0].starts_with('\u{feff}'));
+        assert_eq!(lines[1], "1;\"Иванов; И.И.\";Болт;шт;10;1.50;15.00");
+        assert_eq!(lines[2], "2;Петров;Шайба;шт;3;;0.00");
+        assert_eq!(lines[3], "");
+    }
+}
@@ -0,0 +1,2 @@
+pub mod earning;
+pub mod piece_rate;
@@ -0,0 +1,337 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{
+    check_access,
+    services::{check_money, Items, Select},
+    AppError, CurrentUser, Role,
+};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RequestBody {
+    product_id: i64,
+    // Необязательные уточнения расценки: роль работника и смена
+    role: Option<Role>,
+    shift_id: Option<i64>,
+    rate: Decimal,
+    valid_from: chrono::NaiveDate,
+}
+
+async fn validate(
+    pool: &PgPool,
+    current_user: &CurrentUser,
+    id: Option<i64>,
+    body: &RequestBody,
+) -> Result<i64, AppError> {
+    // Проверка расценки: сумма, организация продукта и смены, уникальность на дату.
+    // Возвращает организацию продукта.
+
+    if !check_money(body.rate) {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Расценка должна быть от 0 до 999 999 999 999,99!"),
+        ));
+    }
+
+    let organization_id: Option<i64> = sqlx::query_scalar(
+        "SELECT p.organization_id
+        FROM products AS p
+        WHERE p.id = $1
+        AND CASE
+            WHEN $2::bigint IS NOT NULL AND $3 not in ('Admin', 'Developer') THEN
+                p.organization_id = $2
+            ELSE TRUE
+        END
+        AND ($4::bigint IS NULL OR EXISTS (
+            SELECT 1 FROM shifts AS s WHERE s.id = $4 AND s.organization_id = p.organization_id
+        ))",
+    )
+    .bind(body.product_id)
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .bind(body.shift_id)
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(organization_id) = organization_id else {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Продукт и смена должны принадлежать вашей организации!"),
+        ));
+    };
+
+    let exists: bool = sqlx::query_scalar(
+        "SELECT EXISTS (
+          SELECT 1 FROM piece_rates
+          WHERE product_id = $1
+          AND role IS NOT DISTINCT FROM $2
+          AND shift_id IS NOT DISTINCT FROM $3
+          AND valid_from = $4
+          AND ($5::bigint IS NULL OR id <> $5)
+        )",
+    )
+    .bind(body.product_id)
+    .bind(body.role.map(|role| role.to_string()))
+    .bind(body.shift_id)
+    .bind(body.valid_from)
+    .bind(id)
+    .fetch_one(pool)
+    .await?;
+
+    if exists {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!(
+                "Расценка с такими условиями на {} уже задана!",
+                body.valid_from.format("%d.%m.%Y")
+            ),
+        ));
+    }
+
+    Ok(organization_id)
+}
+
+pub async fn create_piece_rate(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(body): Json<RequestBody>,
+) -> Result<i64, AppError> {
+    // Бизнес логика создания сдельной расценки
+
+    if !check_access(current_user.role) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    let organization_id = validate(&pool, &current_user, None, &body).await?;
+
+    let row: (i64,) = sqlx::query_as(
+        "INSERT INTO
+          piece_rates (organization_id, product_id, role, shift_id, rate, valid_from, user_id)
+        VALUES
+          ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING
+          id",
+    )
+    .bind(organization_id)
+    .bind(body.product_id)
+    .bind(body.role.map(|role| role.to_string()))
+    .bind(body.shift_id)
+    .bind(body.rate)
+    .bind(body.valid_from)
+    .bind(current_user.id)
+    .fetch_one(&pool)
+    .await?;
+
+    Ok(row.0)
+}
+
+pub async fn edit_piece_rate(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+    Json(body): Json<RequestBody>,
+) -> Result<i64, AppError> {
+    // Бизнес логика редактирования сдельной расценки
+
+    if !check_access(current_user.role) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    let organization_id = validate(&pool, &current_user, Some(id), &body).await?;
+
+    let result = sqlx::query(
+        "UPDATE piece_rates
+        SET product_id=$1, role=$2, shift_id=$3, rate=$4, valid_from=$5, user_id=$6,
+            organization_id=$7, updated_at=NOW()
+        WHERE id = $8
+        AND CASE
+            WHEN $9::bigint IS NOT NULL AND $10 not in ('Admin', 'Developer') THEN
+                organization_id = $9
+            ELSE TRUE
+        END",
+    )
+    .bind(body.product_id)
+    .bind(body.role.map(|role| role.to_string()))
+    .bind(body.shift_id)
+    .bind(body.rate)
+    .bind(body.valid_from)
+    .bind(current_user.id)
+    .bind(organization_id)
+    .bind(id)
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .execute(&pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError(
+            StatusCode::NOT_FOUND,
+            anyhow::anyhow!("Такой записи не существует"),
+        ));
+    }
+
+    Ok(id)
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Q {
+    pub product_id: Option<i64>,
+    #[serde(default = "page")]
+    pub page: i64,
+    #[serde(default = "per_page")]
+    pub per_page: i64,
+}
+
+fn per_page() -> i64 {
+    15
+}
+
+fn page() -> i64 {
+    1
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Item {
+    pub id: i64,
+    pub role: Option<Role>,
+    pub rate: Decimal,
+    pub valid_from: chrono::NaiveDate,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+
+    product: Select,
+    shift: Option<Select>,
+    user: Select,
+}
+
+pub async fn get_piece_rates(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Items<Item>, AppError> {
+    // Бизнес логика получения списка сдельных расценок
+
+    if !check_access(current_user.role) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    let rows = sqlx::query!(
+        "SELECT
+            pr.id,
+            pr.role,
+            pr.rate,
+            pr.valid_from,
+            pr.created_at,
+            JSONB_BUILD_OBJECT(
+                'id', p.id,
+                'name', p.name
+            ) AS product,
+            CASE WHEN s.id IS NOT NULL THEN
+                JSONB_BUILD_OBJECT(
+                    'id', s.id,
+                    'name', s.name
+                )
+            END AS shift,
+            JSONB_BUILD_OBJECT(
+                'id', author.id,
+                'name', author.fio
+            ) AS user
+        FROM piece_rates AS pr
+        INNER JOIN products AS p ON p.id = pr.product_id
+        LEFT JOIN shifts AS s ON s.id = pr.shift_id
+        LEFT JOIN users AS author ON author.id = pr.user_id
+        WHERE
+            CASE
+                WHEN $1::bigint IS NOT NULL AND $2 not in ('Admin', 'Developer') THEN
+                    pr.organization_id = $1
+                ELSE TRUE
+            END
+            AND ($3::bigint IS NULL OR pr.product_id = $3)
+        ORDER BY p.name, pr.valid_from DESC, pr.id DESC
+        OFFSET $4 LIMIT $5;",
+        current_user.organization_id,
+        current_user.role.to_string(),
+        q.product_id,
+        (q.page - 1) * q.per_page,
+        q.per_page,
+    )
+    .map(|row| Item {
+        id: row.id,
+        role: row.role.map(Role::from),
+        rate: row.rate,
+        valid_from: row.valid_from,
+        created_at: row.created_at,
+        product: row.product.into(),
+        shift: row.shift.map(Select::from),
+        user: row.user.into(),
+    })
+    .fetch_all(&pool)
+    .await?;
+
+    // Подсчет данных для пагинации
+    let cnt: i64 = sqlx::query_scalar(
+        "SELECT COUNT(id) FROM piece_rates AS pr WHERE CASE
+        WHEN $1::bigint IS NOT NULL AND $2 not in ('Admin', 'Developer') THEN
+            pr.organization_id = $1
+        ELSE TRUE
+    END
+    AND ($3::bigint IS NULL OR pr.product_id = $3)",
+    )
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .bind(q.product_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap_or(0);
+
+    let cnt = (cnt as f64 / q.per_page as f64).ceil() as i64;
+
+    Ok(Items { items: rows, cnt })
+}
+
+pub async fn delete_piece_rate(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<(), AppError> {
+    // Бизнес логика удаления сдельной расценки
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        let _ = sqlx::query(
+            "DELETE
+        FROM piece_rates
+        WHERE id = $1
+        AND CASE
+            WHEN $2::bigint IS NOT NULL AND $3 not in ('Admin', 'Developer') THEN
+                organization_id = $2
+            ELSE TRUE
+        END;",
+        )
+        .bind(id)
+        .bind(current_user.organization_id)
+        .bind(current_user.role.to_string())
+        .execute(&pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,260 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use chrono::Datelike;
+
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Transaction};
+
+use crate::{
+    check_access, check_is_admin,
+    services::{audit::log::write_audit, Select},
+    AppError, CurrentUser,
+};
+
+pub async fn check_open_period(
+    tx: &mut Transaction<'_, Postgres>,
+    current_user: &CurrentUser,
+    produced_good_id: i64,
+    action: &str,
+) -> Result<(), AppError> {
+    // Проверка, что запись производства не попадает в закрытый период.
+    // Администратор может изменить такую запись, изменение фиксируется в аудите.
+
+    let row: Option<(Option<i64>, chrono::NaiveDate)> = sqlx::query_as(
+        "SELECT
+          pg.organization_id,
+          cp.period
+        FROM
+          produced_goods AS pg
+          INNER JOIN closed_periods AS cp ON cp.organization_id = pg.organization_id
+            AND cp.period = DATE_TRUNC('month', pg.created_at)::date
+        WHERE
+          pg.id = $1",
+    )
+    .bind(produced_good_id)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    let Some((organization_id, period)) = row else {
+        return Ok(());
+    };
+
+    if !check_is_admin(current_user.role) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!(
+                "Период {} закрыт, изменение записей запрещено!",
+                period.format("%m.%Y")
+            ),
+        ));
+    }
+
+    write_audit(
+        tx,
+        current_user.id,
+        organization_id,
+        "closed_period_override",
+        "produced_goods",
+        Some(produced_good_id),
+        serde_json::json!({ "action": action, "period": period }),
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RequestBody {
+    organization_id: Option<i64>,
+    period: chrono::NaiveDate,
+}
+
+pub async fn close_period(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(body): Json<RequestBody>,
+) -> Result<i64, AppError> {
+    // Бизнес логика закрытия месяца. Директор закрывает период своей организации.
+
+    if !check_access(current_user.role) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    let organization_id = if check_is_admin(current_user.role) {
+        body.organization_id.or(current_user.organization_id)
+    } else {
+        current_user.organization_id
+    };
+
+    let Some(organization_id) = organization_id else {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Не указана организация!"),
+        ));
+    };
+
+    // Период всегда хранится первым днем месяца
+    let period = body.period.with_day(1).unwrap_or(body.period);
+
+    let mut tx = pool.begin().await?;
+
+    let row: Option<(i64,)> = sqlx::query_as(
+        "INSERT INTO
+          closed_periods (organization_id, period, user_id)
+        VALUES
+          ($1, $2, $3)
+        ON CONFLICT (organization_id, period) DO NOTHING
+        RETURNING
+          id",
+    )
+    .bind(organization_id)
+    .bind(period)
+    .bind(current_user.id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some((id,)) = row else {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Период {} уже закрыт!", period.format("%m.%Y")),
+        ));
+    };
+
+    write_audit(
+        &mut tx,
+        current_user.id,
+        Some(organization_id),
+        "close_period",
+        "closed_periods",
+        Some(id),
+        serde_json::json!({ "period": period }),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(id)
+}
+
+pub async fn reopen_period(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<(), AppError> {
+    // Бизнес логика открытия ранее закрытого месяца
+
+    if !check_access(current_user.role) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    let mut tx = pool.begin().await?;
+
+    let row: Option<(i64, chrono::NaiveDate)> = sqlx::query_as(
+        "DELETE FROM closed_periods
+        WHERE id = $1
+        AND CASE
+            WHEN $2::bigint IS NOT NULL AND $3 not in ('Admin', 'Developer') THEN
+                organization_id = $2
+            ELSE TRUE
+        END
+        RETURNING organization_id, period",
+    )
+    .bind(id)
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some((organization_id, period)) = row else {
+        return Err(AppError(
+            StatusCode::NOT_FOUND,
+            anyhow::anyhow!("Такой записи не существует"),
+        ));
+    };
+
+    write_audit(
+        &mut tx,
+        current_user.id,
+        Some(organization_id),
+        "reopen_period",
+        "closed_periods",
+        Some(id),
+        serde_json::json!({ "period": period }),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Item {
+    pub id: i64,
+    pub period: chrono::NaiveDate,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+
+    user: Select,
+    organization: Select,
+}
+
+pub async fn get_closed_periods(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<Vec<Item>, AppError> {
+    // Бизнес логика получения закрытых периодов
+
+    if !check_access(current_user.role) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    let rows = sqlx::query!(
+        "SELECT
+            cp.id,
+            cp.period,
+            cp.created_at,
+            JSONB_BUILD_OBJECT(
+                'id', u.id,
+                'name', u.fio
+            ) AS author,
+            JSONB_BUILD_OBJECT(
+                'id', o.id,
+                'name', o.name
+            ) AS organization
+        FROM closed_periods AS cp
+        INNER JOIN users AS u ON u.id = cp.user_id
+        INNER JOIN organizations AS o ON o.id = cp.organization_id
+        WHERE
+            CASE
+                WHEN $1::bigint IS NOT NULL AND $2 not in ('Admin', 'Developer') THEN
+                    cp.organization_id = $1
+                ELSE TRUE
+            END
+        ORDER BY cp.period DESC;",
+        current_user.organization_id,
+        current_user.role.to_string(),
+    )
+    .map(|row| Item {
+        id: row.id,
+        period: row.period,
+        created_at: row.created_at,
+        user: row.author.into(),
+        organization: row.organization.into(),
+    })
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(rows)
+}
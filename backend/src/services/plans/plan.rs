@@ -0,0 +1,609 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use rust_xlsxwriter::*;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{
+    check_access, check_is_admin,
+    services::{Items, Select},
+    AppError, CurrentUser,
+};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RequestBody {
+    product_id: i64,
+    user_id: Option<i64>,
+    date_one: chrono::NaiveDate,
+    date_two: chrono::NaiveDate,
+    cnt: i64,
+}
+
+fn validate(body: &RequestBody) -> Result<(), AppError> {
+    // Проверка корректности периода и плановой цифры
+
+    if body.date_one > body.date_two {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Дата начала периода не может быть больше даты окончания!"),
+        ));
+    }
+
+    if body.cnt <= 0 {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Плановое кол-во должно быть больше нуля!"),
+        ));
+    }
+
+    Ok(())
+}
+
+async fn plan_organization(
+    pool: &PgPool,
+    current_user: &CurrentUser,
+    body: &RequestBody,
+) -> Result<i64, AppError> {
+    // План относится к организации продукта, исполнитель должен быть из той же организации
+
+    let organization_id: Option<i64> =
+        sqlx::query_scalar("SELECT organization_id FROM products WHERE id = $1")
+            .bind(body.product_id)
+            .fetch_optional(pool)
+            .await?;
+
+    let Some(organization_id) = organization_id else {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Такого продукта не существует!"),
+        ));
+    };
+
+    if !check_is_admin(current_user.role) && current_user.organization_id != Some(organization_id) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("Продукт принадлежит другой организации!"),
+        ));
+    }
+
+    if let Some(user_id) = body.user_id {
+        let user_organization_id: Option<Option<i64>> =
+            sqlx::query_scalar("SELECT organization_id FROM users WHERE id = $1")
+                .bind(user_id)
+                .fetch_optional(pool)
+                .await?;
+
+        if user_organization_id != Some(Some(organization_id)) {
+            return Err(AppError(
+                StatusCode::BAD_REQUEST,
+                anyhow::anyhow!("Исполнитель должен быть из организации продукта!"),
+            ));
+        }
+    }
+
+    Ok(organization_id)
+}
+
+pub async fn create_plan(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(body): Json<RequestBody>,
+) -> Result<i64, AppError> {
+    // Бизнес логика создания плана
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        validate(&body)?;
+        let organization_id = plan_organization(&pool, &current_user, &body).await?;
+
+        let row: (i64,) = sqlx::query_as(
+            "INSERT INTO
+              production_plans (product_id, user_id, date_one, date_two, cnt, organization_id)
+            VALUES
+              ($1, $2, $3, $4, $5, $6)
+            RETURNING
+              id",
+        )
+        .bind(body.product_id)
+        .bind(body.user_id)
+        .bind(body.date_one)
+        .bind(body.date_two)
+        .bind(body.cnt)
+        .bind(organization_id)
+        .fetch_one(&pool)
+        .await?;
+
+        Ok(row.0)
+    }
+}
+
+pub async fn edit_plan(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+    Json(body): Json<RequestBody>,
+) -> Result<i64, AppError> {
+    // Бизнес логика редактирования плана
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        validate(&body)?;
+        let organization_id = plan_organization(&pool, &current_user, &body).await?;
+
+        let result = sqlx::query(
+            "UPDATE
+              production_plans
+            SET
+              product_id = $1,
+              user_id = $2,
+              date_one = $3,
+              date_two = $4,
+              cnt = $5,
+              organization_id = $7,
+              updated_at = NOW()
+            WHERE
+              id = $6
+              AND CASE
+                WHEN $9 not in ('Admin', 'Developer') THEN organization_id = $8::bigint
+                ELSE TRUE
+              END",
+        )
+        .bind(body.product_id)
+        .bind(body.user_id)
+        .bind(body.date_one)
+        .bind(body.date_two)
+        .bind(body.cnt)
+        .bind(id)
+        .bind(organization_id)
+        .bind(current_user.organization_id)
+        .bind(current_user.role.to_string())
+        .execute(&pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError(
+                StatusCode::NOT_FOUND,
+                anyhow::anyhow!("Такой записи не существует"),
+            ));
+        }
+
+        Ok(id)
+    }
+}
+
+pub async fn delete_plan(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<(), AppError> {
+    // Бизнес логика удаления плана
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        let _ = sqlx::query(
+            "DELETE
+        FROM production_plans
+        WHERE id = $1
+        AND CASE
+            WHEN $3 not in ('Admin', 'Developer') THEN organization_id = $2::bigint
+            ELSE TRUE
+        END;",
+        )
+        .bind(id)
+        .bind(current_user.organization_id)
+        .bind(current_user.role.to_string())
+        .execute(&pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Q {
+    #[serde(default = "page")]
+    pub page: i64,
+    #[serde(default = "per_page")]
+    pub per_page: i64,
+}
+
+fn per_page() -> i64 {
+    15
+}
+
+fn page() -> i64 {
+    1
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Item {
+    pub id: i64,
+    pub date_one: chrono::NaiveDate,
+    pub date_two: chrono::NaiveDate,
+    pub cnt: i64,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+
+    product: Select,
+    user: Option<Select>,
+    organization: Select,
+}
+
+pub async fn get_plans(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Items<Item>, anyhow::Error> {
+    // Бизнес логика получения списка планов
+
+    let rows = sqlx::query!(
+        "SELECT
+            pp.id,
+            pp.date_one,
+            pp.date_two,
+            pp.cnt,
+            pp.created_at,
+            JSONB_BUILD_OBJECT(
+                'id', p.id,
+                'name', p.name
+            ) AS product,
+            CASE
+                WHEN u.id IS NOT NULL THEN
+                    JSONB_BUILD_OBJECT(
+                        'id', u.id,
+                        'name', u.fio
+                    )
+                ELSE NULL
+            END AS assignee,
+            JSONB_BUILD_OBJECT(
+                'id', o.id,
+                'name', o.name
+            ) AS organization
+        FROM production_plans AS pp
+        INNER JOIN products AS p ON p.id = pp.product_id
+        LEFT JOIN users AS u ON u.id = pp.user_id
+        LEFT JOIN organizations AS o ON o.id = pp.organization_id
+        WHERE
+            CASE
+                WHEN $2 not in ('Admin', 'Developer') THEN pp.organization_id = $1::bigint
+                ELSE TRUE
+            END
+        ORDER BY pp.date_one DESC, pp.id DESC
+        OFFSET $3 LIMIT $4;",
+        current_user.organization_id,
+        current_user.role.to_string(),
+        (q.page - 1) * q.per_page,
+        q.per_page,
+    )
+    .map(|row| Item {
+        id: row.id,
+        date_one: row.date_one,
+        date_two: row.date_two,
+        cnt: row.cnt,
+        created_at: row.created_at,
+        product: row.product.into(),
+        user: row.assignee.map(|u| u.into()),
+        organization: row.organization.into(),
+    })
+    .fetch_all(&pool)
+    .await?;
+
+    // Подсчет данных для пагинации
+    let cnt: i64 = sqlx::query_scalar(
+        "SELECT COUNT(id) FROM production_plans AS pp WHERE CASE
+        WHEN $2 not in ('Admin', 'Developer') THEN pp.organization_id = $1::bigint
+        ELSE TRUE
+    END",
+    )
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .fetch_one(&pool)
+    .await
+    .unwrap_or(0);
+
+    let cnt = (cnt as f64 / q.per_page as f64).ceil() as i64;
+
+    Ok(Items { items: rows, cnt })
+}
+
+pub async fn detail_plan(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<Item, AppError> {
+    // Бизнес логика получения плана
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        let row = sqlx::query!(
+            "SELECT
+                pp.id,
+                pp.date_one,
+                pp.date_two,
+                pp.cnt,
+                pp.created_at,
+                JSONB_BUILD_OBJECT(
+                    'id', p.id,
+                    'name', p.name
+                ) AS product,
+                CASE
+                    WHEN u.id IS NOT NULL THEN
+                        JSONB_BUILD_OBJECT(
+                            'id', u.id,
+                            'name', u.fio
+                        )
+                    ELSE NULL
+                END AS assignee,
+                JSONB_BUILD_OBJECT(
+                    'id', o.id,
+                    'name', o.name
+                ) AS organization
+            FROM production_plans AS pp
+            INNER JOIN products AS p ON p.id = pp.product_id
+            LEFT JOIN users AS u ON u.id = pp.user_id
+            LEFT JOIN organizations AS o ON o.id = pp.organization_id
+            WHERE pp.id = $1
+            AND CASE
+                WHEN $3 not in ('Admin', 'Developer') THEN pp.organization_id = $2::bigint
+                ELSE TRUE
+            END;",
+            id,
+            current_user.organization_id,
+            current_user.role.to_string(),
+        )
+        .fetch_optional(&pool)
+        .await?;
+
+        match row {
+            // Собираем в нужный вид
+            Some(row) => Ok(Item {
+                id: row.id,
+                date_one: row.date_one,
+                date_two: row.date_two,
+                cnt: row.cnt,
+                created_at: row.created_at,
+                product: row.product.into(),
+                user: row.assignee.map(|u| u.into()),
+                organization: row.organization.into(),
+            }),
+            None => Err(AppError(
+                StatusCode::NOT_FOUND,
+                anyhow::anyhow!("Такой записи не существует"),
+            )),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct QFact {
+    pub date_one: chrono::NaiveDate,
+    pub date_two: chrono::NaiveDate,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ItemFact {
+    pub id: i64,
+    pub date_one: chrono::NaiveDate,
+    pub date_two: chrono::NaiveDate,
+    pub name: String,
+    pub measure: String,
+    pub fio: Option<String>,
+    pub plan: i64,
+    pub fact: i64,
+    pub percent: f64,
+}
+
+pub async fn get_plan_fact(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<QFact>,
+) -> Result<Vec<ItemFact>, AppError> {
+    // Бизнес логика сравнения плана и факта за период.
+    // Факт считается по производству с учетом корректировок внутри периода плана.
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        let rows = sqlx::query!(
+            "SELECT
+              pp.id,
+              pp.date_one,
+              pp.date_two,
+              pp.cnt AS plan,
+              p.name AS name,
+              mu.name AS measure,
+              u.fio AS \"fio?\",
+              (
+                SELECT
+                  COALESCE(SUM(pg.cnt + COALESCE(pa.adjustment_cnt::bigint, 0)), 0)
+                FROM
+                  produced_goods AS pg
+                  LEFT JOIN (
+                    SELECT
+                      produced_good_id,
+                      SUM(cnt::bigint) AS adjustment_cnt
+                    FROM
+                      produced_good_adjustments
+                    WHERE
+                      status = 'Approved'
+                    GROUP BY
+                      produced_good_id
+                  ) pa ON pa.produced_good_id = pg.id
+                WHERE
+                  pg.product_id = pp.product_id
+                  AND pg.created_at::date BETWEEN pp.date_one AND pp.date_two
+                  AND (pp.user_id IS NULL OR pg.user_id = pp.user_id)
+                  AND pg.status <> 'Rejected'
+              )::bigint AS fact
+            FROM
+              production_plans AS pp
+              JOIN products AS p ON p.id = pp.product_id
+              JOIN measure_units AS mu ON mu.id = p.measure_unit_id
+              LEFT JOIN users AS u ON u.id = pp.user_id
+            WHERE pp.date_one <= $2 AND pp.date_two >= $1
+            AND CASE
+                WHEN $4 not in ('Admin', 'Developer') THEN pp.organization_id = $3::bigint
+                ELSE TRUE
+              END
+            ORDER BY
+              pp.date_one,
+              pp.id;",
+            q.date_one,
+            q.date_two,
+            current_user.organization_id,
+            current_user.role.to_string(),
+        )
+        .map(|row| {
+            let fact = row.fact.map_or(0, |f| f);
+
+            ItemFact {
+                id: row.id,
+                date_one: row.date_one,
+                date_two: row.date_two,
+                name: row.name,
+                measure: row.measure,
+                fio: row.fio,
+                plan: row.plan,
+                fact,
+                percent: percent(fact, row.plan),
+            }
+        })
+        .fetch_all(&pool)
+        .await?;
+
+        Ok(rows)
+    }
+}
+
+fn percent(fact: i64, plan: i64) -> f64 {
+    // Процент выполнения плана, округленный до сотых
+
+    if plan == 0 {
+        0.0
+    } else {
+        (fact as f64 * 10000.0 / plan as f64).round() / 100.0
+    }
+}
+
+pub async fn generate_excel(
+    items: Vec<ItemFact>,
+    date_one: chrono::NaiveDate,
+    date_two: chrono::NaiveDate,
+) -> Result<Vec<u8>, AppError> {
+    let mut wookbook = Workbook::new();
+
+    // formats
+    let right_fmt = Format::new()
+        .set_align(FormatAlign::Right)
+        .set_border(FormatBorder::Thin);
+
+    // Add a worksheet to the workbook.
+    let worksheet = wookbook.add_worksheet();
+
+    // Set the column weight
+    worksheet.set_column_width(0, 8)?;
+    worksheet.set_column_width(1, 25)?;
+    worksheet.set_column_width(2, 25)?;
+    worksheet.set_column_width(3, 25)?;
+    worksheet.set_column_width(4, 15)?;
+    worksheet.set_column_width(5, 12)?;
+    worksheet.set_column_width(6, 12)?;
+    worksheet.set_column_width(7, 15)?;
+
+    let _ = worksheet.merge_range(
+        0,
+        0,
+        0,
+        7,
+        &format!(
+            "Выполнение плана производства за период: {} - {}",
+            date_one.format("%d.%m.%Y"),
+            date_two.format("%d.%m.%Y")
+        ),
+        &Format::new()
+            .set_bold()
+            .set_align(FormatAlign::Center)
+            .set_border(FormatBorder::Thin),
+    );
+    // Высота
+    let _ = worksheet.set_row_height(0, 30);
+    let _ = worksheet.set_row_height(1, 20);
+
+    for (i, title) in [
+        "#",
+        "Продукт",
+        "Исполнитель",
+        "Период",
+        "Ед.измерения",
+        "План",
+        "Факт",
+        "Выполнение, %",
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let _ = worksheet.write_with_format(
+            1,
+            i as u16,
+            title,
+            &Format::new()
+                .set_bold()
+                .set_align(FormatAlign::Center)
+                .set_background_color(Color::RGB(0xC6C6C6))
+                .set_border(FormatBorder::Thin),
+        );
+    }
+
+    let num_fmt = Format::new().set_border(FormatBorder::Thin);
+    let percent_fmt = Format::new()
+        .set_num_format("0.00")
+        .set_border(FormatBorder::Thin);
+
+    let mut i = 2;
+    items.iter().for_each(|item| {
+        let _ = worksheet.write_with_format(i, 0, item.id, &right_fmt);
+        let _ = worksheet.write_with_format(i, 1, item.name.clone(), &right_fmt);
+        let _ = worksheet.write_with_format(i, 2, item.fio.clone().unwrap_or_default(), &right_fmt);
+        let _ = worksheet.write_with_format(
+            i,
+            3,
+            format!(
+                "{} - {}",
+                item.date_one.format("%d.%m.%Y"),
+                item.date_two.format("%d.%m.%Y")
+            ),
+            &right_fmt,
+        );
+        let _ = worksheet.write_with_format(i, 4, item.measure.clone(), &right_fmt);
+        let _ = worksheet.write_with_format(i, 5, item.plan, &num_fmt);
+        let _ = worksheet.write_with_format(i, 6, item.fact, &num_fmt);
+        let _ = worksheet.write_with_format(i, 7, item.percent, &percent_fmt);
+
+        i += 1;
+    });
+
+    let buffer = wookbook.save_to_buffer()?;
+
+    Ok(buffer)
+}
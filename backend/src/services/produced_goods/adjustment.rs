@@ -0,0 +1,332 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Transaction};
+
+use crate::{
+    check_access,
+    services::{
+        periods::closed_period::check_open_period,
+        stock::movement::{post_production, Kind},
+        work_orders::work_order::refresh_work_order,
+        Items, Select,
+    },
+    AppError, CurrentUser,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum Status {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+impl From<&str> for Status {
+    fn from(value: &str) -> Self {
+        match value {
+            "Pending" => Self::Pending,
+            "Rejected" => Self::Rejected,
+            _ => Self::Approved,
+        }
+    }
+}
+
+impl From<String> for Status {
+    fn from(value: String) -> Self {
+        Self::from(value.as_str())
+    }
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Pending => "Pending",
+            Self::Approved => "Approved",
+            Self::Rejected => "Rejected",
+        })
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct AdjustmentRow {
+    produced_good_id: i64,
+    cnt: i64,
+    product_id: i64,
+    work_order_id: Option<i64>,
+    rejected: bool,
+}
+
+pub async fn apply_adjustment(
+    tx: &mut Transaction<'_, Postgres>,
+    user_id: i64,
+    adjustment_id: i64,
+) -> Result<(), anyhow::Error> {
+    // Утвержденная корректировка проводится по складу и меняет прогресс заказа.
+    // Выпуск отклоненной записи уже списан со склада, ее корректировки не проводятся.
+
+    let row: AdjustmentRow = sqlx::query_as(
+        "SELECT
+          pga.produced_good_id,
+          pga.cnt,
+          pg.product_id,
+          pg.work_order_id,
+          pg.status = 'Rejected' AS rejected
+        FROM
+          produced_good_adjustments AS pga
+          INNER JOIN produced_goods AS pg ON pg.id = pga.produced_good_id
+        WHERE
+          pga.id = $1",
+    )
+    .bind(adjustment_id)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    if !row.rejected {
+        post_production(
+            tx,
+            Kind::Adjustment,
+            user_id,
+            row.produced_good_id,
+            Some(adjustment_id),
+            row.product_id,
+            row.cnt,
+        )
+        .await?;
+    }
+
+    if let Some(work_order_id) = row.work_order_id {
+        refresh_work_order(tx, work_order_id).await?;
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RequestBodyStatus {
+    status: Status,
+    comment: Option<String>,
+}
+
+pub async fn review_adjustment(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+    Json(body): Json<RequestBodyStatus>,
+) -> Result<i64, AppError> {
+    // Бизнес логика утверждения или отклонения корректировки Директором
+
+    if !check_access(current_user.role) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    if body.status == Status::Pending {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Корректировку можно только утвердить или отклонить!"),
+        ));
+    }
+
+    let mut tx = pool.begin().await?;
+
+    let row: Option<(String, i64)> = sqlx::query_as(
+        "SELECT pga.status, pga.produced_good_id
+        FROM produced_good_adjustments AS pga
+        INNER JOIN produced_goods AS pg ON pg.id = pga.produced_good_id
+        WHERE pga.id = $1
+        AND CASE
+            WHEN $2::bigint IS NOT NULL THEN pg.organization_id = $2
+            ELSE TRUE
+        END
+        FOR UPDATE OF pga",
+    )
+    .bind(id)
+    .bind(current_user.organization_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    match row.map(|(status, produced_good_id)| (Status::from(status), produced_good_id)) {
+        Some((Status::Pending, produced_good_id)) => {
+            // Утвержденная корректировка меняет итог записи закрытого периода
+            if body.status == Status::Approved {
+                check_open_period(
+                    &mut tx,
+                    &current_user,
+                    produced_good_id,
+                    "approve_adjustment",
+                )
+                .await?;
+            }
+
+            let _ = sqlx::query(
+                "UPDATE produced_good_adjustments
+                SET status = $1, review_comment = $2, reviewed_by = $3, reviewed_at = NOW()
+                WHERE id = $4",
+            )
+            .bind(body.status.to_string())
+            .bind(body.comment)
+            .bind(current_user.id)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+            if body.status == Status::Approved {
+                apply_adjustment(&mut tx, current_user.id, id).await?;
+            }
+
+            tx.commit().await?;
+
+            Ok(id)
+        }
+        Some(_) => Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Корректировка уже рассмотрена!"),
+        )),
+        None => Err(AppError(
+            StatusCode::NOT_FOUND,
+            anyhow::anyhow!("Такой записи не существует"),
+        )),
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Q {
+    #[serde(default = "page")]
+    pub page: i64,
+    #[serde(default = "per_page")]
+    pub per_page: i64,
+    pub status: Option<Status>,
+}
+
+fn per_page() -> i64 {
+    15
+}
+
+fn page() -> i64 {
+    1
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Item {
+    pub id: i64,
+    pub produced_good_id: i64,
+    pub cnt: i64,
+    pub status: Status,
+    pub comment: Option<String>,
+    pub review_comment: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub reviewed_at: Option<chrono::DateTime<chrono::Utc>>,
+
+    reason: Select,
+    product: Select,
+    user: Select,
+}
+
+pub async fn get_adjustments(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Items<Item>, AppError> {
+    // Бизнес логика получения списка корректировок.
+    // Пользователь видит свои корректировки, Директор - по организации.
+
+    let current_user_id = if check_access(current_user.role) {
+        None
+    } else {
+        Some(current_user.id)
+    };
+
+    let rows = sqlx::query!(
+        "SELECT
+            pga.id,
+            pga.produced_good_id,
+            pga.cnt,
+            pga.status,
+            pga.comment,
+            pga.review_comment,
+            pga.created_at,
+            pga.reviewed_at,
+            JSONB_BUILD_OBJECT(
+                'id', ar.id,
+                'name', ar.name
+            ) AS reason,
+            JSONB_BUILD_OBJECT(
+                'id', p.id,
+                'name', p.name
+            ) AS product,
+            JSONB_BUILD_OBJECT(
+                'id', u.id,
+                'name', u.fio
+            ) AS author
+        FROM produced_good_adjustments AS pga
+        INNER JOIN produced_goods AS pg ON pg.id = pga.produced_good_id
+        INNER JOIN products AS p ON p.id = pg.product_id
+        INNER JOIN users AS u ON u.id = pga.user_id
+        LEFT JOIN adjustment_reasons AS ar ON ar.id = pga.reason_id
+        WHERE
+            CASE
+                WHEN $1::bigint IS NOT NULL THEN
+                    pga.user_id = $1
+                WHEN $2::bigint IS NOT NULL AND $3 = 'Director' THEN
+                    pg.organization_id = $2
+                ELSE TRUE
+            END
+            AND ($4::text IS NULL OR pga.status = $4)
+        ORDER BY pga.id DESC
+        OFFSET $5 LIMIT $6;",
+        current_user_id,
+        current_user.organization_id,
+        current_user.role.to_string(),
+        q.status.map(|s| s.to_string()),
+        (q.page - 1) * q.per_page,
+        q.per_page,
+    )
+    .map(|row| Item {
+        id: row.id,
+        produced_good_id: row.produced_good_id,
+        cnt: row.cnt,
+        status: row.status.into(),
+        comment: row.comment,
+        review_comment: row.review_comment,
+        created_at: row.created_at,
+        reviewed_at: row.reviewed_at,
+        reason: row.reason.into(),
+        product: row.product.into(),
+        user: row.author.into(),
+    })
+    .fetch_all(&pool)
+    .await?;
+
+    // Подсчет данных для пагинации
+    let cnt: i64 = sqlx::query_scalar(
+        "SELECT COUNT(pga.id)
+        FROM produced_good_adjustments AS pga
+        INNER JOIN produced_goods AS pg ON pg.id = pga.produced_good_id
+        WHERE
+            CASE
+                WHEN $1::bigint IS NOT NULL THEN
+                    pga.user_id = $1
+                WHEN $2::bigint IS NOT NULL AND $3 = 'Director' THEN
+                    pg.organization_id = $2
+                ELSE TRUE
+            END
+            AND ($4::text IS NULL OR pga.status = $4)",
+    )
+    .bind(current_user_id)
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .bind(q.status.map(|s| s.to_string()))
+    .fetch_one(&pool)
+    .await
+    .unwrap_or(0);
+
+    let cnt = (cnt as f64 / q.per_page as f64).ceil() as i64;
+
+    Ok(Items { items: rows, cnt })
+}
@@ -4,19 +4,163 @@ use axum::{
     Extension,
 };
 
+use chrono::Datelike;
+use rust_decimal::{prelude::ToPrimitive, Decimal};
 use serde::{Deserialize, Serialize};
-use sqlx::PgPool;
+use sqlx::{postgres::PgArguments, query::QueryAs, PgPool, Postgres};
 
 use crate::{check_access, AppError, CurrentUser};
 use rust_xlsxwriter::*;
 
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Period {
+    Day,
+    // Неделя по ISO, с понедельника
+    Week,
+    Month,
+    Quarter,
+}
+
+impl Period {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Day => "day",
+            Self::Week => "week",
+            Self::Month => "month",
+            Self::Quarter => "quarter",
+        }
+    }
+
+    pub fn interval(&self) -> &'static str {
+        match self {
+            Self::Day => "1 day",
+            Self::Week => "1 week",
+            Self::Month => "1 month",
+            Self::Quarter => "3 months",
+        }
+    }
+
+    pub fn label(&self, date: chrono::NaiveDate) -> String {
+        match self {
+            Self::Day => date.format("%d.%m.%Y").to_string(),
+            Self::Week => format!(
+                "{:02} нед. {}",
+                date.iso_week().week(),
+                date.iso_week().year()
+            ),
+            Self::Month => date.format("%m.%Y").to_string(),
+            Self::Quarter => format!("{} кв. {}", date.month0() / 3 + 1, date.year()),
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct Item {
     pub id: i64,
     pub name: String,
     pub measure: String,
     pub fio: String,
+    pub category: Option<String>,
     pub cnt: i64,
+    // Себестоимость и стоимость выпуска по ценам, действующим на дату производства
+    pub cost_amount: Decimal,
+    pub price_amount: Decimal,
+    // Начало периода и его подпись при группировке по времени
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bucket: Option<chrono::NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub period: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comparison: Option<Comparison>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Compare {
+    // Предыдущий период той же длины
+    Previous,
+    // Тот же период прошлого года
+    Year,
+}
+
+impl Compare {
+    fn range(
+        &self,
+        date_one: chrono::NaiveDate,
+        date_two: chrono::NaiveDate,
+    ) -> Option<(chrono::NaiveDate, chrono::NaiveDate)> {
+        match self {
+            Self::Previous => {
+                let days = date_two.signed_duration_since(date_one).num_days() + 1;
+                let date_two = date_one.pred_opt()?;
+                let date_one = date_two.checked_sub_signed(chrono::Duration::days(days - 1))?;
+
+                Some((date_one, date_two))
+            }
+            Self::Year => Some((
+                date_one.checked_sub_months(chrono::Months::new(12))?,
+                date_two.checked_sub_months(chrono::Months::new(12))?,
+            )),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Comparison {
+    // Период сравнения и показатели за него
+    pub date_one: chrono::NaiveDate,
+    pub date_two: chrono::NaiveDate,
+    pub cnt: i64,
+    pub cost_amount: Decimal,
+    pub price_amount: Decimal,
+    // Изменение к периоду сравнения, процент не считается при нулевой базе
+    pub cnt_delta: i64,
+    pub cnt_delta_percent: Option<f64>,
+    pub cost_delta: Decimal,
+    pub cost_delta_percent: Option<f64>,
+    pub price_delta: Decimal,
+    pub price_delta_percent: Option<f64>,
+}
+
+fn delta_percent(value: f64, base: f64) -> Option<f64> {
+    if base == 0.0 {
+        None
+    } else {
+        Some(((value - base) / base * 10000.0).round() / 100.0)
+    }
+}
+
+// Суммы в ячейки xlsx пишутся числом, округление задает формат ячейки
+fn money(value: Decimal) -> f64 {
+    value.to_f64().unwrap_or_default()
+}
+
+impl Comparison {
+    fn new(
+        item: &Item,
+        previous: Option<&Item>,
+        date_one: chrono::NaiveDate,
+        date_two: chrono::NaiveDate,
+    ) -> Self {
+        let cnt = previous.map_or(0, |it| it.cnt);
+        let cost_amount = previous.map_or(Decimal::ZERO, |it| it.cost_amount);
+        let price_amount = previous.map_or(Decimal::ZERO, |it| it.price_amount);
+
+        Self {
+            date_one,
+            date_two,
+            cnt,
+            cost_amount,
+            price_amount,
+            cnt_delta: item.cnt - cnt,
+            cnt_delta_percent: delta_percent(item.cnt as f64, cnt as f64),
+            cost_delta: item.cost_amount - cost_amount,
+            cost_delta_percent: delta_percent(money(item.cost_amount), money(cost_amount)),
+            price_delta: item.price_amount - price_amount,
+            price_delta_percent: delta_percent(money(item.price_amount), money(price_amount)),
+        }
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -25,7 +169,310 @@ pub struct Q {
     pub date_two: chrono::NaiveDate,
 
     pub product: Option<String>,
+    // Пользователи, продукты, организации и единицы измерения через ;
     pub user: Option<String>,
+    pub products: Option<String>,
+    pub organizations: Option<String>,
+    pub measure_units: Option<String>,
+    // Учитывать только подтвержденные Директором записи
+    #[serde(default)]
+    pub confirmed_only: bool,
+    // Категория продуктов вместе с вложенными категориями
+    pub category_id: Option<i64>,
+    // Фильтры по дополнительным полям записи и продукта: JSON-объект {"код": значение}
+    pub custom_fields: Option<String>,
+    pub product_fields: Option<String>,
+    // Группировка по периодам, границы периодов считаются в часовом поясе tz
+    pub group: Option<Period>,
+    pub tz: Option<String>,
+    // Сравнение с предыдущим периодом или тем же периодом прошлого года
+    pub compare: Option<Compare>,
+}
+
+pub async fn time_zone(pool: &PgPool, tz: &Option<String>) -> Result<String, AppError> {
+    // Часовой пояс из справочника PostgreSQL, по умолчанию - пояс сервера БД
+
+    let tz: Option<String> = sqlx::query_scalar(
+        "SELECT CASE
+          WHEN NULLIF(TRIM($1::text), '') IS NULL THEN current_setting('TimeZone')
+          ELSE (SELECT name FROM pg_timezone_names WHERE name = TRIM($1) LIMIT 1)
+        END",
+    )
+    .bind(tz)
+    .fetch_one(pool)
+    .await?;
+
+    tz.ok_or_else(|| {
+        AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Неизвестный часовой пояс!"),
+        )
+    })
+}
+
+fn parse_ids(value: &Option<String>) -> Option<Vec<i64>> {
+    // Список идентификаторов через ;, пустой список не ограничивает выборку
+
+    value.as_ref().filter(|v| !v.is_empty()).map(|v| {
+        v.split(';')
+            .filter(|s| !s.is_empty())
+            .filter_map(|s| s.parse::<i64>().ok())
+            .collect::<Vec<i64>>()
+    })
+}
+
+fn parse_fields_filter(value: &Option<String>) -> Result<Option<serde_json::Value>, AppError> {
+    // Пустой фильтр не ограничивает выборку
+
+    match value.as_deref().map(str::trim) {
+        None | Some("") => Ok(None),
+        Some(value) => match serde_json::from_str::<serde_json::Value>(value) {
+            Ok(value) if value.is_object() => Ok(Some(value)),
+            _ => Err(AppError(
+                StatusCode::BAD_REQUEST,
+                anyhow::anyhow!("Фильтр по дополнительным полям должен быть JSON-объектом!"),
+            )),
+        },
+    }
+}
+
+// Отбор записей выпуска по фильтрам аналитики, общий для отчетов по продуктам,
+// по категориям и сводной таблицы. Параметры $1-$14 привязывает Filter::bind,
+// собственные параметры запросов нумеруются с $15.
+pub const FILTER: &str = "category_tree AS (
+          SELECT id FROM product_categories WHERE id = $8
+          UNION ALL
+          SELECT pc.id FROM product_categories AS pc
+          JOIN category_tree AS ct ON pc.parent_id = ct.id
+        ),
+        filtered AS (
+          SELECT
+            pg.id
+          FROM
+            produced_goods AS pg
+            JOIN products AS p ON p.id = pg.product_id
+          WHERE (pg.created_at AT TIME ZONE $11)::date BETWEEN $1 AND $2
+          AND CASE
+              WHEN $6 not in ('Admin', 'Developer') THEN pg.organization_id = $5::bigint
+              ELSE TRUE
+            END
+          AND ($3::bigint[] IS NULL OR pg.user_id = ANY($3))
+          AND ($4::VARCHAR IS NULL OR p.name ILIKE '%'||$4||'%')
+          AND ($12::bigint[] IS NULL OR p.id = ANY($12))
+          AND ($13::bigint[] IS NULL OR pg.organization_id = ANY($13))
+          AND ($14::bigint[] IS NULL OR p.measure_unit_id = ANY($14))
+          AND pg.status <> 'Rejected'
+          AND ($7::bool IS NOT TRUE OR pg.status = 'Confirmed')
+          AND ($8::bigint IS NULL OR p.category_id IN (SELECT id FROM category_tree))
+          AND ($9::jsonb IS NULL OR pg.custom_fields @> $9)
+          AND ($10::jsonb IS NULL OR p.custom_fields @> $10)
+        )";
+
+pub struct Filter {
+    date_one: chrono::NaiveDate,
+    date_two: chrono::NaiveDate,
+    users: Option<Vec<i64>>,
+    product: Option<String>,
+    organization_id: Option<i64>,
+    role: String,
+    confirmed_only: bool,
+    category_id: Option<i64>,
+    custom_fields: Option<serde_json::Value>,
+    product_fields: Option<serde_json::Value>,
+    pub tz: String,
+    products: Option<Vec<i64>>,
+    organizations: Option<Vec<i64>>,
+    measure_units: Option<Vec<i64>>,
+}
+
+impl Filter {
+    pub async fn new(pool: &PgPool, current_user: &CurrentUser, q: &Q) -> Result<Self, AppError> {
+        // Проверка и разбор параметров фильтра из запроса
+
+        Ok(Self {
+            date_one: q.date_one,
+            date_two: q.date_two,
+            users: parse_ids(&q.user),
+            product: q.product.clone(),
+            organization_id: current_user.organization_id,
+            role: current_user.role.to_string(),
+            confirmed_only: q.confirmed_only,
+            category_id: q.category_id,
+            custom_fields: parse_fields_filter(&q.custom_fields)?,
+            product_fields: parse_fields_filter(&q.product_fields)?,
+            tz: time_zone(pool, &q.tz).await?,
+            products: parse_ids(&q.products),
+            organizations: parse_ids(&q.organizations),
+            measure_units: parse_ids(&q.measure_units),
+        })
+    }
+
+    pub fn bind<'q, O>(
+        &'q self,
+        query: QueryAs<'q, Postgres, O, PgArguments>,
+    ) -> QueryAs<'q, Postgres, O, PgArguments> {
+        // Параметры $1-$14 для FILTER
+
+        query
+            .bind(self.date_one)
+            .bind(self.date_two)
+            .bind(&self.users)
+            .bind(&self.product)
+            .bind(self.organization_id)
+            .bind(&self.role)
+            .bind(self.confirmed_only)
+            .bind(self.category_id)
+            .bind(&self.custom_fields)
+            .bind(&self.product_fields)
+            .bind(&self.tz)
+            .bind(&self.products)
+            .bind(&self.organizations)
+            .bind(&self.measure_units)
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct Row {
+    id: i64,
+    name: String,
+    measure: String,
+    fio: String,
+    category: Option<String>,
+    bucket: Option<chrono::NaiveDate>,
+    cnt: i64,
+    cost_amount: Decimal,
+    price_amount: Decimal,
+}
+
+async fn analitics(
+    pool: &PgPool,
+    current_user: &CurrentUser,
+    q: &Q,
+) -> Result<Vec<Item>, AppError> {
+    // Выпуск по продуктам и пользователям за период, при группировке - по периодам.
+    // Фильтры объединяются через AND, ограничение своей организацией действует всегда.
+
+    let filter = Filter::new(pool, current_user, q).await?;
+
+    // При группировке каждая пара продукт - пользователь получает все периоды диапазона,
+    // периоды без выпуска заполняются нулями
+    let sql = format!(
+        "WITH RECURSIVE {FILTER},
+        base AS (
+          SELECT
+            p.id as id,
+            p.name as name,
+            mu.name as measure,
+            u.fio as fio,
+            pc.name as category,
+            CASE
+              WHEN $15::text IS NOT NULL THEN
+                DATE_TRUNC($15, pg.created_at AT TIME ZONE $11)::date
+            END AS bucket,
+            SUM(pg.cnt + COALESCE(pa.adjustment_cnt::bigint, 0))::bigint AS cnt,
+            SUM(
+              (pg.cnt + COALESCE(pa.adjustment_cnt::bigint, 0)) * COALESCE(pr.cost, 0)
+            )::numeric AS cost_amount,
+            SUM(
+              (pg.cnt + COALESCE(pa.adjustment_cnt::bigint, 0)) * COALESCE(pr.price, 0)
+            )::numeric AS price_amount
+          FROM
+            products AS p
+            JOIN measure_units as mu ON mu.id = p.measure_unit_id
+            JOIN produced_goods as pg ON p.id = pg.product_id
+            JOIN filtered as f ON f.id = pg.id
+            JOIN users as u ON u.id = pg.user_id
+            LEFT JOIN product_categories as pc ON pc.id = p.category_id
+            LEFT JOIN (
+              SELECT
+                produced_good_id,
+                SUM(cnt::bigint) AS adjustment_cnt
+              FROM
+                produced_good_adjustments
+              WHERE
+                status = 'Approved'
+              GROUP BY
+                produced_good_id
+            ) pa ON pa.produced_good_id = pg.id
+            LEFT JOIN LATERAL (
+              SELECT
+                pp.cost,
+                pp.price
+              FROM
+                product_prices AS pp
+              WHERE
+                pp.product_id = pg.product_id
+                AND pp.valid_from <= (pg.created_at AT TIME ZONE $11)::date
+              ORDER BY
+                pp.valid_from DESC
+              LIMIT 1
+            ) pr ON TRUE
+          GROUP BY
+            p.id,
+            u.fio,
+            measure,
+            pc.name,
+            bucket
+        ),
+        buckets AS (
+          SELECT
+            GENERATE_SERIES(
+              DATE_TRUNC($15, $1::timestamp),
+              $2::timestamp,
+              $16::interval
+            )::date AS bucket
+          WHERE $15::text IS NOT NULL
+        ),
+        series AS (
+          SELECT DISTINCT id, name, measure, fio, category FROM base
+        )
+        SELECT
+          b.id, b.name, b.measure, b.fio, b.category, b.bucket,
+          b.cnt, b.cost_amount, b.price_amount
+        FROM
+          base AS b
+        WHERE $15::text IS NULL
+        UNION ALL
+        SELECT
+          s.id, s.name, s.measure, s.fio, s.category, bk.bucket,
+          COALESCE(b.cnt, 0)::bigint,
+          COALESCE(b.cost_amount, 0)::numeric,
+          COALESCE(b.price_amount, 0)::numeric
+        FROM
+          series AS s
+          CROSS JOIN buckets AS bk
+          LEFT JOIN base AS b ON b.id = s.id AND b.fio = s.fio AND b.bucket = bk.bucket
+        ORDER BY
+          6,
+          7 desc,
+          1 desc;"
+    );
+    let rows: Vec<Row> = filter
+        .bind(sqlx::query_as(&sql))
+        .bind(q.group.map(|group| group.as_str()))
+        .bind(q.group.map(|group| group.interval()))
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| Item {
+            id: row.id,
+            name: row.name,
+            measure: row.measure,
+            fio: row.fio,
+            category: row.category,
+            cnt: row.cnt,
+            cost_amount: row.cost_amount,
+            price_amount: row.price_amount,
+            bucket: row.bucket,
+            period: q
+                .group
+                .zip(row.bucket)
+                .map(|(group, bucket)| group.label(bucket)),
+            comparison: None,
+        })
+        .collect())
 }
 
 pub async fn get_analitics(
@@ -36,153 +483,734 @@ pub async fn get_analitics(
     // Бизнес логика получения продуктв
 
     if !check_access(current_user.role) {
-        Err(AppError(
+        return Err(AppError(
             StatusCode::NOT_FOUND,
             anyhow::anyhow!("У вас нет доступа для данного действия!"),
-        ))
-    } else {
-        let rows = sqlx::query!(
-            "SELECT
-              p.id as id,
-              p.name as name,
-              mu.name as measure,
-              u.fio as fio,
-              SUM(pg.cnt + COALESCE(pa.adjustment_cnt::bigint, 0))::bigint AS cnt
-            FROM
-              products AS p
-              JOIN measure_units as mu ON mu.id = p.measure_unit_id
-              JOIN produced_goods as pg ON p.id = pg.product_id
-              JOIN users as u ON u.id = pg.user_id
-              LEFT JOIN (
-                SELECT
-                  produced_good_id,
-                  SUM(cnt::bigint) AS adjustment_cnt
-                FROM
-                  produced_good_adjustments
-                GROUP BY
-                  produced_good_id
-              ) pa ON pa.produced_good_id = pg.id
-            WHERE pg.created_at::date between $1 AND $2
-            AND CASE
-                WHEN $3::text IS NOT NULL THEN u.id = ANY((string_to_array($3::text, ','))::bigint[])
-                WHEN $4::VARCHAR IS NOT NULL THEN p.name ILIKE '%'||$4||'%'
-                WHEN $5::bigint IS NOT NULL AND $6 = 'Director' THEN pg.organization_id = $5
-                ELSE TRUE
-              END
-            GROUP BY
-              p.id,
-              u.fio,
-              measure
-            ORDER BY
-              cnt desc,
-              p.id desc;",
-            q.date_one,
-            q.date_two,
-            q.user.map_or(None, |u| {
-                if u.is_empty() {
-                    None
-                } else {
-                    Some(
-                        u.split(';')
-                            .filter(|s| !s.is_empty())
-                            .filter_map(|s| s.parse::<i64>().ok())
-                            .map(|s| s.to_string())
-                            .collect::<Vec<String>>()
-                            .join(","),
-                    )
-                }
-            }),
-            q.product,
-            current_user.organization_id,
-            current_user.role.to_string(),
+        ));
+    }
+
+    let Some(compare) = q.compare else {
+        return analitics(&pool, &current_user, &q).await;
+    };
+
+    if q.group.is_some() {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Сравнение периодов недоступно при группировке по времени!"),
+        ));
+    }
+
+    let (date_one, date_two) = compare.range(q.date_one, q.date_two).ok_or_else(|| {
+        AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Невозможно определить период для сравнения!"),
         )
-        .map(|row| Item {
-            id: row.id,
-            name: row.name,
-            measure: row.measure,
-            fio: row.fio,
-            cnt: row.cnt.map_or(0, |cnt| cnt),
-        })
-        .fetch_all(&pool)
-        .await?;
+    })?;
+
+    let mut items = analitics(&pool, &current_user, &q).await?;
+    let previous = analitics(
+        &pool,
+        &current_user,
+        &Q {
+            date_one,
+            date_two,
+            ..q.clone()
+        },
+    )
+    .await?;
 
-        Ok(rows)
+    // Строки, выпуска по которым в текущем периоде нет, показываются с нулями
+    for item in &previous {
+        if !items
+            .iter()
+            .any(|it| it.id == item.id && it.fio == item.fio)
+        {
+            items.push(Item {
+                cnt: 0,
+                cost_amount: Decimal::ZERO,
+                price_amount: Decimal::ZERO,
+                ..item.clone()
+            });
+        }
     }
+
+    for item in items.iter_mut() {
+        let prev = previous
+            .iter()
+            .find(|it| it.id == item.id && it.fio == item.fio);
+
+        item.comparison = Some(Comparison::new(item, prev, date_one, date_two));
+    }
+
+    Ok(items)
 }
 
-pub async fn generate_excel(
-    items: Vec<Item>,
-    date_one: chrono::NaiveDate,
-    date_two: chrono::NaiveDate,
-) -> Result<Vec<u8>, AppError> {
-    let mut wookbook = Workbook::new();
+#[derive(sqlx::FromRow)]
+struct CategoryRow {
+    id: Option<i64>,
+    parent_id: Option<i64>,
+    name: Option<String>,
+    cnt: i64,
+}
 
-    // formats
-    let right_fmt = Format::new()
-        .set_align(FormatAlign::Right)
-        .set_border(FormatBorder::Thin);
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ItemCategory {
+    pub id: Option<i64>,
+    pub parent_id: Option<i64>,
+    pub name: String,
+    pub cnt: i64,
+}
 
-    // Add a worksheet to the workbook.
-    let worksheet = wookbook.add_worksheet();
+pub async fn get_category_analitics(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Vec<ItemCategory>, AppError> {
+    // Бизнес логика выпуска по категориям продуктов.
+    // Итог категории включает выпуск всех вложенных категорий,
+    // продукты без категории собираются в отдельную строку.
 
-    // Set the column weight
-    worksheet.set_column_width(0, 8)?;
-    worksheet.set_column_width(1, 25)?;
-    worksheet.set_column_width(2, 25)?;
-    worksheet.set_column_width(3, 15)?;
-    worksheet.set_column_width(4, 25)?;
+    if !check_access(current_user.role) {
+        return Err(AppError(
+            StatusCode::NOT_FOUND,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    let filter = Filter::new(&pool, &current_user, &q).await?;
+
+    let sql = format!(
+        "WITH RECURSIVE {FILTER},
+        tree AS (
+          SELECT id AS root_id, id FROM product_categories
+          UNION ALL
+          SELECT t.root_id, pc.id FROM product_categories AS pc
+          JOIN tree AS t ON pc.parent_id = t.id
+        ),
+        base AS (
+          SELECT
+            p.category_id,
+            SUM(pg.cnt + COALESCE(pa.adjustment_cnt, 0))::bigint AS cnt
+          FROM
+            produced_goods AS pg
+            JOIN filtered AS f ON f.id = pg.id
+            JOIN products AS p ON p.id = pg.product_id
+            LEFT JOIN (
+              SELECT
+                produced_good_id,
+                SUM(cnt::bigint) AS adjustment_cnt
+              FROM
+                produced_good_adjustments
+              WHERE
+                status = 'Approved'
+              GROUP BY
+                produced_good_id
+            ) pa ON pa.produced_good_id = pg.id
+          GROUP BY
+            p.category_id
+        )
+        SELECT
+          pc.id,
+          pc.parent_id,
+          pc.name,
+          COALESCE(SUM(b.cnt), 0)::bigint AS cnt
+        FROM
+          product_categories AS pc
+          JOIN tree AS t ON t.root_id = pc.id
+          LEFT JOIN base AS b ON b.category_id = t.id
+        WHERE CASE
+            WHEN $6 not in ('Admin', 'Developer') THEN pc.organization_id = $5::bigint
+            ELSE TRUE
+          END
+        GROUP BY
+          pc.id
+        UNION ALL
+        SELECT NULL, NULL, NULL, b.cnt FROM base AS b WHERE b.category_id IS NULL
+        ORDER BY
+          3 NULLS LAST;"
+    );
+    let rows: Vec<CategoryRow> = filter.bind(sqlx::query_as(&sql)).fetch_all(&pool).await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| ItemCategory {
+            id: row.id,
+            parent_id: row.parent_id,
+            name: row.name.unwrap_or_else(|| "Без категории".to_string()),
+            cnt: row.cnt,
+        })
+        .collect())
+}
+
+fn header_fmt() -> Format {
+    Format::new()
+        .set_bold()
+        .set_align(FormatAlign::Center)
+        .set_background_color(Color::RGB(0xC6C6C6))
+        .set_border(FormatBorder::Thin)
+}
+
+pub fn write_title(worksheet: &mut Worksheet, last_col: u16, title: &str) -> Result<(), AppError> {
+    // Заголовок листа в первой строке и высота строк заголовка
 
-    let _ = worksheet.merge_range(
+    worksheet.merge_range(
         0,
         0,
         0,
-        4,
-        &format!(
-            "Отчет по производству товаров за период: {} - {}",
-            date_one.format("%d.%m.%Y"),
-            date_two.format("%d.%m.%Y")
-        ),
+        last_col,
+        title,
         &Format::new()
             .set_bold()
             .set_align(FormatAlign::Center)
             .set_border(FormatBorder::Thin),
-    );
-    // Высота
-    let _ = worksheet.set_row_height(0, 30);
-    let _ = worksheet.set_row_height(1, 20);
-
-    let mut i = 0;
-    ["#", "Продукт", "Пользователь", "Ед.измерения", "Кол-во"].map(|title| {
-        let _ = worksheet.write_with_format(
-            1,
-            i,
-            title,
-            &Format::new()
-                .set_bold()
-                .set_align(FormatAlign::Center)
-                .set_background_color(Color::RGB(0xC6C6C6))
-                .set_border(FormatBorder::Thin),
-        );
-        i += 1
-    });
+    )?;
+    worksheet.set_row_height(0, 30)?;
+    worksheet.set_row_height(1, 20)?;
+
+    Ok(())
+}
+
+pub fn write_headers(worksheet: &mut Worksheet, titles: &[&str]) -> Result<(), AppError> {
+    // Шапка таблицы во второй строке, закрепленная при прокрутке
+
+    for (i, title) in titles.iter().enumerate() {
+        worksheet.write_with_format(1, i as u16, *title, &header_fmt())?;
+    }
+    worksheet.set_freeze_panes(2, 0)?;
+
+    Ok(())
+}
+
+fn sum_formula(first_row: u32, last_row: u32, col: u16) -> Formula {
+    // Сумма по колонке, пустой диапазон дает ноль
+
+    if last_row < first_row {
+        Formula::new("=0")
+    } else {
+        Formula::new(format!(
+            "=SUM({})",
+            cell_range(first_row, col, last_row, col)
+        ))
+    }
+}
+
+const SUMMARY_SHEET: &str = "Сводка";
+const REPORT_SHEET: &str = "Отчет";
+const PIVOT_SHEET: &str = "Продукты и пользователи";
+const DAILY_SHEET: &str = "По дням";
+
+fn summary_sheet(items: &[Item], title: &str) -> Result<Worksheet, AppError> {
+    // Итоги по продуктам и диаграмма стоимости выпуска
+
+    let money_fmt = Format::new()
+        .set_num_format("#,##0.00")
+        .set_border(FormatBorder::Thin);
+    let text_fmt = Format::new().set_border(FormatBorder::Thin);
+    let total_fmt = Format::new()
+        .set_bold()
+        .set_num_format("#,##0.00")
+        .set_background_color(Color::RGB(0xEDEDED))
+        .set_border(FormatBorder::Thin);
+
+    let mut products: Vec<(i64, String, String, i64, Decimal, Decimal)> = Vec::new();
+    for item in items {
+        match products.iter_mut().find(|p| p.0 == item.id) {
+            Some(p) => {
+                p.3 += item.cnt;
+                p.4 += item.cost_amount;
+                p.5 += item.price_amount;
+            }
+            None => products.push((
+                item.id,
+                item.name.clone(),
+                item.measure.clone(),
+                item.cnt,
+                item.cost_amount,
+                item.price_amount,
+            )),
+        }
+    }
+    products.sort_by(|a, b| b.5.cmp(&a.5).then(b.3.cmp(&a.3)));
+
+    let mut worksheet = Worksheet::new();
+    worksheet.set_name(SUMMARY_SHEET)?;
+    worksheet.set_column_width(0, 30)?;
+    worksheet.set_column_width(1, 15)?;
+    worksheet.set_column_width(2, 12)?;
+    worksheet.set_column_width(3, 20)?;
+    worksheet.set_column_width(4, 20)?;
+
+    write_title(&mut worksheet, 4, title)?;
+    write_headers(
+        &mut worksheet,
+        &[
+            "Продукт",
+            "Ед.измерения",
+            "Кол-во",
+            "Себестоимость, руб.",
+            "Стоимость, руб.",
+        ],
+    )?;
+
+    let mut i = 2;
+    for (_, name, measure, cnt, cost_amount, price_amount) in &products {
+        worksheet.write_with_format(i, 0, name, &text_fmt)?;
+        worksheet.write_with_format(i, 1, measure, &text_fmt)?;
+        worksheet.write_with_format(i, 2, *cnt, &text_fmt)?;
+        worksheet.write_with_format(i, 3, money(*cost_amount), &money_fmt)?;
+        worksheet.write_with_format(i, 4, money(*price_amount), &money_fmt)?;
+        i += 1;
+    }
+
+    // Итого по стоимости, кол-во не суммируется из-за разных единиц измерения
+    worksheet.merge_range(i, 0, i, 2, "Итого", &total_fmt)?;
+    worksheet.write_formula_with_format(i, 3, sum_formula(2, i - 1, 3), &total_fmt)?;
+    worksheet.write_formula_with_format(i, 4, sum_formula(2, i - 1, 4), &total_fmt)?;
+
+    if !products.is_empty() {
+        let mut chart = Chart::new(ChartType::Column);
+        chart
+            .add_series()
+            .set_name("Стоимость, руб.")
+            .set_categories((SUMMARY_SHEET, 2, 0, i - 1, 0))
+            .set_values((SUMMARY_SHEET, 2, 4, i - 1, 4));
+        chart.title().set_name("Стоимость выпуска по продуктам");
+        chart.legend().set_hidden();
+        chart.set_width(640).set_height(360);
+
+        worksheet.insert_chart(1, 6, &chart)?;
+    }
+
+    Ok(worksheet)
+}
+
+fn report_sheet(items: &[Item], title: &str) -> Result<Worksheet, AppError> {
+    // Построчный отчет по продуктам и пользователям с автофильтром
+
+    let right_fmt = Format::new()
+        .set_align(FormatAlign::Right)
+        .set_border(FormatBorder::Thin);
+    let money_fmt = Format::new()
+        .set_num_format("#,##0.00")
+        .set_border(FormatBorder::Thin);
+    let total_fmt = Format::new()
+        .set_bold()
+        .set_num_format("#,##0.00")
+        .set_background_color(Color::RGB(0xEDEDED))
+        .set_border(FormatBorder::Thin);
+
+    // При группировке по времени добавляется колонка периода, остальные сдвигаются
+    let grouped = items.iter().any(|item| item.period.is_some());
+    let s: u16 = if grouped { 1 } else { 0 };
+
+    let mut worksheet = Worksheet::new();
+    worksheet.set_name(REPORT_SHEET)?;
+    worksheet.set_column_width(0, 8)?;
+    if grouped {
+        worksheet.set_column_width(1, 15)?;
+    }
+    worksheet.set_column_width(1 + s, 25)?;
+    worksheet.set_column_width(2 + s, 25)?;
+    worksheet.set_column_width(3 + s, 15)?;
+    worksheet.set_column_width(4 + s, 12)?;
+    worksheet.set_column_width(5 + s, 20)?;
+    worksheet.set_column_width(6 + s, 20)?;
+
+    write_title(&mut worksheet, 6 + s, title)?;
+
+    let mut titles = vec!["#"];
+    if grouped {
+        titles.push("Период");
+    }
+    titles.extend([
+        "Продукт",
+        "Пользователь",
+        "Ед.измерения",
+        "Кол-во",
+        "Себестоимость, руб.",
+        "Стоимость, руб.",
+    ]);
+    write_headers(&mut worksheet, &titles)?;
 
     let mut i = 2;
-    items.iter().for_each(|item| {
-        let _ = worksheet.write_with_format(i, 0, item.id, &right_fmt);
-        let _ = worksheet.write_with_format(i, 1, item.name.clone(), &right_fmt);
-        let _ = worksheet.write_with_format(i, 2, item.fio.clone(), &right_fmt);
-        let _ = worksheet.write_with_format(i, 3, item.measure.clone(), &right_fmt);
-        let _ = worksheet.write_with_format(
+    for item in items {
+        worksheet.write_with_format(i, 0, item.id, &right_fmt)?;
+        if grouped {
+            let period = item.period.clone().unwrap_or_default();
+            worksheet.write_with_format(i, 1, period, &right_fmt)?;
+        }
+        worksheet.write_with_format(i, 1 + s, &item.name, &right_fmt)?;
+        worksheet.write_with_format(i, 2 + s, &item.fio, &right_fmt)?;
+        worksheet.write_with_format(i, 3 + s, &item.measure, &right_fmt)?;
+        worksheet.write_with_format(
             i,
-            4,
+            4 + s,
             item.cnt,
             &Format::new().set_border(FormatBorder::Thin),
-        );
+        )?;
+        worksheet.write_with_format(i, 5 + s, money(item.cost_amount), &money_fmt)?;
+        worksheet.write_with_format(i, 6 + s, money(item.price_amount), &money_fmt)?;
+
+        i += 1;
+    }
+
+    if !items.is_empty() {
+        worksheet.autofilter(1, 0, i - 1, 6 + s)?;
+    }
+
+    worksheet.merge_range(i, 0, i, 4 + s, "Итого", &total_fmt)?;
+    worksheet.write_formula_with_format(i, 5 + s, sum_formula(2, i - 1, 5 + s), &total_fmt)?;
+    worksheet.write_formula_with_format(i, 6 + s, sum_formula(2, i - 1, 6 + s), &total_fmt)?;
+
+    Ok(worksheet)
+}
+
+fn pivot_sheet(items: &[Item], title: &str) -> Result<Worksheet, AppError> {
+    // Сводная таблица: продукты по строкам, пользователи по колонкам, итог по продукту
+
+    let text_fmt = Format::new().set_border(FormatBorder::Thin);
+    let total_fmt = Format::new()
+        .set_bold()
+        .set_background_color(Color::RGB(0xEDEDED))
+        .set_border(FormatBorder::Thin);
+
+    let mut users: Vec<String> = items.iter().map(|item| item.fio.clone()).collect();
+    users.sort();
+    users.dedup();
+
+    let mut products: Vec<(i64, String, String)> = Vec::new();
+    for item in items {
+        if !products.iter().any(|p| p.0 == item.id) {
+            products.push((item.id, item.name.clone(), item.measure.clone()));
+        }
+    }
+    products.sort_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(&b.0)));
+
+    let last_col = users.len() as u16 + 2;
+
+    let mut worksheet = Worksheet::new();
+    worksheet.set_name(PIVOT_SHEET)?;
+    worksheet.set_column_width(0, 30)?;
+    worksheet.set_column_width(1, 15)?;
+    for col in 2..=last_col {
+        worksheet.set_column_width(col, 15)?;
+    }
+
+    write_title(&mut worksheet, last_col, title)?;
+
+    let mut titles = vec!["Продукт", "Ед.измерения"];
+    titles.extend(users.iter().map(String::as_str));
+    titles.push("Итого");
+    write_headers(&mut worksheet, &titles)?;
+    worksheet.set_freeze_panes(2, 2)?;
+
+    let mut i = 2;
+    for (id, name, measure) in &products {
+        worksheet.write_with_format(i, 0, name, &text_fmt)?;
+        worksheet.write_with_format(i, 1, measure, &text_fmt)?;
+
+        for (n, user) in users.iter().enumerate() {
+            let cnt: i64 = items
+                .iter()
+                .filter(|item| item.id == *id && item.fio == *user)
+                .map(|item| item.cnt)
+                .sum();
+            worksheet.write_with_format(i, n as u16 + 2, cnt, &text_fmt)?;
+        }
+
+        // Итог по продукту в одной единице измерения
+        let formula = if users.is_empty() {
+            Formula::new("=0")
+        } else {
+            Formula::new(format!("=SUM({})", cell_range(i, 2, i, last_col - 1)))
+        };
+        worksheet.write_formula_with_format(i, last_col, formula, &total_fmt)?;
+
+        i += 1;
+    }
+
+    if !products.is_empty() {
+        worksheet.autofilter(1, 0, i - 1, last_col)?;
+    }
+
+    Ok(worksheet)
+}
 
+fn daily_sheet(daily: &[Item], title: &str) -> Result<Worksheet, AppError> {
+    // Выпуск по дням и продуктам, справа - стоимость по дням с графиком
+
+    let text_fmt = Format::new().set_border(FormatBorder::Thin);
+    let date_fmt = Format::new()
+        .set_num_format("dd.mm.yyyy")
+        .set_align(FormatAlign::Left)
+        .set_border(FormatBorder::Thin);
+    let money_fmt = Format::new()
+        .set_num_format("#,##0.00")
+        .set_border(FormatBorder::Thin);
+    let total_fmt = Format::new()
+        .set_bold()
+        .set_num_format("#,##0.00")
+        .set_background_color(Color::RGB(0xEDEDED))
+        .set_border(FormatBorder::Thin);
+
+    let mut rows: Vec<(
+        chrono::NaiveDate,
+        i64,
+        String,
+        String,
+        i64,
+        Decimal,
+        Decimal,
+    )> = Vec::new();
+    let mut days: Vec<(chrono::NaiveDate, Decimal, Decimal)> = Vec::new();
+    for item in daily {
+        let Some(bucket) = item.bucket else {
+            continue;
+        };
+
+        match days.iter_mut().find(|d| d.0 == bucket) {
+            Some(d) => {
+                d.1 += item.cost_amount;
+                d.2 += item.price_amount;
+            }
+            None => days.push((bucket, item.cost_amount, item.price_amount)),
+        }
+
+        // Дни без выпуска продукта не выводятся построчно
+        if item.cnt == 0 {
+            continue;
+        }
+        match rows.iter_mut().find(|r| r.0 == bucket && r.1 == item.id) {
+            Some(r) => {
+                r.4 += item.cnt;
+                r.5 += item.cost_amount;
+                r.6 += item.price_amount;
+            }
+            None => rows.push((
+                bucket,
+                item.id,
+                item.name.clone(),
+                item.measure.clone(),
+                item.cnt,
+                item.cost_amount,
+                item.price_amount,
+            )),
+        }
+    }
+    rows.sort_by(|a, b| a.0.cmp(&b.0).then(a.2.cmp(&b.2)));
+    days.sort_by_key(|day| day.0);
+
+    let mut worksheet = Worksheet::new();
+    worksheet.set_name(DAILY_SHEET)?;
+    worksheet.set_column_width(0, 12)?;
+    worksheet.set_column_width(1, 30)?;
+    worksheet.set_column_width(2, 15)?;
+    worksheet.set_column_width(3, 12)?;
+    worksheet.set_column_width(4, 20)?;
+    worksheet.set_column_width(5, 20)?;
+    worksheet.set_column_width(7, 12)?;
+    worksheet.set_column_width(8, 20)?;
+    worksheet.set_column_width(9, 20)?;
+
+    write_title(&mut worksheet, 5, title)?;
+    write_headers(
+        &mut worksheet,
+        &[
+            "Дата",
+            "Продукт",
+            "Ед.измерения",
+            "Кол-во",
+            "Себестоимость, руб.",
+            "Стоимость, руб.",
+        ],
+    )?;
+
+    let mut i = 2;
+    for (date, _, name, measure, cnt, cost_amount, price_amount) in &rows {
+        worksheet.write_with_format(i, 0, date, &date_fmt)?;
+        worksheet.write_with_format(i, 1, name, &text_fmt)?;
+        worksheet.write_with_format(i, 2, measure, &text_fmt)?;
+        worksheet.write_with_format(i, 3, *cnt, &text_fmt)?;
+        worksheet.write_with_format(i, 4, money(*cost_amount), &money_fmt)?;
+        worksheet.write_with_format(i, 5, money(*price_amount), &money_fmt)?;
         i += 1;
-    });
+    }
+
+    if !rows.is_empty() {
+        worksheet.autofilter(1, 0, i - 1, 5)?;
+    }
+
+    worksheet.merge_range(i, 0, i, 3, "Итого", &total_fmt)?;
+    worksheet.write_formula_with_format(i, 4, sum_formula(2, i - 1, 4), &total_fmt)?;
+    worksheet.write_formula_with_format(i, 5, sum_formula(2, i - 1, 5), &total_fmt)?;
+
+    // Итоги по дням, включая дни без выпуска
+    for (n, title) in ["Дата", "Себестоимость, руб.", "Стоимость, руб."]
+        .iter()
+        .enumerate()
+    {
+        worksheet.write_with_format(1, n as u16 + 7, *title, &header_fmt())?;
+    }
+
+    let mut j = 2;
+    for (date, cost_amount, price_amount) in &days {
+        worksheet.write_with_format(j, 7, date, &date_fmt)?;
+        worksheet.write_with_format(j, 8, money(*cost_amount), &money_fmt)?;
+        worksheet.write_with_format(j, 9, money(*price_amount), &money_fmt)?;
+        j += 1;
+    }
+
+    if !days.is_empty() {
+        let mut chart = Chart::new(ChartType::Line);
+        chart
+            .add_series()
+            .set_name("Себестоимость, руб.")
+            .set_categories((DAILY_SHEET, 2, 7, j - 1, 7))
+            .set_values((DAILY_SHEET, 2, 8, j - 1, 8));
+        chart
+            .add_series()
+            .set_name("Стоимость, руб.")
+            .set_categories((DAILY_SHEET, 2, 7, j - 1, 7))
+            .set_values((DAILY_SHEET, 2, 9, j - 1, 9));
+        chart.title().set_name("Выпуск по дням");
+        chart.set_width(640).set_height(360);
+
+        worksheet.insert_chart(j + 1, 7, &chart)?;
+    }
+
+    Ok(worksheet)
+}
+
+pub async fn generate_excel(
+    items: Vec<Item>,
+    daily: Vec<Item>,
+    date_one: chrono::NaiveDate,
+    date_two: chrono::NaiveDate,
+) -> Result<Vec<u8>, AppError> {
+    // Отчет из нескольких листов: сводка по продуктам, построчный отчет,
+    // сводная таблица продуктов и пользователей, выпуск по дням
+
+    let mut wookbook = Workbook::new();
+
+    let title = format!(
+        "Отчет по производству товаров за период: {} - {}",
+        date_one.format("%d.%m.%Y"),
+        date_two.format("%d.%m.%Y")
+    );
+
+    wookbook.push_worksheet(summary_sheet(&items, &title)?);
+    wookbook.push_worksheet(report_sheet(&items, &title)?);
+    wookbook.push_worksheet(pivot_sheet(&items, &title)?);
+    wookbook.push_worksheet(daily_sheet(&daily, &title)?);
 
     let buffer = wookbook.save_to_buffer()?;
 
     Ok(buffer)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> chrono::NaiveDate {
+        chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn item(cnt: i64, cost_amount: Decimal, price_amount: Decimal) -> Item {
+        Item {
+            id: 1,
+            name: "Болт".to_string(),
+            measure: "шт".to_string(),
+            fio: "Иванов".to_string(),
+            category: None,
+            cnt,
+            cost_amount,
+            price_amount,
+            bucket: None,
+            period: None,
+            comparison: None,
+        }
+    }
+
+    #[test]
+    fn previous_range() {
+        // Период той же длины, заканчивающийся накануне начала
+        assert_eq!(
+            Compare::Previous.range(date(2024, 3, 1), date(2024, 3, 31)),
+            Some((date(2024, 1, 30), date(2024, 2, 29)))
+        );
+        assert_eq!(
+            Compare::Previous.range(date(2024, 3, 5), date(2024, 3, 5)),
+            Some((date(2024, 3, 4), date(2024, 3, 4)))
+        );
+        assert_eq!(
+            Compare::Previous.range(date(2024, 1, 1), date(2024, 1, 7)),
+            Some((date(2023, 12, 25), date(2023, 12, 31)))
+        );
+    }
+
+    #[test]
+    fn year_range() {
+        assert_eq!(
+            Compare::Year.range(date(2024, 3, 1), date(2024, 3, 31)),
+            Some((date(2023, 3, 1), date(2023, 3, 31)))
+        );
+        // 29 февраля переходит в последний день февраля прошлого года
+        assert_eq!(
+            Compare::Year.range(date(2024, 2, 29), date(2024, 3, 31)),
+            Some((date(2023, 2, 28), date(2023, 3, 31)))
+        );
+    }
+
+    #[test]
+    fn comparison_deltas() {
+        let previous = item(100, Decimal::new(50, 0), Decimal::new(80, 0));
+        let comparison = Comparison::new(
+            &item(120, Decimal::new(605, 1), Decimal::new(100, 0)),
+            Some(&previous),
+            date(2024, 2, 1),
+            date(2024, 2, 29),
+        );
+
+        assert_eq!(comparison.cnt, 100);
+        assert_eq!(comparison.cnt_delta, 20);
+        assert_eq!(comparison.cnt_delta_percent, Some(20.0));
+        assert_eq!(comparison.cost_delta, Decimal::new(105, 1));
+        assert_eq!(comparison.cost_delta_percent, Some(21.0));
+        assert_eq!(comparison.price_delta, Decimal::new(20, 0));
+        assert_eq!(comparison.price_delta_percent, Some(25.0));
+    }
+
+    #[test]
+    fn comparison_without_previous() {
+        // При нулевой базе процент изменения не считается
+        let comparison = Comparison::new(
+            &item(7, Decimal::new(35, 1), Decimal::new(10, 0)),
+            None,
+            date(2024, 2, 1),
+            date(2024, 2, 29),
+        );
+
+        assert_eq!(comparison.cnt, 0);
+        assert_eq!(comparison.cnt_delta, 7);
+        assert_eq!(comparison.cnt_delta_percent, None);
+        assert_eq!(comparison.price_delta, Decimal::new(10, 0));
+        assert_eq!(comparison.price_delta_percent, None);
+
+        // Снижение до нуля - минус сто процентов
+        let comparison = Comparison::new(
+            &item(0, Decimal::ZERO, Decimal::ZERO),
+            Some(&item(4, Decimal::new(2, 0), Decimal::new(8, 0))),
+            date(2024, 2, 1),
+            date(2024, 2, 29),
+        );
+        assert_eq!(comparison.cnt_delta, -4);
+        assert_eq!(comparison.cnt_delta_percent, Some(-100.0));
+    }
+
+    #[test]
+    fn delta_percent_rounding() {
+        assert_eq!(delta_percent(1.0, 3.0), Some(-66.67));
+        assert_eq!(delta_percent(5.0, 0.0), None);
+    }
+}
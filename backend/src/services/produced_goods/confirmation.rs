@@ -0,0 +1,225 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{
+    check_access,
+    services::{
+        stock::movement::{post_production, Kind},
+        work_orders::work_order::refresh_work_order,
+    },
+    AppError, CurrentUser,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum Status {
+    Submitted,
+    Confirmed,
+    Rejected,
+}
+
+impl From<&str> for Status {
+    fn from(value: &str) -> Self {
+        match value {
+            "Submitted" => Self::Submitted,
+            "Rejected" => Self::Rejected,
+            _ => Self::Confirmed,
+        }
+    }
+}
+
+impl From<String> for Status {
+    fn from(value: String) -> Self {
+        Self::from(value.as_str())
+    }
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Submitted => "Submitted",
+            Self::Confirmed => "Confirmed",
+            Self::Rejected => "Rejected",
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RequestBodyStatus {
+    status: Status,
+    comment: Option<String>,
+}
+
+pub async fn review_produced_good(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+    Json(body): Json<RequestBodyStatus>,
+) -> Result<i64, AppError> {
+    // Бизнес логика подтверждения или отклонения записи производства Директором.
+    // При отклонении комментарий обязателен.
+
+    if !check_access(current_user.role) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    let comment = body
+        .comment
+        .as_deref()
+        .map(str::trim)
+        .filter(|c| !c.is_empty());
+
+    match (body.status, comment) {
+        (Status::Submitted, _) => {
+            return Err(AppError(
+                StatusCode::BAD_REQUEST,
+                anyhow::anyhow!("Запись можно только подтвердить или отклонить!"),
+            ));
+        }
+        (Status::Rejected, None) => {
+            return Err(AppError(
+                StatusCode::BAD_REQUEST,
+                anyhow::anyhow!("Укажите причину отклонения!"),
+            ));
+        }
+        _ => {}
+    }
+
+    let mut tx = pool.begin().await?;
+
+    let status: Option<String> = sqlx::query_scalar(
+        "SELECT status
+        FROM produced_goods
+        WHERE id = $1
+        AND CASE
+            WHEN $2::bigint IS NOT NULL THEN organization_id = $2
+            ELSE TRUE
+        END
+        FOR UPDATE",
+    )
+    .bind(id)
+    .bind(current_user.organization_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    match status.map(Status::from) {
+        Some(Status::Submitted) => {
+            let _ = sqlx::query(
+                "UPDATE produced_goods
+                SET status = $1, review_comment = $2, reviewed_by = $3, reviewed_at = NOW()
+                WHERE id = $4",
+            )
+            .bind(body.status.to_string())
+            .bind(comment)
+            .bind(current_user.id)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+            // Отклоненная запись больше не входит в прогресс заказа,
+            // ее выпуск вместе с утвержденными корректировками списывается со склада
+            if body.status == Status::Rejected {
+                let (work_order_id, product_id, cnt): (Option<i64>, i64, i64) = sqlx::query_as(
+                    "SELECT
+                      pg.work_order_id,
+                      pg.product_id,
+                      (pg.cnt + COALESCE(SUM(pga.cnt::bigint), 0))::bigint
+                    FROM
+                      produced_goods AS pg
+                      LEFT JOIN produced_good_adjustments AS pga ON pga.produced_good_id = pg.id
+                        AND pga.status = 'Approved'
+                    WHERE
+                      pg.id = $1
+                    GROUP BY
+                      pg.id",
+                )
+                .bind(id)
+                .fetch_one(&mut *tx)
+                .await?;
+
+                post_production(
+                    &mut tx,
+                    Kind::Adjustment,
+                    current_user.id,
+                    id,
+                    None,
+                    product_id,
+                    -cnt,
+                )
+                .await?;
+
+                if let Some(work_order_id) = work_order_id {
+                    refresh_work_order(&mut tx, work_order_id).await?;
+                }
+            }
+
+            tx.commit().await?;
+
+            Ok(id)
+        }
+        Some(_) => Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Запись уже рассмотрена!"),
+        )),
+        None => Err(AppError(
+            StatusCode::NOT_FOUND,
+            anyhow::anyhow!("Такой записи не существует"),
+        )),
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RequestBodyConfirm {
+    ids: Vec<i64>,
+}
+
+pub async fn confirm_produced_goods(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(body): Json<RequestBodyConfirm>,
+) -> Result<i64, AppError> {
+    // Бизнес логика массового подтверждения записей производства.
+    // Подтверждаются только ожидающие записи организации, возвращается их кол-во.
+
+    if !check_access(current_user.role) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    if body.ids.is_empty() {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Не выбраны записи для подтверждения!"),
+        ));
+    }
+
+    let result = sqlx::query(
+        "UPDATE produced_goods
+        SET status = $1, reviewed_by = $2, reviewed_at = NOW()
+        WHERE id = ANY($3)
+        AND status = $4
+        AND CASE
+            WHEN $5::bigint IS NOT NULL THEN organization_id = $5
+            ELSE TRUE
+        END",
+    )
+    .bind(Status::Confirmed.to_string())
+    .bind(current_user.id)
+    .bind(&body.ids)
+    .bind(Status::Submitted.to_string())
+    .bind(current_user.organization_id)
+    .execute(&pool)
+    .await?;
+
+    Ok(result.rows_affected() as i64)
+}
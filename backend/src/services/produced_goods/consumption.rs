@@ -0,0 +1,207 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Extension,
+};
+
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{check_access, AppError, CurrentUser};
+use rust_xlsxwriter::*;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Item {
+    pub id: i64,
+    pub name: String,
+    pub measure: String,
+    pub organization: String,
+    pub cnt: f64,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Q {
+    pub date_one: chrono::NaiveDate,
+    pub date_two: chrono::NaiveDate,
+}
+
+pub async fn get_consumption(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Vec<Item>, AppError> {
+    // Бизнес логика расчета теоретического расхода материалов.
+    // Выпуск за период раскладывается по спецификациям до конечных компонентов
+    // (у которых нет собственной спецификации).
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::NOT_FOUND,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        let rows: Vec<(i64, String, String, String, f64)> = sqlx::query_as(
+            "WITH RECURSIVE produced AS (
+              SELECT
+                pg.product_id,
+                SUM(pg.cnt + COALESCE(pa.adjustment_cnt::bigint, 0))::float8 AS cnt
+              FROM
+                produced_goods AS pg
+                LEFT JOIN (
+                  SELECT
+                    produced_good_id,
+                    SUM(cnt::bigint) AS adjustment_cnt
+                  FROM
+                    produced_good_adjustments
+                  WHERE
+                    status = 'Approved'
+                  GROUP BY
+                    produced_good_id
+                ) pa ON pa.produced_good_id = pg.id
+              WHERE pg.created_at::date BETWEEN $1 AND $2
+              AND pg.status <> 'Rejected'
+              AND CASE
+                  WHEN $3::bigint IS NOT NULL AND $4 = 'Director' THEN pg.organization_id = $3
+                  ELSE TRUE
+                END
+              GROUP BY
+                pg.product_id
+            ),
+            explode AS (
+              SELECT
+                bi.component_id,
+                pr.cnt * bi.cnt AS cnt,
+                ARRAY[pr.product_id, bi.component_id] AS path
+              FROM
+                produced AS pr
+                JOIN bom_items AS bi ON bi.product_id = pr.product_id
+              UNION ALL
+              SELECT
+                bi.component_id,
+                e.cnt * bi.cnt AS cnt,
+                e.path || bi.component_id
+              FROM
+                explode AS e
+                JOIN bom_items AS bi ON bi.product_id = e.component_id
+              WHERE NOT bi.component_id = ANY(e.path)
+            )
+            SELECT
+              c.id,
+              c.name,
+              mu.name AS measure,
+              o.name AS organization,
+              SUM(e.cnt)::float8 AS cnt
+            FROM
+              explode AS e
+              JOIN products AS c ON c.id = e.component_id
+              JOIN measure_units AS mu ON mu.id = c.measure_unit_id
+              JOIN organizations AS o ON o.id = c.organization_id
+            WHERE NOT EXISTS (
+              SELECT 1 FROM bom_items AS sub WHERE sub.product_id = e.component_id
+            )
+            GROUP BY
+              c.id,
+              c.name,
+              mu.name,
+              o.name
+            ORDER BY
+              o.name,
+              cnt DESC,
+              c.id;",
+        )
+        .bind(q.date_one)
+        .bind(q.date_two)
+        .bind(current_user.organization_id)
+        .bind(current_user.role.to_string())
+        .fetch_all(&pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| Item {
+                id: row.0,
+                name: row.1,
+                measure: row.2,
+                organization: row.3,
+                cnt: row.4,
+            })
+            .collect())
+    }
+}
+
+pub async fn generate_excel(
+    items: Vec<Item>,
+    date_one: chrono::NaiveDate,
+    date_two: chrono::NaiveDate,
+) -> Result<Vec<u8>, AppError> {
+    let mut wookbook = Workbook::new();
+
+    // formats
+    let right_fmt = Format::new()
+        .set_align(FormatAlign::Right)
+        .set_border(FormatBorder::Thin);
+
+    // Add a worksheet to the workbook.
+    let worksheet = wookbook.add_worksheet();
+
+    // Set the column weight
+    worksheet.set_column_width(0, 8)?;
+    worksheet.set_column_width(1, 25)?;
+    worksheet.set_column_width(2, 25)?;
+    worksheet.set_column_width(3, 15)?;
+    worksheet.set_column_width(4, 25)?;
+
+    let _ = worksheet.merge_range(
+        0,
+        0,
+        0,
+        4,
+        &format!(
+            "Расход материалов за период: {} - {}",
+            date_one.format("%d.%m.%Y"),
+            date_two.format("%d.%m.%Y")
+        ),
+        &Format::new()
+            .set_bold()
+            .set_align(FormatAlign::Center)
+            .set_border(FormatBorder::Thin),
+    );
+    // Высота
+    let _ = worksheet.set_row_height(0, 30);
+    let _ = worksheet.set_row_height(1, 20);
+
+    for (i, title) in ["#", "Материал", "Организация", "Ед.измерения", "Расход"]
+        .into_iter()
+        .enumerate()
+    {
+        let _ = worksheet.write_with_format(
+            1,
+            i as u16,
+            title,
+            &Format::new()
+                .set_bold()
+                .set_align(FormatAlign::Center)
+                .set_background_color(Color::RGB(0xC6C6C6))
+                .set_border(FormatBorder::Thin),
+        );
+    }
+
+    let cnt_fmt = Format::new()
+        .set_num_format("0.000")
+        .set_border(FormatBorder::Thin);
+
+    let mut i = 2;
+    items.iter().for_each(|item| {
+        let _ = worksheet.write_with_format(i, 0, item.id, &right_fmt);
+        let _ = worksheet.write_with_format(i, 1, item.name.clone(), &right_fmt);
+        let _ = worksheet.write_with_format(i, 2, item.organization.clone(), &right_fmt);
+        let _ = worksheet.write_with_format(i, 3, item.measure.clone(), &right_fmt);
+        let _ = worksheet.write_with_format(i, 4, item.cnt, &cnt_fmt);
+
+        i += 1;
+    });
+
+    let buffer = wookbook.save_to_buffer()?;
+
+    Ok(buffer)
+}
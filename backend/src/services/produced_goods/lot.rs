@@ -0,0 +1,267 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension,
+};
+
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Transaction};
+
+use crate::{
+    check_access,
+    services::{produced_goods::adjustment::Status, Select},
+    AppError, CurrentUser,
+};
+
+// Шаблон по умолчанию для продуктов без собственного шаблона
+const DEFAULT_PATTERN: &str = "{ID}-{YYYY}{MM}{DD}-{SEQ}";
+
+fn render_prefix(pattern: &str, product_id: i64, date: chrono::NaiveDate) -> String {
+    // Подстановка полей шаблона номера партии, кроме порядкового номера.
+    // Шаблон без {SEQ} дополняется им, иначе номера за день совпали бы.
+
+    let prefix = pattern
+        .replace("{ID}", &product_id.to_string())
+        .replace("{YYYY}", &date.format("%Y").to_string())
+        .replace("{YY}", &date.format("%y").to_string())
+        .replace("{MM}", &date.format("%m").to_string())
+        .replace("{DD}", &date.format("%d").to_string());
+
+    if prefix.contains("{SEQ}") {
+        prefix
+    } else {
+        format!("{}-{{SEQ}}", prefix)
+    }
+}
+
+fn render_lot(pattern: &str, product_id: i64, date: chrono::NaiveDate, seq: i64) -> String {
+    // Подстановка полей шаблона номера партии
+
+    render_prefix(pattern, product_id, date).replacen("{SEQ}", &format!("{:03}", seq), 1)
+}
+
+pub async fn next_lot(
+    tx: &mut Transaction<'_, Postgres>,
+    product_id: i64,
+) -> Result<String, anyhow::Error> {
+    // Генерация номера партии по шаблону продукта.
+    // Продукт блокируется, чтобы номер выдавался последовательно. Счетчик хранится
+    // отдельно от записей, поэтому удаление записи не приводит к повтору номера.
+
+    let (pattern, date): (Option<String>, chrono::NaiveDate) =
+        sqlx::query_as("SELECT lot_pattern, CURRENT_DATE FROM products WHERE id = $1 FOR UPDATE")
+            .bind(product_id)
+            .fetch_one(&mut **tx)
+            .await?;
+
+    let pattern = pattern.as_deref().unwrap_or(DEFAULT_PATTERN);
+    let prefix = render_prefix(pattern, product_id, date);
+
+    let seq: Option<i64> = sqlx::query_scalar(
+        "UPDATE lot_sequences SET seq = seq + 1
+        WHERE product_id = $1 AND prefix = $2
+        RETURNING seq",
+    )
+    .bind(product_id)
+    .bind(&prefix)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    let seq = match seq {
+        Some(seq) => seq,
+        None => {
+            // Первый номер по ключу продолжает уже выданные номера,
+            // в том числе выданные до появления счетчика
+            let (before, after) = prefix.split_once("{SEQ}").unwrap_or((&prefix, ""));
+
+            sqlx::query_scalar(
+                "INSERT INTO lot_sequences (product_id, prefix, seq)
+                SELECT
+                  $1,
+                  $2,
+                  COALESCE(MAX(
+                    CASE WHEN s.seq ~ '^[0-9]{1,18}$' THEN s.seq::bigint END
+                  ), 0) + 1
+                FROM
+                  produced_goods AS pg
+                  CROSS JOIN LATERAL (
+                    SELECT SUBSTRING(
+                      pg.lot
+                      FROM CHAR_LENGTH($3) + 1
+                      FOR CHAR_LENGTH(pg.lot) - CHAR_LENGTH($3) - CHAR_LENGTH($4)
+                    ) AS seq
+                  ) AS s
+                WHERE
+                  pg.product_id = $1
+                  AND STARTS_WITH(pg.lot, $3)
+                  AND RIGHT(pg.lot, CHAR_LENGTH($4)) = $4
+                  AND CHAR_LENGTH(pg.lot) > CHAR_LENGTH($3) + CHAR_LENGTH($4)
+                RETURNING seq",
+            )
+            .bind(product_id)
+            .bind(&prefix)
+            .bind(before)
+            .bind(after)
+            .fetch_one(&mut **tx)
+            .await?
+        }
+    };
+
+    Ok(render_lot(pattern, product_id, date, seq))
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ItemAdjustment {
+    pub id: i64,
+    pub cnt: i64,
+    pub status: Status,
+    pub comment: Option<String>,
+    pub fio: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Item {
+    pub id: i64,
+    pub lot: String,
+    pub cnt: i64,
+    pub shift: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub adjustments: Vec<ItemAdjustment>,
+
+    product: Select,
+    user: Select,
+    organization: Select,
+}
+
+pub async fn get_lot(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(lot): Path<String>,
+) -> Result<Vec<Item>, AppError> {
+    // Бизнес логика прослеживаемости партии:
+    // кто и когда выпустил, в какую смену и какие были корректировки.
+
+    if !check_access(current_user.role) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    let rows = sqlx::query!(
+        "SELECT
+            pg.id,
+            pg.lot AS \"lot!\",
+            pg.cnt,
+            pg.created_at,
+            s.name AS \"shift?\",
+            JSONB_BUILD_OBJECT(
+                'id', p.id,
+                'name', p.name
+            ) AS product,
+            JSONB_BUILD_OBJECT(
+                'id', u.id,
+                'name', u.fio
+            ) AS author,
+            JSONB_BUILD_OBJECT(
+                'id', o.id,
+                'name', o.name
+            ) AS organization,
+            (
+                SELECT COALESCE(JSONB_AGG(JSONB_BUILD_OBJECT(
+                    'id', pga.id,
+                    'cnt', pga.cnt,
+                    'status', pga.status,
+                    'comment', pga.comment,
+                    'fio', au.fio,
+                    'created_at', pga.created_at
+                ) ORDER BY pga.id), '[]'::jsonb)
+                FROM produced_good_adjustments AS pga
+                INNER JOIN users AS au ON au.id = pga.user_id
+                WHERE pga.produced_good_id = pg.id
+            ) AS adjustments
+        FROM produced_goods AS pg
+        INNER JOIN products AS p ON p.id = pg.product_id
+        INNER JOIN users AS u ON u.id = pg.user_id
+        LEFT JOIN shifts AS s ON s.id = pg.shift_id
+        LEFT JOIN organizations AS o ON o.id = pg.organization_id
+        WHERE pg.lot = $1
+        AND CASE
+            WHEN $2::bigint IS NOT NULL AND $3 not in ('Admin', 'Developer') THEN
+                pg.organization_id = $2
+            ELSE TRUE
+        END
+        ORDER BY pg.id;",
+        lot,
+        current_user.organization_id,
+        current_user.role.to_string(),
+    )
+    .map(|row| Item {
+        id: row.id,
+        lot: row.lot,
+        cnt: row.cnt,
+        shift: row.shift,
+        created_at: row.created_at,
+        adjustments: row
+            .adjustments
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default(),
+        product: row.product.into(),
+        user: row.author.into(),
+        organization: row.organization.into(),
+    })
+    .fetch_all(&pool)
+    .await?;
+
+    if rows.is_empty() {
+        Err(AppError(
+            StatusCode::NOT_FOUND,
+            anyhow::anyhow!("Партия не найдена"),
+        ))
+    } else {
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> chrono::NaiveDate {
+        chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn default_pattern() {
+        assert_eq!(
+            render_lot(DEFAULT_PATTERN, 12, date(2024, 3, 5), 7),
+            "12-20240305-007"
+        );
+    }
+
+    #[test]
+    fn all_fields() {
+        assert_eq!(
+            render_lot("P{ID}/{YY}.{MM}.{DD}/{SEQ}", 3, date(2024, 12, 31), 1234),
+            "P3/24.12.31/1234"
+        );
+    }
+
+    #[test]
+    fn pattern_without_seq() {
+        assert_eq!(
+            render_lot("{YYYY}{MM}{DD}", 1, date(2024, 1, 2), 2),
+            "20240102-002"
+        );
+        assert_eq!(render_prefix("{YYYY}", 1, date(2024, 1, 2)), "2024-{SEQ}");
+    }
+
+    #[test]
+    fn prefix_keeps_seq() {
+        assert_eq!(
+            render_prefix(DEFAULT_PATTERN, 12, date(2024, 3, 5)),
+            "12-20240305-{SEQ}"
+        );
+    }
+}
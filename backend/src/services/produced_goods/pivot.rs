@@ -0,0 +1,513 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Extension,
+};
+
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{
+    check_access,
+    services::produced_goods::analitic::{
+        write_headers, write_title, Filter, Period, FILTER, Q as AnaliticQ,
+    },
+    AppError, CurrentUser,
+};
+use rust_xlsxwriter::*;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Dimension {
+    Product,
+    User,
+    Organization,
+    Measure,
+    // Период по группировке group
+    Date,
+}
+
+impl Dimension {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Product => "product",
+            Self::User => "user",
+            Self::Organization => "organization",
+            Self::Measure => "measure",
+            Self::Date => "date",
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Product => "Продукт",
+            Self::User => "Пользователь",
+            Self::Organization => "Организация",
+            Self::Measure => "Ед.измерения",
+            Self::Date => "Период",
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Measure {
+    // Выпуск с учетом одобренных корректировок
+    Sum,
+    // Количество записей
+    Count,
+    // Средний выпуск на запись
+    Avg,
+    // Сумма одобренных корректировок
+    Adjustments,
+}
+
+impl Measure {
+    fn value(&self, row: &Row) -> f64 {
+        match self {
+            Self::Sum => row.sum,
+            Self::Count => row.count,
+            Self::Avg => row.avg,
+            Self::Adjustments => row.adjustments,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Sum => "Выпуск",
+            Self::Count => "Записей",
+            Self::Avg => "Средний выпуск",
+            Self::Adjustments => "Корректировки",
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct Row {
+    row_key: String,
+    row_name: String,
+    col_key: String,
+    col_name: String,
+    // Строка итогов: 1, если измерение свернуто в GROUPING SETS
+    no_row: i32,
+    no_col: i32,
+    sum: f64,
+    count: f64,
+    avg: f64,
+    adjustments: f64,
+}
+
+// Период, фильтры, группировка дат и часовой пояс - из параметров аналитики
+#[derive(Deserialize, Debug, Clone)]
+pub struct Q {
+    pub rows: Dimension,
+    // Без колонок матрица состоит из одной колонки итогов
+    pub cols: Option<Dimension>,
+    // Показатели через ;, по умолчанию сумма выпуска
+    pub measures: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Header {
+    pub key: String,
+    pub name: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Pivot {
+    pub rows: Vec<Header>,
+    pub cols: Vec<Header>,
+    pub measures: Vec<Measure>,
+    // values[строка][колонка][показатель], пустая ячейка - null
+    pub values: Vec<Vec<Vec<Option<f64>>>>,
+    pub row_totals: Vec<Vec<f64>>,
+    pub col_totals: Vec<Vec<f64>>,
+    pub total: Vec<f64>,
+}
+
+fn parse_measures(value: &Option<String>) -> Result<Vec<Measure>, AppError> {
+    let mut measures = Vec::new();
+
+    for measure in value
+        .as_deref()
+        .unwrap_or("")
+        .split(';')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        let measure: Measure =
+            serde_json::from_value(serde_json::json!(measure)).map_err(|_| {
+                AppError(
+                    StatusCode::BAD_REQUEST,
+                    anyhow::anyhow!("Неизвестный показатель {}!", measure),
+                )
+            })?;
+
+        if !measures.contains(&measure) {
+            measures.push(measure);
+        }
+    }
+
+    if measures.is_empty() {
+        measures.push(Measure::Sum);
+    }
+
+    Ok(measures)
+}
+
+pub async fn get_pivot(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+    Query(filter): Query<AnaliticQ>,
+) -> Result<Pivot, AppError> {
+    // Бизнес логика сводной таблицы выпуска: строки и колонки по выбранным измерениям,
+    // в ячейках выбранные показатели. Итоги считаются в БД через GROUPING SETS,
+    // поэтому среднее в итогах - среднее по записям, а не по ячейкам.
+
+    if !check_access(current_user.role) {
+        return Err(AppError(
+            StatusCode::NOT_FOUND,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    if q.cols == Some(q.rows) {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Измерения строк и колонок должны различаться!"),
+        ));
+    }
+
+    let measures = parse_measures(&q.measures)?;
+    let period = filter.group.unwrap_or(Period::Day);
+    let filter = Filter::new(&pool, &current_user, &filter).await?;
+
+    let sql = format!(
+        "WITH RECURSIVE {FILTER},
+            base AS (
+              SELECT
+                CASE $15::text
+                  WHEN 'product' THEN p.id::text
+                  WHEN 'user' THEN u.id::text
+                  WHEN 'organization' THEN COALESCE(o.id::text, '')
+                  WHEN 'measure' THEN mu.id::text
+                  WHEN 'date' THEN DATE_TRUNC($17, pg.created_at AT TIME ZONE $11)::date::text
+                  ELSE ''
+                END AS row_key,
+                CASE $15::text
+                  WHEN 'product' THEN p.name
+                  WHEN 'user' THEN u.fio
+                  WHEN 'organization' THEN COALESCE(o.name, 'Без организации')
+                  WHEN 'measure' THEN mu.name
+                  ELSE ''
+                END AS row_name,
+                CASE $16::text
+                  WHEN 'product' THEN p.id::text
+                  WHEN 'user' THEN u.id::text
+                  WHEN 'organization' THEN COALESCE(o.id::text, '')
+                  WHEN 'measure' THEN mu.id::text
+                  WHEN 'date' THEN DATE_TRUNC($17, pg.created_at AT TIME ZONE $11)::date::text
+                  ELSE ''
+                END AS col_key,
+                CASE $16::text
+                  WHEN 'product' THEN p.name
+                  WHEN 'user' THEN u.fio
+                  WHEN 'organization' THEN COALESCE(o.name, 'Без организации')
+                  WHEN 'measure' THEN mu.name
+                  ELSE ''
+                END AS col_name,
+                pg.cnt + COALESCE(pa.adjustment_cnt, 0) AS qty,
+                COALESCE(pa.adjustment_cnt, 0) AS adjustment
+              FROM
+                produced_goods AS pg
+                JOIN filtered AS f ON f.id = pg.id
+                JOIN products AS p ON p.id = pg.product_id
+                JOIN measure_units AS mu ON mu.id = p.measure_unit_id
+                JOIN users AS u ON u.id = pg.user_id
+                LEFT JOIN organizations AS o ON o.id = pg.organization_id
+                LEFT JOIN (
+                  SELECT
+                    produced_good_id,
+                    SUM(cnt::bigint) AS adjustment_cnt
+                  FROM
+                    produced_good_adjustments
+                  WHERE
+                    status = 'Approved'
+                  GROUP BY
+                    produced_good_id
+                ) pa ON pa.produced_good_id = pg.id
+            )
+            SELECT
+              COALESCE(row_key, '') AS row_key,
+              COALESCE(row_name, '') AS row_name,
+              COALESCE(col_key, '') AS col_key,
+              COALESCE(col_name, '') AS col_name,
+              GROUPING(row_key)::int4 AS no_row,
+              GROUPING(col_key)::int4 AS no_col,
+              COALESCE(SUM(qty), 0)::double precision AS sum,
+              COUNT(*)::double precision AS count,
+              COALESCE(ROUND(AVG(qty)::numeric, 2), 0)::double precision AS avg,
+              COALESCE(SUM(adjustment), 0)::double precision AS adjustments
+            FROM
+              base
+            GROUP BY
+              GROUPING SETS (
+                (row_key, row_name, col_key, col_name),
+                (row_key, row_name),
+                (col_key, col_name),
+                ()
+              );"
+    );
+    let rows: Vec<Row> = filter
+        .bind(sqlx::query_as(&sql))
+        .bind(q.rows.as_str())
+        .bind(q.cols.map(|cols| cols.as_str()))
+        .bind(period.as_str())
+        .fetch_all(&pool)
+        .await?;
+
+    let header = |dimension: Option<Dimension>, key: String, name: String| match dimension {
+        Some(Dimension::Date) => Header {
+            name: chrono::NaiveDate::parse_from_str(&key, "%Y-%m-%d")
+                .map(|date| period.label(date))
+                .unwrap_or_default(),
+            key,
+        },
+        None => Header {
+            key,
+            name: "Итого".to_string(),
+        },
+        _ => Header { key, name },
+    };
+    // Периоды идут по порядку, остальные измерения - по названию
+    let sort = |dimension: Option<Dimension>, headers: &mut Vec<Header>| {
+        if dimension == Some(Dimension::Date) {
+            headers.sort_by(|a, b| a.key.cmp(&b.key));
+        } else {
+            headers.sort_by(|a, b| a.name.cmp(&b.name).then(a.key.cmp(&b.key)));
+        }
+    };
+    let pick = |row: &Row| measures.iter().map(|m| m.value(row)).collect::<Vec<f64>>();
+
+    let mut row_headers = Vec::new();
+    let mut col_headers = Vec::new();
+    let mut cells = Vec::new();
+    let mut row_totals = std::collections::HashMap::new();
+    let mut col_totals = std::collections::HashMap::new();
+    let mut total = vec![0.0; measures.len()];
+
+    for row in rows {
+        let values = pick(&row);
+        match (row.no_row, row.no_col) {
+            (0, 0) => cells.push((row.row_key, row.col_key, values)),
+            (0, _) => {
+                row_headers.push(header(Some(q.rows), row.row_key.clone(), row.row_name));
+                row_totals.insert(row.row_key, values);
+            }
+            (_, 0) => {
+                col_headers.push(header(q.cols, row.col_key.clone(), row.col_name));
+                col_totals.insert(row.col_key, values);
+            }
+            _ => total = values,
+        }
+    }
+
+    sort(Some(q.rows), &mut row_headers);
+    sort(q.cols, &mut col_headers);
+
+    let mut values = vec![vec![vec![None; measures.len()]; col_headers.len()]; row_headers.len()];
+    for (row_key, col_key, cell) in cells {
+        let row = row_headers.iter().position(|h| h.key == row_key);
+        let col = col_headers.iter().position(|h| h.key == col_key);
+        if let (Some(row), Some(col)) = (row, col) {
+            values[row][col] = cell.into_iter().map(Some).collect();
+        }
+    }
+
+    Ok(Pivot {
+        row_totals: row_headers
+            .iter()
+            .map(|h| row_totals.remove(&h.key).unwrap_or_default())
+            .collect(),
+        col_totals: col_headers
+            .iter()
+            .map(|h| col_totals.remove(&h.key).unwrap_or_default())
+            .collect(),
+        rows: row_headers,
+        cols: col_headers,
+        measures,
+        values,
+        total,
+    })
+}
+
+pub fn generate_excel(
+    pivot: &Pivot,
+    q: &Q,
+    date_one: chrono::NaiveDate,
+    date_two: chrono::NaiveDate,
+) -> Result<Vec<u8>, AppError> {
+    // Сводная таблица на одном листе: на каждую колонку - по колонке на показатель,
+    // справа итоги по строкам, внизу итоги по колонкам
+
+    let text_fmt = Format::new().set_border(FormatBorder::Thin);
+    let num_fmt = Format::new()
+        .set_num_format("#,##0.##")
+        .set_border(FormatBorder::Thin);
+    let total_fmt = Format::new()
+        .set_bold()
+        .set_num_format("#,##0.##")
+        .set_background_color(Color::RGB(0xEDEDED))
+        .set_border(FormatBorder::Thin);
+
+    // Без измерения колонок единственная колонка и есть итог по строке,
+    // при пустой выборке остается только колонка итога
+    let with_totals = q.cols.is_some() || pivot.cols.is_empty();
+    let mut cols: Vec<&str> = pivot.cols.iter().map(|h| h.name.as_str()).collect();
+    if with_totals {
+        cols.push("Итого");
+    }
+
+    let mut titles = vec![q.rows.name().to_string()];
+    for col in &cols {
+        for measure in &pivot.measures {
+            titles.push(if pivot.measures.len() > 1 {
+                format!("{}: {}", col, measure.name())
+            } else {
+                col.to_string()
+            });
+        }
+    }
+    let last_col = titles.len() as u16 - 1;
+
+    let mut wookbook = Workbook::new();
+    let worksheet = wookbook.add_worksheet();
+    worksheet.set_name("Сводная таблица")?;
+    worksheet.set_column_width(0, 30)?;
+    for col in 1..=last_col {
+        worksheet.set_column_width(col, 15)?;
+    }
+
+    write_title(
+        worksheet,
+        last_col,
+        &format!(
+            "Сводная таблица выпуска за период: {} - {}",
+            date_one.format("%d.%m.%Y"),
+            date_two.format("%d.%m.%Y")
+        ),
+    )?;
+    write_headers(
+        worksheet,
+        &titles.iter().map(String::as_str).collect::<Vec<&str>>(),
+    )?;
+    worksheet.set_freeze_panes(2, 1)?;
+
+    let mut i = 2;
+    for (n, header) in pivot.rows.iter().enumerate() {
+        worksheet.write_with_format(i, 0, &header.name, &text_fmt)?;
+
+        let mut col = 1;
+        for cell in &pivot.values[n] {
+            for value in cell {
+                match value {
+                    Some(value) => worksheet.write_number_with_format(i, col, *value, &num_fmt)?,
+                    None => worksheet.write_blank(i, col, &num_fmt)?,
+                };
+                col += 1;
+            }
+        }
+        if with_totals {
+            for value in &pivot.row_totals[n] {
+                worksheet.write_number_with_format(i, col, *value, &total_fmt)?;
+                col += 1;
+            }
+        }
+
+        i += 1;
+    }
+
+    worksheet.write_with_format(i, 0, "Итого", &total_fmt)?;
+    let mut col = 1;
+    for totals in &pivot.col_totals {
+        for value in totals {
+            worksheet.write_number_with_format(i, col, *value, &total_fmt)?;
+            col += 1;
+        }
+    }
+    if with_totals {
+        for value in &pivot.total {
+            worksheet.write_number_with_format(i, col, *value, &total_fmt)?;
+            col += 1;
+        }
+    }
+
+    let buffer = wookbook.save_to_buffer()?;
+
+    Ok(buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn q(cols: Option<Dimension>) -> Q {
+        Q {
+            rows: Dimension::Product,
+            cols,
+            measures: None,
+        }
+    }
+
+    fn excel(pivot: &Pivot, cols: Option<Dimension>) -> Result<Vec<u8>, AppError> {
+        generate_excel(
+            pivot,
+            &q(cols),
+            chrono::NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            chrono::NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+        )
+    }
+
+    fn header(key: &str, name: &str) -> Header {
+        Header {
+            key: key.to_string(),
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn excel_with_cols() {
+        let pivot = Pivot {
+            rows: vec![header("1", "Болт"), header("2", "Гайка")],
+            cols: vec![header("10", "Иванов"), header("11", "Петров")],
+            measures: vec![Measure::Sum, Measure::Count],
+            values: vec![
+                vec![vec![Some(5.0), Some(1.0)], vec![None, None]],
+                vec![vec![Some(2.0), Some(1.0)], vec![Some(3.0), Some(2.0)]],
+            ],
+            row_totals: vec![vec![5.0, 1.0], vec![5.0, 3.0]],
+            col_totals: vec![vec![7.0, 2.0], vec![3.0, 2.0]],
+            total: vec![10.0, 4.0],
+        };
+
+        let buffer = excel(&pivot, Some(Dimension::User));
+        assert!(buffer.is_ok_and(|buffer| buffer.starts_with(b"PK")));
+    }
+
+    #[test]
+    fn excel_empty() {
+        let pivot = Pivot {
+            rows: vec![],
+            cols: vec![],
+            measures: vec![Measure::Sum],
+            values: vec![],
+            row_totals: vec![],
+            col_totals: vec![],
+            total: vec![0.0],
+        };
+
+        assert!(excel(&pivot, None).is_ok());
+        assert!(excel(&pivot, Some(Dimension::Date)).is_ok());
+    }
+}
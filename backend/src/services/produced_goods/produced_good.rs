@@ -5,11 +5,29 @@ use axum::{
 };
 
 use serde::{Deserialize, Serialize};
-use sqlx::PgPool;
+use sqlx::{PgPool, Postgres, Transaction};
 
 use crate::{
     check_access, check_is_admin,
-    services::{Items, Select},
+    services::{
+        attachments::{
+            attachment::{remove_files, take_storage_keys, Entity},
+            storage::Storage,
+        },
+        periods::closed_period::check_open_period,
+        produced_goods::{
+            adjustment::{self, apply_adjustment},
+            confirmation::Status,
+            lot::next_lot,
+        },
+        rbs::{
+            custom_field::{self, check_custom_fields},
+            shift::current_shift,
+        },
+        stock::movement::{post_production, Kind},
+        work_orders::work_order::{check_open_work_order, refresh_work_order},
+        Items, Select,
+    },
     AppError, CurrentUser, Role,
 };
 
@@ -17,18 +35,200 @@ use crate::{
 pub struct RequestBody {
     product_id: i64,
     cnt: i64,
+    work_order_id: Option<i64>,
+    #[serde(default)]
+    defects: Vec<RequestBodyDefect>,
+    lot: Option<String>,
+    // Значения дополнительных полей организации: {"код": значение}
+    custom_fields: Option<serde_json::Value>,
+    // Оборудование, на котором произведен выпуск
+    machine_id: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Created {
+    pub id: i64,
+    pub lot: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RequestBodyDefect {
+    defect_reason_id: i64,
+    cnt: i64,
+}
+
+async fn save_defects(
+    tx: &mut Transaction<'_, Postgres>,
+    produced_good_id: i64,
+    defects: &[RequestBodyDefect],
+) -> Result<(), AppError> {
+    // Сохранение брака записи: кол-во по причинам организации записи
+
+    if defects.iter().any(|d| d.cnt <= 0) {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Кол-во брака должно быть больше нуля!"),
+        ));
+    }
+
+    let mut reason_ids = defects
+        .iter()
+        .map(|d| d.defect_reason_id)
+        .collect::<Vec<i64>>();
+    reason_ids.sort();
+    reason_ids.dedup();
+
+    if reason_ids.len() != defects.len() {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Причина брака указана несколько раз!"),
+        ));
+    }
+
+    let cnt: i64 = sqlx::query_scalar(
+        "SELECT
+          COUNT(dr.id)
+        FROM
+          defect_reasons AS dr
+          INNER JOIN produced_goods AS pg ON pg.organization_id = dr.organization_id
+        WHERE
+          pg.id = $1
+          AND dr.id = ANY($2)",
+    )
+    .bind(produced_good_id)
+    .bind(&reason_ids)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    if cnt != reason_ids.len() as i64 {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Причина брака не найдена в организации!"),
+        ));
+    }
+
+    let _ = sqlx::query("DELETE FROM produced_good_defects WHERE produced_good_id = $1")
+        .bind(produced_good_id)
+        .execute(&mut **tx)
+        .await?;
+
+    for defect in defects {
+        let _ = sqlx::query(
+            "INSERT
+            INTO produced_good_defects (produced_good_id, defect_reason_id, cnt) VALUES
+            ($1, $2, $3)",
+        )
+        .bind(produced_good_id)
+        .bind(defect.defect_reason_id)
+        .bind(defect.cnt)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    Ok(())
+}
+
+async fn save_custom_fields(
+    tx: &mut Transaction<'_, Postgres>,
+    produced_good_id: i64,
+    values: Option<serde_json::Value>,
+) -> Result<(), AppError> {
+    // Дополнительные поля проверяются по описаниям организации записи
+
+    let organization_id: Option<i64> =
+        sqlx::query_scalar("SELECT organization_id FROM produced_goods WHERE id = $1")
+            .bind(produced_good_id)
+            .fetch_one(&mut **tx)
+            .await?;
+
+    let custom_fields = match organization_id {
+        Some(organization_id) => {
+            check_custom_fields(
+                &mut **tx,
+                organization_id,
+                custom_field::Entity::ProducedGood,
+                values,
+            )
+            .await?
+        }
+        None => serde_json::json!({}),
+    };
+
+    let _ = sqlx::query("UPDATE produced_goods SET custom_fields = $1 WHERE id = $2")
+        .bind(custom_fields)
+        .bind(produced_good_id)
+        .execute(&mut **tx)
+        .await?;
+
+    Ok(())
+}
+
+async fn save_machine(
+    tx: &mut Transaction<'_, Postgres>,
+    produced_good_id: i64,
+    machine_id: Option<i64>,
+) -> Result<(), AppError> {
+    // Оборудование должно быть действующим и принадлежать организации записи
+
+    let result = sqlx::query(
+        "UPDATE produced_goods AS pg
+        SET machine_id = $1
+        WHERE pg.id = $2
+        AND (
+          $1::bigint IS NULL
+          OR pg.machine_id IS NOT DISTINCT FROM $1
+          OR EXISTS (
+            SELECT 1 FROM machines AS m
+            WHERE m.id = $1 AND m.organization_id = pg.organization_id AND m.active
+          )
+        )",
+    )
+    .bind(machine_id)
+    .bind(produced_good_id)
+    .execute(&mut **tx)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Оборудование не найдено или отключено!"),
+        ));
+    }
+
+    Ok(())
 }
 
 pub async fn create_produced_good(
     State(pool): State<PgPool>,
     Extension(current_user): Extension<CurrentUser>,
     Json(body): Json<RequestBody>,
-) -> Result<i64, anyhow::Error> {
+) -> Result<Created, AppError> {
     // Бизнес логика создания продукта
 
-    let row: (i64,) = sqlx::query_as(
+    let mut tx = pool.begin().await?;
+
+    if let Some(work_order_id) = body.work_order_id {
+        check_open_work_order(&mut tx, &current_user, work_order_id, body.product_id).await?;
+    }
+
+    // Номер партии вводится вручную или генерируется по шаблону продукта
+    let (lot, lot_generated) = match body.lot.as_deref().map(str::trim) {
+        Some(lot) if !lot.is_empty() => (lot.to_string(), false),
+        _ => (next_lot(&mut tx, body.product_id).await?, true),
+    };
+
+    // Записи Директора не требуют подтверждения
+    let status = if check_access(current_user.role) {
+        Status::Confirmed
+    } else {
+        Status::Submitted
+    };
+
+    let row: (i64, i64) = sqlx::query_as(
         "INSERT INTO
-          produced_goods (product_id, user_id, cnt, organization_id)
+          produced_goods (
+            product_id, user_id, cnt, organization_id, work_order_id, lot, lot_generated, status
+          )
         VALUES
           (
             $1,
@@ -44,19 +244,68 @@ pub async fn create_produced_good(
                   p.id = $1
               )
               ELSE $4
-            END
+            END,
+            $5,
+            $6,
+            $7,
+            $8
           )
         RETURNING
-          id",
+          id,
+          organization_id",
     )
     .bind(body.product_id)
     .bind(current_user.id)
     .bind(body.cnt)
     .bind(current_user.organization_id)
-    .fetch_one(&pool)
+    .bind(body.work_order_id)
+    .bind(&lot)
+    .bind(lot_generated)
+    .bind(status.to_string())
+    .fetch_one(&mut *tx)
     .await?;
 
-    Ok(row.0)
+    // Смена определяется по времени внесения записи
+    if let Some(shift_id) = current_shift(&mut tx, row.1).await? {
+        let _ = sqlx::query("UPDATE produced_goods SET shift_id = $1 WHERE id = $2")
+            .bind(shift_id)
+            .bind(row.0)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    save_defects(&mut tx, row.0, &body.defects).await?;
+    save_custom_fields(&mut tx, row.0, body.custom_fields).await?;
+    save_machine(&mut tx, row.0, body.machine_id).await?;
+
+    // Выпуск приходуется на склад по умолчанию
+    post_production(
+        &mut tx,
+        Kind::Receipt,
+        current_user.id,
+        row.0,
+        None,
+        body.product_id,
+        body.cnt,
+    )
+    .await?;
+
+    if let Some(work_order_id) = body.work_order_id {
+        refresh_work_order(&mut tx, work_order_id).await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(Created { id: row.0, lot })
+}
+
+#[derive(sqlx::FromRow)]
+struct Prev {
+    work_order_id: Option<i64>,
+    product_id: i64,
+    cnt: i64,
+    adjustment_cnt: i64,
+    rejected: bool,
 }
 
 pub async fn edit_produced_good(
@@ -73,12 +322,77 @@ pub async fn edit_produced_good(
             anyhow::anyhow!("У вас нет доступа для данного действия!"),
         ))
     } else {
+        let mut tx = pool.begin().await?;
+
+        // Состояние записи до изменения: заказ, продукт, кол-во и корректировки
+        let prev: Option<Prev> = sqlx::query_as(
+            "SELECT
+              pg.work_order_id,
+              pg.product_id,
+              pg.cnt,
+              COALESCE(SUM(pga.cnt::bigint), 0)::bigint AS adjustment_cnt,
+              pg.status = 'Rejected' AS rejected
+            FROM
+              produced_goods AS pg
+              LEFT JOIN produced_good_adjustments AS pga ON pga.produced_good_id = pg.id
+                AND pga.status = 'Approved'
+            WHERE
+              pg.id = $1
+            GROUP BY
+              pg.id",
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(Prev {
+            work_order_id: prev_work_order_id,
+            product_id: prev_product_id,
+            cnt: prev_cnt,
+            adjustment_cnt: adj,
+            rejected,
+        }) = prev
+        else {
+            return Err(AppError(
+                StatusCode::NOT_FOUND,
+                anyhow::anyhow!("Такой записи не существует"),
+            ));
+        };
+
+        check_open_period(&mut tx, &current_user, id, "edit").await?;
+
+        // Продукт сверяется с заказом при смене как заказа, так и продукта
+        if let Some(work_order_id) = body
+            .work_order_id
+            .filter(|w| Some(*w) != prev_work_order_id || prev_product_id != body.product_id)
+        {
+            check_open_work_order(&mut tx, &current_user, work_order_id, body.product_id).await?;
+        }
+
+        // При смене продукта весь выпуск записи сторнируется со старого продукта.
+        // Выпуск отклоненной записи списан при отклонении и по складу не проводится.
+        if !rejected && prev_product_id != body.product_id {
+            post_production(
+                &mut tx,
+                Kind::Adjustment,
+                current_user.id,
+                id,
+                None,
+                prev_product_id,
+                -(prev_cnt + adj),
+            )
+            .await?;
+        }
+
         let _ = sqlx::query(
             "UPDATE
               produced_goods
             SET
               product_id = $1,
               cnt = $2,
+              work_order_id = $5,
+              lot = COALESCE(NULLIF(TRIM($6), ''), lot),
+              lot_generated = lot_generated AND COALESCE(NULLIF(TRIM($6), ''), lot) = lot,
               organization_id = CASE
                 WHEN $4::bigint IS NULL THEN (
                   SELECT
@@ -98,9 +412,42 @@ pub async fn edit_produced_good(
         .bind(body.cnt)
         .bind(id)
         .bind(current_user.organization_id)
-        .execute(&pool)
+        .bind(body.work_order_id)
+        .bind(&body.lot)
+        .execute(&mut *tx)
         .await?;
 
+        save_defects(&mut tx, id, &body.defects).await?;
+        save_custom_fields(&mut tx, id, body.custom_fields).await?;
+        save_machine(&mut tx, id, body.machine_id).await?;
+
+        if !rejected {
+            let cnt = if prev_product_id != body.product_id {
+                body.cnt + adj
+            } else {
+                body.cnt - prev_cnt
+            };
+            post_production(
+                &mut tx,
+                Kind::Adjustment,
+                current_user.id,
+                id,
+                None,
+                body.product_id,
+                cnt,
+            )
+            .await?;
+        }
+
+        for work_order_id in [prev_work_order_id, body.work_order_id]
+            .into_iter()
+            .flatten()
+        {
+            refresh_work_order(&mut tx, work_order_id).await?;
+        }
+
+        tx.commit().await?;
+
         Ok(id)
     }
 }
@@ -108,6 +455,7 @@ pub async fn edit_produced_good(
 pub async fn delete_produced_good(
     State(pool): State<PgPool>,
     Extension(current_user): Extension<CurrentUser>,
+    Extension(storage): Extension<Storage>,
     Path(id): Path<i64>,
 ) -> Result<(), AppError> {
     // Бизнес логика удаления производства
@@ -118,15 +466,58 @@ pub async fn delete_produced_good(
             anyhow::anyhow!("У вас нет доступа для данного действия!"),
         ))
     } else {
+        let mut tx = pool.begin().await?;
+
+        check_open_period(&mut tx, &current_user, id, "delete").await?;
+
+        // Удаленный выпуск списывается со склада корректировкой,
+        // выпуск отклоненной записи списан при отклонении
+        let row: Option<(i64, i64)> = sqlx::query_as(
+            "SELECT
+              pg.product_id,
+              (pg.cnt + COALESCE(SUM(pga.cnt::bigint), 0))::bigint
+            FROM
+              produced_goods AS pg
+              LEFT JOIN produced_good_adjustments AS pga ON pga.produced_good_id = pg.id
+                AND pga.status = 'Approved'
+            WHERE
+              pg.id = $1
+              AND pg.status <> 'Rejected'
+            GROUP BY
+              pg.id",
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if let Some((product_id, cnt)) = row {
+            post_production(
+                &mut tx,
+                Kind::Adjustment,
+                current_user.id,
+                id,
+                None,
+                product_id,
+                -cnt,
+            )
+            .await?;
+        }
+
+        let keys = take_storage_keys(&mut tx, Entity::ProducedGood, id).await?;
+
         let _ = sqlx::query(
             "DELETE
         FROM produced_goods
         WHERE id = $1;",
         )
         .bind(id)
-        .execute(&pool)
+        .execute(&mut *tx)
         .await?;
 
+        tx.commit().await?;
+
+        remove_files(&storage, keys).await;
+
         Ok(())
     }
 }
@@ -137,6 +528,7 @@ pub struct Q {
     pub page: i64,
     #[serde(default = "per_page")]
     pub per_page: i64,
+    pub status: Option<Status>,
 }
 
 fn per_page() -> i64 {
@@ -162,11 +554,26 @@ pub struct USelect {
     pub email: String,
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ItemDefect {
+    pub id: i64,
+    pub name: String,
+    pub cnt: i64,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct Item {
     pub id: i64,
     pub cnt: i64,
     pub adj: i64,
+    pub defect: i64,
+    pub defects: Vec<ItemDefect>,
+    pub work_order_id: Option<i64>,
+    pub machine_id: Option<i64>,
+    pub lot: Option<String>,
+    pub status: Status,
+    pub review_comment: Option<String>,
+    pub custom_fields: serde_json::Value,
     pub created_at: chrono::DateTime<chrono::Utc>,
 
     product: ItemProduct,
@@ -174,6 +581,12 @@ pub struct Item {
     organization: Select,
 }
 
+fn parse_defects(value: Option<serde_json::Value>) -> Vec<ItemDefect> {
+    value
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
 pub async fn get_produced_goods(
     State(pool): State<PgPool>,
     Extension(current_user): Extension<CurrentUser>,
@@ -190,6 +603,12 @@ pub async fn get_produced_goods(
         "SELECT
     pg.id,
     pg.cnt,
+    pg.work_order_id,
+    pg.machine_id,
+    pg.lot,
+    pg.status,
+    pg.review_comment,
+    pg.custom_fields,
     pg.created_at,
     p.id AS product_id,
     p.name AS product_name,
@@ -199,6 +618,16 @@ pub async fn get_produced_goods(
     u.fio AS user_fio,
     u.email AS user_email,
     SUM(COALESCE(pga.cnt::INTEGER, 0)) AS adj,
+    (
+        SELECT COALESCE(JSONB_AGG(JSONB_BUILD_OBJECT(
+            'id', dr.id,
+            'name', dr.name,
+            'cnt', pgd.cnt
+        ) ORDER BY dr.name), '[]'::jsonb)
+        FROM produced_good_defects AS pgd
+        INNER JOIN defect_reasons AS dr ON dr.id = pgd.defect_reason_id
+        WHERE pgd.produced_good_id = pg.id
+    ) AS defects,
     JSONB_BUILD_OBJECT(
         'id', o.id,
         'name', o.name
@@ -208,6 +637,7 @@ INNER JOIN users AS u on u.id = pg.user_id
 INNER JOIN products AS p  on p.id = pg.product_id
 INNER JOIN measure_units AS mu on mu.id = p.measure_unit_id
 LEFT JOIN produced_good_adjustments AS pga on pga.produced_good_id = pg.id
+    AND pga.status = 'Approved'
 LEFT JOIN organizations AS o ON o.id = pg.organization_id
 WHERE
     CASE
@@ -217,8 +647,15 @@ WHERE
             pg.organization_id = $2
         ELSE TRUE
     END
+    AND ($6::text IS NULL OR pg.status = $6)
 GROUP BY pg.id,
   pg.cnt,
+  pg.work_order_id,
+  pg.machine_id,
+  pg.lot,
+  pg.status,
+  pg.review_comment,
+  pg.custom_fields,
   pg.created_at,
   p.id,
   p.name,
@@ -234,27 +671,40 @@ OFFSET $4 LIMIT $5;",
         current_user.organization_id,
         current_user.role.to_string(),
         (q.page - 1) * q.per_page,
-        q.per_page
+        q.per_page,
+        q.status.map(|s| s.to_string()),
     )
-    .map(|row| Item {
-        id: row.id,
-        cnt: row.cnt,
-        adj: row.adj.map_or(0, |a| a),
-        created_at: row.created_at,
-        product: ItemProduct {
-            id: row.product_id,
-            name: row.product_name,
-            measure_unit: Select {
-                id: row.measure_unit_id,
-                name: row.measure_unit_name,
+    .map(|row| {
+        let defects = parse_defects(row.defects);
+
+        Item {
+            id: row.id,
+            cnt: row.cnt,
+            adj: row.adj.map_or(0, |a| a),
+            defect: defects.iter().map(|d| d.cnt).sum(),
+            defects,
+            work_order_id: row.work_order_id,
+            machine_id: row.machine_id,
+            lot: row.lot,
+            status: row.status.into(),
+            review_comment: row.review_comment,
+            custom_fields: row.custom_fields,
+            created_at: row.created_at,
+            product: ItemProduct {
+                id: row.product_id,
+                name: row.product_name,
+                measure_unit: Select {
+                    id: row.measure_unit_id,
+                    name: row.measure_unit_name,
+                },
             },
-        },
-        organization: row.organization.into(),
-        user: USelect {
-            id: row.user_id,
-            fio: row.user_fio,
-            email: row.user_email,
-        },
+            organization: row.organization.into(),
+            user: USelect {
+                id: row.user_id,
+                fio: row.user_fio,
+                email: row.user_email,
+            },
+        }
     })
     .fetch_all(&pool)
     .await?;
@@ -264,9 +714,11 @@ OFFSET $4 LIMIT $5;",
         WHEN $1::bigint IS NOT NULL THEN
             pg.user_id = $1
         ELSE TRUE
-    END",
+    END
+    AND ($2::text IS NULL OR pg.status = $2)",
     )
     .bind(current_user_id)
+    .bind(q.status.map(|s| s.to_string()))
     .fetch_one(&pool)
     .await
     .unwrap_or(0);
@@ -292,6 +744,12 @@ pub async fn detail_produced_good(
             "SELECT
         pg.id,
         pg.cnt,
+        pg.work_order_id,
+        pg.machine_id,
+        pg.lot,
+        pg.status,
+        pg.review_comment,
+        pg.custom_fields,
         pg.created_at,
         p.id AS product_id,
         p.name AS product_name,
@@ -301,6 +759,16 @@ pub async fn detail_produced_good(
         u.fio AS user_fio,
         u.email AS user_email,
         SUM(COALESCE(pga.cnt::INTEGER, 0)) AS adj,
+        (
+            SELECT COALESCE(JSONB_AGG(JSONB_BUILD_OBJECT(
+                'id', dr.id,
+                'name', dr.name,
+                'cnt', pgd.cnt
+            ) ORDER BY dr.name), '[]'::jsonb)
+            FROM produced_good_defects AS pgd
+            INNER JOIN defect_reasons AS dr ON dr.id = pgd.defect_reason_id
+            WHERE pgd.produced_good_id = pg.id
+        ) AS defects,
         JSONB_BUILD_OBJECT(
             'id', o.id,
             'name', o.name
@@ -310,10 +778,17 @@ pub async fn detail_produced_good(
     INNER JOIN products AS p  on p.id = pg.product_id
     INNER JOIN measure_units AS mu on mu.id = p.measure_unit_id
     LEFT JOIN produced_good_adjustments AS pga on pga.produced_good_id = pg.id
+    AND pga.status = 'Approved'
     LEFT JOIN organizations AS o ON o.id = pg.organization_id
     WHERE p.id = $1
     GROUP BY pg.id,
       pg.cnt,
+      pg.work_order_id,
+      pg.machine_id,
+      pg.lot,
+      pg.status,
+      pg.review_comment,
+      pg.custom_fields,
       pg.created_at,
       p.id,
       p.name,
@@ -330,26 +805,38 @@ pub async fn detail_produced_good(
 
         match row {
             // Собираем в нужный вид
-            Some(row) => Ok(Item {
-                id: row.id,
-                cnt: row.cnt,
-                adj: row.adj.map_or(0, |a| a),
-                created_at: row.created_at,
-                product: ItemProduct {
-                    id: row.product_id,
-                    name: row.product_name,
-                    measure_unit: Select {
-                        id: row.measure_unit_id,
-                        name: row.measure_unit_name,
+            Some(row) => {
+                let defects = parse_defects(row.defects);
+
+                Ok(Item {
+                    id: row.id,
+                    cnt: row.cnt,
+                    adj: row.adj.map_or(0, |a| a),
+                    defect: defects.iter().map(|d| d.cnt).sum(),
+                    defects,
+                    work_order_id: row.work_order_id,
+                    machine_id: row.machine_id,
+                    lot: row.lot,
+                    status: row.status.into(),
+                    review_comment: row.review_comment,
+                    custom_fields: row.custom_fields,
+                    created_at: row.created_at,
+                    product: ItemProduct {
+                        id: row.product_id,
+                        name: row.product_name,
+                        measure_unit: Select {
+                            id: row.measure_unit_id,
+                            name: row.measure_unit_name,
+                        },
                     },
-                },
-                organization: row.organization.into(),
-                user: USelect {
-                    id: row.user_id,
-                    fio: row.user_fio,
-                    email: row.user_email,
-                },
-            }),
+                    organization: row.organization.into(),
+                    user: USelect {
+                        id: row.user_id,
+                        fio: row.user_fio,
+                        email: row.user_email,
+                    },
+                })
+            }
             None => Err(AppError(
                 StatusCode::NOT_FOUND,
                 anyhow::anyhow!("Такой записи не существует"),
@@ -361,6 +848,8 @@ pub async fn detail_produced_good(
 #[derive(Serialize, Deserialize, Debug)]
 pub struct RequestBodyAdj {
     cnt: i64,
+    reason_id: i64,
+    comment: String,
 }
 
 pub async fn add_adj_produced_goods(
@@ -368,19 +857,87 @@ pub async fn add_adj_produced_goods(
     Extension(current_user): Extension<CurrentUser>,
     Path(id): Path<i64>,
     Json(body): Json<RequestBodyAdj>,
-) -> Result<i64, anyhow::Error> {
-    // Бизнес логика создания продукта
+) -> Result<i64, AppError> {
+    // Бизнес логика создания корректировки.
+    // Причина и комментарий обязательны. Если у организации задан порог,
+    // корректировка больше порога ждет утверждения Директором.
+
+    let comment = body.comment.trim();
+    if body.cnt == 0 || comment.is_empty() {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Укажите кол-во, причину и комментарий корректировки!"),
+        ));
+    }
+
+    let mut tx = pool.begin().await?;
+
+    let row: Option<(Option<i64>, bool)> = sqlx::query_as(
+        "SELECT
+          o.adjustment_threshold,
+          EXISTS (
+            SELECT 1 FROM adjustment_reasons AS ar
+            WHERE ar.id = $2 AND ar.organization_id = pg.organization_id
+          )
+        FROM
+          produced_goods AS pg
+          LEFT JOIN organizations AS o ON o.id = pg.organization_id
+        WHERE
+          pg.id = $1
+          AND CASE
+            WHEN $3::bigint IS NOT NULL THEN pg.organization_id = $3
+            ELSE TRUE
+          END",
+    )
+    .bind(id)
+    .bind(body.reason_id)
+    .bind(current_user.organization_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some((threshold, reason_exists)) = row else {
+        return Err(AppError(
+            StatusCode::NOT_FOUND,
+            anyhow::anyhow!("Такой записи не существует"),
+        ));
+    };
+
+    if !reason_exists {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Причина корректировки не найдена в организации!"),
+        ));
+    }
+
+    check_open_period(&mut tx, &current_user, id, "add_adjustment").await?;
+
+    // Директор и администратор утверждают свои корректировки сразу
+    let status = match threshold {
+        Some(threshold) if !check_access(current_user.role) && body.cnt.abs() > threshold => {
+            adjustment::Status::Pending
+        }
+        _ => adjustment::Status::Approved,
+    };
 
     let row: (i64,) = sqlx::query_as(
         "INSERT
-            INTO produced_good_adjustments (user_id, produced_good_id, cnt) VALUES
-            ($1, $2, $3) RETURNING id",
+            INTO produced_good_adjustments (user_id, produced_good_id, cnt, reason_id, comment, status) VALUES
+            ($1, $2, $3, $4, $5, $6) RETURNING id",
     )
     .bind(current_user.id)
     .bind(id)
     .bind(body.cnt)
-    .fetch_one(&pool)
+    .bind(body.reason_id)
+    .bind(comment)
+    .bind(status.to_string())
+    .fetch_one(&mut *tx)
     .await?;
 
+    if status == adjustment::Status::Approved {
+        apply_adjustment(&mut tx, current_user.id, row.0).await?;
+    }
+
+    tx.commit().await?;
+
     Ok(row.0)
 }
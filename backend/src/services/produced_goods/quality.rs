@@ -0,0 +1,246 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Extension,
+};
+
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{check_access, AppError, CurrentUser};
+
+#[derive(Deserialize, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Group {
+    #[default]
+    Product,
+    User,
+    Day,
+    Week,
+    Month,
+}
+
+impl Group {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Product => "product",
+            Self::User => "user",
+            Self::Day => "day",
+            Self::Week => "week",
+            Self::Month => "month",
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Q {
+    pub date_one: chrono::NaiveDate,
+    pub date_two: chrono::NaiveDate,
+    #[serde(default)]
+    pub group: Group,
+
+    pub product_id: Option<i64>,
+    pub user_id: Option<i64>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Item {
+    pub key: String,
+    pub name: String,
+    pub good: i64,
+    pub defect: i64,
+    pub yield_rate: f64,
+    pub defect_rate: f64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ItemReason {
+    pub id: i64,
+    pub name: String,
+    pub cnt: i64,
+    pub percent: f64,
+    pub cumulative: f64,
+}
+
+fn percent(part: i64, total: i64) -> f64 {
+    if total > 0 {
+        (part as f64 * 10000.0 / total as f64).round() / 100.0
+    } else {
+        0.0
+    }
+}
+
+pub async fn get_quality(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Vec<Item>, AppError> {
+    // Бизнес логика расчета выхода годного и доли брака.
+    // Годное - кол-во с корректировками, брак - сумма по причинам брака записи.
+
+    if !check_access(current_user.role) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    let rows: Vec<(String, String, i64, i64)> = sqlx::query_as(
+        "WITH base AS (
+          SELECT
+            pg.product_id,
+            pg.user_id,
+            pg.created_at,
+            pg.cnt + COALESCE(pa.adjustment_cnt, 0) AS good,
+            COALESCE(pd.defect_cnt, 0) AS defect
+          FROM
+            produced_goods AS pg
+            LEFT JOIN (
+              SELECT
+                produced_good_id,
+                SUM(cnt::bigint) AS adjustment_cnt
+              FROM
+                produced_good_adjustments
+              WHERE
+                status = 'Approved'
+              GROUP BY
+                produced_good_id
+            ) pa ON pa.produced_good_id = pg.id
+            LEFT JOIN (
+              SELECT
+                produced_good_id,
+                SUM(cnt) AS defect_cnt
+              FROM
+                produced_good_defects
+              GROUP BY
+                produced_good_id
+            ) pd ON pd.produced_good_id = pg.id
+          WHERE pg.created_at::date BETWEEN $1 AND $2
+          AND pg.status <> 'Rejected'
+          AND CASE
+              WHEN $3::bigint IS NOT NULL AND $4 = 'Director' THEN pg.organization_id = $3
+              ELSE TRUE
+            END
+          AND ($6::bigint IS NULL OR pg.product_id = $6)
+          AND ($7::bigint IS NULL OR pg.user_id = $7)
+        )
+        SELECT
+          CASE $5::text
+            WHEN 'product' THEN p.id::text
+            WHEN 'user' THEN u.id::text
+            ELSE DATE_TRUNC($5, b.created_at)::date::text
+          END AS key,
+          CASE $5
+            WHEN 'product' THEN p.name || ', ' || mu.name
+            WHEN 'user' THEN u.fio
+            ELSE TO_CHAR(DATE_TRUNC($5, b.created_at), 'DD.MM.YYYY')
+          END AS name,
+          SUM(b.good)::bigint AS good,
+          SUM(b.defect)::bigint AS defect
+        FROM
+          base AS b
+          JOIN products AS p ON p.id = b.product_id
+          JOIN measure_units AS mu ON mu.id = p.measure_unit_id
+          JOIN users AS u ON u.id = b.user_id
+        GROUP BY
+          1,
+          2
+        ORDER BY
+          1;",
+    )
+    .bind(q.date_one)
+    .bind(q.date_two)
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .bind(q.group.as_str())
+    .bind(q.product_id)
+    .bind(q.user_id)
+    .fetch_all(&pool)
+    .await?;
+
+    let mut items = rows
+        .into_iter()
+        .map(|row| Item {
+            key: row.0,
+            name: row.1,
+            good: row.2,
+            defect: row.3,
+            yield_rate: percent(row.2, row.2 + row.3),
+            defect_rate: percent(row.3, row.2 + row.3),
+        })
+        .collect::<Vec<Item>>();
+
+    // Периоды идут по порядку, продукты и сотрудники - от худшей доли брака
+    if matches!(q.group, Group::Product | Group::User) {
+        items.sort_by(|a, b| b.defect_rate.total_cmp(&a.defect_rate));
+    }
+
+    Ok(items)
+}
+
+pub async fn get_defect_pareto(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Vec<ItemReason>, AppError> {
+    // Бизнес логика Парето причин брака: причины по убыванию кол-ва
+    // с долей и накопленной долей от всего брака за период.
+
+    if !check_access(current_user.role) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    let rows: Vec<(i64, String, i64)> = sqlx::query_as(
+        "SELECT
+          dr.id,
+          dr.name,
+          SUM(pgd.cnt)::bigint AS cnt
+        FROM
+          produced_good_defects AS pgd
+          JOIN defect_reasons AS dr ON dr.id = pgd.defect_reason_id
+          JOIN produced_goods AS pg ON pg.id = pgd.produced_good_id
+        WHERE pg.created_at::date BETWEEN $1 AND $2
+        AND pg.status <> 'Rejected'
+        AND CASE
+            WHEN $3::bigint IS NOT NULL AND $4 = 'Director' THEN pg.organization_id = $3
+            ELSE TRUE
+          END
+        AND ($5::bigint IS NULL OR pg.product_id = $5)
+        AND ($6::bigint IS NULL OR pg.user_id = $6)
+        GROUP BY
+          dr.id,
+          dr.name
+        ORDER BY
+          cnt DESC,
+          dr.name;",
+    )
+    .bind(q.date_one)
+    .bind(q.date_two)
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .bind(q.product_id)
+    .bind(q.user_id)
+    .fetch_all(&pool)
+    .await?;
+
+    let total: i64 = rows.iter().map(|row| row.2).sum();
+    let mut cumulative = 0;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            cumulative += row.2;
+
+            ItemReason {
+                id: row.0,
+                name: row.1,
+                cnt: row.2,
+                percent: percent(row.2, total),
+                cumulative: percent(cumulative, total),
+            }
+        })
+        .collect())
+}
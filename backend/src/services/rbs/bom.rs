@@ -0,0 +1,264 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{check_access, services::Select, AppError, CurrentUser};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RequestBody {
+    component_id: i64,
+    cnt: f64,
+}
+
+async fn validate(
+    pool: &PgPool,
+    current_user: &CurrentUser,
+    product_id: i64,
+    body: &RequestBody,
+) -> Result<(), AppError> {
+    // Проверка компонента спецификации: кол-во, организация и отсутствие циклов
+
+    if body.cnt <= 0.0 {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Кол-во на единицу продукта должно быть больше нуля!"),
+        ));
+    }
+
+    if body.component_id == product_id {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Продукт не может входить в собственную спецификацию!"),
+        ));
+    }
+
+    let same_organization: bool = sqlx::query_scalar(
+        "SELECT
+          COUNT(DISTINCT p.organization_id) = 1 AND COUNT(p.id) = 2
+        FROM products AS p
+        WHERE p.id IN ($1, $2)
+        AND CASE
+            WHEN $3::bigint IS NOT NULL THEN p.organization_id = $3
+            ELSE TRUE
+        END",
+    )
+    .bind(product_id)
+    .bind(body.component_id)
+    .bind(current_user.organization_id)
+    .fetch_one(pool)
+    .await?;
+
+    if !same_organization {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Продукт и компонент должны принадлежать одной организации!"),
+        ));
+    }
+
+    // Продукт не должен встречаться ниже по дереву компонента
+    let is_cycle: bool = sqlx::query_scalar(
+        "WITH RECURSIVE tree AS (
+            SELECT component_id FROM bom_items WHERE product_id = $1
+            UNION
+            SELECT bi.component_id
+            FROM bom_items AS bi
+            INNER JOIN tree AS t ON bi.product_id = t.component_id
+        )
+        SELECT EXISTS (SELECT 1 FROM tree WHERE component_id = $2)",
+    )
+    .bind(body.component_id)
+    .bind(product_id)
+    .fetch_one(pool)
+    .await?;
+
+    if is_cycle {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Компонент уже содержит данный продукт в своей спецификации!"),
+        ));
+    }
+
+    Ok(())
+}
+
+pub async fn create_bom_item(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(product_id): Path<i64>,
+    Json(body): Json<RequestBody>,
+) -> Result<i64, AppError> {
+    // Бизнес логика добавления компонента в спецификацию
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        validate(&pool, &current_user, product_id, &body).await?;
+
+        let row: (i64,) = sqlx::query_as(
+            "INSERT INTO
+              bom_items (product_id, component_id, cnt, organization_id)
+            SELECT
+              p.id,
+              $2,
+              $3,
+              p.organization_id
+            FROM
+              products AS p
+            WHERE
+              p.id = $1
+            RETURNING
+              id",
+        )
+        .bind(product_id)
+        .bind(body.component_id)
+        .bind(body.cnt)
+        .fetch_one(&pool)
+        .await?;
+
+        Ok(row.0)
+    }
+}
+
+pub async fn edit_bom_item(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+    Json(body): Json<RequestBody>,
+) -> Result<i64, AppError> {
+    // Бизнес логика редактирования компонента спецификации
+
+    if !check_access(current_user.role) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    let product_id: Option<i64> =
+        sqlx::query_scalar("SELECT product_id FROM bom_items WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&pool)
+            .await?;
+
+    match product_id {
+        Some(product_id) => {
+            validate(&pool, &current_user, product_id, &body).await?;
+
+            let _ = sqlx::query(
+                "UPDATE bom_items
+                SET component_id=$1, cnt=$2, updated_at=NOW()
+                WHERE id = $3",
+            )
+            .bind(body.component_id)
+            .bind(body.cnt)
+            .bind(id)
+            .execute(&pool)
+            .await?;
+
+            Ok(id)
+        }
+        None => Err(AppError(
+            StatusCode::NOT_FOUND,
+            anyhow::anyhow!("Такой записи не существует"),
+        )),
+    }
+}
+
+pub async fn delete_bom_item(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<(), AppError> {
+    // Бизнес логика удаления компонента спецификации
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        let _ = sqlx::query(
+            "DELETE
+        FROM bom_items
+        WHERE id = $1
+        AND CASE
+            WHEN $2::bigint IS NOT NULL THEN organization_id = $2
+            ELSE TRUE
+        END;",
+        )
+        .bind(id)
+        .bind(current_user.organization_id)
+        .execute(&pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Item {
+    pub id: i64,
+    pub cnt: f64,
+
+    component: Select,
+    measure_unit: Select,
+}
+
+pub async fn get_bom(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(product_id): Path<i64>,
+) -> Result<Vec<Item>, AppError> {
+    // Бизнес логика получения спецификации продукта (первый уровень)
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        let rows = sqlx::query!(
+            "SELECT
+                bi.id,
+                bi.cnt,
+                JSONB_BUILD_OBJECT(
+                    'id', c.id,
+                    'name', c.name
+                ) AS component,
+                JSONB_BUILD_OBJECT(
+                    'id', mu.id,
+                    'name', mu.name
+                ) AS measure_unit
+            FROM bom_items AS bi
+            INNER JOIN products AS c ON c.id = bi.component_id
+            INNER JOIN measure_units AS mu ON mu.id = c.measure_unit_id
+            WHERE bi.product_id = $1
+            AND CASE
+                WHEN $2::bigint IS NOT NULL AND $3 not in ('Admin', 'Developer') THEN
+                    bi.organization_id = $2
+                ELSE TRUE
+            END
+            ORDER BY bi.id;",
+            product_id,
+            current_user.organization_id,
+            current_user.role.to_string(),
+        )
+        .map(|row| Item {
+            id: row.id,
+            cnt: row.cnt,
+            component: row.component.into(),
+            measure_unit: row.measure_unit.into(),
+        })
+        .fetch_all(&pool)
+        .await?;
+
+        Ok(rows)
+    }
+}
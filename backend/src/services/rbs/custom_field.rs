@@ -0,0 +1,613 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::PgPool;
+
+use crate::{
+    check_access, check_is_admin,
+    services::{Items, Select},
+    AppError, CurrentUser,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum Entity {
+    Product,
+    ProducedGood,
+}
+
+impl From<&str> for Entity {
+    fn from(value: &str) -> Self {
+        match value {
+            "Product" => Self::Product,
+            _ => Self::ProducedGood,
+        }
+    }
+}
+
+impl From<String> for Entity {
+    fn from(value: String) -> Self {
+        Self::from(value.as_str())
+    }
+}
+
+impl std::fmt::Display for Entity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Product => "Product",
+            Self::ProducedGood => "ProducedGood",
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    Text,
+    Number,
+    Enum,
+    Date,
+    Boolean,
+}
+
+impl From<&str> for Kind {
+    fn from(value: &str) -> Self {
+        match value {
+            "Number" => Self::Number,
+            "Enum" => Self::Enum,
+            "Date" => Self::Date,
+            "Boolean" => Self::Boolean,
+            _ => Self::Text,
+        }
+    }
+}
+
+impl From<String> for Kind {
+    fn from(value: String) -> Self {
+        Self::from(value.as_str())
+    }
+}
+
+impl std::fmt::Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Text => "Text",
+            Self::Number => "Number",
+            Self::Enum => "Enum",
+            Self::Date => "Date",
+            Self::Boolean => "Boolean",
+        })
+    }
+}
+
+fn check_value(kind: Kind, options: &[String], value: Value) -> Option<Value> {
+    // Приведение значения к типу поля. None - значение не подходит.
+    // Из форм числа, даты и флаги могут прийти строкой.
+
+    match (kind, value) {
+        (Kind::Text, Value::String(s)) => Some(Value::String(s.trim().to_string())),
+        (Kind::Number, Value::Number(n)) => Some(Value::Number(n)),
+        (Kind::Number, Value::String(s)) => s
+            .trim()
+            .replace(',', ".")
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number),
+        (Kind::Enum, Value::String(s)) if options.contains(&s) => Some(Value::String(s)),
+        (Kind::Date, Value::String(s)) => chrono::NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d")
+            .ok()
+            .map(|d| Value::String(d.format("%Y-%m-%d").to_string())),
+        (Kind::Boolean, Value::Bool(b)) => Some(Value::Bool(b)),
+        (Kind::Boolean, Value::String(s)) => s.parse::<bool>().ok().map(Value::Bool),
+        _ => None,
+    }
+}
+
+pub async fn check_custom_fields<'c>(
+    executor: impl sqlx::PgExecutor<'c>,
+    organization_id: i64,
+    entity: Entity,
+    values: Option<Value>,
+) -> Result<Value, AppError> {
+    // Проверка значений дополнительных полей по описаниям организации.
+    // Возвращает объект с приведенными значениями, пустые значения не сохраняются.
+
+    let fields: Vec<(String, String, String, Value, bool)> = sqlx::query_as(
+        "SELECT code, name, kind, options, required
+        FROM custom_fields
+        WHERE organization_id = $1 AND entity = $2",
+    )
+    .bind(organization_id)
+    .bind(entity.to_string())
+    .fetch_all(executor)
+    .await?;
+
+    let mut values = match values {
+        None | Some(Value::Null) => Map::new(),
+        Some(Value::Object(values)) => values,
+        Some(_) => {
+            return Err(AppError(
+                StatusCode::BAD_REQUEST,
+                anyhow::anyhow!("Дополнительные поля должны передаваться объектом!"),
+            ))
+        }
+    };
+
+    let mut result = Map::new();
+    for (code, name, kind, options, required) in fields {
+        let options: Vec<String> = serde_json::from_value(options).unwrap_or_default();
+
+        let value = values
+            .remove(&code)
+            .filter(|v| !v.is_null() && v.as_str().is_none_or(|s| !s.trim().is_empty()));
+
+        match value {
+            Some(value) => match check_value(Kind::from(kind), &options, value) {
+                Some(value) => {
+                    result.insert(code, value);
+                }
+                None => {
+                    return Err(AppError(
+                        StatusCode::BAD_REQUEST,
+                        anyhow::anyhow!("Неверное значение поля «{}»!", name),
+                    ))
+                }
+            },
+            None if required => {
+                return Err(AppError(
+                    StatusCode::BAD_REQUEST,
+                    anyhow::anyhow!("Заполните поле «{}»!", name),
+                ))
+            }
+            None => {}
+        }
+    }
+
+    if let Some(code) = values.keys().next() {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Неизвестное поле «{}»!", code),
+        ));
+    }
+
+    Ok(Value::Object(result))
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RequestBody {
+    organization_id: Option<i64>,
+    entity: Entity,
+    code: String,
+    name: String,
+    kind: Kind,
+    #[serde(default)]
+    options: Vec<String>,
+    #[serde(default)]
+    required: bool,
+    #[serde(default)]
+    position: i32,
+}
+
+impl RequestBody {
+    fn normalize(mut self) -> Result<Self, AppError> {
+        // Код поля - ключ в JSONB, допускаются строчные латинские буквы, цифры и "_"
+
+        self.code = self.code.trim().to_string();
+        let is_valid_code = self.code.starts_with(|c: char| c.is_ascii_lowercase())
+            && self
+                .code
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+            && self.code.len() <= 63;
+
+        if !is_valid_code {
+            return Err(AppError(
+                StatusCode::BAD_REQUEST,
+                anyhow::anyhow!("Код поля должен состоять из латинских букв, цифр и \"_\"!"),
+            ));
+        }
+
+        // Повторы убираются с сохранением порядка первого вхождения
+        let mut seen = std::collections::HashSet::new();
+        self.options = self
+            .options
+            .into_iter()
+            .map(|o| o.trim().to_string())
+            .filter(|o| !o.is_empty() && seen.insert(o.clone()))
+            .collect();
+
+        match self.kind {
+            Kind::Enum if self.options.is_empty() => Err(AppError(
+                StatusCode::BAD_REQUEST,
+                anyhow::anyhow!("Укажите значения списка!"),
+            )),
+            Kind::Enum => Ok(self),
+            _ => Ok(Self {
+                options: Vec::new(),
+                ..self
+            }),
+        }
+    }
+}
+
+pub async fn create_custom_field(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(body): Json<RequestBody>,
+) -> Result<i64, AppError> {
+    // Бизнес логика создания дополнительного поля
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        let body = body.normalize()?;
+        let organization_id = if check_is_admin(current_user.role) {
+            body.organization_id
+        } else {
+            current_user.organization_id
+        };
+
+        match organization_id {
+            Some(organization_id) => {
+                let exists: bool = sqlx::query_scalar(
+                    "SELECT EXISTS (
+                      SELECT 1 FROM custom_fields
+                      WHERE organization_id = $1 AND entity = $2 AND code = $3
+                    )",
+                )
+                .bind(organization_id)
+                .bind(body.entity.to_string())
+                .bind(&body.code)
+                .fetch_one(&pool)
+                .await?;
+
+                if exists {
+                    return Err(AppError(
+                        StatusCode::BAD_REQUEST,
+                        anyhow::anyhow!("Поле с кодом «{}» уже существует!", body.code),
+                    ));
+                }
+
+                let row: (i64,) = sqlx::query_as(
+                    "INSERT
+                    INTO custom_fields (
+                      organization_id, entity, code, name, kind, options, required, position
+                    ) VALUES
+                    ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING id",
+                )
+                .bind(organization_id)
+                .bind(body.entity.to_string())
+                .bind(body.code)
+                .bind(body.name)
+                .bind(body.kind.to_string())
+                .bind(serde_json::json!(body.options))
+                .bind(body.required)
+                .bind(body.position)
+                .fetch_one(&pool)
+                .await?;
+
+                Ok(row.0)
+            }
+            _ => Err(AppError(
+                StatusCode::BAD_REQUEST,
+                anyhow::anyhow!("Невозможно создать запись без организации!"),
+            )),
+        }
+    }
+}
+
+pub async fn edit_custom_field(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+    Json(body): Json<RequestBody>,
+) -> Result<i64, AppError> {
+    // Бизнес логика редактирования дополнительного поля.
+    // Код, тип и сущность не меняются, иначе сохраненные значения потеряют смысл.
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        let body = body.normalize()?;
+
+        let result = sqlx::query(
+            "UPDATE custom_fields
+            SET name=$1, options=$2, required=$3, position=$4, updated_at=NOW()
+            WHERE id = $5
+            AND kind = $6
+            AND CASE
+                WHEN $8 not in ('Admin', 'Developer') THEN organization_id = $7::bigint
+                ELSE TRUE
+            END",
+        )
+        .bind(body.name)
+        .bind(serde_json::json!(body.options))
+        .bind(body.required)
+        .bind(body.position)
+        .bind(id)
+        .bind(body.kind.to_string())
+        .bind(current_user.organization_id)
+        .bind(current_user.role.to_string())
+        .execute(&pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError(
+                StatusCode::BAD_REQUEST,
+                anyhow::anyhow!("Поле не найдено или изменен его тип!"),
+            ));
+        }
+
+        Ok(id)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Q {
+    #[serde(default = "page")]
+    pub page: i64,
+    #[serde(default = "per_page")]
+    pub per_page: i64,
+    pub entity: Option<Entity>,
+    // Для администраторов: поля выбранной организации
+    pub organization_id: Option<i64>,
+}
+
+fn per_page() -> i64 {
+    15
+}
+
+fn page() -> i64 {
+    1
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Item {
+    pub id: i64,
+    pub entity: Entity,
+    pub code: String,
+    pub name: String,
+    pub kind: Kind,
+    pub options: Vec<String>,
+    pub required: bool,
+    pub position: i32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+
+    organization: Select,
+}
+
+pub async fn get_custom_fields(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Items<Item>, anyhow::Error> {
+    // Бизнес логика получения списка дополнительных полей в порядке вывода в форме
+
+    let organization_id = if check_is_admin(current_user.role) {
+        q.organization_id
+    } else {
+        current_user.organization_id
+    };
+
+    let rows = sqlx::query!(
+        "SELECT
+            cf.id,
+            cf.entity,
+            cf.code,
+            cf.name,
+            cf.kind,
+            cf.options,
+            cf.required,
+            cf.position,
+            cf.created_at,
+            JSONB_BUILD_OBJECT(
+                'id', o.id,
+                'name', o.name
+            ) AS organization
+        FROM custom_fields AS cf
+        LEFT JOIN organizations AS o ON o.id = cf.organization_id
+        WHERE
+            ($1::bigint IS NULL OR cf.organization_id = $1)
+            AND ($2::text IS NULL OR cf.entity = $2)
+        ORDER BY cf.entity, cf.position, cf.id
+        OFFSET $3 LIMIT $4;",
+        organization_id,
+        q.entity.map(|e| e.to_string()),
+        (q.page - 1) * q.per_page,
+        q.per_page,
+    )
+    .map(|row| Item {
+        id: row.id,
+        entity: row.entity.into(),
+        code: row.code,
+        name: row.name,
+        kind: row.kind.into(),
+        options: serde_json::from_value(row.options).unwrap_or_default(),
+        required: row.required,
+        position: row.position,
+        created_at: row.created_at,
+        organization: row.organization.into(),
+    })
+    .fetch_all(&pool)
+    .await?;
+
+    // Подсчет данных для пагинации
+    let cnt: i64 = sqlx::query_scalar(
+        "SELECT COUNT(id) FROM custom_fields AS cf
+        WHERE ($1::bigint IS NULL OR cf.organization_id = $1)
+        AND ($2::text IS NULL OR cf.entity = $2)",
+    )
+    .bind(organization_id)
+    .bind(q.entity.map(|e| e.to_string()))
+    .fetch_one(&pool)
+    .await
+    .unwrap_or(0);
+
+    let cnt = (cnt as f64 / q.per_page as f64).ceil() as i64;
+
+    Ok(Items { items: rows, cnt })
+}
+
+pub async fn detail_custom_field(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<Item, AppError> {
+    // Бизнес логика получения дополнительного поля
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        let row = sqlx::query!(
+            "SELECT
+            cf.id,
+            cf.entity,
+            cf.code,
+            cf.name,
+            cf.kind,
+            cf.options,
+            cf.required,
+            cf.position,
+            cf.created_at,
+            JSONB_BUILD_OBJECT(
+                'id', o.id,
+                'name', o.name
+            ) AS organization
+        FROM custom_fields AS cf
+        LEFT JOIN organizations AS o ON o.id = cf.organization_id
+        WHERE cf.id = $1
+        AND CASE
+            WHEN $2::bigint IS NOT NULL AND $3 not in ('Admin', 'Developer') THEN
+                cf.organization_id = $2
+            ELSE TRUE
+        END;",
+            id,
+            current_user.organization_id,
+            current_user.role.to_string(),
+        )
+        .fetch_optional(&pool)
+        .await?;
+
+        match row {
+            Some(row) => Ok(Item {
+                id: row.id,
+                entity: row.entity.into(),
+                code: row.code,
+                name: row.name,
+                kind: row.kind.into(),
+                options: serde_json::from_value(row.options).unwrap_or_default(),
+                required: row.required,
+                position: row.position,
+                created_at: row.created_at,
+                organization: row.organization.into(),
+            }),
+            None => Err(AppError(
+                StatusCode::FORBIDDEN,
+                anyhow::anyhow!("Такой записи не существует"),
+            )),
+        }
+    }
+}
+
+pub async fn delete_custom_field(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<(), AppError> {
+    // Бизнес логика удаления дополнительного поля вместе с его значениями в записях
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        let mut tx = pool.begin().await?;
+
+        let row: Option<(i64, String, String)> = sqlx::query_as(
+            "DELETE
+        FROM custom_fields
+        WHERE id = $1
+        AND CASE
+            WHEN $3 not in ('Admin', 'Developer') THEN organization_id = $2::bigint
+            ELSE TRUE
+        END
+        RETURNING organization_id, entity, code",
+        )
+        .bind(id)
+        .bind(current_user.organization_id)
+        .bind(current_user.role.to_string())
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if let Some((organization_id, entity, code)) = row {
+            let sql = match Entity::from(entity) {
+                Entity::Product => {
+                    "UPDATE products SET custom_fields = custom_fields - $1
+                    WHERE organization_id = $2 AND custom_fields ? $1"
+                }
+                Entity::ProducedGood => {
+                    "UPDATE produced_goods SET custom_fields = custom_fields - $1
+                    WHERE organization_id = $2 AND custom_fields ? $1"
+                }
+            };
+
+            let _ = sqlx::query(sql)
+                .bind(code)
+                .bind(organization_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn body(kind: &str, options: &[&str]) -> RequestBody {
+        serde_json::from_value(serde_json::json!({
+            "entity": "Product",
+            "code": "color",
+            "name": "Цвет",
+            "kind": kind,
+            "options": options,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn options_unique() {
+        let body = body("Enum", &["a", " b ", "a", "", "b", "c"]).normalize();
+
+        assert_eq!(body.ok().unwrap().options, ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn enum_without_options() {
+        assert!(body("Enum", &[" ", ""]).normalize().is_err());
+    }
+
+    #[test]
+    fn options_only_for_enum() {
+        let body = body("Text", &["a"]).normalize();
+
+        assert!(body.ok().unwrap().options.is_empty());
+    }
+}
@@ -0,0 +1,334 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{
+    check_access, check_is_admin,
+    services::{Items, Select},
+    AppError, CurrentUser,
+};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RequestBody {
+    organization_id: Option<i64>,
+    // Код причины для отчетов, уникален в организации
+    code: String,
+    name: String,
+}
+
+async fn check_code(
+    pool: &PgPool,
+    organization_id: i64,
+    id: Option<i64>,
+    code: &str,
+) -> Result<(), AppError> {
+    // Проверка кода причины простоя
+
+    if code.trim().is_empty() {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Код причины простоя обязателен!"),
+        ));
+    }
+
+    let exists: bool = sqlx::query_scalar(
+        "SELECT EXISTS (
+          SELECT 1 FROM downtime_reasons
+          WHERE organization_id = $1 AND code = $2 AND ($3::bigint IS NULL OR id <> $3)
+        )",
+    )
+    .bind(organization_id)
+    .bind(code.trim())
+    .bind(id)
+    .fetch_one(pool)
+    .await?;
+
+    if exists {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Причина простоя с кодом {} уже существует!", code.trim()),
+        ));
+    }
+
+    Ok(())
+}
+
+pub async fn create_downtime_reason(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(body): Json<RequestBody>,
+) -> Result<i64, AppError> {
+    // Бизнес логика создания причины простоя
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        let organization_id = if check_is_admin(current_user.role) {
+            body.organization_id
+        } else {
+            current_user.organization_id
+        };
+
+        match organization_id {
+            Some(organization_id) => {
+                check_code(&pool, organization_id, None, &body.code).await?;
+
+                let row: (i64,) = sqlx::query_as(
+                    "INSERT
+                    INTO downtime_reasons (code, name, organization_id) VALUES
+                    ($1, $2, $3) RETURNING id",
+                )
+                .bind(body.code.trim())
+                .bind(body.name)
+                .bind(organization_id)
+                .fetch_one(&pool)
+                .await?;
+
+                Ok(row.0)
+            }
+            _ => Err(AppError(
+                StatusCode::BAD_REQUEST,
+                anyhow::anyhow!("Невозможно создать запись без организации!"),
+            )),
+        }
+    }
+}
+
+pub async fn edit_downtime_reason(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+    Json(body): Json<RequestBody>,
+) -> Result<i64, AppError> {
+    // Бизнес логика редактирования причины простоя
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        let organization_id = if check_is_admin(current_user.role) {
+            body.organization_id
+        } else {
+            current_user.organization_id
+        };
+
+        match organization_id {
+            Some(organization_id) => {
+                check_code(&pool, organization_id, Some(id), &body.code).await?;
+
+                let _ = sqlx::query(
+                    "UPDATE downtime_reasons
+                    SET code=$1, name=$2, organization_id=$3, updated_at=NOW()
+                    WHERE id = $4",
+                )
+                .bind(body.code.trim())
+                .bind(body.name)
+                .bind(organization_id)
+                .bind(id)
+                .execute(&pool)
+                .await?;
+
+                Ok(id)
+            }
+            _ => Err(AppError(
+                StatusCode::BAD_REQUEST,
+                anyhow::anyhow!("Невозможно отредактировать запись без организации!"),
+            )),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Q {
+    #[serde(default = "page")]
+    pub page: i64,
+    #[serde(default = "per_page")]
+    pub per_page: i64,
+}
+
+fn per_page() -> i64 {
+    15
+}
+
+fn page() -> i64 {
+    1
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Item {
+    pub id: i64,
+    pub code: String,
+    pub name: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+
+    organization: Select,
+}
+
+pub async fn get_downtime_reasons(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Items<Item>, anyhow::Error> {
+    // Бизнес логика получения списка причин простоя
+
+    let rows = sqlx::query!(
+        "SELECT
+            dr.id,
+            dr.code,
+            dr.name,
+            dr.created_at,
+            JSONB_BUILD_OBJECT(
+                'id', o.id,
+                'name', o.name
+            ) AS organization
+        FROM downtime_reasons AS dr
+        LEFT JOIN organizations AS o ON o.id = dr.organization_id
+        WHERE
+            CASE
+                WHEN $1::bigint IS NOT NULL AND $2 not in ('Admin', 'Developer') THEN
+                    dr.organization_id = $1
+                ELSE TRUE
+            END
+        ORDER BY dr.code, dr.id DESC
+        OFFSET $3 LIMIT $4;",
+        current_user.organization_id,
+        current_user.role.to_string(),
+        (q.page - 1) * q.per_page,
+        q.per_page,
+    )
+    .map(|row| Item {
+        id: row.id,
+        code: row.code,
+        name: row.name,
+        created_at: row.created_at,
+        organization: row.organization.into(),
+    })
+    .fetch_all(&pool)
+    .await?;
+
+    // Подсчет данных для пагинации
+    let cnt: i64 = sqlx::query_scalar(
+        "SELECT COUNT(id) FROM downtime_reasons AS dr WHERE CASE
+        WHEN $1::bigint IS NOT NULL AND $2 not in ('Admin', 'Developer') THEN
+            dr.organization_id = $1
+        ELSE TRUE
+    END",
+    )
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .fetch_one(&pool)
+    .await
+    .unwrap_or(0);
+
+    let cnt = (cnt as f64 / q.per_page as f64).ceil() as i64;
+
+    Ok(Items { items: rows, cnt })
+}
+
+pub async fn detail_downtime_reason(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<Item, AppError> {
+    // Бизнес логика получения причины простоя
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        let row = sqlx::query!(
+            "SELECT
+            dr.id,
+            dr.code,
+            dr.name,
+            dr.created_at,
+            JSONB_BUILD_OBJECT(
+                'id', o.id,
+                'name', o.name
+            ) AS organization
+        FROM downtime_reasons AS dr
+        LEFT JOIN organizations AS o ON o.id = dr.organization_id
+        WHERE dr.id = $1
+        AND CASE
+            WHEN $2::bigint IS NOT NULL AND $3 not in ('Admin', 'Developer') THEN
+                dr.organization_id = $2
+            ELSE TRUE
+        END;",
+            id,
+            current_user.organization_id,
+            current_user.role.to_string(),
+        )
+        .fetch_optional(&pool)
+        .await?;
+
+        match row {
+            Some(row) => Ok(Item {
+                id: row.id,
+                code: row.code,
+                name: row.name,
+                created_at: row.created_at,
+                organization: row.organization.into(),
+            }),
+            None => Err(AppError(
+                StatusCode::FORBIDDEN,
+                anyhow::anyhow!("Такой записи не существует"),
+            )),
+        }
+    }
+}
+
+pub async fn delete_downtime_reason(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<(), AppError> {
+    // Бизнес логика удаления причины простоя
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        let is_used: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM downtimes WHERE downtime_reason_id = $1)",
+        )
+        .bind(id)
+        .fetch_one(&pool)
+        .await?;
+
+        if is_used {
+            return Err(AppError(
+                StatusCode::BAD_REQUEST,
+                anyhow::anyhow!("Нельзя удалить причину простоя, которая уже использовалась!"),
+            ));
+        }
+
+        let _ = sqlx::query(
+            "DELETE
+        FROM downtime_reasons
+        WHERE id = $1
+        AND CASE
+            WHEN $2::bigint IS NOT NULL THEN organization_id = $2
+            ELSE TRUE
+        END;",
+        )
+        .bind(id)
+        .bind(current_user.organization_id)
+        .execute(&pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -8,15 +8,199 @@ use sqlx::PgPool;
 
 use crate::{
     check_access, check_is_admin,
-    services::{Items, Select},
+    services::{
+        attachments::{
+            attachment::{remove_files, take_storage_keys, Entity},
+            storage::Storage,
+        },
+        rbs::custom_field::{self, check_custom_fields},
+        Items, Select,
+    },
     AppError, CurrentUser,
 };
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum BarcodeType {
+    #[serde(rename = "EAN13")]
+    Ean13,
+    Code128,
+}
+
+impl From<&str> for BarcodeType {
+    fn from(value: &str) -> Self {
+        match value {
+            "EAN13" => Self::Ean13,
+            _ => Self::Code128,
+        }
+    }
+}
+
+impl From<String> for BarcodeType {
+    fn from(value: String) -> Self {
+        Self::from(value.as_str())
+    }
+}
+
+impl std::fmt::Display for BarcodeType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Ean13 => "EAN13",
+            Self::Code128 => "Code128",
+        })
+    }
+}
+
+fn check_barcode(barcode_type: BarcodeType, barcode: &str) -> bool {
+    // Проверка штрихкода. EAN-13 - 13 цифр с контрольной цифрой по модулю 10.
+    // Code128 кодирует ASCII, его контрольный символ по модулю 103
+    // добавляется при печати и в данных не хранится.
+
+    match barcode_type {
+        BarcodeType::Ean13 => {
+            let digits = barcode
+                .chars()
+                .filter_map(|c| c.to_digit(10))
+                .collect::<Vec<u32>>();
+            if barcode.len() != 13 || digits.len() != 13 {
+                return false;
+            }
+
+            let sum: u32 = digits[..12]
+                .iter()
+                .enumerate()
+                .map(|(i, d)| if i % 2 == 0 { *d } else { d * 3 })
+                .sum();
+
+            (10 - sum % 10) % 10 == digits[12]
+        }
+        BarcodeType::Code128 => {
+            !barcode.is_empty()
+                && barcode.len() <= 80
+                && barcode
+                    .chars()
+                    .all(|c| c.is_ascii() && !c.is_ascii_control())
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct RequestBody {
     name: String,
     organization_id: Option<i64>,
     measure_unit_id: i64,
+    lot_pattern: Option<String>,
+    sku: Option<String>,
+    barcode: Option<String>,
+    barcode_type: Option<BarcodeType>,
+    description: Option<String>,
+    category_id: Option<i64>,
+    // Значения дополнительных полей организации: {"код": значение}
+    custom_fields: Option<serde_json::Value>,
+}
+
+impl RequestBody {
+    fn normalize(mut self) -> Result<Self, AppError> {
+        // Пустые строки из формы считаются незаполненными полями
+
+        let trim = |v: Option<String>| v.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+        self.lot_pattern = trim(self.lot_pattern);
+        self.sku = trim(self.sku);
+        self.barcode = trim(self.barcode);
+        self.description = trim(self.description);
+
+        match (&self.barcode, self.barcode_type) {
+            (None, _) => self.barcode_type = None,
+            (Some(barcode), Some(barcode_type)) if check_barcode(barcode_type, barcode) => {}
+            (Some(_), Some(_)) => {
+                return Err(AppError(
+                    StatusCode::BAD_REQUEST,
+                    anyhow::anyhow!("Некорректный штрихкод или контрольная цифра!"),
+                ))
+            }
+            (Some(_), None) => {
+                return Err(AppError(
+                    StatusCode::BAD_REQUEST,
+                    anyhow::anyhow!("Укажите тип штрихкода!"),
+                ))
+            }
+        }
+
+        Ok(self)
+    }
+}
+
+async fn check_category(
+    pool: &PgPool,
+    organization_id: i64,
+    category_id: Option<i64>,
+) -> Result<(), AppError> {
+    // Категория продукта должна принадлежать его организации
+
+    let Some(category_id) = category_id else {
+        return Ok(());
+    };
+
+    let exists: bool = sqlx::query_scalar(
+        "SELECT EXISTS (
+            SELECT 1 FROM product_categories WHERE id = $1 AND organization_id = $2
+        )",
+    )
+    .bind(category_id)
+    .bind(organization_id)
+    .fetch_one(pool)
+    .await?;
+
+    if exists {
+        Ok(())
+    } else {
+        Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Категория не найдена в организации!"),
+        ))
+    }
+}
+
+async fn check_unique(
+    pool: &PgPool,
+    organization_id: i64,
+    id: Option<i64>,
+    body: &RequestBody,
+) -> Result<(), AppError> {
+    // Артикул и штрихкод уникальны в пределах организации
+
+    let (sku_exists, barcode_exists): (bool, bool) = sqlx::query_as(
+        "SELECT
+          EXISTS (
+            SELECT 1 FROM products
+            WHERE organization_id = $1 AND sku = $3 AND ($2::bigint IS NULL OR id <> $2)
+          ),
+          EXISTS (
+            SELECT 1 FROM products
+            WHERE organization_id = $1 AND barcode = $4 AND ($2::bigint IS NULL OR id <> $2)
+          )",
+    )
+    .bind(organization_id)
+    .bind(id)
+    .bind(&body.sku)
+    .bind(&body.barcode)
+    .fetch_one(pool)
+    .await?;
+
+    if sku_exists {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Продукт с таким артикулом уже существует!"),
+        ));
+    }
+
+    if barcode_exists {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Продукт с таким штрихкодом уже существует!"),
+        ));
+    }
+
+    Ok(())
 }
 
 pub async fn create_product(
@@ -26,6 +210,8 @@ pub async fn create_product(
 ) -> Result<i64, AppError> {
     // Бизнес логика создания продукта
 
+    let body = body.normalize()?;
+
     if !check_access(current_user.role) {
         Err(AppError(
             StatusCode::FORBIDDEN,
@@ -40,14 +226,31 @@ pub async fn create_product(
 
         match organization_id {
             Some(organization_id) => {
+                check_category(&pool, organization_id, body.category_id).await?;
+                check_unique(&pool, organization_id, None, &body).await?;
+                let custom_fields = check_custom_fields(
+                    &pool,
+                    organization_id,
+                    custom_field::Entity::Product,
+                    body.custom_fields,
+                )
+                .await?;
+
                 let row: (i64,) = sqlx::query_as(
                     "INSERT
-INTO products (name, measure_unit_id, organization_id) VALUES
-                    ($1, $2, $3) RETURNING id",
+INTO products (name, measure_unit_id, organization_id, lot_pattern, sku, barcode, barcode_type, description, category_id, custom_fields) VALUES
+                    ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) RETURNING id",
                 )
                 .bind(body.name)
                 .bind(body.measure_unit_id)
                 .bind(organization_id)
+                .bind(body.lot_pattern)
+                .bind(body.sku)
+                .bind(body.barcode)
+                .bind(body.barcode_type.map(|t| t.to_string()))
+                .bind(body.description)
+                .bind(body.category_id)
+                .bind(custom_fields)
                 .fetch_one(&pool)
                 .await?;
 
@@ -69,6 +272,8 @@ pub async fn edit_product(
 ) -> Result<i64, AppError> {
     // Бизнес логика редактирования продукта
 
+    let body = body.normalize()?;
+
     if !check_access(current_user.role) {
         Err(AppError(
             StatusCode::FORBIDDEN,
@@ -83,15 +288,33 @@ pub async fn edit_product(
 
         match organization_id {
             Some(organization_id) => {
+                check_category(&pool, organization_id, body.category_id).await?;
+                check_unique(&pool, organization_id, Some(id), &body).await?;
+                let custom_fields = check_custom_fields(
+                    &pool,
+                    organization_id,
+                    custom_field::Entity::Product,
+                    body.custom_fields,
+                )
+                .await?;
+
                 let _ = sqlx::query(
                     "UPDATE products
-SET name=$1, measure_unit_id=$2, organization_id=$3, updated_at=NOW()
+SET name=$1, measure_unit_id=$2, organization_id=$3, lot_pattern=$5, sku=$6, barcode=$7,
+    barcode_type=$8, description=$9, category_id=$10, custom_fields=$11, updated_at=NOW()
                     WHERE id = $4",
                 )
                 .bind(body.name)
                 .bind(body.measure_unit_id)
                 .bind(organization_id)
                 .bind(id)
+                .bind(body.lot_pattern)
+                .bind(body.sku)
+                .bind(body.barcode)
+                .bind(body.barcode_type.map(|t| t.to_string()))
+                .bind(body.description)
+                .bind(body.category_id)
+                .bind(custom_fields)
                 .execute(&pool)
                 .await?;
 
@@ -111,6 +334,8 @@ pub struct Q {
     pub page: i64,
     #[serde(default = "per_page")]
     pub per_page: i64,
+    // Фильтр по категории вместе с вложенными категориями
+    pub category_id: Option<i64>,
 }
 
 fn per_page() -> i64 {
@@ -125,10 +350,17 @@ fn page() -> i64 {
 pub struct Item {
     pub id: i64,
     pub name: String,
+    pub lot_pattern: Option<String>,
+    pub sku: Option<String>,
+    pub barcode: Option<String>,
+    pub barcode_type: Option<BarcodeType>,
+    pub description: Option<String>,
+    pub custom_fields: serde_json::Value,
     pub created_at: chrono::DateTime<chrono::Utc>,
 
     organization: Select,
     measure_unit: Select,
+    category: Option<Select>,
 }
 
 pub async fn get_products(
@@ -139,9 +371,21 @@ pub async fn get_products(
     // Бизнес логика получения списка продуктв
 
     let rows = sqlx::query!(
-        "SELECT
+        "WITH RECURSIVE category_tree AS (
+            SELECT id FROM product_categories WHERE id = $5
+            UNION ALL
+            SELECT pc.id FROM product_categories AS pc
+            JOIN category_tree AS ct ON pc.parent_id = ct.id
+        )
+        SELECT
             p.id,
             p.name,
+            p.lot_pattern,
+            p.sku,
+            p.barcode,
+            p.barcode_type,
+            p.description,
+            p.custom_fields,
             p.created_at,
             JSONB_BUILD_OBJECT(
                 'id', mu.id,
@@ -150,38 +394,68 @@ pub async fn get_products(
             JSONB_BUILD_OBJECT(
                 'id', o.id,
                 'name', o.name
-            ) AS organization
+            ) AS organization,
+            CASE WHEN pc.id IS NOT NULL THEN JSONB_BUILD_OBJECT(
+                'id', pc.id,
+                'name', pc.name
+            ) END AS category
         FROM products AS p
         LEFT JOIN measure_units AS mu on mu.id = p.measure_unit_id
         LEFT JOIN organizations AS o ON o.id = p.organization_id
+        LEFT JOIN product_categories AS pc ON pc.id = p.category_id
         WHERE
             CASE
                 WHEN $1::bigint IS NOT NULL AND $2 not in ('Admin', 'Developer') THEN
                     p.organization_id = $1
                 ELSE TRUE
             END
+            AND ($5::bigint IS NULL OR p.category_id IN (SELECT id FROM category_tree))
         ORDER BY p.id DESC
         OFFSET $3 LIMIT $4;",
         current_user.organization_id,
         current_user.role.to_string(),
         (q.page - 1) * q.per_page,
         q.per_page,
+        q.category_id,
     )
     .map(|row| Item {
         id: row.id,
         name: row.name,
+        lot_pattern: row.lot_pattern,
+        sku: row.sku,
+        barcode: row.barcode,
+        barcode_type: row.barcode_type.map(BarcodeType::from),
+        description: row.description,
+        custom_fields: row.custom_fields,
         created_at: row.created_at,
         organization: row.organization.into(),
         measure_unit: row.measure_unit.into(),
+        category: row.category.map(Select::from),
     })
     .fetch_all(&pool)
     .await?;
 
     // Подсчет данных для пагинации
-    let cnt: i64 = sqlx::query_scalar("SELECT COUNT(id) FROM products;")
-        .fetch_one(&pool)
-        .await
-        .unwrap_or(0);
+    let cnt: i64 = sqlx::query_scalar(
+        "WITH RECURSIVE category_tree AS (
+            SELECT id FROM product_categories WHERE id = $3
+            UNION ALL
+            SELECT pc.id FROM product_categories AS pc
+            JOIN category_tree AS ct ON pc.parent_id = ct.id
+        )
+        SELECT COUNT(id) FROM products AS p WHERE CASE
+            WHEN $1::bigint IS NOT NULL AND $2 not in ('Admin', 'Developer') THEN
+                p.organization_id = $1
+            ELSE TRUE
+        END
+        AND ($3::bigint IS NULL OR p.category_id IN (SELECT id FROM category_tree))",
+    )
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .bind(q.category_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap_or(0);
 
     let cnt = (cnt as f64 / q.per_page as f64).ceil() as i64;
 
@@ -205,6 +479,12 @@ pub async fn detail_product(
             "SELECT
                 p.id,
                 p.name,
+                p.lot_pattern,
+                p.sku,
+                p.barcode,
+                p.barcode_type,
+                p.description,
+                p.custom_fields,
                 p.created_at,
                 JSONB_BUILD_OBJECT(
                     'id', mu.id,
@@ -213,10 +493,15 @@ pub async fn detail_product(
                 JSONB_BUILD_OBJECT(
                     'id', o.id,
                     'name', o.name
-                ) AS organization
+                ) AS organization,
+                CASE WHEN pc.id IS NOT NULL THEN JSONB_BUILD_OBJECT(
+                    'id', pc.id,
+                    'name', pc.name
+                ) END AS category
             FROM products AS p
             LEFT JOIN measure_units AS mu on mu.id = p.measure_unit_id
             LEFT JOIN organizations AS o ON o.id = p.organization_id
+            LEFT JOIN product_categories AS pc ON pc.id = p.category_id
         WHERE p.id = $1;",
             id,
         )
@@ -228,9 +513,16 @@ pub async fn detail_product(
             Some(row) => Ok(Item {
                 id: row.id,
                 name: row.name,
+                lot_pattern: row.lot_pattern,
+                sku: row.sku,
+                barcode: row.barcode,
+                barcode_type: row.barcode_type.map(BarcodeType::from),
+                description: row.description,
+                custom_fields: row.custom_fields,
                 created_at: row.created_at,
                 organization: row.organization.into(),
                 measure_unit: row.measure_unit.into(),
+                category: row.category.map(Select::from),
             }),
             None => Err(AppError(
                 StatusCode::FORBIDDEN,
@@ -243,6 +535,7 @@ pub async fn detail_product(
 pub async fn delete_product(
     State(pool): State<PgPool>,
     Extension(current_user): Extension<CurrentUser>,
+    Extension(storage): Extension<Storage>,
     Path(id): Path<i64>,
 ) -> Result<(), AppError> {
     // Бизнес логика удаления продуктв
@@ -253,15 +546,58 @@ pub async fn delete_product(
             anyhow::anyhow!("У вас нет доступа для данного действия!"),
         ))
     } else {
+        let mut tx = pool.begin().await?;
+
+        let keys = take_storage_keys(&mut tx, Entity::Product, id).await?;
+
         let _ = sqlx::query(
             "delete
         from products
         where id = $1;",
         )
         .bind(id)
-        .execute(&pool)
+        .execute(&mut *tx)
         .await?;
 
+        tx.commit().await?;
+
+        remove_files(&storage, keys).await;
+
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ean13_check_digit() {
+        assert!(check_barcode(BarcodeType::Ean13, "4006381333931"));
+        assert!(check_barcode(BarcodeType::Ean13, "5901234123457"));
+        assert!(check_barcode(BarcodeType::Ean13, "0000000000000"));
+
+        assert!(!check_barcode(BarcodeType::Ean13, "4006381333932"));
+        assert!(!check_barcode(BarcodeType::Ean13, "5901234123450"));
+    }
+
+    #[test]
+    fn ean13_format() {
+        assert!(!check_barcode(BarcodeType::Ean13, ""));
+        assert!(!check_barcode(BarcodeType::Ean13, "400638133393"));
+        assert!(!check_barcode(BarcodeType::Ean13, "40063813339310"));
+        assert!(!check_barcode(BarcodeType::Ean13, "400638133393A"));
+        assert!(!check_barcode(BarcodeType::Ean13, "4006381 33393"));
+    }
+
+    #[test]
+    fn code128_charset() {
+        assert!(check_barcode(BarcodeType::Code128, "ABC-123 xyz/9"));
+        assert!(check_barcode(BarcodeType::Code128, &"A".repeat(80)));
+
+        assert!(!check_barcode(BarcodeType::Code128, ""));
+        assert!(!check_barcode(BarcodeType::Code128, &"A".repeat(81)));
+        assert!(!check_barcode(BarcodeType::Code128, "ABC\t123"));
+        assert!(!check_barcode(BarcodeType::Code128, "Штрихкод"));
+    }
+}
@@ -0,0 +1,379 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Transaction};
+
+use crate::{
+    check_access, check_is_admin,
+    services::{Items, Select},
+    AppError, CurrentUser,
+};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RequestBody {
+    organization_id: Option<i64>,
+    parent_id: Option<i64>,
+    name: String,
+}
+
+async fn check_parent(
+    tx: &mut Transaction<'_, Postgres>,
+    organization_id: i64,
+    id: Option<i64>,
+    parent_id: Option<i64>,
+) -> Result<(), AppError> {
+    // Родитель должен быть из той же организации и не быть самой категорией
+    // или ее потомком, иначе дерево замкнется в цикл.
+
+    let Some(parent_id) = parent_id else {
+        return Ok(());
+    };
+
+    let (exists, is_descendant): (bool, bool) = sqlx::query_as(
+        "WITH RECURSIVE tree AS (
+          SELECT id FROM product_categories WHERE id = $3
+          UNION ALL
+          SELECT pc.id FROM product_categories AS pc JOIN tree AS t ON pc.parent_id = t.id
+        )
+        SELECT
+          EXISTS (
+            SELECT 1 FROM product_categories WHERE id = $1 AND organization_id = $2
+          ),
+          EXISTS (SELECT 1 FROM tree WHERE id = $1)",
+    )
+    .bind(parent_id)
+    .bind(organization_id)
+    .bind(id)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    if !exists {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Родительская категория не найдена в организации!"),
+        ));
+    }
+
+    if is_descendant {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Категория не может быть вложена сама в себя!"),
+        ));
+    }
+
+    Ok(())
+}
+
+pub async fn create_product_category(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(body): Json<RequestBody>,
+) -> Result<i64, AppError> {
+    // Бизнес логика создания категории продуктов
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        let organization_id = if check_is_admin(current_user.role) {
+            body.organization_id
+        } else {
+            current_user.organization_id
+        };
+
+        match organization_id {
+            Some(organization_id) => {
+                let mut tx = pool.begin().await?;
+
+                check_parent(&mut tx, organization_id, None, body.parent_id).await?;
+
+                let row: (i64,) = sqlx::query_as(
+                    "INSERT
+                    INTO product_categories (name, organization_id, parent_id) VALUES
+                    ($1, $2, $3) RETURNING id",
+                )
+                .bind(body.name)
+                .bind(organization_id)
+                .bind(body.parent_id)
+                .fetch_one(&mut *tx)
+                .await?;
+
+                tx.commit().await?;
+
+                Ok(row.0)
+            }
+            _ => Err(AppError(
+                StatusCode::BAD_REQUEST,
+                anyhow::anyhow!("Невозможно создать запись без организации!"),
+            )),
+        }
+    }
+}
+
+pub async fn edit_product_category(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+    Json(body): Json<RequestBody>,
+) -> Result<i64, AppError> {
+    // Бизнес логика редактирования категории продуктов
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        let organization_id = if check_is_admin(current_user.role) {
+            body.organization_id
+        } else {
+            current_user.organization_id
+        };
+
+        match organization_id {
+            Some(organization_id) => {
+                let mut tx = pool.begin().await?;
+
+                check_parent(&mut tx, organization_id, Some(id), body.parent_id).await?;
+
+                let _ = sqlx::query(
+                    "UPDATE product_categories
+                    SET name=$1, organization_id=$2, parent_id=$3, updated_at=NOW()
+                    WHERE id = $4",
+                )
+                .bind(body.name)
+                .bind(organization_id)
+                .bind(body.parent_id)
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+
+                tx.commit().await?;
+
+                Ok(id)
+            }
+            _ => Err(AppError(
+                StatusCode::BAD_REQUEST,
+                anyhow::anyhow!("Невозможно отредактировать запись без организации!"),
+            )),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Q {
+    #[serde(default = "page")]
+    pub page: i64,
+    #[serde(default = "per_page")]
+    pub per_page: i64,
+}
+
+fn per_page() -> i64 {
+    15
+}
+
+fn page() -> i64 {
+    1
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Item {
+    pub id: i64,
+    pub parent_id: Option<i64>,
+    pub name: String,
+    // Полный путь от корня: "Напитки / Соки"
+    pub path: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+
+    organization: Select,
+}
+
+pub async fn get_product_categories(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Items<Item>, anyhow::Error> {
+    // Бизнес логика получения списка категорий продуктов в порядке дерева
+
+    let rows = sqlx::query!(
+        "WITH RECURSIVE tree AS (
+            SELECT id, name::text AS path
+            FROM product_categories
+            WHERE parent_id IS NULL
+            UNION ALL
+            SELECT pc.id, t.path || ' / ' || pc.name
+            FROM product_categories AS pc
+            JOIN tree AS t ON pc.parent_id = t.id
+        )
+        SELECT
+            pc.id,
+            pc.parent_id,
+            pc.name,
+            t.path AS \"path!\",
+            pc.created_at,
+            JSONB_BUILD_OBJECT(
+                'id', o.id,
+                'name', o.name
+            ) AS organization
+        FROM product_categories AS pc
+        INNER JOIN tree AS t ON t.id = pc.id
+        LEFT JOIN organizations AS o ON o.id = pc.organization_id
+        WHERE
+            CASE
+                WHEN $1::bigint IS NOT NULL AND $2 not in ('Admin', 'Developer') THEN
+                    pc.organization_id = $1
+                ELSE TRUE
+            END
+        ORDER BY t.path
+        OFFSET $3 LIMIT $4;",
+        current_user.organization_id,
+        current_user.role.to_string(),
+        (q.page - 1) * q.per_page,
+        q.per_page,
+    )
+    .map(|row| Item {
+        id: row.id,
+        parent_id: row.parent_id,
+        name: row.name,
+        path: row.path,
+        created_at: row.created_at,
+        organization: row.organization.into(),
+    })
+    .fetch_all(&pool)
+    .await?;
+
+    // Подсчет данных для пагинации
+    let cnt: i64 = sqlx::query_scalar(
+        "SELECT COUNT(id) FROM product_categories AS pc WHERE CASE
+        WHEN $1::bigint IS NOT NULL AND $2 not in ('Admin', 'Developer') THEN
+            pc.organization_id = $1
+        ELSE TRUE
+    END",
+    )
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .fetch_one(&pool)
+    .await
+    .unwrap_or(0);
+
+    let cnt = (cnt as f64 / q.per_page as f64).ceil() as i64;
+
+    Ok(Items { items: rows, cnt })
+}
+
+pub async fn detail_product_category(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<Item, AppError> {
+    // Бизнес логика получения категории продуктов
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        let row = sqlx::query!(
+            "WITH RECURSIVE tree AS (
+            SELECT id, name::text AS path
+            FROM product_categories
+            WHERE parent_id IS NULL
+            UNION ALL
+            SELECT pc.id, t.path || ' / ' || pc.name
+            FROM product_categories AS pc
+            JOIN tree AS t ON pc.parent_id = t.id
+        )
+        SELECT
+            pc.id,
+            pc.parent_id,
+            pc.name,
+            t.path AS \"path!\",
+            pc.created_at,
+            JSONB_BUILD_OBJECT(
+                'id', o.id,
+                'name', o.name
+            ) AS organization
+        FROM product_categories AS pc
+        INNER JOIN tree AS t ON t.id = pc.id
+        LEFT JOIN organizations AS o ON o.id = pc.organization_id
+        WHERE pc.id = $1
+        AND CASE
+            WHEN $2::bigint IS NOT NULL AND $3 not in ('Admin', 'Developer') THEN
+                pc.organization_id = $2
+            ELSE TRUE
+        END;",
+            id,
+            current_user.organization_id,
+            current_user.role.to_string(),
+        )
+        .fetch_optional(&pool)
+        .await?;
+
+        match row {
+            Some(row) => Ok(Item {
+                id: row.id,
+                parent_id: row.parent_id,
+                name: row.name,
+                path: row.path,
+                created_at: row.created_at,
+                organization: row.organization.into(),
+            }),
+            None => Err(AppError(
+                StatusCode::FORBIDDEN,
+                anyhow::anyhow!("Такой записи не существует"),
+            )),
+        }
+    }
+}
+
+pub async fn delete_product_category(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<(), AppError> {
+    // Бизнес логика удаления категории продуктов.
+    // Продукты категории остаются без категории, вложенные категории удалить нельзя.
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        let has_children: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM product_categories WHERE parent_id = $1)",
+        )
+        .bind(id)
+        .fetch_one(&pool)
+        .await?;
+
+        if has_children {
+            return Err(AppError(
+                StatusCode::BAD_REQUEST,
+                anyhow::anyhow!("Нельзя удалить категорию с вложенными категориями!"),
+            ));
+        }
+
+        let _ = sqlx::query(
+            "DELETE
+        FROM product_categories
+        WHERE id = $1
+        AND CASE
+            WHEN $2::bigint IS NOT NULL THEN organization_id = $2
+            ELSE TRUE
+        END;",
+        )
+        .bind(id)
+        .bind(current_user.organization_id)
+        .execute(&pool)
+        .await?;
+
+        Ok(())
+    }
+}
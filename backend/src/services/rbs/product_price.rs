@@ -0,0 +1,282 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{
+    check_access,
+    services::{check_money, Select},
+    AppError, CurrentUser,
+};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RequestBody {
+    // Дата, с которой действуют себестоимость и цена
+    valid_from: chrono::NaiveDate,
+    cost: Decimal,
+    price: Decimal,
+}
+
+fn validate(body: &RequestBody) -> Result<(), AppError> {
+    // Проверка себестоимости и цены единицы продукта
+
+    if !check_money(body.cost) || !check_money(body.price) {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Себестоимость и цена должны быть от 0 до 999 999 999 999,99!"),
+        ));
+    }
+
+    Ok(())
+}
+
+async fn check_unique(
+    pool: &PgPool,
+    product_id: i64,
+    id: Option<i64>,
+    body: &RequestBody,
+) -> Result<(), AppError> {
+    // На одну дату у продукта может быть только одна запись
+
+    let exists: bool = sqlx::query_scalar(
+        "SELECT EXISTS (
+          SELECT 1 FROM product_prices
+          WHERE product_id = $1 AND valid_from = $2 AND ($3::bigint IS NULL OR id <> $3)
+        )",
+    )
+    .bind(product_id)
+    .bind(body.valid_from)
+    .bind(id)
+    .fetch_one(pool)
+    .await?;
+
+    if exists {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!(
+                "Цена продукта на {} уже задана!",
+                body.valid_from.format("%d.%m.%Y")
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+pub async fn create_product_price(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(product_id): Path<i64>,
+    Json(body): Json<RequestBody>,
+) -> Result<i64, AppError> {
+    // Бизнес логика добавления себестоимости и цены продукта
+
+    if !check_access(current_user.role) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    validate(&body)?;
+    check_unique(&pool, product_id, None, &body).await?;
+
+    let row: Option<(i64,)> = sqlx::query_as(
+        "INSERT INTO
+          product_prices (organization_id, product_id, cost, price, valid_from, user_id)
+        SELECT
+          p.organization_id,
+          p.id,
+          $2,
+          $3,
+          $4,
+          $5
+        FROM
+          products AS p
+        WHERE
+          p.id = $1
+          AND CASE
+            WHEN $6::bigint IS NOT NULL AND $7 not in ('Admin', 'Developer') THEN
+              p.organization_id = $6
+            ELSE TRUE
+          END
+        RETURNING
+          id",
+    )
+    .bind(product_id)
+    .bind(body.cost)
+    .bind(body.price)
+    .bind(body.valid_from)
+    .bind(current_user.id)
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .fetch_optional(&pool)
+    .await?;
+
+    match row {
+        Some(row) => Ok(row.0),
+        None => Err(AppError(
+            StatusCode::NOT_FOUND,
+            anyhow::anyhow!("Такой записи не существует"),
+        )),
+    }
+}
+
+pub async fn edit_product_price(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+    Json(body): Json<RequestBody>,
+) -> Result<i64, AppError> {
+    // Бизнес логика редактирования себестоимости и цены продукта
+
+    if !check_access(current_user.role) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    validate(&body)?;
+
+    let product_id: Option<i64> = sqlx::query_scalar(
+        "SELECT product_id
+        FROM product_prices
+        WHERE id = $1
+        AND CASE
+            WHEN $2::bigint IS NOT NULL AND $3 not in ('Admin', 'Developer') THEN
+                organization_id = $2
+            ELSE TRUE
+        END",
+    )
+    .bind(id)
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .fetch_optional(&pool)
+    .await?;
+
+    match product_id {
+        Some(product_id) => {
+            check_unique(&pool, product_id, Some(id), &body).await?;
+
+            let _ = sqlx::query(
+                "UPDATE product_prices
+                SET cost=$1, price=$2, valid_from=$3, user_id=$4, updated_at=NOW()
+                WHERE id = $5",
+            )
+            .bind(body.cost)
+            .bind(body.price)
+            .bind(body.valid_from)
+            .bind(current_user.id)
+            .bind(id)
+            .execute(&pool)
+            .await?;
+
+            Ok(id)
+        }
+        None => Err(AppError(
+            StatusCode::NOT_FOUND,
+            anyhow::anyhow!("Такой записи не существует"),
+        )),
+    }
+}
+
+pub async fn delete_product_price(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<(), AppError> {
+    // Бизнес логика удаления себестоимости и цены продукта
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        let _ = sqlx::query(
+            "DELETE
+        FROM product_prices
+        WHERE id = $1
+        AND CASE
+            WHEN $2::bigint IS NOT NULL AND $3 not in ('Admin', 'Developer') THEN
+                organization_id = $2
+            ELSE TRUE
+        END;",
+        )
+        .bind(id)
+        .bind(current_user.organization_id)
+        .bind(current_user.role.to_string())
+        .execute(&pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Item {
+    pub id: i64,
+    pub valid_from: chrono::NaiveDate,
+    pub cost: Decimal,
+    pub price: Decimal,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+
+    user: Select,
+}
+
+pub async fn get_product_prices(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(product_id): Path<i64>,
+) -> Result<Vec<Item>, AppError> {
+    // Бизнес логика получения истории себестоимости и цен продукта, новые записи первыми
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        let rows = sqlx::query!(
+            "SELECT
+                pp.id,
+                pp.valid_from,
+                pp.cost,
+                pp.price,
+                pp.created_at,
+                JSONB_BUILD_OBJECT(
+                    'id', author.id,
+                    'name', author.fio
+                ) AS user
+            FROM product_prices AS pp
+            LEFT JOIN users AS author ON author.id = pp.user_id
+            WHERE pp.product_id = $1
+            AND CASE
+                WHEN $2::bigint IS NOT NULL AND $3 not in ('Admin', 'Developer') THEN
+                    pp.organization_id = $2
+                ELSE TRUE
+            END
+            ORDER BY pp.valid_from DESC;",
+            product_id,
+            current_user.organization_id,
+            current_user.role.to_string(),
+        )
+        .map(|row| Item {
+            id: row.id,
+            valid_from: row.valid_from,
+            cost: row.cost,
+            price: row.price,
+            created_at: row.created_at,
+            user: row.user.into(),
+        })
+        .fetch_all(&pool)
+        .await?;
+
+        Ok(rows)
+    }
+}
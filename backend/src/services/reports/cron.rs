@@ -0,0 +1,276 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Timelike};
+
+// Расписание в формате cron из пяти полей: минута час день месяц день_недели.
+// Поддерживаются *, списки через запятую, диапазоны a-b и шаг /n.
+// День недели 0-7, воскресенье - 0 или 7. Если поля дня месяца и дня недели оба
+// ограничены, достаточно совпадения любого из них, как в классическом cron. Поле,
+// начинающееся с *, считается неограниченным, и тогда должны совпасть оба: */2 * 1 -
+// нечетные числа, выпадающие на понедельник.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schedule {
+    minutes: Vec<bool>,
+    hours: Vec<bool>,
+    days: Vec<bool>,
+    months: Vec<bool>,
+    weekdays: Vec<bool>,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+fn parse_field(field: &str, min: u32, max: u32) -> Result<Vec<bool>, String> {
+    let mut values = vec![false; max as usize + 1];
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (
+                range,
+                step.parse::<u32>()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or(format!("неверный шаг в {}", part))?,
+            ),
+            None => (part, 1),
+        };
+
+        let (from, to) = if range == "*" {
+            (min, max)
+        } else if let Some((from, to)) = range.split_once('-') {
+            (
+                from.parse()
+                    .map_err(|_| format!("неверное значение {}", part))?,
+                to.parse()
+                    .map_err(|_| format!("неверное значение {}", part))?,
+            )
+        } else {
+            let value = range
+                .parse()
+                .map_err(|_| format!("неверное значение {}", part))?;
+            // Одиночное значение с шагом означает диапазон до конца: 5/15
+            (value, if part.contains('/') { max } else { value })
+        };
+
+        if from < min || to > max || from > to {
+            return Err(format!("значение {} вне диапазона {}-{}", part, min, max));
+        }
+
+        for value in (from..=to).step_by(step as usize) {
+            values[value as usize] = true;
+        }
+    }
+
+    Ok(values)
+}
+
+impl Schedule {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let fields = value.split_whitespace().collect::<Vec<&str>>();
+        let [minutes, hours, days, months, weekdays] = fields[..] else {
+            return Err("ожидается пять полей: минута час день месяц день_недели".to_string());
+        };
+
+        // Воскресенье 7 переносится в 0, дни недели хранятся как 0-6
+        let mut weekdays_values = parse_field(weekdays, 0, 7)?;
+        if weekdays_values.pop() == Some(true) {
+            weekdays_values[0] = true;
+        }
+
+        Ok(Self {
+            minutes: parse_field(minutes, 0, 59)?,
+            hours: parse_field(hours, 0, 23)?,
+            days: parse_field(days, 1, 31)?,
+            months: parse_field(months, 1, 12)?,
+            weekdays: weekdays_values,
+            any_day: days.starts_with('*'),
+            any_weekday: weekdays.starts_with('*'),
+        })
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let day = self.days[date.day() as usize];
+        let weekday = self.weekdays[date.weekday().num_days_from_sunday() as usize];
+
+        if self.any_day || self.any_weekday {
+            day && weekday
+        } else {
+            day || weekday
+        }
+    }
+
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        // Ближайшее время запуска строго после after, с точностью до минуты.
+        // Поиск ограничен пятью годами, чтобы расписание вроде 31 февраля не зациклилось.
+
+        let mut time = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = after + Duration::days(5 * 366);
+
+        while time <= limit {
+            let date = time.date();
+
+            if !self.months[date.month() as usize] {
+                let (year, month) = if date.month() == 12 {
+                    (date.year() + 1, 1)
+                } else {
+                    (date.year(), date.month() + 1)
+                };
+                time = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+
+            if !self.day_matches(date) {
+                time = date.succ_opt()?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+
+            if !self.hours[time.hour() as usize] {
+                time = date.and_hms_opt(time.hour(), 0, 0)? + Duration::hours(1);
+                continue;
+            }
+
+            if !self.minutes[time.minute() as usize] {
+                time += Duration::minutes(1);
+                continue;
+            }
+
+            return Some(time);
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, min, 0)
+            .unwrap()
+    }
+
+    fn next(schedule: &str, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        Schedule::parse(schedule).unwrap().next_after(after)
+    }
+
+    #[test]
+    fn lists() {
+        let schedule = "0,30 9,18 * * *";
+        assert_eq!(
+            next(schedule, at(2024, 3, 4, 9, 0)),
+            Some(at(2024, 3, 4, 9, 30))
+        );
+        assert_eq!(
+            next(schedule, at(2024, 3, 4, 9, 30)),
+            Some(at(2024, 3, 4, 18, 0))
+        );
+        assert_eq!(
+            next(schedule, at(2024, 3, 4, 18, 45)),
+            Some(at(2024, 3, 5, 9, 0))
+        );
+    }
+
+    #[test]
+    fn ranges() {
+        // С понедельника по пятницу, с 9 до 11 часов; 2024-03-08 - пятница
+        assert_eq!(
+            next("0 9-11 * * 1-5", at(2024, 3, 8, 11, 0)),
+            Some(at(2024, 3, 11, 9, 0))
+        );
+    }
+
+    #[test]
+    fn steps() {
+        assert_eq!(
+            next("*/15 * * * *", at(2024, 3, 4, 10, 7)),
+            Some(at(2024, 3, 4, 10, 15))
+        );
+        // Значение с шагом - от значения до конца диапазона: 5, 25, 45
+        assert_eq!(
+            next("5/20 * * * *", at(2024, 3, 4, 10, 45)),
+            Some(at(2024, 3, 4, 11, 5))
+        );
+        assert_eq!(
+            next("0 0-12/6 * * *", at(2024, 3, 4, 6, 0)),
+            Some(at(2024, 3, 4, 12, 0))
+        );
+    }
+
+    #[test]
+    fn sunday_is_zero_or_seven() {
+        assert_eq!(Schedule::parse("0 8 * * 7"), Schedule::parse("0 8 * * 0"));
+        // 2024-03-09 - суббота
+        assert_eq!(
+            next("0 8 * * 7", at(2024, 3, 9, 12, 0)),
+            Some(at(2024, 3, 10, 8, 0))
+        );
+    }
+
+    #[test]
+    fn day_or_weekday() {
+        // Пятницы и 13-е числа; 2024-03-08 - пятница, 13-е - среда
+        let schedule = "0 9 13 * 5";
+        assert_eq!(
+            next(schedule, at(2024, 3, 8, 9, 0)),
+            Some(at(2024, 3, 13, 9, 0))
+        );
+        assert_eq!(
+            next(schedule, at(2024, 3, 13, 9, 0)),
+            Some(at(2024, 3, 15, 9, 0))
+        );
+    }
+
+    #[test]
+    fn star_step_day_and_weekday() {
+        // День месяца начинается с *, поэтому нужны оба совпадения:
+        // понедельники марта 2024 - 4, 11, 18, 25, из них нечетные 11 и 25
+        let schedule = "0 9 */2 * 1";
+        assert_eq!(
+            next(schedule, at(2024, 3, 1, 0, 0)),
+            Some(at(2024, 3, 11, 9, 0))
+        );
+        assert_eq!(
+            next(schedule, at(2024, 3, 11, 9, 0)),
+            Some(at(2024, 3, 25, 9, 0))
+        );
+    }
+
+    #[test]
+    fn month_rollover() {
+        assert_eq!(
+            next("0 0 1 * *", at(2024, 12, 15, 10, 0)),
+            Some(at(2025, 1, 1, 0, 0))
+        );
+        // В апреле нет 31-го числа
+        assert_eq!(
+            next("30 23 31 * *", at(2024, 4, 1, 0, 0)),
+            Some(at(2024, 5, 31, 23, 30))
+        );
+        assert_eq!(
+            next("0 0 29 2 *", at(2024, 3, 1, 0, 0)),
+            Some(at(2028, 2, 29, 0, 0))
+        );
+    }
+
+    #[test]
+    fn never() {
+        assert_eq!(next("0 0 31 2 *", at(2024, 1, 1, 0, 0)), None);
+    }
+
+    #[test]
+    fn invalid() {
+        for schedule in [
+            "60 * * * *",
+            "* * *",
+            "* * * * * *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "0 0 0 * *",
+            "0 0 * 13 *",
+            "0 0 * * 8",
+            "a * * * *",
+        ] {
+            assert!(Schedule::parse(schedule).is_err(), "{}", schedule);
+        }
+    }
+}
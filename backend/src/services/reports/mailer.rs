@@ -0,0 +1,91 @@
+use lettre::{
+    message::{header::ContentType, Attachment, Mailbox, MultiPart, SinglePart},
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+
+// Отправка почты через SMTP. Настройки задаются переменными окружения:
+//   SMTP_HOST - адрес сервера, без него отправка отключена;
+//   SMTP_PORT - порт, по умолчанию зависит от SMTP_TLS;
+//   SMTP_TLS - none, starttls (по умолчанию) или tls;
+//   SMTP_USER и SMTP_PASSWORD - учетная запись, если сервер требует авторизацию;
+//   SMTP_FROM - адрес отправителя, по умолчанию reports@localhost.
+// Для локального перехватчика писем из docker-compose (mailpit):
+//   SMTP_HOST=127.0.0.1, SMTP_PORT=1025, SMTP_TLS=none, письма видны на http://127.0.0.1:8025.
+#[derive(Clone)]
+pub struct Mailer {
+    transport: Option<AsyncSmtpTransport<Tokio1Executor>>,
+    from: Mailbox,
+}
+
+impl Mailer {
+    pub fn from_env() -> Result<Self, anyhow::Error> {
+        let from = std::env::var("SMTP_FROM")
+            .unwrap_or_else(|_| "reports@localhost".to_string())
+            .parse::<Mailbox>()?;
+
+        let Ok(host) = std::env::var("SMTP_HOST") else {
+            return Ok(Self {
+                transport: None,
+                from,
+            });
+        };
+
+        let tls = std::env::var("SMTP_TLS").unwrap_or_else(|_| "starttls".to_string());
+        let mut builder = match tls.as_str() {
+            "none" => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&host),
+            "starttls" => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&host)?,
+            "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(&host)?,
+            _ => anyhow::bail!("unknown SMTP_TLS: {}", tls),
+        };
+
+        if let Ok(port) = std::env::var("SMTP_PORT") {
+            builder = builder.port(port.parse()?);
+        }
+
+        if let (Ok(user), Ok(password)) =
+            (std::env::var("SMTP_USER"), std::env::var("SMTP_PASSWORD"))
+        {
+            builder = builder.credentials(Credentials::new(user, password));
+        }
+
+        Ok(Self {
+            transport: Some(builder.build()),
+            from,
+        })
+    }
+
+    pub async fn send(
+        &self,
+        recipients: &[String],
+        subject: &str,
+        text: &str,
+        attachment: (&str, &str, Vec<u8>),
+    ) -> Result<(), anyhow::Error> {
+        // Письмо с текстом и одним вложением: (имя файла, тип, содержимое)
+
+        let Some(transport) = &self.transport else {
+            anyhow::bail!("Отправка почты не настроена: не задан SMTP_HOST");
+        };
+
+        let (filename, content_type, data) = attachment;
+
+        let mut builder = Message::builder().from(self.from.clone()).subject(subject);
+        for recipient in recipients {
+            builder = builder.to(recipient.parse::<Mailbox>()?);
+        }
+
+        let message = builder.multipart(
+            MultiPart::mixed()
+                .singlepart(SinglePart::plain(text.to_string()))
+                .singlepart(
+                    Attachment::new(filename.to_string())
+                        .body(data, ContentType::parse(content_type)?),
+                ),
+        )?;
+
+        transport.send(message).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,6 @@
+pub mod cron;
+pub mod mailer;
+pub mod pdf;
+pub mod report;
+pub mod scheduler;
+pub mod subscription;
@@ -0,0 +1,339 @@
+use printpdf::{
+    image_crate, Image, ImageTransform, IndirectFontRef, Line, Mm, PdfDocument,
+    PdfDocumentReference, PdfLayerReference, Point,
+};
+use rust_decimal::{Decimal, RoundingStrategy};
+
+// Шрифт должен содержать кириллицу, путь задается переменными PDF_FONT и PDF_FONT_BOLD.
+// Без них DejaVu Sans ищется в каталогах шрифтов распространенных дистрибутивов.
+const DEFAULT_FONTS: [&str; 4] = [
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/dejavu-sans-fonts/DejaVuSans.ttf",
+    "/usr/share/fonts/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans.ttf",
+];
+const DEFAULT_BOLD_FONTS: [&str; 4] = [
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans-Bold.ttf",
+    "/usr/share/fonts/dejavu-sans-fonts/DejaVuSans-Bold.ttf",
+    "/usr/share/fonts/dejavu/DejaVuSans-Bold.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans-Bold.ttf",
+];
+
+// Лист A4 книжной ориентации, размеры в мм
+pub const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+pub const MARGIN: f32 = 15.0;
+const ROW_HEIGHT: f32 = 6.0;
+const TABLE_FONT: f32 = 9.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Column {
+    pub title: &'static str,
+    pub width: f32,
+    pub align: Align,
+}
+
+fn font_file(var: &str, defaults: &[&str]) -> Result<Vec<u8>, anyhow::Error> {
+    // Файл из переменной окружения, без нее - первый найденный из стандартных путей
+
+    if let Ok(path) = std::env::var(var) {
+        return std::fs::read(&path)
+            .map_err(|e| anyhow::anyhow!("can't read font {}: {}", path, e));
+    }
+
+    defaults
+        .iter()
+        .find_map(|path| std::fs::read(path).ok())
+        .ok_or_else(|| anyhow::anyhow!("font not found, set {} to a TTF file with Cyrillic", var))
+}
+
+fn text_width(text: &str, size: f32) -> f32 {
+    // Приблизительная ширина строки в мм: средний символ - 0.55 кегля, 1 pt = 0.3528 мм
+
+    text.chars().count() as f32 * size * 0.55 * 0.3528
+}
+
+fn fit(text: &str, size: f32, width: f32) -> String {
+    // Обрезка текста по ширине колонки с многоточием
+
+    if text_width(text, size) <= width {
+        return text.to_string();
+    }
+
+    let mut value = text.to_string();
+    while !value.is_empty() && text_width(&value, size) + text_width("…", size) > width {
+        value.pop();
+    }
+    value.push('…');
+
+    value
+}
+
+pub fn amount(value: Decimal) -> String {
+    // Денежная сумма с разделителем разрядов: 1 234 567.89
+
+    let value = format!(
+        "{:.2}",
+        value.round_dp_with_strategy(2, RoundingStrategy::MidpointAwayFromZero)
+    );
+    let (int, frac) = value.split_once('.').unwrap_or((&value, "00"));
+    let (sign, digits) = int.strip_prefix('-').map_or(("", int), |d| ("-", d));
+
+    let mut grouped = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push(' ');
+        }
+        grouped.push(c);
+    }
+
+    format!("{}{}.{}", sign, grouped, frac)
+}
+
+pub struct Pdf {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    font: IndirectFontRef,
+    bold: IndirectFontRef,
+    // Расстояние от верхнего края листа до текущей строки
+    y: f32,
+}
+
+impl Pdf {
+    pub fn new(title: &str) -> Result<Self, anyhow::Error> {
+        let (doc, page, layer) = PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Слой 1");
+
+        let regular = font_file("PDF_FONT", &DEFAULT_FONTS)?;
+        let bold =
+            font_file("PDF_FONT_BOLD", &DEFAULT_BOLD_FONTS).unwrap_or_else(|_| regular.clone());
+        let font = doc.add_external_font(regular.as_slice())?;
+        let bold = doc.add_external_font(bold.as_slice())?;
+        let layer = doc.get_page(page).get_layer(layer);
+
+        Ok(Self {
+            doc,
+            layer,
+            font,
+            bold,
+            y: MARGIN,
+        })
+    }
+
+    fn new_page(&mut self) {
+        let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Слой 1");
+        self.layer = self.doc.get_page(page).get_layer(layer);
+        self.y = MARGIN;
+    }
+
+    pub fn ensure(&mut self, height: f32) -> bool {
+        // Перенос на новый лист, если блок не помещается. Возвращает true при переносе
+
+        if self.y + height > PAGE_HEIGHT - MARGIN {
+            self.new_page();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn down(&mut self, height: f32) {
+        self.y += height;
+    }
+
+    pub fn text(&self, text: &str, size: f32, x: f32, bold: bool) {
+        // Текст по базовой линии текущей строки
+
+        let font = if bold { &self.bold } else { &self.font };
+        self.layer
+            .use_text(text, size, Mm(x), Mm(PAGE_HEIGHT - self.y), font);
+    }
+
+    pub fn paragraph(&mut self, text: &str, size: f32, bold: bool) {
+        // Строка текста на всю ширину листа с переходом на следующую строку
+
+        let height = size * 0.3528 * 1.6;
+        self.ensure(height);
+        self.down(height);
+        self.text(
+            &fit(text, size, PAGE_WIDTH - MARGIN * 2.0),
+            size,
+            MARGIN,
+            bold,
+        );
+    }
+
+    pub fn hline(&self, x1: f32, x2: f32, thickness: f32) {
+        self.layer.set_outline_thickness(thickness);
+        self.layer.add_line(Line {
+            points: vec![
+                (Point::new(Mm(x1), Mm(PAGE_HEIGHT - self.y)), false),
+                (Point::new(Mm(x2), Mm(PAGE_HEIGHT - self.y)), false),
+            ],
+            is_closed: false,
+        });
+    }
+
+    pub fn header(&mut self, organization: &str, logo: Option<&[u8]>) {
+        // Шапка: логотип слева, название организации справа от него, черта под шапкой
+
+        let height = 18.0;
+        let mut x = MARGIN;
+
+        if let Some(image) = logo
+            .and_then(|data| image_crate::load_from_memory(data).ok())
+            .map(|image| image_crate::DynamicImage::ImageRgb8(image.to_rgb8()))
+        {
+            let dpi = image.height() as f32 * 25.4 / height;
+            let width = image.width() as f32 * 25.4 / dpi;
+
+            Image::from_dynamic_image(&image).add_to_layer(
+                self.layer.clone(),
+                ImageTransform {
+                    translate_x: Some(Mm(x)),
+                    translate_y: Some(Mm(PAGE_HEIGHT - self.y - height)),
+                    dpi: Some(dpi),
+                    ..Default::default()
+                },
+            );
+            x += width + 5.0;
+        }
+
+        self.down(height / 2.0 + 2.0);
+        self.text(
+            &fit(organization, 14.0, PAGE_WIDTH - MARGIN - x),
+            14.0,
+            x,
+            true,
+        );
+        self.down(height / 2.0);
+        self.hline(MARGIN, PAGE_WIDTH - MARGIN, 1.0);
+        self.down(4.0);
+    }
+
+    fn cells(&mut self, columns: &[Column], cells: &[String], bold: bool) {
+        self.down(ROW_HEIGHT);
+
+        let mut x = MARGIN;
+        for (column, cell) in columns.iter().zip(cells) {
+            let value = fit(cell, TABLE_FONT, column.width - 2.0);
+            let left = match column.align {
+                Align::Left => x + 1.0,
+                Align::Right => x + column.width - 1.0 - text_width(&value, TABLE_FONT),
+            };
+            self.text(&value, TABLE_FONT, left, bold);
+            x += column.width;
+        }
+
+        self.down(1.5);
+        self.hline(MARGIN, x, if bold { 0.8 } else { 0.3 });
+    }
+
+    pub fn table(&mut self, columns: &[Column], rows: &[Vec<String>], totals: Option<&[String]>) {
+        // Таблица с шапкой, повторяемой на каждом листе, и строкой итогов
+
+        let titles = columns
+            .iter()
+            .map(|column| column.title.to_string())
+            .collect::<Vec<String>>();
+
+        self.ensure(ROW_HEIGHT * 3.0);
+        self.cells(columns, &titles, true);
+
+        for row in rows {
+            if self.ensure(ROW_HEIGHT + 1.5) {
+                self.cells(columns, &titles, true);
+            }
+            self.cells(columns, row, false);
+        }
+
+        if let Some(totals) = totals {
+            self.ensure(ROW_HEIGHT + 1.5);
+            self.cells(columns, totals, true);
+        }
+    }
+
+    pub fn signatures(&mut self, items: &[(&str, &str)]) {
+        // Строки подписей: должность, линия для подписи, расшифровка
+
+        self.down(6.0);
+        for (title, name) in items {
+            self.ensure(16.0);
+            self.down(12.0);
+            self.text(title, 10.0, MARGIN, false);
+            self.hline(85.0, 135.0, 0.5);
+            self.text(
+                &fit(name, 10.0, PAGE_WIDTH - MARGIN - 140.0),
+                10.0,
+                140.0,
+                false,
+            );
+            self.down(3.5);
+            self.text("(подпись)", 7.0, 103.0, false);
+        }
+    }
+
+    pub fn save(self) -> Result<Vec<u8>, anyhow::Error> {
+        Ok(self.doc.save_to_bytes()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn amount_groups_digits() {
+        assert_eq!(amount(Decimal::new(0, 0)), "0.00");
+        assert_eq!(amount(Decimal::new(999, 0)), "999.00");
+        assert_eq!(amount(Decimal::new(1000, 0)), "1 000.00");
+        assert_eq!(amount(Decimal::new(123456789, 2)), "1 234 567.89");
+        assert_eq!(
+            amount(Decimal::new(100000000000000, 2)),
+            "1 000 000 000 000.00"
+        );
+    }
+
+    #[test]
+    fn amount_negative() {
+        // Знак не попадает в группы разрядов
+        assert_eq!(amount(Decimal::new(-123456, 2)), "-1 234.56");
+        assert_eq!(amount(Decimal::new(-100000, 2)), "-1 000.00");
+        assert_eq!(amount(Decimal::new(-5, 1)), "-0.50");
+    }
+
+    #[test]
+    fn amount_rounds_half_away_from_zero() {
+        assert_eq!(amount(Decimal::new(12345, 3)), "12.35");
+        assert_eq!(amount(Decimal::new(-12345, 3)), "-12.35");
+        assert_eq!(amount(Decimal::new(99999, 3)), "100.00");
+        assert_eq!(amount(Decimal::new(-1, 3)), "0.00");
+    }
+
+    #[test]
+    fn fit_keeps_short_text() {
+        assert_eq!(fit("Болт", TABLE_FONT, 50.0), "Болт");
+        assert_eq!(fit("", TABLE_FONT, 0.0), "");
+    }
+
+    #[test]
+    fn fit_truncates_with_ellipsis() {
+        let text = "Очень длинное наименование продукта";
+        let value = fit(text, TABLE_FONT, 20.0);
+
+        assert!(value.ends_with('…'));
+        assert!(text.starts_with(value.trim_end_matches('…')));
+        assert!(text_width(&value, TABLE_FONT) <= 20.0);
+        // Отбрасывается не больше, чем нужно для многоточия
+        let kept = value.chars().count() - 1;
+        assert!(text_width(&text.chars().take(kept + 2).collect::<String>(), TABLE_FONT) > 20.0);
+
+        // Колонка уже многоточия
+        assert_eq!(fit(text, TABLE_FONT, 1.0), "…");
+    }
+}
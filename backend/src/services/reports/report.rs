@@ -0,0 +1,421 @@
+use axum::{
+    body::Bytes,
+    extract::{Query, State},
+    http::StatusCode,
+    Extension,
+};
+
+use serde::Deserialize;
+use sqlx::PgPool;
+
+use crate::{
+    check_access,
+    services::{
+        attachments::storage::Storage,
+        produced_goods::analitic::{self, time_zone, Item},
+        reports::pdf::{amount, Align, Column, Pdf, MARGIN, PAGE_WIDTH},
+    },
+    AppError, CurrentUser,
+};
+
+struct Organization {
+    name: String,
+    logo: Option<Bytes>,
+}
+
+async fn organization(
+    pool: &PgPool,
+    storage: &Storage,
+    organization_id: Option<i64>,
+) -> Result<Option<Organization>, AppError> {
+    // Название и логотип организации для шапки отчета
+
+    let Some(organization_id) = organization_id else {
+        return Ok(None);
+    };
+
+    let row: Option<(String, Option<String>)> =
+        sqlx::query_as("SELECT name, logo_key FROM organizations WHERE id = $1")
+            .bind(organization_id)
+            .fetch_optional(pool)
+            .await?;
+
+    let Some((name, logo_key)) = row else {
+        return Ok(None);
+    };
+
+    // Без логотипа отчет все равно формируется
+    let logo = match logo_key {
+        Some(key) => storage.get(&key).await.ok(),
+        None => None,
+    };
+
+    Ok(Some(Organization { name, logo }))
+}
+
+async fn user_fio(pool: &PgPool, id: i64) -> Result<String, AppError> {
+    let fio: String = sqlx::query_scalar("SELECT fio FROM users WHERE id = $1")
+        .bind(id)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(fio)
+}
+
+async fn render<F>(build: F) -> Result<Vec<u8>, AppError>
+where
+    F: FnOnce() -> Result<Vec<u8>, anyhow::Error> + Send + 'static,
+{
+    // Документ PDF не Send, поэтому собирается целиком в отдельном потоке
+
+    let buffer = tokio::task::spawn_blocking(build).await??;
+
+    Ok(buffer)
+}
+
+fn render_header(pdf: &mut Pdf, organization: &Option<Organization>) {
+    if let Some(organization) = organization {
+        pdf.header(&organization.name, organization.logo.as_deref());
+    }
+}
+
+pub async fn analitics_pdf(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(storage): Extension<Storage>,
+    Query(q): Query<analitic::Q>,
+) -> Result<Vec<u8>, AppError> {
+    // Бизнес логика печатного отчета по производству за период.
+    // Данные и права доступа те же, что у отчета аналитики в Excel.
+
+    let items = analitic::get_analitics(
+        State(pool.clone()),
+        Extension(current_user.clone()),
+        Query(q.clone()),
+    )
+    .await?;
+    let organization = organization(&pool, &storage, current_user.organization_id).await?;
+    let fio = user_fio(&pool, current_user.id).await?;
+
+    render(move || {
+        let title = format!(
+            "Отчет по производству за период: {} - {}",
+            q.date_one.format("%d.%m.%Y"),
+            q.date_two.format("%d.%m.%Y")
+        );
+
+        let mut pdf = Pdf::new(&title)?;
+        render_header(&mut pdf, &organization);
+        pdf.paragraph(&title, 13.0, true);
+        pdf.down(4.0);
+
+        let grouped = items.iter().any(|item| item.period.is_some());
+        let mut columns = vec![Column {
+            title: "№",
+            width: 10.0,
+            align: Align::Right,
+        }];
+        if grouped {
+            columns.push(Column {
+                title: "Период",
+                width: 22.0,
+                align: Align::Left,
+            });
+        }
+        let name_width = if grouped { 38.0 } else { 49.0 };
+        columns.extend([
+            Column {
+                title: "Продукт",
+                width: name_width,
+                align: Align::Left,
+            },
+            Column {
+                title: "Пользователь",
+                width: name_width,
+                align: Align::Left,
+            },
+            Column {
+                title: "Ед.изм.",
+                width: 14.0,
+                align: Align::Left,
+            },
+            Column {
+                title: "Кол-во",
+                width: 16.0,
+                align: Align::Right,
+            },
+            Column {
+                title: "Себест., руб.",
+                width: 21.0,
+                align: Align::Right,
+            },
+            Column {
+                title: "Стоим., руб.",
+                width: 21.0,
+                align: Align::Right,
+            },
+        ]);
+
+        let rows = items
+            .iter()
+            .enumerate()
+            .map(|(i, item): (usize, &Item)| {
+                let mut row = vec![(i + 1).to_string()];
+                if grouped {
+                    row.push(item.period.clone().unwrap_or_default());
+                }
+                row.extend([
+                    item.name.clone(),
+                    item.fio.clone(),
+                    item.measure.clone(),
+                    item.cnt.to_string(),
+                    amount(item.cost_amount),
+                    amount(item.price_amount),
+                ]);
+                row
+            })
+            .collect::<Vec<Vec<String>>>();
+
+        // Кол-во в итогах не суммируется из-за разных единиц измерения
+        let mut totals = vec![String::new(); columns.len()];
+        totals[1] = "Итого".to_string();
+        totals[columns.len() - 2] = amount(items.iter().map(|item| item.cost_amount).sum());
+        totals[columns.len() - 1] = amount(items.iter().map(|item| item.price_amount).sum());
+
+        pdf.table(&columns, &rows, Some(totals.as_slice()));
+        pdf.signatures(&[("Директор", ""), ("Составил", fio.as_str())]);
+
+        pdf.save()
+    })
+    .await
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ShiftQ {
+    pub shift_id: i64,
+    // Дата начала смены, ночная смена заканчивается на следующий день
+    pub date: chrono::NaiveDate,
+    pub tz: Option<String>,
+}
+
+// Выпуск за смену по продукту и пользователю
+#[derive(sqlx::FromRow)]
+struct ShiftRow {
+    product: String,
+    measure: String,
+    fio: String,
+    records: i64,
+    cnt: i64,
+    defect: i64,
+}
+
+pub async fn shift_act_pdf(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(storage): Extension<Storage>,
+    Query(q): Query<ShiftQ>,
+) -> Result<Vec<u8>, AppError> {
+    // Бизнес логика акта выпуска продукции за смену: выпуск по продуктам и работникам
+    // с учетом одобренных корректировок, брак, итоги и подписи. Отклоненные записи не входят.
+
+    if !check_access(current_user.role) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    let tz = time_zone(&pool, &q.tz).await?;
+
+    let shift: Option<(String, chrono::NaiveTime, chrono::NaiveTime, i64)> = sqlx::query_as(
+        "SELECT name, start_time, end_time, organization_id
+        FROM shifts
+        WHERE id = $1
+        AND CASE
+            WHEN $3 not in ('Admin', 'Developer') THEN organization_id = $2::bigint
+            ELSE TRUE
+        END",
+    )
+    .bind(q.shift_id)
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .fetch_optional(&pool)
+    .await?;
+
+    let Some((shift_name, start_time, end_time, organization_id)) = shift else {
+        return Err(AppError(
+            StatusCode::NOT_FOUND,
+            anyhow::anyhow!("Такой смены не существует"),
+        ));
+    };
+
+    // Сдвиг на начало смены относит записи ночной смены после полуночи к дате ее начала
+    let rows: Vec<ShiftRow> = sqlx::query_as(
+        "SELECT
+          p.name AS product,
+          mu.name AS measure,
+          u.fio,
+          COUNT(*)::bigint AS records,
+          SUM(pg.cnt + COALESCE(pa.adjustment_cnt, 0))::bigint AS cnt,
+          COALESCE(SUM(d.defect_cnt), 0)::bigint AS defect
+        FROM
+          produced_goods AS pg
+          JOIN shifts AS s ON s.id = pg.shift_id
+          JOIN products AS p ON p.id = pg.product_id
+          JOIN measure_units AS mu ON mu.id = p.measure_unit_id
+          JOIN users AS u ON u.id = pg.user_id
+          LEFT JOIN (
+            SELECT
+              produced_good_id,
+              SUM(cnt::bigint) AS adjustment_cnt
+            FROM
+              produced_good_adjustments
+            WHERE
+              status = 'Approved'
+            GROUP BY
+              produced_good_id
+          ) pa ON pa.produced_good_id = pg.id
+          LEFT JOIN (
+            SELECT
+              produced_good_id,
+              SUM(cnt::bigint) AS defect_cnt
+            FROM
+              produced_good_defects
+            GROUP BY
+              produced_good_id
+          ) d ON d.produced_good_id = pg.id
+        WHERE pg.shift_id = $1
+        AND ((pg.created_at AT TIME ZONE $3) - s.start_time::interval)::date = $2
+        AND pg.status <> 'Rejected'
+        GROUP BY
+          p.id,
+          p.name,
+          mu.name,
+          u.id,
+          u.fio
+        ORDER BY
+          p.name,
+          u.fio",
+    )
+    .bind(q.shift_id)
+    .bind(q.date)
+    .bind(&tz)
+    .fetch_all(&pool)
+    .await?;
+
+    let organization = organization(&pool, &storage, Some(organization_id)).await?;
+    let fio = user_fio(&pool, current_user.id).await?;
+
+    render(move || {
+        let title = format!(
+            "Акт выпуска продукции за смену «{}» от {}",
+            shift_name,
+            q.date.format("%d.%m.%Y")
+        );
+
+        let mut pdf = Pdf::new(&title)?;
+        render_header(&mut pdf, &organization);
+        pdf.paragraph(&title, 13.0, true);
+        pdf.paragraph(
+            &format!(
+                "Время смены: {} - {}",
+                start_time.format("%H:%M"),
+                end_time.format("%H:%M")
+            ),
+            10.0,
+            false,
+        );
+        pdf.down(4.0);
+
+        let columns = [
+            Column {
+                title: "№",
+                width: 10.0,
+                align: Align::Right,
+            },
+            Column {
+                title: "Продукт",
+                width: 52.0,
+                align: Align::Left,
+            },
+            Column {
+                title: "Ед.изм.",
+                width: 16.0,
+                align: Align::Left,
+            },
+            Column {
+                title: "Работник",
+                width: 50.0,
+                align: Align::Left,
+            },
+            Column {
+                title: "Записей",
+                width: 18.0,
+                align: Align::Right,
+            },
+            Column {
+                title: "Выпуск",
+                width: 18.0,
+                align: Align::Right,
+            },
+            Column {
+                title: "Брак",
+                width: 16.0,
+                align: Align::Right,
+            },
+        ];
+
+        let table = rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                vec![
+                    (i + 1).to_string(),
+                    row.product.clone(),
+                    row.measure.clone(),
+                    row.fio.clone(),
+                    row.records.to_string(),
+                    row.cnt.to_string(),
+                    row.defect.to_string(),
+                ]
+            })
+            .collect::<Vec<Vec<String>>>();
+
+        // Выпуск и брак суммируются только при одной единице измерения
+        let single_measure = rows.windows(2).all(|w| w[0].measure == w[1].measure);
+        let sum = |value: fn(&ShiftRow) -> i64| {
+            if single_measure {
+                rows.iter().map(value).sum::<i64>().to_string()
+            } else {
+                String::new()
+            }
+        };
+        let totals = vec![
+            String::new(),
+            "Итого".to_string(),
+            String::new(),
+            String::new(),
+            rows.iter().map(|row| row.records).sum::<i64>().to_string(),
+            sum(|row| row.cnt),
+            sum(|row| row.defect),
+        ];
+
+        pdf.table(&columns, &table, Some(totals.as_slice()));
+
+        if rows.is_empty() {
+            pdf.paragraph("За смену нет записей о выпуске.", 10.0, false);
+        }
+
+        pdf.down(2.0);
+        pdf.hline(MARGIN, PAGE_WIDTH - MARGIN, 0.3);
+        pdf.signatures(&[
+            ("Мастер смены", ""),
+            ("Принял (склад)", ""),
+            ("Директор", ""),
+            ("Составил", fio.as_str()),
+        ]);
+
+        pdf.save()
+    })
+    .await
+}
@@ -0,0 +1,156 @@
+use sqlx::PgPool;
+
+use crate::{
+    services::reports::{
+        cron::Schedule,
+        mailer::Mailer,
+        subscription::{deliver, next_run},
+    },
+    AppError,
+};
+
+// Интервал проверки подписок, расписание cron имеет точность до минуты
+const TICK: std::time::Duration = std::time::Duration::from_secs(60);
+// Срок захвата подписки на время отправки: если процесс упадет во время отправки,
+// по истечении срока подписка будет захвачена снова
+const LEASE_MINUTES: i32 = 10;
+// Неудачная отправка повторяется через RETRY_MINUTES, всего не больше ATTEMPTS попыток
+const RETRY_MINUTES: i32 = 5;
+const ATTEMPTS: i32 = 3;
+
+#[derive(sqlx::FromRow)]
+struct Claimed {
+    id: i64,
+    schedule: String,
+    time_zone: String,
+    attempts: i32,
+    // Срок захвата, по нему видно, что подписку не изменили во время отправки
+    next_run_at: chrono::DateTime<chrono::Utc>,
+}
+
+async fn claim(pool: &PgPool) -> Result<Option<Claimed>, AppError> {
+    // Захват одной подписки, время запуска которой наступило, на время отправки.
+    // SKIP LOCKED и сдвиг запуска на срок захвата не дают нескольким экземплярам
+    // бэкенда отправить отчет дважды, а следующий запуск по расписанию
+    // назначается только после отправки.
+
+    let row: Option<Claimed> = sqlx::query_as(
+        "UPDATE report_subscriptions
+        SET next_run_at = NOW() + make_interval(mins => $1)
+        WHERE id = (
+          SELECT id
+          FROM report_subscriptions
+          WHERE active AND next_run_at <= NOW()
+          ORDER BY next_run_at
+          LIMIT 1
+          FOR UPDATE SKIP LOCKED
+        )
+        RETURNING id, schedule, time_zone, attempts, next_run_at",
+    )
+    .bind(LEASE_MINUTES)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row)
+}
+
+async fn finish(pool: &PgPool, claimed: &Claimed, sent: bool) -> Result<(), AppError> {
+    // Неудачная отправка повторяется, пока есть попытки. После отправки или последней
+    // попытки подписка сдвигается на следующий запуск по расписанию.
+    // Если подписку изменили во время отправки, ее новое расписание не трогается.
+
+    let attempts = claimed.attempts + 1;
+    if !sent && attempts < ATTEMPTS {
+        let _ = sqlx::query(
+            "UPDATE report_subscriptions
+            SET next_run_at = NOW() + make_interval(mins => $3), last_run_at = NOW(),
+              attempts = $4
+            WHERE id = $1 AND next_run_at = $2",
+        )
+        .bind(claimed.id)
+        .bind(claimed.next_run_at)
+        .bind(RETRY_MINUTES)
+        .bind(attempts)
+        .execute(pool)
+        .await?;
+
+        return Ok(());
+    }
+
+    // Пропущенные за время простоя запуски не догоняются, берется ближайший будущий
+    let next = match Schedule::parse(&claimed.schedule) {
+        Ok(schedule) => next_run(pool, &schedule, &claimed.time_zone).await?,
+        Err(e) => {
+            tracing::warn!(
+                "report subscription {}: schedule {:?}: {}",
+                claimed.id,
+                claimed.schedule,
+                e
+            );
+            None
+        }
+    };
+
+    // Подписка без следующего запуска отключается,
+    // иначе она пропала бы из очереди молча с активным флагом
+    if next.is_none() {
+        tracing::warn!(
+            "report subscription {}: no next run, deactivated",
+            claimed.id
+        );
+    }
+
+    let _ = sqlx::query(
+        "UPDATE report_subscriptions
+        SET next_run_at = $3, last_run_at = NOW(), attempts = 0,
+          active = $3::timestamptz IS NOT NULL
+        WHERE id = $1 AND next_run_at = $2",
+    )
+    .bind(claimed.id)
+    .bind(claimed.next_run_at)
+    .bind(next)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn run(pool: PgPool, mailer: Mailer) {
+    // Фоновая отправка отчетов по расписанию подписок, работает в процессе бэкенда
+
+    let mut interval = tokio::time::interval(TICK);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+
+        loop {
+            let claimed = match claim(&pool).await {
+                Ok(Some(claimed)) => claimed,
+                Ok(None) => break,
+                Err(e) => {
+                    tracing::error!("report scheduler: {:#}", e.1);
+                    break;
+                }
+            };
+
+            let sent = match deliver(&pool, &mailer, claimed.id).await {
+                Ok(delivery) => match delivery.error {
+                    Some(error) => {
+                        tracing::warn!("report subscription {} failed: {}", claimed.id, error);
+                        false
+                    }
+                    None => true,
+                },
+                Err(e) => {
+                    tracing::error!("report subscription {}: {:#}", claimed.id, e.1);
+                    false
+                }
+            };
+
+            if let Err(e) = finish(&pool, &claimed, sent).await {
+                tracing::error!("report subscription {}: {:#}", claimed.id, e.1);
+            }
+        }
+    }
+}
@@ -0,0 +1,917 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    check_access, check_is_admin,
+    services::{
+        produced_goods::analitic::{self, time_zone, Period},
+        reports::{cron::Schedule, mailer::Mailer},
+        Items, Select,
+    },
+    AppError, CurrentUser, Role,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportType {
+    // Отчет по производству за период в Excel, как на странице аналитики
+    Analitics,
+}
+
+impl ReportType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Analitics => "analitics",
+        }
+    }
+}
+
+impl TryFrom<String> for ReportType {
+    type Error = AppError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "analitics" => Ok(Self::Analitics),
+            _ => Err(AppError(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                anyhow::anyhow!("Неизвестный тип отчета: {}", value),
+            )),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportPeriod {
+    // Вчерашний день
+    Day,
+    // Прошлая неделя с понедельника по воскресенье
+    Week,
+    // Прошлый календарный месяц
+    Month,
+}
+
+impl ReportPeriod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Day => "day",
+            Self::Week => "week",
+            Self::Month => "month",
+        }
+    }
+
+    pub fn range(&self, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+        // Последний полностью завершившийся период относительно сегодняшней даты
+
+        match self {
+            Self::Day => {
+                let day = today - Duration::days(1);
+                (day, day)
+            }
+            Self::Week => {
+                let monday =
+                    today - Duration::days(today.weekday().num_days_from_monday() as i64 + 7);
+                (monday, monday + Duration::days(6))
+            }
+            Self::Month => {
+                let last = today.with_day(1).unwrap_or(today) - Duration::days(1);
+                (last.with_day(1).unwrap_or(last), last)
+            }
+        }
+    }
+}
+
+impl From<String> for ReportPeriod {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "day" => Self::Day,
+            "month" => Self::Month,
+            _ => Self::Week,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum DeliveryStatus {
+    Sent,
+    Failed,
+}
+
+impl From<String> for DeliveryStatus {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "Sent" => Self::Sent,
+            _ => Self::Failed,
+        }
+    }
+}
+
+impl std::fmt::Display for DeliveryStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Sent => "Sent",
+            Self::Failed => "Failed",
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RequestBody {
+    organization_id: Option<i64>,
+    name: String,
+    report_type: ReportType,
+    // Параметры отчета аналитики без дат: продукты, пользователи, категория, группировка и т.д.
+    filters: Option<Value>,
+    period: ReportPeriod,
+    // Расписание cron, например "0 8 * * 1" - по понедельникам в 8:00
+    schedule: String,
+    // Часовой пояс расписания и границ периода, по умолчанию - пояс сервера БД
+    time_zone: Option<String>,
+    recipients: Vec<String>,
+    #[serde(default = "active")]
+    active: bool,
+}
+
+fn active() -> bool {
+    true
+}
+
+fn bad_request(message: String) -> AppError {
+    AppError(StatusCode::BAD_REQUEST, anyhow::anyhow!(message))
+}
+
+fn report_q(
+    filters: &Value,
+    date_one: NaiveDate,
+    date_two: NaiveDate,
+    tz: &str,
+) -> Result<analitic::Q, AppError> {
+    // Параметры аналитики из фильтров подписки с датами отчетного периода
+
+    let mut filters = match filters {
+        Value::Object(filters) => filters.clone(),
+        Value::Null => serde_json::Map::new(),
+        _ => return Err(bad_request("Фильтры должны быть объектом!".to_string())),
+    };
+    filters.insert("date_one".to_string(), Value::from(date_one.to_string()));
+    filters.insert("date_two".to_string(), Value::from(date_two.to_string()));
+    filters.insert("tz".to_string(), Value::from(tz));
+
+    serde_json::from_value(Value::Object(filters))
+        .map_err(|e| bad_request(format!("Неверные фильтры отчета: {}", e)))
+}
+
+fn recipients(body: &RequestBody) -> Result<Vec<String>, AppError> {
+    let recipients = body
+        .recipients
+        .iter()
+        .map(|recipient| recipient.trim().to_string())
+        .filter(|recipient| !recipient.is_empty())
+        .collect::<Vec<String>>();
+
+    if recipients.is_empty() {
+        return Err(bad_request(
+            "Укажите хотя бы одного получателя!".to_string(),
+        ));
+    }
+
+    if let Some(recipient) = recipients
+        .iter()
+        .find(|recipient| recipient.parse::<lettre::Address>().is_err())
+    {
+        return Err(bad_request(format!("Неверный адрес почты: {}", recipient)));
+    }
+
+    Ok(recipients)
+}
+
+pub async fn next_run(
+    pool: &PgPool,
+    schedule: &Schedule,
+    tz: &str,
+) -> Result<Option<chrono::DateTime<chrono::Utc>>, AppError> {
+    // Следующий запуск по расписанию: расписание задано в местном времени часового пояса
+
+    let now: chrono::NaiveDateTime =
+        sqlx::query_scalar("SELECT (NOW() AT TIME ZONE $1)::timestamp")
+            .bind(tz)
+            .fetch_one(pool)
+            .await?;
+
+    let Some(next) = schedule.next_after(now) else {
+        return Ok(None);
+    };
+
+    let next = sqlx::query_scalar("SELECT $1::timestamp AT TIME ZONE $2")
+        .bind(next)
+        .bind(tz)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(Some(next))
+}
+
+struct Valid {
+    filters: Value,
+    tz: String,
+    recipients: Vec<String>,
+    next_run_at: chrono::DateTime<chrono::Utc>,
+}
+
+async fn validate(pool: &PgPool, body: &RequestBody) -> Result<Valid, AppError> {
+    // Проверка расписания, получателей и фильтров подписки
+
+    if body.name.trim().is_empty() {
+        return Err(bad_request("Укажите название подписки!".to_string()));
+    }
+
+    let schedule = Schedule::parse(&body.schedule)
+        .map_err(|e| bad_request(format!("Неверное расписание: {}", e)))?;
+    let recipients = recipients(body)?;
+    let tz = time_zone(pool, &body.time_zone).await?;
+
+    let filters = body
+        .filters
+        .clone()
+        .unwrap_or(Value::Object(Default::default()));
+    let (date_one, date_two) = body.period.range(chrono::Utc::now().date_naive());
+    report_q(&filters, date_one, date_two, &tz)?;
+
+    let next_run_at = next_run(pool, &schedule, &tz)
+        .await?
+        .ok_or_else(|| bad_request("Расписание никогда не сработает!".to_string()))?;
+
+    Ok(Valid {
+        filters,
+        tz,
+        recipients,
+        next_run_at,
+    })
+}
+
+pub async fn create_subscription(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(body): Json<RequestBody>,
+) -> Result<i64, AppError> {
+    // Бизнес логика создания подписки на отчет.
+    // Отчет будет строиться с правами создавшего подписку пользователя.
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        let valid = validate(&pool, &body).await?;
+
+        let organization_id = if check_is_admin(current_user.role) {
+            body.organization_id
+        } else {
+            current_user.organization_id
+        };
+
+        match organization_id {
+            Some(organization_id) => {
+                let row: (i64,) = sqlx::query_as(
+                    "INSERT
+                    INTO report_subscriptions (
+                        organization_id, user_id, name, report_type, filters, period,
+                        schedule, time_zone, recipients, active, next_run_at
+                    ) VALUES
+                    ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11) RETURNING id",
+                )
+                .bind(organization_id)
+                .bind(current_user.id)
+                .bind(body.name.trim())
+                .bind(body.report_type.as_str())
+                .bind(&valid.filters)
+                .bind(body.period.as_str())
+                .bind(body.schedule.trim())
+                .bind(&valid.tz)
+                .bind(&valid.recipients)
+                .bind(body.active)
+                .bind(valid.next_run_at)
+                .fetch_one(&pool)
+                .await?;
+
+                Ok(row.0)
+            }
+            _ => Err(AppError(
+                StatusCode::BAD_REQUEST,
+                anyhow::anyhow!("Невозможно создать запись без организации!"),
+            )),
+        }
+    }
+}
+
+pub async fn edit_subscription(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+    Json(body): Json<RequestBody>,
+) -> Result<i64, AppError> {
+    // Бизнес логика редактирования подписки на отчет, следующий запуск пересчитывается
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        let valid = validate(&pool, &body).await?;
+
+        let organization_id = if check_is_admin(current_user.role) {
+            body.organization_id
+        } else {
+            current_user.organization_id
+        };
+
+        match organization_id {
+            Some(organization_id) => {
+                let result = sqlx::query(
+                    "UPDATE report_subscriptions
+                    SET organization_id=$1, name=$2, report_type=$3, filters=$4, period=$5,
+                        schedule=$6, time_zone=$7, recipients=$8, active=$9, next_run_at=$10,
+                        attempts=0, updated_at=NOW()
+                    WHERE id = $11
+                    AND CASE
+                        WHEN $13 not in ('Admin', 'Developer') THEN organization_id = $12::bigint
+                        ELSE TRUE
+                    END",
+                )
+                .bind(organization_id)
+                .bind(body.name.trim())
+                .bind(body.report_type.as_str())
+                .bind(&valid.filters)
+                .bind(body.period.as_str())
+                .bind(body.schedule.trim())
+                .bind(&valid.tz)
+                .bind(&valid.recipients)
+                .bind(body.active)
+                .bind(valid.next_run_at)
+                .bind(id)
+                .bind(current_user.organization_id)
+                .bind(current_user.role.to_string())
+                .execute(&pool)
+                .await?;
+
+                if result.rows_affected() == 0 {
+                    return Err(AppError(
+                        StatusCode::NOT_FOUND,
+                        anyhow::anyhow!("Такой записи не существует"),
+                    ));
+                }
+
+                Ok(id)
+            }
+            _ => Err(AppError(
+                StatusCode::BAD_REQUEST,
+                anyhow::anyhow!("Невозможно отредактировать запись без организации!"),
+            )),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Q {
+    #[serde(default = "page")]
+    pub page: i64,
+    #[serde(default = "per_page")]
+    pub per_page: i64,
+}
+
+fn per_page() -> i64 {
+    15
+}
+
+fn page() -> i64 {
+    1
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Item {
+    pub id: i64,
+    pub name: String,
+    pub report_type: ReportType,
+    pub filters: Value,
+    pub period: ReportPeriod,
+    pub schedule: String,
+    pub time_zone: String,
+    pub recipients: Vec<String>,
+    pub active: bool,
+    pub next_run_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_run_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+
+    organization: Select,
+    // Владелец подписки, с его правами строится отчет
+    user: Select,
+}
+
+#[derive(sqlx::FromRow)]
+struct Row {
+    id: i64,
+    name: String,
+    report_type: String,
+    filters: Value,
+    period: String,
+    schedule: String,
+    time_zone: String,
+    recipients: Vec<String>,
+    active: bool,
+    next_run_at: Option<chrono::DateTime<chrono::Utc>>,
+    last_run_at: Option<chrono::DateTime<chrono::Utc>>,
+    created_at: chrono::DateTime<chrono::Utc>,
+    organization: Value,
+    owner: Value,
+}
+
+impl TryFrom<Row> for Item {
+    type Error = AppError;
+
+    fn try_from(row: Row) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.id,
+            name: row.name,
+            report_type: row.report_type.try_into()?,
+            filters: row.filters,
+            period: row.period.into(),
+            schedule: row.schedule,
+            time_zone: row.time_zone,
+            recipients: row.recipients,
+            active: row.active,
+            next_run_at: row.next_run_at,
+            last_run_at: row.last_run_at,
+            created_at: row.created_at,
+            organization: row.organization.into(),
+            user: row.owner.into(),
+        })
+    }
+}
+
+pub async fn get_subscriptions(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Items<Item>, AppError> {
+    // Бизнес логика получения списка подписок на отчеты
+
+    if !check_access(current_user.role) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    let rows: Vec<Row> = sqlx::query_as(
+        "SELECT
+            rs.id,
+            rs.name,
+            rs.report_type,
+            rs.filters,
+            rs.period,
+            rs.schedule,
+            rs.time_zone,
+            rs.recipients,
+            rs.active,
+            rs.next_run_at,
+            rs.last_run_at,
+            rs.created_at,
+            JSONB_BUILD_OBJECT(
+                'id', o.id,
+                'name', o.name
+            ) AS organization,
+            JSONB_BUILD_OBJECT(
+                'id', u.id,
+                'name', u.fio
+            ) AS owner
+        FROM report_subscriptions AS rs
+        LEFT JOIN organizations AS o ON o.id = rs.organization_id
+        LEFT JOIN users AS u ON u.id = rs.user_id
+        WHERE
+            CASE
+                WHEN $2 not in ('Admin', 'Developer') THEN rs.organization_id = $1::bigint
+                ELSE TRUE
+            END
+        ORDER BY rs.active DESC, rs.name, rs.id DESC
+        OFFSET $3 LIMIT $4;",
+    )
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .bind((q.page - 1) * q.per_page)
+    .bind(q.per_page)
+    .fetch_all(&pool)
+    .await?;
+
+    // Подсчет данных для пагинации
+    let cnt: i64 = sqlx::query_scalar(
+        "SELECT COUNT(id) FROM report_subscriptions AS rs WHERE CASE
+        WHEN $2 not in ('Admin', 'Developer') THEN rs.organization_id = $1::bigint
+        ELSE TRUE
+    END",
+    )
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .fetch_one(&pool)
+    .await
+    .unwrap_or(0);
+
+    let cnt = (cnt as f64 / q.per_page as f64).ceil() as i64;
+
+    Ok(Items {
+        items: rows
+            .into_iter()
+            .map(Item::try_from)
+            .collect::<Result<Vec<Item>, AppError>>()?,
+        cnt,
+    })
+}
+
+async fn check_subscription(
+    pool: &PgPool,
+    current_user: &CurrentUser,
+    id: i64,
+) -> Result<(), AppError> {
+    // Подписка доступна только в своей организации
+
+    if !check_access(current_user.role) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    let exists: bool = sqlx::query_scalar(
+        "SELECT EXISTS (
+          SELECT 1 FROM report_subscriptions
+          WHERE id = $1
+          AND CASE
+            WHEN $3 not in ('Admin', 'Developer') THEN organization_id = $2::bigint
+            ELSE TRUE
+          END
+        )",
+    )
+    .bind(id)
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .fetch_one(pool)
+    .await?;
+
+    if !exists {
+        return Err(AppError(
+            StatusCode::NOT_FOUND,
+            anyhow::anyhow!("Такой записи не существует"),
+        ));
+    }
+
+    Ok(())
+}
+
+pub async fn delete_subscription(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<(), AppError> {
+    // Бизнес логика удаления подписки вместе с историей отправок
+
+    check_subscription(&pool, &current_user, id).await?;
+
+    let _ = sqlx::query("DELETE FROM report_subscriptions WHERE id = $1")
+        .bind(id)
+        .execute(&pool)
+        .await?;
+
+    Ok(())
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Delivery {
+    pub id: i64,
+    pub status: DeliveryStatus,
+    pub date_one: NaiveDate,
+    pub date_two: NaiveDate,
+    pub recipients: Vec<String>,
+    pub error: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+type DeliveryRow = (
+    i64,
+    String,
+    NaiveDate,
+    NaiveDate,
+    Vec<String>,
+    Option<String>,
+    chrono::DateTime<chrono::Utc>,
+);
+
+impl From<DeliveryRow> for Delivery {
+    fn from(row: DeliveryRow) -> Self {
+        Self {
+            id: row.0,
+            status: row.1.into(),
+            date_one: row.2,
+            date_two: row.3,
+            recipients: row.4,
+            error: row.5,
+            created_at: row.6,
+        }
+    }
+}
+
+pub async fn get_deliveries(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+    Query(q): Query<Q>,
+) -> Result<Items<Delivery>, AppError> {
+    // Бизнес логика получения истории отправок подписки, новые сверху
+
+    check_subscription(&pool, &current_user, id).await?;
+
+    let rows: Vec<DeliveryRow> = sqlx::query_as(
+        "SELECT id, status, date_one, date_two, recipients, error, created_at
+        FROM report_deliveries
+        WHERE subscription_id = $1
+        ORDER BY created_at DESC, id DESC
+        OFFSET $2 LIMIT $3",
+    )
+    .bind(id)
+    .bind((q.page - 1) * q.per_page)
+    .bind(q.per_page)
+    .fetch_all(&pool)
+    .await?;
+
+    // Подсчет данных для пагинации
+    let cnt: i64 =
+        sqlx::query_scalar("SELECT COUNT(id) FROM report_deliveries WHERE subscription_id = $1")
+            .bind(id)
+            .fetch_one(&pool)
+            .await
+            .unwrap_or(0);
+
+    let cnt = (cnt as f64 / q.per_page as f64).ceil() as i64;
+
+    Ok(Items {
+        items: rows.into_iter().map(Delivery::from).collect(),
+        cnt,
+    })
+}
+
+pub async fn send_subscription(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(mailer): Extension<Mailer>,
+    Path(id): Path<i64>,
+) -> Result<Delivery, AppError> {
+    // Бизнес логика внеочередной отправки отчета по подписке, расписание не сдвигается
+
+    check_subscription(&pool, &current_user, id).await?;
+
+    deliver(&pool, &mailer, id).await
+}
+
+struct Subscription {
+    organization_id: i64,
+    name: String,
+    filters: Value,
+    period: ReportPeriod,
+    time_zone: String,
+    recipients: Vec<String>,
+    owner: CurrentUser,
+}
+
+async fn send_report(
+    pool: &PgPool,
+    mailer: &Mailer,
+    subscription: &Subscription,
+    date_one: NaiveDate,
+    date_two: NaiveDate,
+) -> Result<(), AppError> {
+    // Отчет строится и проверяется на доступ от имени владельца подписки
+
+    if subscription.owner.blocked {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("Владелец подписки заблокирован"),
+        ));
+    }
+
+    let mut q = report_q(
+        &subscription.filters,
+        date_one,
+        date_two,
+        &subscription.time_zone,
+    )?;
+    // Администратор видит все организации, отчет ограничивается организацией подписки
+    if check_is_admin(subscription.owner.role) && q.organizations.is_none() {
+        q.organizations = Some(subscription.organization_id.to_string());
+    }
+
+    let items = analitic::get_analitics(
+        State(pool.clone()),
+        Extension(subscription.owner.clone()),
+        Query(q.clone()),
+    )
+    .await?;
+    let daily = analitic::get_analitics(
+        State(pool.clone()),
+        Extension(subscription.owner.clone()),
+        Query(analitic::Q {
+            group: Some(Period::Day),
+            compare: None,
+            ..q
+        }),
+    )
+    .await?;
+    let buffer = analitic::generate_excel(items, daily, date_one, date_two).await?;
+
+    let period = format!(
+        "{} - {}",
+        date_one.format("%d.%m.%Y"),
+        date_two.format("%d.%m.%Y")
+    );
+    let filename = format!(
+        "period_report_{}_{}.xlsx",
+        date_one.format("%d.%m.%Y"),
+        date_two.format("%d.%m.%Y")
+    );
+
+    mailer
+        .send(
+            &subscription.recipients,
+            &format!("{}: отчет за {}", subscription.name, period),
+            &format!(
+                "Отчет по производству за период {} во вложении.\n\nПисьмо отправлено автоматически по подписке «{}».",
+                period, subscription.name
+            ),
+            (
+                &filename,
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+                buffer,
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[derive(sqlx::FromRow)]
+struct OwnerRow {
+    organization_id: Option<i64>,
+    role: String,
+    email: String,
+    fio: Option<String>,
+    blocked: bool,
+}
+
+#[derive(sqlx::FromRow)]
+struct SubscriptionRow {
+    organization_id: i64,
+    user_id: i64,
+    name: String,
+    filters: Value,
+    period: String,
+    time_zone: String,
+    recipients: Vec<String>,
+}
+
+pub async fn deliver(pool: &PgPool, mailer: &Mailer, id: i64) -> Result<Delivery, AppError> {
+    // Формирование и отправка отчета по подписке за последний завершившийся период.
+    // Результат, в том числе ошибка, сохраняется в истории отправок.
+
+    let row: Option<SubscriptionRow> = sqlx::query_as(
+        "SELECT organization_id, user_id, name, filters, period, time_zone, recipients
+        FROM report_subscriptions
+        WHERE id = $1",
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(row) = row else {
+        return Err(AppError(
+            StatusCode::NOT_FOUND,
+            anyhow::anyhow!("Такой записи не существует"),
+        ));
+    };
+
+    let owner: OwnerRow = sqlx::query_as(
+        "SELECT organization_id, role, email, fio, blocked FROM users WHERE id = $1",
+    )
+    .bind(row.user_id)
+    .fetch_one(pool)
+    .await?;
+
+    let subscription = Subscription {
+        organization_id: row.organization_id,
+        name: row.name,
+        filters: row.filters,
+        period: row.period.into(),
+        time_zone: row.time_zone,
+        recipients: row.recipients,
+        owner: CurrentUser {
+            id: row.user_id,
+            organization_id: owner.organization_id,
+            role: Role::from(owner.role),
+            email: owner.email,
+            fio: owner.fio,
+            blocked: owner.blocked,
+            // Отчет строится без сессии пользователя
+            token: Uuid::nil(),
+        },
+    };
+
+    let today: NaiveDate = sqlx::query_scalar("SELECT (NOW() AT TIME ZONE $1)::date")
+        .bind(&subscription.time_zone)
+        .fetch_one(pool)
+        .await?;
+    let (date_one, date_two) = subscription.period.range(today);
+
+    let (status, error) = match send_report(pool, mailer, &subscription, date_one, date_two).await {
+        Ok(()) => (DeliveryStatus::Sent, None),
+        Err(e) => (DeliveryStatus::Failed, Some(format!("{:#}", e.1))),
+    };
+
+    let row: DeliveryRow = sqlx::query_as(
+        "INSERT
+        INTO report_deliveries (subscription_id, status, date_one, date_two, recipients, error)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING id, status, date_one, date_two, recipients, error, created_at",
+    )
+    .bind(id)
+    .bind(status.to_string())
+    .bind(date_one)
+    .bind(date_two)
+    .bind(&subscription.recipients)
+    .bind(&error)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn day_range() {
+        assert_eq!(
+            ReportPeriod::Day.range(date(2024, 3, 1)),
+            (date(2024, 2, 29), date(2024, 2, 29))
+        );
+        assert_eq!(
+            ReportPeriod::Day.range(date(2024, 1, 1)),
+            (date(2023, 12, 31), date(2023, 12, 31))
+        );
+    }
+
+    #[test]
+    fn week_range() {
+        // Прошлая неделя с понедельника по воскресенье, в том числе через новый год
+        let previous = (date(2023, 12, 25), date(2023, 12, 31));
+        assert_eq!(ReportPeriod::Week.range(date(2024, 1, 1)), previous);
+        assert_eq!(ReportPeriod::Week.range(date(2024, 1, 3)), previous);
+        // Воскресенье относится к текущей неделе
+        assert_eq!(ReportPeriod::Week.range(date(2024, 1, 7)), previous);
+        assert_eq!(
+            ReportPeriod::Week.range(date(2024, 1, 8)),
+            (date(2024, 1, 1), date(2024, 1, 7))
+        );
+    }
+
+    #[test]
+    fn month_range() {
+        assert_eq!(
+            ReportPeriod::Month.range(date(2024, 1, 1)),
+            (date(2023, 12, 1), date(2023, 12, 31))
+        );
+        assert_eq!(
+            ReportPeriod::Month.range(date(2024, 1, 31)),
+            (date(2023, 12, 1), date(2023, 12, 31))
+        );
+        // Високосный февраль
+        assert_eq!(
+            ReportPeriod::Month.range(date(2024, 3, 15)),
+            (date(2024, 2, 1), date(2024, 2, 29))
+        );
+        assert_eq!(
+            ReportPeriod::Month.range(date(2023, 3, 1)),
+            (date(2023, 2, 1), date(2023, 2, 28))
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Extension,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{check_access, services::Select, AppError, CurrentUser};
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Q {
+    pub warehouse_id: Option<i64>,
+    pub product_id: Option<i64>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct QAsOf {
+    pub date: chrono::NaiveDate,
+    pub warehouse_id: Option<i64>,
+    pub product_id: Option<i64>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Item {
+    pub cnt: i64,
+
+    warehouse: Select,
+    product: Select,
+    measure_unit: Select,
+}
+
+pub async fn get_stock(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Vec<Item>, AppError> {
+    // Бизнес логика получения текущих остатков
+
+    calc_stock(&pool, &current_user, q.warehouse_id, q.product_id, None).await
+}
+
+pub async fn get_stock_as_of(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<QAsOf>,
+) -> Result<Vec<Item>, AppError> {
+    // Бизнес логика получения остатков на конец указанной даты
+
+    calc_stock(
+        &pool,
+        &current_user,
+        q.warehouse_id,
+        q.product_id,
+        Some(q.date),
+    )
+    .await
+}
+
+async fn calc_stock(
+    pool: &PgPool,
+    current_user: &CurrentUser,
+    warehouse_id: Option<i64>,
+    product_id: Option<i64>,
+    date: Option<chrono::NaiveDate>,
+) -> Result<Vec<Item>, AppError> {
+    // Остаток - сумма движений журнала по складу и продукту
+
+    if !check_access(current_user.role) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    let rows: Vec<(i64, String, i64, String, i64, String, i64)> = sqlx::query_as(
+        "SELECT
+          w.id,
+          w.name,
+          p.id,
+          p.name,
+          mu.id,
+          mu.name,
+          SUM(sm.cnt)::bigint AS cnt
+        FROM
+          stock_movements AS sm
+          INNER JOIN warehouses AS w ON w.id = sm.warehouse_id
+          INNER JOIN products AS p ON p.id = sm.product_id
+          INNER JOIN measure_units AS mu ON mu.id = p.measure_unit_id
+        WHERE
+          CASE
+            WHEN $1::bigint IS NOT NULL AND $2 not in ('Admin', 'Developer') THEN
+                sm.organization_id = $1
+            ELSE TRUE
+          END
+          AND ($3::bigint IS NULL OR sm.warehouse_id = $3)
+          AND ($4::bigint IS NULL OR sm.product_id = $4)
+          AND ($5::date IS NULL OR sm.created_at::date <= $5)
+        GROUP BY
+          w.id,
+          w.name,
+          p.id,
+          p.name,
+          mu.id,
+          mu.name
+        HAVING
+          SUM(sm.cnt) <> 0
+        ORDER BY
+          w.name,
+          p.name;",
+    )
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .bind(warehouse_id)
+    .bind(product_id)
+    .bind(date)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| Item {
+            warehouse: Select {
+                id: row.0,
+                name: row.1,
+            },
+            product: Select {
+                id: row.2,
+                name: row.3,
+            },
+            measure_unit: Select {
+                id: row.4,
+                name: row.5,
+            },
+            cnt: row.6,
+        })
+        .collect())
+}
@@ -0,0 +1,427 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Transaction};
+
+use crate::{
+    check_access,
+    services::{stock::warehouse::default_warehouse, Items, Select},
+    AppError, CurrentUser,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    Receipt,
+    Adjustment,
+    Shipment,
+    Transfer,
+    WriteOff,
+}
+
+impl From<&str> for Kind {
+    fn from(value: &str) -> Self {
+        match value {
+            "Receipt" => Self::Receipt,
+            "Shipment" => Self::Shipment,
+            "Transfer" => Self::Transfer,
+            "WriteOff" => Self::WriteOff,
+            _ => Self::Adjustment,
+        }
+    }
+}
+
+impl From<String> for Kind {
+    fn from(value: String) -> Self {
+        Self::from(value.as_str())
+    }
+}
+
+impl std::fmt::Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Receipt => "Receipt",
+            Self::Adjustment => "Adjustment",
+            Self::Shipment => "Shipment",
+            Self::Transfer => "Transfer",
+            Self::WriteOff => "WriteOff",
+        })
+    }
+}
+
+pub async fn post_production(
+    tx: &mut Transaction<'_, Postgres>,
+    kind: Kind,
+    user_id: i64,
+    produced_good_id: i64,
+    produced_good_adjustment_id: Option<i64>,
+    product_id: i64,
+    cnt: i64,
+) -> Result<(), anyhow::Error> {
+    // Проводка выпуска производства на склад по умолчанию организации записи
+
+    if cnt == 0 {
+        return Ok(());
+    }
+
+    let organization_id: i64 =
+        sqlx::query_scalar("SELECT organization_id FROM produced_goods WHERE id = $1")
+            .bind(produced_good_id)
+            .fetch_one(&mut **tx)
+            .await?;
+
+    let warehouse_id = default_warehouse(tx, organization_id).await?;
+
+    let _ = sqlx::query(
+        "INSERT INTO
+          stock_movements (
+            organization_id,
+            warehouse_id,
+            product_id,
+            user_id,
+            kind,
+            cnt,
+            produced_good_id,
+            produced_good_adjustment_id
+          )
+        VALUES
+          ($1, $2, $3, $4, $5, $6, $7, $8)",
+    )
+    .bind(organization_id)
+    .bind(warehouse_id)
+    .bind(product_id)
+    .bind(user_id)
+    .bind(kind.to_string())
+    .bind(cnt)
+    .bind(produced_good_id)
+    .bind(produced_good_adjustment_id)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RequestBody {
+    warehouse_id: i64,
+    product_id: i64,
+    kind: Kind,
+    cnt: i64,
+    to_warehouse_id: Option<i64>,
+    comment: Option<String>,
+}
+
+async fn get_balance(
+    tx: &mut Transaction<'_, Postgres>,
+    warehouse_id: i64,
+    product_id: i64,
+) -> Result<i64, anyhow::Error> {
+    // Текущий остаток продукта на складе
+
+    let cnt: i64 = sqlx::query_scalar(
+        "SELECT COALESCE(SUM(cnt), 0)::bigint
+        FROM stock_movements
+        WHERE warehouse_id = $1 AND product_id = $2",
+    )
+    .bind(warehouse_id)
+    .bind(product_id)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    Ok(cnt)
+}
+
+pub async fn create_movement(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(body): Json<RequestBody>,
+) -> Result<i64, AppError> {
+    // Бизнес логика ручного движения по складу.
+    // Приход создается только производством, корректировка может быть любого знака,
+    // отгрузка, перемещение и списание уменьшают остаток склада-источника.
+
+    if !check_access(current_user.role) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    if body.kind == Kind::Receipt {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Приход на склад создается только из производства!"),
+        ));
+    }
+
+    if body.cnt == 0 || (body.kind != Kind::Adjustment && body.cnt < 0) {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Некорректное кол-во для движения по складу!"),
+        ));
+    }
+
+    let mut tx = pool.begin().await?;
+
+    // Блокируем склад, чтобы проверка остатка и запись шли последовательно
+    let organization_id: Option<i64> = sqlx::query_scalar(
+        "SELECT w.organization_id
+        FROM warehouses AS w
+        INNER JOIN products AS p ON p.organization_id = w.organization_id
+        WHERE w.id = $1 AND p.id = $2
+        AND CASE
+            WHEN $3::bigint IS NOT NULL THEN w.organization_id = $3
+            ELSE TRUE
+        END
+        FOR UPDATE OF w",
+    )
+    .bind(body.warehouse_id)
+    .bind(body.product_id)
+    .bind(current_user.organization_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some(organization_id) = organization_id else {
+        return Err(AppError(
+            StatusCode::NOT_FOUND,
+            anyhow::anyhow!("Склад или продукт не найден в организации!"),
+        ));
+    };
+
+    let to_warehouse_id = match (body.kind, body.to_warehouse_id) {
+        (Kind::Transfer, Some(to_warehouse_id)) if to_warehouse_id != body.warehouse_id => {
+            let exists: bool = sqlx::query_scalar(
+                "SELECT EXISTS (
+                    SELECT 1 FROM warehouses WHERE id = $1 AND organization_id = $2
+                )",
+            )
+            .bind(to_warehouse_id)
+            .bind(organization_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+            if !exists {
+                return Err(AppError(
+                    StatusCode::NOT_FOUND,
+                    anyhow::anyhow!("Склад-получатель не найден в организации!"),
+                ));
+            }
+
+            Some(to_warehouse_id)
+        }
+        (Kind::Transfer, _) => {
+            return Err(AppError(
+                StatusCode::BAD_REQUEST,
+                anyhow::anyhow!("Для перемещения укажите другой склад-получатель!"),
+            ));
+        }
+        _ => None,
+    };
+
+    // Расход: все движения, кроме корректировки, списывают остаток
+    let cnt = if body.kind == Kind::Adjustment {
+        body.cnt
+    } else {
+        -body.cnt
+    };
+
+    if cnt < 0 && get_balance(&mut tx, body.warehouse_id, body.product_id).await? + cnt < 0 {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Недостаточно остатка на складе!"),
+        ));
+    }
+
+    let row: (i64,) = sqlx::query_as(
+        "INSERT INTO
+          stock_movements (
+            organization_id,
+            warehouse_id,
+            product_id,
+            user_id,
+            kind,
+            cnt,
+            counterpart_warehouse_id,
+            comment
+          )
+        VALUES
+          ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING
+          id",
+    )
+    .bind(organization_id)
+    .bind(body.warehouse_id)
+    .bind(body.product_id)
+    .bind(current_user.id)
+    .bind(body.kind.to_string())
+    .bind(cnt)
+    .bind(to_warehouse_id)
+    .bind(&body.comment)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    // Перемещение - парная запись прихода на склад-получатель
+    if let Some(to_warehouse_id) = to_warehouse_id {
+        let _ = sqlx::query(
+            "INSERT INTO
+              stock_movements (
+                organization_id,
+                warehouse_id,
+                product_id,
+                user_id,
+                kind,
+                cnt,
+                counterpart_warehouse_id,
+                comment
+              )
+            VALUES
+              ($1, $2, $3, $4, $5, $6, $7, $8)",
+        )
+        .bind(organization_id)
+        .bind(to_warehouse_id)
+        .bind(body.product_id)
+        .bind(current_user.id)
+        .bind(Kind::Transfer.to_string())
+        .bind(body.cnt)
+        .bind(body.warehouse_id)
+        .bind(&body.comment)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(row.0)
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Q {
+    #[serde(default = "page")]
+    pub page: i64,
+    #[serde(default = "per_page")]
+    pub per_page: i64,
+    pub warehouse_id: Option<i64>,
+    pub product_id: Option<i64>,
+}
+
+fn per_page() -> i64 {
+    15
+}
+
+fn page() -> i64 {
+    1
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Item {
+    pub id: i64,
+    pub kind: Kind,
+    pub cnt: i64,
+    pub produced_good_id: Option<i64>,
+    pub produced_good_adjustment_id: Option<i64>,
+    pub counterpart_warehouse_id: Option<i64>,
+    pub comment: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+
+    warehouse: Select,
+    product: Select,
+    user: Select,
+}
+
+pub async fn get_movements(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Items<Item>, AppError> {
+    // Бизнес логика получения журнала движений
+
+    if !check_access(current_user.role) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    let rows = sqlx::query!(
+        "SELECT
+            sm.id,
+            sm.kind,
+            sm.cnt,
+            sm.produced_good_id,
+            sm.produced_good_adjustment_id,
+            sm.counterpart_warehouse_id,
+            sm.comment,
+            sm.created_at,
+            JSONB_BUILD_OBJECT(
+                'id', w.id,
+                'name', w.name
+            ) AS warehouse,
+            JSONB_BUILD_OBJECT(
+                'id', p.id,
+                'name', p.name
+            ) AS product,
+            JSONB_BUILD_OBJECT(
+                'id', u.id,
+                'name', u.fio
+            ) AS author
+        FROM stock_movements AS sm
+        INNER JOIN warehouses AS w ON w.id = sm.warehouse_id
+        INNER JOIN products AS p ON p.id = sm.product_id
+        INNER JOIN users AS u ON u.id = sm.user_id
+        WHERE
+            CASE
+                WHEN $1::bigint IS NOT NULL AND $2 not in ('Admin', 'Developer') THEN
+                    sm.organization_id = $1
+                ELSE TRUE
+            END
+            AND ($3::bigint IS NULL OR sm.warehouse_id = $3)
+            AND ($4::bigint IS NULL OR sm.product_id = $4)
+        ORDER BY sm.id DESC
+        OFFSET $5 LIMIT $6;",
+        current_user.organization_id,
+        current_user.role.to_string(),
+        q.warehouse_id,
+        q.product_id,
+        (q.page - 1) * q.per_page,
+        q.per_page,
+    )
+    .map(|row| Item {
+        id: row.id,
+        kind: row.kind.into(),
+        cnt: row.cnt,
+        produced_good_id: row.produced_good_id,
+        produced_good_adjustment_id: row.produced_good_adjustment_id,
+        counterpart_warehouse_id: row.counterpart_warehouse_id,
+        comment: row.comment,
+        created_at: row.created_at,
+        warehouse: row.warehouse.into(),
+        product: row.product.into(),
+        user: row.author.into(),
+    })
+    .fetch_all(&pool)
+    .await?;
+
+    // Подсчет данных для пагинации
+    let cnt: i64 = sqlx::query_scalar(
+        "SELECT COUNT(id) FROM stock_movements AS sm WHERE CASE
+        WHEN $1::bigint IS NOT NULL AND $2 not in ('Admin', 'Developer') THEN
+            sm.organization_id = $1
+        ELSE TRUE
+    END
+    AND ($3::bigint IS NULL OR sm.warehouse_id = $3)
+    AND ($4::bigint IS NULL OR sm.product_id = $4)",
+    )
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .bind(q.warehouse_id)
+    .bind(q.product_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap_or(0);
+
+    let cnt = (cnt as f64 / q.per_page as f64).ceil() as i64;
+
+    Ok(Items { items: rows, cnt })
+}
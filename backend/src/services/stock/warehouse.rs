@@ -0,0 +1,357 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Transaction};
+
+use crate::{
+    check_access, check_is_admin,
+    services::{Items, Select},
+    AppError, CurrentUser,
+};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RequestBody {
+    organization_id: Option<i64>,
+    name: String,
+    #[serde(default)]
+    is_default: bool,
+}
+
+pub async fn default_warehouse(
+    tx: &mut Transaction<'_, Postgres>,
+    organization_id: i64,
+) -> Result<i64, anyhow::Error> {
+    // Склад по умолчанию для организации. Создается при первом обращении.
+
+    let row: (i64,) = sqlx::query_as(
+        "WITH w AS (
+            SELECT id FROM warehouses WHERE organization_id = $1 AND is_default
+        ),
+        ins AS (
+            INSERT INTO warehouses (organization_id, name, is_default)
+            SELECT $1, 'Основной склад', TRUE
+            WHERE NOT EXISTS (SELECT 1 FROM w)
+            RETURNING id
+        )
+        SELECT id FROM w
+        UNION ALL
+        SELECT id FROM ins",
+    )
+    .bind(organization_id)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    Ok(row.0)
+}
+
+pub async fn create_warehouse(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(body): Json<RequestBody>,
+) -> Result<i64, AppError> {
+    // Бизнес логика создания склада
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        let organization_id = if check_is_admin(current_user.role) {
+            body.organization_id
+        } else {
+            current_user.organization_id
+        };
+
+        match organization_id {
+            Some(organization_id) => {
+                let mut tx = pool.begin().await?;
+
+                if body.is_default {
+                    reset_default(&mut tx, organization_id).await?;
+                }
+
+                let row: (i64,) = sqlx::query_as(
+                    "INSERT
+                    INTO warehouses (name, is_default, organization_id) VALUES
+                    ($1, $2, $3) RETURNING id",
+                )
+                .bind(body.name)
+                .bind(body.is_default)
+                .bind(organization_id)
+                .fetch_one(&mut *tx)
+                .await?;
+
+                tx.commit().await?;
+
+                Ok(row.0)
+            }
+            _ => Err(AppError(
+                StatusCode::BAD_REQUEST,
+                anyhow::anyhow!("Невозможно создать запись без организации!"),
+            )),
+        }
+    }
+}
+
+pub async fn edit_warehouse(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+    Json(body): Json<RequestBody>,
+) -> Result<i64, AppError> {
+    // Бизнес логика редактирования склада
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else {
+        let organization_id: Option<i64> = sqlx::query_scalar(
+            "SELECT organization_id
+            FROM warehouses
+            WHERE id = $1
+            AND CASE
+                WHEN $2::bigint IS NOT NULL THEN organization_id = $2
+                ELSE TRUE
+            END",
+        )
+        .bind(id)
+        .bind(current_user.organization_id)
+        .fetch_optional(&pool)
+        .await?;
+
+        match organization_id {
+            Some(organization_id) => {
+                let mut tx = pool.begin().await?;
+
+                if body.is_default {
+                    reset_default(&mut tx, organization_id).await?;
+                }
+
+                let _ = sqlx::query(
+                    "UPDATE warehouses
+                    SET name=$1, is_default=$2, updated_at=NOW()
+                    WHERE id = $3",
+                )
+                .bind(body.name)
+                .bind(body.is_default)
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+
+                tx.commit().await?;
+
+                Ok(id)
+            }
+            _ => Err(AppError(
+                StatusCode::NOT_FOUND,
+                anyhow::anyhow!("Такой записи не существует"),
+            )),
+        }
+    }
+}
+
+async fn reset_default(
+    tx: &mut Transaction<'_, Postgres>,
+    organization_id: i64,
+) -> Result<(), anyhow::Error> {
+    // Снятие признака склада по умолчанию перед назначением нового
+
+    let _ = sqlx::query(
+        "UPDATE warehouses
+        SET is_default = FALSE, updated_at = NOW()
+        WHERE organization_id = $1 AND is_default",
+    )
+    .bind(organization_id)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Q {
+    #[serde(default = "page")]
+    pub page: i64,
+    #[serde(default = "per_page")]
+    pub per_page: i64,
+}
+
+fn per_page() -> i64 {
+    15
+}
+
+fn page() -> i64 {
+    1
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Item {
+    pub id: i64,
+    pub name: String,
+    pub is_default: bool,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+
+    organization: Select,
+}
+
+pub async fn get_warehouses(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Items<Item>, anyhow::Error> {
+    // Бизнес логика получения списка складов
+
+    let rows = sqlx::query!(
+        "SELECT
+            w.id,
+            w.name,
+            w.is_default,
+            w.created_at,
+            JSONB_BUILD_OBJECT(
+                'id', o.id,
+                'name', o.name
+            ) AS organization
+        FROM warehouses AS w
+        LEFT JOIN organizations AS o ON o.id = w.organization_id
+        WHERE
+            CASE
+                WHEN $1::bigint IS NOT NULL AND $2 not in ('Admin', 'Developer') THEN
+                    w.organization_id = $1
+                ELSE TRUE
+            END
+        ORDER BY w.id DESC
+        OFFSET $3 LIMIT $4;",
+        current_user.organization_id,
+        current_user.role.to_string(),
+        (q.page - 1) * q.per_page,
+        q.per_page,
+    )
+    .map(|row| Item {
+        id: row.id,
+        name: row.name,
+        is_default: row.is_default,
+        created_at: row.created_at,
+        organization: row.organization.into(),
+    })
+    .fetch_all(&pool)
+    .await?;
+
+    // Подсчет данных для пагинации
+    let cnt: i64 = sqlx::query_scalar(
+        "SELECT COUNT(id) FROM warehouses AS w WHERE CASE
+        WHEN $1::bigint IS NOT NULL AND $2 not in ('Admin', 'Developer') THEN
+            w.organization_id = $1
+        ELSE TRUE
+    END",
+    )
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .fetch_one(&pool)
+    .await
+    .unwrap_or(0);
+
+    let cnt = (cnt as f64 / q.per_page as f64).ceil() as i64;
+
+    Ok(Items { items: rows, cnt })
+}
+
+pub async fn detail_warehouse(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<Item, AppError> {
+    // Бизнес логика получения склада
+
+    let row = sqlx::query!(
+        "SELECT
+            w.id,
+            w.name,
+            w.is_default,
+            w.created_at,
+            JSONB_BUILD_OBJECT(
+                'id', o.id,
+                'name', o.name
+            ) AS organization
+        FROM warehouses AS w
+        LEFT JOIN organizations AS o ON o.id = w.organization_id
+        WHERE w.id = $1
+        AND CASE
+            WHEN $2::bigint IS NOT NULL AND $3 not in ('Admin', 'Developer') THEN
+                w.organization_id = $2
+            ELSE TRUE
+        END;",
+        id,
+        current_user.organization_id,
+        current_user.role.to_string(),
+    )
+    .fetch_optional(&pool)
+    .await?;
+
+    match row {
+        Some(row) => Ok(Item {
+            id: row.id,
+            name: row.name,
+            is_default: row.is_default,
+            created_at: row.created_at,
+            organization: row.organization.into(),
+        }),
+        None => Err(AppError(
+            StatusCode::NOT_FOUND,
+            anyhow::anyhow!("Такой записи не существует"),
+        )),
+    }
+}
+
+pub async fn delete_warehouse(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<(), AppError> {
+    // Бизнес логика удаления склада. Склад с движениями удалить нельзя.
+
+    if !check_access(current_user.role) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    let has_movements: bool = sqlx::query_scalar(
+        "SELECT EXISTS (
+            SELECT 1 FROM stock_movements
+            WHERE warehouse_id = $1 OR counterpart_warehouse_id = $1
+        )",
+    )
+    .bind(id)
+    .fetch_one(&pool)
+    .await?;
+
+    if has_movements {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Нельзя удалить склад, по которому есть движения!"),
+        ));
+    }
+
+    let _ = sqlx::query(
+        "DELETE
+    FROM warehouses
+    WHERE id = $1
+    AND CASE
+        WHEN $2::bigint IS NOT NULL THEN organization_id = $2
+        ELSE TRUE
+    END;",
+    )
+    .bind(id)
+    .bind(current_user.organization_id)
+    .execute(&pool)
+    .await?;
+
+    Ok(())
+}
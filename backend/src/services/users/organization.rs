@@ -1,11 +1,23 @@
-use crate::{check_is_admin, services::Items, AppError, CurrentUser};
+use crate::{
+    check_access, check_is_admin,
+    services::{
+        attachments::{
+            attachment::{detect_content_type, extension, MAX_FILE_SIZE},
+            storage::Storage,
+        },
+        Items,
+    },
+    AppError, CurrentUser, Role,
+};
 use axum::{
-    extract::{Path, Query, State},
+    body::Bytes,
+    extract::{Multipart, Path, Query, State},
     http::StatusCode,
     Extension, Json,
 };
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
+use uuid::Uuid;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct RequestBody {
@@ -66,6 +78,140 @@ pub async fn edit_organization(
     }
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RequestBodySettings {
+    adjustment_threshold: Option<i64>,
+}
+
+fn check_own_organization(current_user: &CurrentUser, id: i64) -> bool {
+    // Администратор управляет любой организацией, Директор - только своей
+
+    check_is_admin(current_user.role)
+        || (current_user.role == Role::Director && current_user.organization_id == Some(id))
+}
+
+pub async fn edit_organization_settings(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+    Json(body): Json<RequestBodySettings>,
+) -> Result<i64, AppError> {
+    // Бизнес логика изменения настроек организации
+
+    if !check_access(current_user.role) || !check_own_organization(&current_user, id) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    if body.adjustment_threshold.is_some_and(|t| t < 0) {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Порог корректировки не может быть отрицательным!"),
+        ));
+    }
+
+    let _ = sqlx::query(
+        "UPDATE organizations
+        SET adjustment_threshold=$1, updated_at=NOW()
+        WHERE id = $2",
+    )
+    .bind(body.adjustment_threshold)
+    .bind(id)
+    .execute(&pool)
+    .await?;
+
+    Ok(id)
+}
+
+pub async fn upload_organization_logo(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Extension(storage): Extension<Storage>,
+    Path(id): Path<i64>,
+    mut multipart: Multipart,
+) -> Result<i64, AppError> {
+    // Бизнес логика загрузки логотипа организации для печатных отчетов.
+    // Ожидается поле формы "file" с изображением, прежний логотип удаляется.
+
+    if !check_access(current_user.role) || !check_own_organization(&current_user, id) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    let mut data: Option<Bytes> = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError(e.status(), e.into()))?
+    {
+        if field.name() == Some("file") {
+            data = Some(
+                field
+                    .bytes()
+                    .await
+                    .map_err(|e| AppError(e.status(), e.into()))?,
+            );
+        }
+    }
+
+    let Some(data) = data else {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Файл не передан!"),
+        ));
+    };
+
+    if data.len() > MAX_FILE_SIZE {
+        return Err(AppError(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            anyhow::anyhow!("Размер файла превышает {} МБ!", MAX_FILE_SIZE / 1024 / 1024),
+        ));
+    }
+
+    let Some(content_type) = detect_content_type(&data).filter(|c| c.starts_with("image/")) else {
+        return Err(AppError(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            anyhow::anyhow!("Недопустимый тип файла!"),
+        ));
+    };
+
+    let old_key: Option<Option<String>> =
+        sqlx::query_scalar("SELECT logo_key FROM organizations WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&pool)
+            .await?;
+    let Some(old_key) = old_key else {
+        return Err(AppError(
+            StatusCode::NOT_FOUND,
+            anyhow::anyhow!("Такой записи не существует"),
+        ));
+    };
+
+    let logo_key = format!("{}/logo_{}.{}", id, Uuid::new_v4(), extension(content_type));
+    storage.put(&logo_key, data).await?;
+
+    let _ = sqlx::query(
+        "UPDATE organizations
+        SET logo_key=$1, updated_at=NOW()
+        WHERE id = $2",
+    )
+    .bind(&logo_key)
+    .bind(id)
+    .execute(&pool)
+    .await?;
+
+    if let Some(old_key) = old_key {
+        // Старый файл не критичен, ошибку удаления не пробрасываем
+        let _ = storage.delete(&old_key).await;
+    }
+
+    Ok(id)
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct Q {
     #[serde(default = "page")]
@@ -86,6 +232,7 @@ fn page() -> i64 {
 pub struct Item {
     pub id: i64,
     pub name: String,
+    pub adjustment_threshold: Option<i64>,
 
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
@@ -108,6 +255,7 @@ pub async fn get_organizations(
             "SELECT
             id,
             name,
+            adjustment_threshold,
             created_at
         FROM organizations
         ORDER BY id DESC
@@ -137,7 +285,7 @@ pub async fn detail_organization(
 ) -> Result<Item, AppError> {
     // Бизнес логика редактирования пользователя
 
-    if !check_is_admin(current_user.role) {
+    if !check_own_organization(&current_user, id) {
         Err(AppError(
             StatusCode::FORBIDDEN,
             anyhow::anyhow!("У вас нет доступа для данного действия!"),
@@ -148,6 +296,7 @@ pub async fn detail_organization(
             "SELECT
             id,
             name,
+            adjustment_threshold,
             created_at
         FROM organizations
         WHERE id = $1;",
@@ -0,0 +1,643 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Transaction};
+
+use crate::{
+    check_access, check_is_admin,
+    services::{Items, Select},
+    AppError, CurrentUser,
+};
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub enum Status {
+    #[default]
+    Draft,
+    Released,
+    InProgress,
+    Completed,
+    Closed,
+}
+
+impl From<&str> for Status {
+    fn from(value: &str) -> Self {
+        // Получение статуса заказа из ссылки на строку
+        match value {
+            "Draft" => Status::Draft,
+            "Released" => Status::Released,
+            "InProgress" => Status::InProgress,
+            "Completed" => Status::Completed,
+            "Closed" => Status::Closed,
+            _ => Status::Draft,
+        }
+    }
+}
+
+impl From<String> for Status {
+    fn from(value: String) -> Self {
+        // Получение статуса заказа из строки
+        Status::from(value.as_str())
+    }
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Status::Draft => "Draft",
+            Status::Released => "Released",
+            Status::InProgress => "InProgress",
+            Status::Completed => "Completed",
+            Status::Closed => "Closed",
+        })
+    }
+}
+
+impl Status {
+    pub fn is_open(&self) -> bool {
+        // Заказ открыт для привязки производства
+
+        matches!(self, Status::Released | Status::InProgress)
+    }
+
+    pub fn can_move_to(&self, to: Status) -> bool {
+        // Допустимые переходы жизненного цикла заказа
+
+        matches!(
+            (self, to),
+            (Status::Draft, Status::Released)
+                | (Status::Released, Status::Draft)
+                | (Status::Released, Status::InProgress)
+                | (Status::InProgress, Status::Completed)
+                | (Status::Completed, Status::InProgress)
+                | (Status::Completed, Status::Closed)
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RequestBody {
+    product_id: i64,
+    cnt: i64,
+    due_date: chrono::NaiveDate,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RequestBodyStatus {
+    status: Status,
+}
+
+pub async fn create_work_order(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Json(body): Json<RequestBody>,
+) -> Result<i64, AppError> {
+    // Бизнес логика создания заказа на производство
+
+    if !check_access(current_user.role) {
+        Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ))
+    } else if body.cnt <= 0 {
+        Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Кол-во в заказе должно быть больше нуля!"),
+        ))
+    } else {
+        let organization_id = product_organization(&pool, &current_user, body.product_id).await?;
+
+        let row: (i64,) = sqlx::query_as(
+            "INSERT INTO
+              work_orders (product_id, user_id, cnt, due_date, status, organization_id)
+            VALUES
+              ($1, $2, $3, $4, $5, $6)
+            RETURNING
+              id",
+        )
+        .bind(body.product_id)
+        .bind(current_user.id)
+        .bind(body.cnt)
+        .bind(body.due_date)
+        .bind(Status::Draft.to_string())
+        .bind(organization_id)
+        .fetch_one(&pool)
+        .await?;
+
+        Ok(row.0)
+    }
+}
+
+pub async fn edit_work_order(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+    Json(body): Json<RequestBody>,
+) -> Result<i64, AppError> {
+    // Бизнес логика редактирования заказа. Менять можно только черновик.
+
+    if !check_access(current_user.role) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    if body.cnt <= 0 {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Кол-во в заказе должно быть больше нуля!"),
+        ));
+    }
+
+    let status = get_status(&pool, &current_user, id).await?;
+    if status != Status::Draft {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Редактировать можно только заказ в статусе черновика!"),
+        ));
+    }
+
+    let organization_id = product_organization(&pool, &current_user, body.product_id).await?;
+
+    let _ = sqlx::query(
+        "UPDATE
+          work_orders
+        SET
+          product_id = $1,
+          cnt = $2,
+          due_date = $3,
+          organization_id = $4,
+          updated_at = NOW()
+        WHERE
+          id = $5",
+    )
+    .bind(body.product_id)
+    .bind(body.cnt)
+    .bind(body.due_date)
+    .bind(organization_id)
+    .bind(id)
+    .execute(&pool)
+    .await?;
+
+    Ok(id)
+}
+
+pub async fn change_status_work_order(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+    Json(body): Json<RequestBodyStatus>,
+) -> Result<i64, AppError> {
+    // Бизнес логика перевода заказа по жизненному циклу
+
+    if !check_access(current_user.role) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    let status = get_status(&pool, &current_user, id).await?;
+    if !status.can_move_to(body.status) {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!(
+                "Недопустимый переход статуса: {} -> {}",
+                status.to_string(),
+                body.status.to_string()
+            ),
+        ));
+    }
+
+    let _ = sqlx::query(
+        "UPDATE
+          work_orders
+        SET
+          status = $1,
+          updated_at = NOW()
+        WHERE
+          id = $2",
+    )
+    .bind(body.status.to_string())
+    .bind(id)
+    .execute(&pool)
+    .await?;
+
+    Ok(id)
+}
+
+pub async fn delete_work_order(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<(), AppError> {
+    // Бизнес логика удаления заказа. Удалить можно только черновик.
+
+    if !check_access(current_user.role) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("У вас нет доступа для данного действия!"),
+        ));
+    }
+
+    let status = get_status(&pool, &current_user, id).await?;
+    if status != Status::Draft {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Удалить можно только заказ в статусе черновика!"),
+        ));
+    }
+
+    let _ = sqlx::query(
+        "DELETE
+    FROM work_orders
+    WHERE id = $1
+    AND CASE
+        WHEN $3 not in ('Admin', 'Developer') THEN organization_id = $2::bigint
+        ELSE TRUE
+    END;",
+    )
+    .bind(id)
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .execute(&pool)
+    .await?;
+
+    Ok(())
+}
+
+async fn product_organization(
+    pool: &PgPool,
+    current_user: &CurrentUser,
+    product_id: i64,
+) -> Result<i64, AppError> {
+    // Заказ относится к организации продукта, директор заказывает только свои продукты
+
+    let organization_id: Option<i64> =
+        sqlx::query_scalar("SELECT organization_id FROM products WHERE id = $1")
+            .bind(product_id)
+            .fetch_optional(pool)
+            .await?;
+
+    let Some(organization_id) = organization_id else {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Такого продукта не существует!"),
+        ));
+    };
+
+    if !check_is_admin(current_user.role) && current_user.organization_id != Some(organization_id) {
+        return Err(AppError(
+            StatusCode::FORBIDDEN,
+            anyhow::anyhow!("Продукт принадлежит другой организации!"),
+        ));
+    }
+
+    Ok(organization_id)
+}
+
+async fn get_status(
+    pool: &PgPool,
+    current_user: &CurrentUser,
+    id: i64,
+) -> Result<Status, AppError> {
+    // Текущий статус заказа с учетом организации пользователя
+
+    let row: Option<(String,)> = sqlx::query_as(
+        "SELECT status
+        FROM work_orders
+        WHERE id = $1
+        AND CASE
+            WHEN $3 not in ('Admin', 'Developer') THEN organization_id = $2::bigint
+            ELSE TRUE
+        END",
+    )
+    .bind(id)
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .fetch_optional(pool)
+    .await?;
+
+    match row {
+        Some(row) => Ok(row.0.into()),
+        None => Err(AppError(
+            StatusCode::NOT_FOUND,
+            anyhow::anyhow!("Такой записи не существует"),
+        )),
+    }
+}
+
+pub async fn check_open_work_order(
+    tx: &mut Transaction<'_, Postgres>,
+    current_user: &CurrentUser,
+    id: i64,
+    product_id: i64,
+) -> Result<(), AppError> {
+    // Проверка, что к заказу можно привязать производство продукта
+
+    let row: Option<(String, i64)> = sqlx::query_as(
+        "SELECT status, product_id
+        FROM work_orders
+        WHERE id = $1
+        AND CASE
+            WHEN $3 not in ('Admin', 'Developer') THEN organization_id = $2::bigint
+            ELSE TRUE
+        END
+        FOR UPDATE",
+    )
+    .bind(id)
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    match row {
+        Some((status, wo_product_id)) => {
+            if !Status::from(status).is_open() {
+                Err(AppError(
+                    StatusCode::BAD_REQUEST,
+                    anyhow::anyhow!("Заказ не открыт для производства!"),
+                ))
+            } else if wo_product_id != product_id {
+                Err(AppError(
+                    StatusCode::BAD_REQUEST,
+                    anyhow::anyhow!("Продукт не совпадает с продуктом заказа!"),
+                ))
+            } else {
+                Ok(())
+            }
+        }
+        None => Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow::anyhow!("Такого заказа не существует!"),
+        )),
+    }
+}
+
+pub async fn refresh_work_order(
+    tx: &mut Transaction<'_, Postgres>,
+    id: i64,
+) -> Result<(), anyhow::Error> {
+    // Пересчет прогресса заказа: первая выработка переводит заказ в работу,
+    // выполнение плана целиком - в завершенные. После удаления, отклонения или
+    // корректировки записей заказ возвращается назад. Закрытый заказ не меняется.
+
+    let _ = sqlx::query(
+        "UPDATE
+          work_orders AS wo
+        SET
+          status = CASE
+            WHEN wo.status IN ('Released', 'InProgress') AND f.done >= wo.cnt THEN 'Completed'
+            WHEN wo.status = 'Completed' AND f.done < wo.cnt THEN 'InProgress'
+            WHEN wo.status = 'Released' AND f.done > 0 THEN 'InProgress'
+            WHEN wo.status = 'InProgress' AND f.done <= 0 THEN 'Released'
+            ELSE wo.status
+          END,
+          updated_at = NOW()
+        FROM
+          (
+            SELECT
+              COALESCE(SUM(pg.cnt + COALESCE(pa.adjustment_cnt::bigint, 0)), 0)::bigint AS done
+            FROM
+              produced_goods AS pg
+              LEFT JOIN (
+                SELECT
+                  produced_good_id,
+                  SUM(cnt::bigint) AS adjustment_cnt
+                FROM
+                  produced_good_adjustments
+                WHERE
+                  status = 'Approved'
+                GROUP BY
+                  produced_good_id
+              ) pa ON pa.produced_good_id = pg.id
+            WHERE
+              pg.work_order_id = $1
+              AND pg.status <> 'Rejected'
+          ) AS f
+        WHERE
+          wo.id = $1",
+    )
+    .bind(id)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Q {
+    #[serde(default = "page")]
+    pub page: i64,
+    #[serde(default = "per_page")]
+    pub per_page: i64,
+
+    pub status: Option<Status>,
+}
+
+fn per_page() -> i64 {
+    15
+}
+
+fn page() -> i64 {
+    1
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Item {
+    pub id: i64,
+    pub cnt: i64,
+    pub done: i64,
+    pub due_date: chrono::NaiveDate,
+    pub status: Status,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+
+    product: Select,
+    measure_unit: Select,
+    organization: Select,
+}
+
+pub async fn get_work_orders(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Query(q): Query<Q>,
+) -> Result<Items<Item>, anyhow::Error> {
+    // Бизнес логика получения списка заказов
+
+    let status = q.status.map(|s| s.to_string());
+
+    let rows = sqlx::query!(
+        "SELECT
+            wo.id,
+            wo.cnt,
+            wo.due_date,
+            wo.status,
+            wo.created_at,
+            (
+              SELECT
+                COALESCE(SUM(pg.cnt + COALESCE(pa.adjustment_cnt::bigint, 0)), 0)
+              FROM
+                produced_goods AS pg
+                LEFT JOIN (
+                  SELECT
+                    produced_good_id,
+                    SUM(cnt::bigint) AS adjustment_cnt
+                  FROM
+                    produced_good_adjustments
+                  WHERE
+                    status = 'Approved'
+                  GROUP BY
+                    produced_good_id
+                ) pa ON pa.produced_good_id = pg.id
+              WHERE
+                pg.work_order_id = wo.id
+                AND pg.status <> 'Rejected'
+            )::bigint AS done,
+            JSONB_BUILD_OBJECT(
+                'id', p.id,
+                'name', p.name
+            ) AS product,
+            JSONB_BUILD_OBJECT(
+                'id', mu.id,
+                'name', mu.name
+            ) AS measure_unit,
+            JSONB_BUILD_OBJECT(
+                'id', o.id,
+                'name', o.name
+            ) AS organization
+        FROM work_orders AS wo
+        INNER JOIN products AS p ON p.id = wo.product_id
+        INNER JOIN measure_units AS mu ON mu.id = p.measure_unit_id
+        LEFT JOIN organizations AS o ON o.id = wo.organization_id
+        WHERE
+            CASE
+                WHEN $2 not in ('Admin', 'Developer') THEN wo.organization_id = $1::bigint
+                ELSE TRUE
+            END
+            AND ($3::text IS NULL OR wo.status = $3)
+        ORDER BY wo.due_date, wo.id DESC
+        OFFSET $4 LIMIT $5;",
+        current_user.organization_id,
+        current_user.role.to_string(),
+        status,
+        (q.page - 1) * q.per_page,
+        q.per_page,
+    )
+    .map(|row| Item {
+        id: row.id,
+        cnt: row.cnt,
+        done: row.done.map_or(0, |d| d),
+        due_date: row.due_date,
+        status: row.status.into(),
+        created_at: row.created_at,
+        product: row.product.into(),
+        measure_unit: row.measure_unit.into(),
+        organization: row.organization.into(),
+    })
+    .fetch_all(&pool)
+    .await?;
+
+    // Подсчет данных для пагинации
+    let cnt: i64 = sqlx::query_scalar(
+        "SELECT COUNT(id) FROM work_orders AS wo WHERE CASE
+        WHEN $2 not in ('Admin', 'Developer') THEN wo.organization_id = $1::bigint
+        ELSE TRUE
+    END
+    AND ($3::text IS NULL OR wo.status = $3)",
+    )
+    .bind(current_user.organization_id)
+    .bind(current_user.role.to_string())
+    .bind(status)
+    .fetch_one(&pool)
+    .await
+    .unwrap_or(0);
+
+    let cnt = (cnt as f64 / q.per_page as f64).ceil() as i64;
+
+    Ok(Items { items: rows, cnt })
+}
+
+pub async fn detail_work_order(
+    State(pool): State<PgPool>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<Item, AppError> {
+    // Бизнес логика получения заказа
+
+    let row = sqlx::query!(
+        "SELECT
+            wo.id,
+            wo.cnt,
+            wo.due_date,
+            wo.status,
+            wo.created_at,
+            (
+              SELECT
+                COALESCE(SUM(pg.cnt + COALESCE(pa.adjustment_cnt::bigint, 0)), 0)
+              FROM
+                produced_goods AS pg
+                LEFT JOIN (
+                  SELECT
+                    produced_good_id,
+                    SUM(cnt::bigint) AS adjustment_cnt
+                  FROM
+                    produced_good_adjustments
+                  WHERE
+                    status = 'Approved'
+                  GROUP BY
+                    produced_good_id
+                ) pa ON pa.produced_good_id = pg.id
+              WHERE
+                pg.work_order_id = wo.id
+                AND pg.status <> 'Rejected'
+            )::bigint AS done,
+            JSONB_BUILD_OBJECT(
+                'id', p.id,
+                'name', p.name
+            ) AS product,
+            JSONB_BUILD_OBJECT(
+                'id', mu.id,
+                'name', mu.name
+            ) AS measure_unit,
+            JSONB_BUILD_OBJECT(
+                'id', o.id,
+                'name', o.name
+            ) AS organization
+        FROM work_orders AS wo
+        INNER JOIN products AS p ON p.id = wo.product_id
+        INNER JOIN measure_units AS mu ON mu.id = p.measure_unit_id
+        LEFT JOIN organizations AS o ON o.id = wo.organization_id
+        WHERE wo.id = $1
+        AND CASE
+            WHEN $3 not in ('Admin', 'Developer') THEN wo.organization_id = $2::bigint
+            ELSE TRUE
+        END;",
+        id,
+        current_user.organization_id,
+        current_user.role.to_string(),
+    )
+    .fetch_optional(&pool)
+    .await?;
+
+    match row {
+        // Собираем в нужный вид
+        Some(row) => Ok(Item {
+            id: row.id,
+            cnt: row.cnt,
+            done: row.done.map_or(0, |d| d),
+            due_date: row.due_date,
+            status: row.status.into(),
+            created_at: row.created_at,
+            product: row.product.into(),
+            measure_unit: row.measure_unit.into(),
+            organization: row.organization.into(),
+        }),
+        None => Err(AppError(
+            StatusCode::NOT_FOUND,
+            anyhow::anyhow!("Такой записи не существует"),
+        )),
+    }
+}
@@ -0,0 +1,432 @@
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+use yew::prelude::*;
+
+use super::Analitic;
+
+const WIDTH: f64 = 960.0;
+const HEIGHT: f64 = 380.0;
+const LEFT: f64 = 70.0;
+const RIGHT: f64 = 20.0;
+const TOP: f64 = 40.0;
+const BOTTOM: f64 = 90.0;
+
+const COLORS: [&str; 10] = [
+    "#3b82f6", "#ef4444", "#10b981", "#f59e0b", "#8b5cf6", "#ec4899", "#14b8a6", "#f97316",
+    "#6366f1", "#84cc16",
+];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ChartKind {
+    // Столбцы по продуктам
+    Product,
+    // Столбцы по пользователям
+    User,
+    // Линия выпуска по периодам
+    Trend,
+    // Столбцы по периодам с разбивкой по пользователям
+    Stacked,
+}
+
+impl ChartKind {
+    fn title(&self) -> &'static str {
+        match self {
+            Self::Product => "По продуктам",
+            Self::User => "По пользователям",
+            Self::Trend => "Динамика",
+            Self::Stacked => "Динамика по пользователям",
+        }
+    }
+}
+
+struct Data {
+    labels: Vec<String>,
+    // Название серии и значения по подписям
+    series: Vec<(String, Vec<f64>)>,
+}
+
+fn position(values: &mut Vec<String>, value: &str) -> usize {
+    // Индекс значения с сохранением порядка первого появления
+
+    match values.iter().position(|v| v == value) {
+        Some(i) => i,
+        None => {
+            values.push(value.to_string());
+            values.len() - 1
+        }
+    }
+}
+
+fn measures(items: &[Analitic]) -> Vec<String> {
+    // Единицы измерения в порядке первого появления
+
+    let mut measures = Vec::new();
+    for item in items {
+        position(&mut measures, &item.measure);
+    }
+
+    measures
+}
+
+fn chart_data(items: &[Analitic], kind: ChartKind, measure: &str) -> Data {
+    // Данные графика из ответа аналитики.
+    // Выпуск в разных единицах измерения не складывается: графики по пользователям
+    // и периодам строятся по одной выбранной единице.
+
+    let mut labels = Vec::new();
+    let mut names = Vec::new();
+    let mut cells: Vec<(usize, usize, f64)> = Vec::new();
+
+    for item in items {
+        if kind != ChartKind::Product && item.measure != measure {
+            continue;
+        }
+
+        let (label, name) = match kind {
+            ChartKind::Product => (format!("{}, {}", item.name, item.measure), String::new()),
+            ChartKind::User => (item.fio.clone(), String::new()),
+            ChartKind::Trend => (item.period.clone().unwrap_or_default(), String::new()),
+            ChartKind::Stacked => (item.period.clone().unwrap_or_default(), item.fio.clone()),
+        };
+
+        let label = position(&mut labels, &label);
+        let name = position(&mut names, &name);
+        cells.push((label, name, item.cnt as f64));
+    }
+
+    // Продукты и пользователи - от большего выпуска к меньшему
+    let mut series = names
+        .into_iter()
+        .map(|name| (name, vec![0.0; labels.len()]))
+        .collect::<Vec<(String, Vec<f64>)>>();
+    for (label, name, value) in cells {
+        series[name].1[label] += value;
+    }
+
+    if matches!(kind, ChartKind::Product | ChartKind::User) {
+        if let Some((_, values)) = series.first() {
+            let mut order = (0..labels.len()).collect::<Vec<usize>>();
+            order.sort_by(|a, b| values[*b].total_cmp(&values[*a]));
+
+            labels = order.iter().map(|i| labels[*i].clone()).collect();
+            series = series
+                .into_iter()
+                .map(|(name, values)| (name, order.iter().map(|i| values[*i]).collect()))
+                .collect();
+        }
+    }
+
+    Data { labels, series }
+}
+
+fn short(label: &str) -> String {
+    if label.chars().count() > 18 {
+        format!("{}…", label.chars().take(17).collect::<String>())
+    } else {
+        label.to_string()
+    }
+}
+
+fn draw(canvas: &HtmlCanvasElement, items: &[Analitic], kind: ChartKind, measure: &str) {
+    // Отрисовка графика на canvas: оси, сетка, столбцы или линия, легенда
+
+    let Some(ctx) = canvas
+        .get_context("2d")
+        .ok()
+        .flatten()
+        .and_then(|ctx| ctx.dyn_into::<CanvasRenderingContext2d>().ok())
+    else {
+        return;
+    };
+
+    // Белый фон, чтобы PNG не был прозрачным
+    ctx.set_fill_style(&JsValue::from_str("#ffffff"));
+    ctx.fill_rect(0.0, 0.0, WIDTH, HEIGHT);
+    ctx.set_font("12px sans-serif");
+
+    let data = chart_data(items, kind, measure);
+    ctx.set_fill_style(&JsValue::from_str("#6b7280"));
+    if data.labels.is_empty() || data.labels.iter().all(|l| l.is_empty()) {
+        let message = if matches!(kind, ChartKind::Trend | ChartKind::Stacked) {
+            "Выберите группировку по времени"
+        } else {
+            "Нет данных"
+        };
+        ctx.set_text_align("center");
+        let _ = ctx.fill_text(message, WIDTH / 2.0, HEIGHT / 2.0);
+        return;
+    }
+
+    let plot_w = WIDTH - LEFT - RIGHT;
+    let plot_h = HEIGHT - TOP - BOTTOM;
+    let max = (0..data.labels.len())
+        .map(|i| data.series.iter().map(|s| s.1[i]).sum::<f64>())
+        .fold(0.0, f64::max)
+        .max(1.0);
+
+    // Сетка и подписи оси значений
+    ctx.set_stroke_style(&JsValue::from_str("#e5e7eb"));
+    ctx.set_line_width(1.0);
+    ctx.set_text_align("right");
+    for i in 0..=5 {
+        let y = TOP + plot_h - plot_h * i as f64 / 5.0;
+        ctx.begin_path();
+        ctx.move_to(LEFT, y);
+        ctx.line_to(LEFT + plot_w, y);
+        ctx.stroke();
+        let _ = ctx.fill_text(&format!("{:.0}", max * i as f64 / 5.0), LEFT - 6.0, y + 4.0);
+    }
+
+    let step = plot_w / data.labels.len() as f64;
+    let y = |value: f64| TOP + plot_h - plot_h * value / max;
+
+    if kind == ChartKind::Trend {
+        let values = &data.series[0].1;
+        ctx.set_stroke_style(&JsValue::from_str(COLORS[0]));
+        ctx.set_line_width(2.0);
+        ctx.begin_path();
+        for (i, value) in values.iter().enumerate() {
+            let x = LEFT + step * (i as f64 + 0.5);
+            if i == 0 {
+                ctx.move_to(x, y(*value));
+            } else {
+                ctx.line_to(x, y(*value));
+            }
+        }
+        ctx.stroke();
+
+        ctx.set_fill_style(&JsValue::from_str(COLORS[0]));
+        for (i, value) in values.iter().enumerate() {
+            let x = LEFT + step * (i as f64 + 0.5);
+            ctx.begin_path();
+            let _ = ctx.arc(x, y(*value), 3.0, 0.0, std::f64::consts::PI * 2.0);
+            ctx.fill();
+        }
+    } else {
+        let bar = (step * 0.7).min(60.0);
+        for i in 0..data.labels.len() {
+            let x = LEFT + step * (i as f64 + 0.5) - bar / 2.0;
+            let mut bottom = 0.0;
+            for (n, (_, values)) in data.series.iter().enumerate() {
+                let top = bottom + values[i];
+                ctx.set_fill_style(&JsValue::from_str(COLORS[n % COLORS.len()]));
+                ctx.fill_rect(x, y(top), bar, y(bottom) - y(top));
+                bottom = top;
+            }
+        }
+    }
+
+    // Подписи категорий под углом
+    ctx.set_fill_style(&JsValue::from_str("#374151"));
+    ctx.set_text_align("right");
+    for (i, label) in data.labels.iter().enumerate() {
+        ctx.save();
+        let _ = ctx.translate(LEFT + step * (i as f64 + 0.5), TOP + plot_h + 12.0);
+        let _ = ctx.rotate(-std::f64::consts::PI / 4.0);
+        let _ = ctx.fill_text(&short(label), 0.0, 0.0);
+        ctx.restore();
+    }
+
+    // Легенда для разбивки по пользователям
+    if kind == ChartKind::Stacked {
+        ctx.set_text_align("left");
+        let mut x = LEFT;
+        for (n, (name, _)) in data.series.iter().enumerate() {
+            ctx.set_fill_style(&JsValue::from_str(COLORS[n % COLORS.len()]));
+            ctx.fill_rect(x, 12.0, 12.0, 12.0);
+            ctx.set_fill_style(&JsValue::from_str("#374151"));
+            let name = short(name);
+            let _ = ctx.fill_text(&name, x + 16.0, 22.0);
+            x += 28.0 + name.chars().count() as f64 * 7.0;
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub items: Vec<Analitic>,
+}
+
+#[function_component(AnaliticChart)]
+pub fn analitic_chart(Props { items }: &Props) -> Html {
+    // Компонент графиков аналитики с переключением вида и выгрузкой в PNG
+
+    let kind = use_state_eq(|| ChartKind::Product);
+    let measure = use_state_eq(String::new);
+    let canvas_ref = use_node_ref();
+
+    // Выбранная единица измерения, если ее нет в ответе - первая из ответа
+    let measures = measures(items);
+    let current = if measures.contains(&*measure) {
+        (*measure).clone()
+    } else {
+        measures.first().cloned().unwrap_or_default()
+    };
+
+    {
+        let canvas_ref = canvas_ref.clone();
+        use_effect_with(
+            (items.clone(), *kind, current.clone()),
+            move |(items, kind, measure)| {
+                if let Some(canvas) = canvas_ref.cast::<HtmlCanvasElement>() {
+                    draw(&canvas, items, *kind, measure);
+                }
+            },
+        );
+    }
+
+    let onclick_kind = |value: ChartKind| {
+        let kind = kind.clone();
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+            kind.set(value);
+        })
+    };
+
+    let onclick_measure = |value: String| {
+        let measure = measure.clone();
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+            measure.set(value.clone());
+        })
+    };
+
+    let onclick_png = {
+        let canvas_ref = canvas_ref.clone();
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+
+            let Some(canvas) = canvas_ref.cast::<HtmlCanvasElement>() else {
+                return;
+            };
+            let Ok(url) = canvas.to_data_url_with_type("image/png") else {
+                return;
+            };
+
+            let document = web_sys::window().unwrap().document().unwrap();
+            let a = document.create_element("a").unwrap();
+            let a = a.dyn_into::<web_sys::HtmlAnchorElement>().unwrap();
+
+            a.set_href(&url);
+            a.set_download("analitics_chart.png");
+            a.click();
+        })
+    };
+
+    let button_class = |active: bool| {
+        if active {
+            "px-3 py-1 rounded-md text-sm bg-blue-500 text-white"
+        } else {
+            "px-3 py-1 rounded-md text-sm border border-gray-300 text-gray-600 hover:bg-gray-50"
+        }
+    };
+
+    html! {
+        <div class="rounded-lg border border-gray-200 shadow-md mx-5 my-2 bg-white p-2">
+            <div class="flex flex-wrap gap-2 mb-2">
+                {
+                    [ChartKind::Product, ChartKind::User, ChartKind::Trend, ChartKind::Stacked]
+                        .into_iter()
+                        .map(|value| html! {
+                            <button onclick={onclick_kind(value)} class={button_class(*kind == value)}>
+                                {value.title()}
+                            </button>
+                        }).collect::<Html>()
+                }
+                if *kind != ChartKind::Product && measures.len() > 1 {
+                    {
+                        measures.iter().map(|value| html! {
+                            <button
+                                onclick={onclick_measure(value.clone())}
+                                class={button_class(current == *value)}
+                            >
+                                {value}
+                            </button>
+                        }).collect::<Html>()
+                    }
+                }
+                <button onclick={onclick_png} class={classes!(button_class(false), "ml-auto")}>
+                    {"Скачать PNG"}
+                </button>
+            </div>
+            <canvas
+                ref={canvas_ref}
+                width={WIDTH.to_string()}
+                height={HEIGHT.to_string()}
+                class="w-full h-auto"
+            />
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(name: &str, measure: &str, fio: &str, period: &str, cnt: i64) -> Analitic {
+        Analitic {
+            id: 1,
+            name: name.to_string(),
+            measure: measure.to_string(),
+            fio: fio.to_string(),
+            category: None,
+            cnt,
+            cost_amount: 0.0,
+            price_amount: 0.0,
+            period: Some(period.to_string()),
+            comparison: None,
+        }
+    }
+
+    fn items() -> Vec<Analitic> {
+        vec![
+            item("Болт", "шт", "Иванов", "01.03", 10),
+            item("Мука", "кг", "Иванов", "01.03", 500),
+            item("Гайка", "шт", "Петров", "01.03", 30),
+            item("Болт", "шт", "Иванов", "02.03", 5),
+        ]
+    }
+
+    #[test]
+    fn measures_in_order() {
+        assert_eq!(measures(&items()), vec!["шт", "кг"]);
+    }
+
+    #[test]
+    fn product_keeps_all_measures() {
+        // Подпись продукта содержит единицу измерения, столбцы по убыванию
+        let data = chart_data(&items(), ChartKind::Product, "шт");
+
+        assert_eq!(data.labels, vec!["Мука, кг", "Гайка, шт", "Болт, шт"]);
+        assert_eq!(data.series, vec![(String::new(), vec![500.0, 30.0, 15.0])]);
+    }
+
+    #[test]
+    fn user_sums_one_measure() {
+        // Килограммы не складываются со штуками
+        let data = chart_data(&items(), ChartKind::User, "шт");
+        assert_eq!(data.labels, vec!["Петров", "Иванов"]);
+        assert_eq!(data.series, vec![(String::new(), vec![30.0, 15.0])]);
+
+        let data = chart_data(&items(), ChartKind::User, "кг");
+        assert_eq!(data.labels, vec!["Иванов"]);
+        assert_eq!(data.series, vec![(String::new(), vec![500.0])]);
+    }
+
+    #[test]
+    fn trend_and_stacked_one_measure() {
+        let data = chart_data(&items(), ChartKind::Trend, "шт");
+        assert_eq!(data.labels, vec!["01.03", "02.03"]);
+        assert_eq!(data.series, vec![(String::new(), vec![40.0, 5.0])]);
+
+        let data = chart_data(&items(), ChartKind::Stacked, "шт");
+        assert_eq!(data.labels, vec!["01.03", "02.03"]);
+        assert_eq!(
+            data.series,
+            vec![
+                ("Иванов".to_string(), vec![10.0, 5.0]),
+                ("Петров".to_string(), vec![30.0, 0.0]),
+            ]
+        );
+    }
+}
@@ -1,11 +1,15 @@
 use crate::{
     components::{
-        analitic::{list::AnaliticList, Analitic},
-        elements::multiselect::{Item, MultiSelect},
+        analitic::{chart::AnaliticChart, list::AnaliticList, pivot::AnaliticPivot, Analitic},
+        elements::{
+            custom_fields::{fetch_custom_fields, CustomField},
+            multiselect::{Item, MultiSelect},
+        },
         footer::Footer,
         header::component::HeaderComponent,
+        rbs::product::ProductCategory,
     },
-    AppContext, ResponseItems, Route, User,
+    time_zone, AppContext, ResponseItems, Route, User,
 };
 
 use gloo::{
@@ -15,7 +19,7 @@ use gloo::{
 
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::JsCast;
-use web_sys::HtmlSelectElement;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
 use yew::prelude::*;
 use yew_router::hooks::{use_location, use_navigator};
 
@@ -26,6 +30,40 @@ pub struct Q {
 
     product: Option<String>,
     user: Option<String>,
+    #[serde(default)]
+    confirmed_only: bool,
+    category_id: Option<i64>,
+    group: Option<String>,
+    compare: Option<String>,
+}
+
+fn fields_filter(
+    fields: &[CustomField],
+    field_id: Option<i64>,
+    value: &str,
+) -> Option<(&'static str, String)> {
+    // Параметр фильтра по дополнительному полю: имя параметра и JSON-объект {"код": значение}
+
+    let field = fields.iter().find(|f| Some(f.id) == field_id)?;
+    if value.is_empty() {
+        return None;
+    }
+
+    let value = match field.kind.as_str() {
+        "Number" => serde_json::json!(value.replace(',', ".").parse::<f64>().ok()?),
+        "Boolean" => serde_json::json!(value == "true"),
+        _ => serde_json::json!(value),
+    };
+    let param = if field.entity == "Product" {
+        "product_fields"
+    } else {
+        "custom_fields"
+    };
+
+    Some((
+        param,
+        serde_json::json!({ field.code.clone(): value }).to_string(),
+    ))
 }
 
 #[function_component(AnaliticComponent)]
@@ -64,13 +102,35 @@ pub fn analitic() -> Html {
 
     let product = use_state_eq(|| location.query::<Q>().map(|it| it.product).unwrap_or(None));
     let user = use_state_eq(|| location.query::<Q>().map(|it| it.user).unwrap_or(None));
+    // Все записи или только подтвержденные Директором
+    let confirmed_only = use_state_eq(|| {
+        location
+            .query::<Q>()
+            .map(|it| it.confirmed_only)
+            .unwrap_or(false)
+    });
+    let category_id = use_state_eq(|| {
+        location
+            .query::<Q>()
+            .map(|it| it.category_id)
+            .unwrap_or(None)
+    });
+    // Группировка по дням, неделям, месяцам или кварталам
+    let group = use_state_eq(|| location.query::<Q>().map(|it| it.group).unwrap_or(None));
+    // Сравнение с предыдущим периодом или прошлым годом, не сочетается с группировкой
+    let compare = use_state_eq(|| location.query::<Q>().map(|it| it.compare).unwrap_or(None));
 
     // Для списка в selected
     let users: UseStateHandle<Vec<User>> = use_state_eq(Vec::new);
+    let categories: UseStateHandle<Vec<ProductCategory>> = use_state_eq(Vec::new);
+    let fields: UseStateHandle<Vec<CustomField>> = use_state_eq(Vec::new);
     {
         let users = users.clone();
+        let categories = categories.clone();
+        let fields = fields.clone();
         use_effect_with((), move |_| {
             let users = users.clone();
+            let categories = categories.clone();
             wasm_bindgen_futures::spawn_local(async move {
                 let mut header_bearer = String::from("Bearer ");
                 let token: Option<String> = LocalStorage::get("token").unwrap_or(None);
@@ -90,24 +150,71 @@ pub fn analitic() -> Html {
                     .unwrap();
 
                 users.set(response.items.clone());
+
+                let response = http::Request::get("/api/product-categories")
+                    .header("Content-Type", "application/json")
+                    .header("Authorization", &header_bearer)
+                    .query([("page", "1"), ("per_page", "10000")])
+                    .send()
+                    .await
+                    .unwrap()
+                    .json::<ResponseItems<ProductCategory>>()
+                    .await
+                    .unwrap();
+
+                categories.set(response.items);
+
+                let mut items = fetch_custom_fields("ProducedGood", None).await;
+                items.extend(fetch_custom_fields("Product", None).await);
+                fields.set(items);
             });
         });
     }
 
+    // Фильтр по одному дополнительному полю записи или продукта
+    let field_id: UseStateHandle<Option<i64>> = use_state_eq(|| None);
+    let field_value = use_state_eq(|| "".to_string());
+    let filter = fields_filter(&fields, *field_id, &field_value);
+
     let items: UseStateHandle<Vec<Analitic>> = use_state_eq(Vec::new);
     {
         let items = items.clone();
         let users = users.clone();
         let navigator = use_navigator();
         use_effect_with(
-            (*date_one, *date_two, (*product).clone(), (*user).clone()),
-            move |(date_one, date_two, product, user)| {
+            (
+                *date_one,
+                *date_two,
+                (*product).clone(),
+                (*user).clone(),
+                *confirmed_only,
+                *category_id,
+                filter.clone(),
+                (*group).clone(),
+                (*compare).clone(),
+            ),
+            move |(
+                date_one,
+                date_two,
+                product,
+                user,
+                confirmed_only,
+                category_id,
+                filter,
+                group,
+                compare,
+            )| {
                 let items = items.clone();
                 let users = users.clone();
                 let cloned_date_one = *date_one;
                 let cloned_date_two = *date_two;
                 let cloned_product = (*product).clone();
                 let cloned_user = (*user).clone();
+                let cloned_confirmed_only = *confirmed_only;
+                let cloned_category_id = *category_id;
+                let cloned_filter = filter.clone();
+                let cloned_group = group.clone();
+                let cloned_compare = compare.clone();
                 wasm_bindgen_futures::spawn_local(async move {
                     let mut header_bearer = String::from("Bearer ");
                     let token: Option<String> = LocalStorage::get("token").unwrap_or(None);
@@ -141,6 +248,31 @@ pub fn analitic() -> Html {
                         q.push(("user", user));
                     }
 
+                    if cloned_confirmed_only {
+                        q.push(("confirmed_only", "true"));
+                    }
+
+                    let category = cloned_category_id.map(|c| c.to_string());
+                    if let Some(category) = &category {
+                        q.push(("category_id", category));
+                    }
+
+                    if let Some((param, value)) = &cloned_filter {
+                        q.push((param, value));
+                    }
+
+                    let tz = time_zone();
+                    if let Some(group) = &cloned_group {
+                        q.push(("group", group));
+                        if let Some(tz) = &tz {
+                            q.push(("tz", tz));
+                        }
+                    }
+
+                    if let Some(compare) = &cloned_compare {
+                        q.push(("compare", compare));
+                    }
+
                     let response = http::Request::get("/api/analitics")
                         .header("Content-Type", "application/json")
                         .header("Authorization", &header_bearer)
@@ -164,6 +296,10 @@ pub fn analitic() -> Html {
 
                                     product: cloned_product.filter(|u| !u.is_empty()),
                                     user: cloned_user.filter(|u| !u.is_empty()),
+                                    confirmed_only: cloned_confirmed_only,
+                                    category_id: cloned_category_id,
+                                    group: cloned_group,
+                                    compare: cloned_compare,
                                 },
                             )
                             .unwrap();
@@ -223,16 +359,111 @@ pub fn analitic() -> Html {
         })
     };
 
-    let download_report = {
+    let onchange_confirmed_only = {
+        let cloned_confirmed_only = confirmed_only.clone();
+        Callback::from(move |event: Event| {
+            let value = event
+                .target()
+                .unwrap()
+                .unchecked_into::<HtmlInputElement>()
+                .checked();
+
+            cloned_confirmed_only.set(value);
+        })
+    };
+
+    let onchange_category = {
+        let cloned_category_id = category_id.clone();
+        Callback::from(move |event: Event| {
+            let value = event
+                .target()
+                .unwrap()
+                .unchecked_into::<HtmlSelectElement>()
+                .value();
+
+            cloned_category_id.set(value.parse::<i64>().ok());
+        })
+    };
+
+    let onchange_group = {
+        let cloned_group = group.clone();
+        let cloned_compare = compare.clone();
+        Callback::from(move |event: Event| {
+            let value = event
+                .target()
+                .unwrap()
+                .unchecked_into::<HtmlSelectElement>()
+                .value();
+
+            if !value.is_empty() {
+                cloned_compare.set(None);
+            }
+            cloned_group.set(Some(value).filter(|v| !v.is_empty()));
+        })
+    };
+
+    let onchange_compare = {
+        let cloned_group = group.clone();
+        let cloned_compare = compare.clone();
+        Callback::from(move |event: Event| {
+            let value = event
+                .target()
+                .unwrap()
+                .unchecked_into::<HtmlSelectElement>()
+                .value();
+
+            if !value.is_empty() {
+                cloned_group.set(None);
+            }
+            cloned_compare.set(Some(value).filter(|v| !v.is_empty()));
+        })
+    };
+
+    let onchange_field = {
+        let cloned_field_id = field_id.clone();
+        let cloned_field_value = field_value.clone();
+        Callback::from(move |event: Event| {
+            let value = event
+                .target()
+                .unwrap()
+                .unchecked_into::<HtmlSelectElement>()
+                .value();
+
+            cloned_field_id.set(value.parse::<i64>().ok());
+            cloned_field_value.set("".to_string());
+        })
+    };
+
+    let onchange_field_value = {
+        let cloned_field_value = field_value.clone();
+        Callback::from(move |event: Event| {
+            let value = event
+                .target()
+                .unwrap()
+                .unchecked_into::<HtmlInputElement>()
+                .value();
+
+            cloned_field_value.set(value.trim().to_string());
+        })
+    };
+
+    // Отчет в Excel или PDF по тем же фильтрам
+    let download_report = |url: &'static str| {
         let cloned_date_one = *date_one;
         let cloned_date_two = *date_two;
         let cloned_product = (*product).clone();
         let cloned_user = (*user).clone();
+        let cloned_confirmed_only = *confirmed_only;
+        let cloned_category_id = *category_id;
+        let cloned_filter = filter.clone();
+        let cloned_group = (*group).clone();
         Callback::from(move |e: MouseEvent| {
             e.prevent_default();
 
             let cloned_product = cloned_product.clone();
             let cloned_user = cloned_user.clone();
+            let cloned_filter = cloned_filter.clone();
+            let cloned_group = cloned_group.clone();
             wasm_bindgen_futures::spawn_local(async move {
                 let mut header_bearer = String::from("Bearer ");
                 let token: Option<String> = LocalStorage::get("token").unwrap_or(None);
@@ -252,7 +483,28 @@ pub fn analitic() -> Html {
                     q.push(("user", user));
                 }
 
-                let resp = http::Request::post("/api/upload-report")
+                if cloned_confirmed_only {
+                    q.push(("confirmed_only", "true"));
+                }
+
+                let category = cloned_category_id.map(|c| c.to_string());
+                if let Some(category) = &category {
+                    q.push(("category_id", category));
+                }
+
+                if let Some((param, value)) = &cloned_filter {
+                    q.push((param, value));
+                }
+
+                let tz = time_zone();
+                if let Some(group) = &cloned_group {
+                    q.push(("group", group));
+                    if let Some(tz) = &tz {
+                        q.push(("tz", tz));
+                    }
+                }
+
+                let resp = http::Request::post(url)
                     .header("Content-Type", "application/json")
                     .header("Authorization", &header_bearer)
                     .query(q)
@@ -311,7 +563,23 @@ pub fn analitic() -> Html {
         <HeaderComponent />
         <div class="flex justify-end">
             <button
-                onclick={download_report}
+                onclick={download_report("/api/upload-report-pdf")}
+                class="
+                    min-w-[151px]
+                    px-4
+                    py-2
+                    border
+                    border-blue-500
+                    text-blue-500
+                    rounded-md
+                    hover:bg-blue-50
+                    mt-2
+                "
+            >
+                {"Скачать PDF"}
+            </button>
+            <button
+                onclick={download_report("/api/upload-report")}
                 class="
                     min-w-[151px]
                     px-4
@@ -401,6 +669,133 @@ pub fn analitic() -> Html {
             //     value={(*user).clone()}
             // />
 
+            <select
+                onchange={onchange_category}
+                class="
+                    w-[calc((100vw - 2.5rem - 15px) / 2)]
+                    px-4
+                    py-2
+                    text-gray-600
+                    rounded-md
+                    font-normal
+                    text-sm
+                    border
+                    border-gray-300
+                    focus:border-indigo-700
+                    focus:outline-none
+                "
+            >
+                <option selected={category_id.is_none()} value="">{"Все категории"}</option>
+                {
+                    (*categories).iter().map(|c| html! {
+                        <option selected={*category_id == Some(c.id)} value={c.id.to_string()}>{c.path.clone()}</option>
+                    }).collect::<Html>()
+                }
+            </select>
+
+            <select
+                onchange={onchange_group}
+                class="
+                    px-4
+                    py-2
+                    text-gray-600
+                    rounded-md
+                    font-normal
+                    text-sm
+                    border
+                    border-gray-300
+                    focus:border-indigo-700
+                    focus:outline-none
+                "
+            >
+                {
+                    [("", "Без группировки"), ("day", "По дням"), ("week", "По неделям"), ("month", "По месяцам"), ("quarter", "По кварталам")]
+                        .iter()
+                        .map(|(value, name)| html! {
+                            <option selected={group.as_deref().unwrap_or("") == *value} value={*value}>{*name}</option>
+                        }).collect::<Html>()
+                }
+            </select>
+
+            <select
+                onchange={onchange_compare}
+                class="
+                    px-4
+                    py-2
+                    text-gray-600
+                    rounded-md
+                    font-normal
+                    text-sm
+                    border
+                    border-gray-300
+                    focus:border-indigo-700
+                    focus:outline-none
+                "
+            >
+                {
+                    [("", "Без сравнения"), ("previous", "С прошлым периодом"), ("year", "С прошлым годом")]
+                        .iter()
+                        .map(|(value, name)| html! {
+                            <option selected={compare.as_deref().unwrap_or("") == *value} value={*value}>{*name}</option>
+                        }).collect::<Html>()
+                }
+            </select>
+
+            if !fields.is_empty() {
+                <select
+                    onchange={onchange_field}
+                    class="
+                        px-4
+                        py-2
+                        text-gray-600
+                        rounded-md
+                        font-normal
+                        text-sm
+                        border
+                        border-gray-300
+                        focus:border-indigo-700
+                        focus:outline-none
+                    "
+                >
+                    <option selected={field_id.is_none()} value="">{"Доп. поле"}</option>
+                    {
+                        fields.iter().map(|f| html! {
+                            <option selected={*field_id == Some(f.id)} value={f.id.to_string()}>
+                                {format!("{}{}", f.name, if f.entity == "Product" { " (продукт)" } else { "" })}
+                            </option>
+                        }).collect::<Html>()
+                    }
+                </select>
+                <input
+                    type="text"
+                    onchange={onchange_field_value}
+                    disabled={field_id.is_none()}
+                    class="
+                        px-4
+                        py-2
+                        text-gray-600
+                        rounded-md
+                        font-normal
+                        text-sm
+                        border
+                        border-gray-300
+                        focus:border-indigo-700
+                        focus:outline-none
+                    "
+                    placeholder="Значение"
+                    value={(*field_value).clone()}
+                />
+            }
+
+            <label class="flex items-center gap-2 px-2 text-sm text-gray-600">
+                <input
+                    type="checkbox"
+                    onchange={onchange_confirmed_only}
+                    checked={*confirmed_only}
+                />
+                {"Только подтвержденные"}
+            </label>
+
             <input
                 type="date"
                 onchange={onchange_date_one}
@@ -447,15 +842,26 @@ pub fn analitic() -> Html {
                 value={date_two.to_string()}
             />
         </div>
+        <AnaliticChart items={(*items).clone()} />
         <div class="overflow-auto rounded-lg border border-gray-200 shadow-md mx-5 my-2 max-h-[68%]">
             <table class="w-full border-collapse bg-white text-left text-sm text-gray-500 table-auto">
                 <thead class="bg-gray-50 sticky top-0">
                     <tr>
                     <th scope="col" class="px-6 py-4 font-medium text-gray-900 uppercase">{"#"}</th>
+                    if items.iter().any(|item| item.period.is_some()) {
+                        <th scope="col" class="px-6 py-4 font-medium text-gray-900 uppercase">{"Период"}</th>
+                    }
                     <th scope="col" class="px-6 py-4 font-medium text-gray-900 uppercase">{"Продукт"}</th>
                     <th scope="col" class="px-6 py-4 font-medium text-gray-900 uppercase">{"Пользователь"}</th>
+                    <th scope="col" class="px-6 py-4 font-medium text-gray-900 uppercase">{"Категория"}</th>
                     <th scope="col" class="px-6 py-4 font-medium text-gray-900 uppercase">{"Eдиница Измерения"}</th>
                     <th scope="col" class="px-6 py-4 font-medium text-gray-900 uppercase">{"Кол-во"}</th>
+                    <th scope="col" class="px-6 py-4 font-medium text-gray-900 uppercase">{"Себестоимость, руб."}</th>
+                    <th scope="col" class="px-6 py-4 font-medium text-gray-900 uppercase">{"Стоимость, руб."}</th>
+                    if items.iter().any(|item| item.comparison.is_some()) {
+                        <th scope="col" class="px-6 py-4 font-medium text-gray-900 uppercase">{"Δ Кол-во"}</th>
+                        <th scope="col" class="px-6 py-4 font-medium text-gray-900 uppercase">{"Δ Стоимость, руб."}</th>
+                    }
                     // <th scope="col" class="px-6 py-4 font-medium text-gray-900 uppercase"></th>
                     </tr>
                 </thead>
@@ -467,6 +873,15 @@ pub fn analitic() -> Html {
                 </tbody>
             </table>
         </div>
+        <AnaliticPivot
+            date_one={*date_one}
+            date_two={*date_two}
+            product={(*product).clone()}
+            user={(*user).clone()}
+            confirmed_only={*confirmed_only}
+            category_id={*category_id}
+            filter={filter.clone()}
+        />
 
         <Footer />
 
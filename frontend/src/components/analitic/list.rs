@@ -9,6 +9,16 @@ pub struct Props {
     pub current_user: Option<User>,
 }
 
+fn delta(value: String, percent: Option<f64>) -> String {
+    // Изменение со знаком и процент, если база сравнения не нулевая
+
+    let sign = if value.starts_with('-') { "" } else { "+" };
+    match percent {
+        Some(percent) => format!("{}{} ({:+.1}%)", sign, value, percent),
+        None => format!("{}{}", sign, value),
+    }
+}
+
 #[function_component(AnaliticList)]
 pub fn produced_good_lists(
     Props {
@@ -18,6 +28,8 @@ pub fn produced_good_lists(
 ) -> Html {
     // Компонент списка данных для аналитики
 
+    let grouped = items.iter().any(|item| item.period.is_some());
+
     html! {
         <>
         {items.iter().map(|item|{
@@ -25,13 +37,30 @@ pub fn produced_good_lists(
             html! {
                 <tr class="hover:bg-gray-50">
                     <td class="px-6 py-4">{item.id}</td>
+                    if grouped {
+                        <td class="px-6 py-4">{item.period.clone().unwrap_or_default()}</td>
+                    }
                     <td class="px-6 py-4">{item.name.clone()}</td>
                     <td class="px-6 py-4">{item.fio.clone()}</td>
+                    <td class="px-6 py-4">{item.category.clone().unwrap_or("-".into())}</td>
                     <td class="px-6 py-4">{item.measure.clone()}</td>
                     <td class="px-6 py-4">{item.cnt}</td>
+                    <td class="px-6 py-4">{format!("{:.2}", item.cost_amount)}</td>
+                    <td class="px-6 py-4">{format!("{:.2}", item.price_amount)}</td>
+                    if let Some(comparison) = &item.comparison {
+                        <td class="px-6 py-4">{delta(comparison.cnt_delta.to_string(), comparison.cnt_delta_percent)}</td>
+                        <td class="px-6 py-4">{delta(format!("{:.2}", comparison.price_delta), comparison.price_delta_percent)}</td>
+                    }
                 </tr>
             }
         }).collect::<Vec<_>>()}
+        if !items.is_empty() {
+            <tr class="font-bold">
+                <td class="px-6 py-4" colspan={if grouped { "7" } else { "6" }}>{"Итого"}</td>
+                <td class="px-6 py-4">{format!("{:.2}", items.iter().map(|item| item.cost_amount).sum::<f64>())}</td>
+                <td class="px-6 py-4">{format!("{:.2}", items.iter().map(|item| item.price_amount).sum::<f64>())}</td>
+            </tr>
+        }
         </>
     }
 }
@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
 
+pub mod chart;
 pub mod component;
 pub mod list;
+pub mod pivot;
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Analitic {
@@ -9,5 +11,27 @@ pub struct Analitic {
     pub name: String,
     pub measure: String,
     pub fio: String,
+    #[serde(default)]
+    pub category: Option<String>,
     pub cnt: i64,
+    #[serde(default)]
+    pub cost_amount: f64,
+    #[serde(default)]
+    pub price_amount: f64,
+    // Подпись периода при группировке по времени
+    #[serde(default)]
+    pub period: Option<String>,
+    // Изменение к периоду сравнения
+    #[serde(default)]
+    pub comparison: Option<Comparison>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Comparison {
+    pub cnt: i64,
+    pub price_amount: f64,
+    pub cnt_delta: i64,
+    pub cnt_delta_percent: Option<f64>,
+    pub price_delta: f64,
+    pub price_delta_percent: Option<f64>,
 }
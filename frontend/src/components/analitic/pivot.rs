@@ -0,0 +1,366 @@
+use crate::time_zone;
+
+use gloo::{
+    net::http,
+    storage::{LocalStorage, Storage},
+};
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Header {
+    pub key: String,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct Pivot {
+    pub rows: Vec<Header>,
+    pub cols: Vec<Header>,
+    pub measures: Vec<String>,
+    // values[строка][колонка][показатель], пустая ячейка - null
+    pub values: Vec<Vec<Vec<Option<f64>>>>,
+    pub row_totals: Vec<Vec<f64>>,
+    pub col_totals: Vec<Vec<f64>>,
+    pub total: Vec<f64>,
+}
+
+const DIMENSIONS: [(&str, &str); 5] = [
+    ("product", "Продукт"),
+    ("user", "Пользователь"),
+    ("organization", "Организация"),
+    ("measure", "Ед.измерения"),
+    ("date", "Период"),
+];
+
+const MEASURES: [(&str, &str); 4] = [
+    ("sum", "Выпуск"),
+    ("count", "Записей"),
+    ("avg", "Средний выпуск"),
+    ("adjustments", "Корректировки"),
+];
+
+const GROUPS: [(&str, &str); 4] = [
+    ("day", "По дням"),
+    ("week", "По неделям"),
+    ("month", "По месяцам"),
+    ("quarter", "По кварталам"),
+];
+
+fn header_bearer() -> String {
+    let mut header_bearer = String::from("Bearer ");
+    let token: Option<String> = LocalStorage::get("token").unwrap_or(None);
+    if let Some(t) = token {
+        header_bearer.push_str(&t);
+    }
+
+    header_bearer
+}
+
+fn format_value(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{:.0}", value)
+    } else {
+        format!("{:.2}", value)
+    }
+}
+
+// Фильтры страницы аналитики, сводная таблица строится по тем же записям
+#[derive(Properties, PartialEq, Clone)]
+pub struct Props {
+    pub date_one: chrono::NaiveDate,
+    pub date_two: chrono::NaiveDate,
+    pub product: Option<String>,
+    pub user: Option<String>,
+    pub confirmed_only: bool,
+    pub category_id: Option<i64>,
+    pub filter: Option<(&'static str, String)>,
+}
+
+#[derive(Clone, PartialEq)]
+struct Params {
+    rows: String,
+    // Пустая строка - без колонок, только итог по строке
+    cols: String,
+    measure: String,
+    group: String,
+}
+
+fn query(props: &Props, params: &Params) -> Vec<(&'static str, String)> {
+    // Параметры запроса сводной таблицы для просмотра и выгрузки
+
+    let mut q = vec![
+        ("date_one", props.date_one.to_string()),
+        ("date_two", props.date_two.to_string()),
+        ("rows", params.rows.clone()),
+        ("measures", params.measure.clone()),
+        ("group", params.group.clone()),
+    ];
+
+    if !params.cols.is_empty() {
+        q.push(("cols", params.cols.clone()));
+    }
+
+    if let Some(product) = &props.product {
+        q.push(("product", product.clone()));
+    }
+
+    if let Some(user) = &props.user {
+        q.push(("user", user.clone()));
+    }
+
+    if props.confirmed_only {
+        q.push(("confirmed_only", "true".to_string()));
+    }
+
+    if let Some(category_id) = props.category_id {
+        q.push(("category_id", category_id.to_string()));
+    }
+
+    if let Some((param, value)) = &props.filter {
+        q.push((param, value.clone()));
+    }
+
+    if let Some(tz) = time_zone() {
+        q.push(("tz", tz));
+    }
+
+    q
+}
+
+async fn download_report(props: Props, params: Params) {
+    // Скачивание сводной таблицы в Excel
+
+    let resp = http::Request::post("/api/upload-pivot-report")
+        .header("Authorization", &header_bearer())
+        .query(query(&props, &params))
+        .send()
+        .await
+        .unwrap();
+
+    if !resp.ok() {
+        return;
+    }
+
+    let filename = format!(
+        "pivot_report_{}_{}.xlsx",
+        props.date_one.format("%d.%m.%Y"),
+        props.date_two.format("%d.%m.%Y"),
+    );
+    let bytes = resp.binary().await.unwrap();
+
+    let u8_array = js_sys::Uint8Array::from(&bytes[..]);
+    let array = js_sys::Array::new_with_length(1);
+    array.set(0, u8_array.into());
+
+    let blob = web_sys::Blob::new_with_u8_array_sequence_and_options(
+        &array,
+        web_sys::BlobPropertyBag::new().type_("application/octet-stream"),
+    )
+    .unwrap();
+    let url = web_sys::Url::create_object_url_with_blob(&blob).unwrap();
+    let document = web_sys::window().unwrap().document().unwrap();
+
+    let a = document.create_element("a").unwrap();
+    let a = a.dyn_into::<web_sys::HtmlAnchorElement>().unwrap();
+
+    a.set_href(&url);
+    a.set_download(&filename);
+    a.click();
+
+    web_sys::Url::revoke_object_url(&url).unwrap();
+}
+
+#[function_component(AnaliticPivot)]
+pub fn analitic_pivot(props: &Props) -> Html {
+    // Компонент сводной таблицы выпуска с выбором измерений и показателя
+
+    let params = use_state_eq(|| Params {
+        rows: "product".to_string(),
+        cols: "user".to_string(),
+        measure: "sum".to_string(),
+        group: "day".to_string(),
+    });
+
+    let pivot: UseStateHandle<Pivot> = use_state_eq(Pivot::default);
+    {
+        let pivot = pivot.clone();
+        use_effect_with(
+            (props.clone(), (*params).clone()),
+            move |(props, params)| {
+                let pivot = pivot.clone();
+                let q = query(props, params);
+                wasm_bindgen_futures::spawn_local(async move {
+                    let resp = http::Request::get("/api/analitics/pivot")
+                        .header("Content-Type", "application/json")
+                        .header("Authorization", &header_bearer())
+                        .query(q)
+                        .send()
+                        .await
+                        .unwrap();
+
+                    if resp.ok() {
+                        pivot.set(resp.json::<Pivot>().await.unwrap_or_default());
+                    } else {
+                        pivot.set(Pivot::default());
+                    }
+                });
+            },
+        );
+    }
+
+    let onchange = |set: fn(&mut Params, String)| {
+        let params = params.clone();
+        Callback::from(move |event: Event| {
+            let value = event
+                .target()
+                .unwrap()
+                .unchecked_into::<HtmlSelectElement>()
+                .value();
+
+            let mut next = (*params).clone();
+            set(&mut next, value);
+            params.set(next);
+        })
+    };
+
+    let onclick_excel = {
+        let props = props.clone();
+        let params = params.clone();
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+
+            let props = props.clone();
+            let params = (*params).clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                download_report(props, params).await;
+            });
+        })
+    };
+
+    let select_class = "
+        px-4
+        py-2
+        text-gray-600
+        rounded-md
+        font-normal
+        text-sm
+        border
+        border-gray-300
+        focus:border-indigo-700
+        focus:outline-none
+    ";
+    let rows_name = DIMENSIONS
+        .iter()
+        .find(|(value, _)| *value == params.rows)
+        .map_or("", |(_, name)| *name);
+    let with_cols = !params.cols.is_empty();
+    let with_date = params.rows == "date" || params.cols == "date";
+
+    html! {
+        <div class="rounded-lg border border-gray-200 shadow-md mx-5 my-2 bg-white p-2">
+            <div class="flex flex-wrap gap-2 mb-2">
+                // Строки и колонки не могут совпадать
+                <select
+                    onchange={onchange(|params, value| {
+                        if params.cols == value {
+                            params.cols = String::new();
+                        }
+                        params.rows = value;
+                    })}
+                    class={select_class}
+                >
+                    {
+                        DIMENSIONS.iter().map(|(value, name)| html! {
+                            <option selected={params.rows == *value} value={*value}>{format!("Строки: {}", name)}</option>
+                        }).collect::<Html>()
+                    }
+                </select>
+                <select onchange={onchange(|params, value| params.cols = value)} class={select_class}>
+                    <option selected={!with_cols} value="">{"Без колонок"}</option>
+                    {
+                        DIMENSIONS.iter().filter(|(value, _)| params.rows != *value).map(|(value, name)| html! {
+                            <option selected={params.cols == *value} value={*value}>{format!("Колонки: {}", name)}</option>
+                        }).collect::<Html>()
+                    }
+                </select>
+                <select onchange={onchange(|params, value| params.measure = value)} class={select_class}>
+                    {
+                        MEASURES.iter().map(|(value, name)| html! {
+                            <option selected={params.measure == *value} value={*value}>{*name}</option>
+                        }).collect::<Html>()
+                    }
+                </select>
+                if with_date {
+                    <select onchange={onchange(|params, value| params.group = value)} class={select_class}>
+                        {
+                            GROUPS.iter().map(|(value, name)| html! {
+                                <option selected={params.group == *value} value={*value}>{*name}</option>
+                            }).collect::<Html>()
+                        }
+                    </select>
+                }
+                <button
+                    onclick={onclick_excel}
+                    class="ml-auto px-3 py-1 rounded-md text-sm border border-gray-300 text-gray-600 hover:bg-gray-50"
+                >
+                    {"Скачать Excel"}
+                </button>
+            </div>
+            <div class="overflow-auto max-h-[60vh]">
+                <table class="w-full border-collapse bg-white text-left text-sm text-gray-500 table-auto">
+                    <thead class="bg-gray-50 sticky top-0">
+                        <tr>
+                            <th scope="col" class="px-6 py-4 font-medium text-gray-900 uppercase">{rows_name}</th>
+                            {
+                                pivot.cols.iter().map(|col| html! {
+                                    <th scope="col" class="px-6 py-4 font-medium text-gray-900 uppercase">{col.name.clone()}</th>
+                                }).collect::<Html>()
+                            }
+                            if with_cols {
+                                <th scope="col" class="px-6 py-4 font-medium text-gray-900 uppercase">{"Итого"}</th>
+                            }
+                        </tr>
+                    </thead>
+                    <tbody class="divide-y divide-gray-100 border-t border-gray-100">
+                        {
+                            pivot.rows.iter().enumerate().map(|(i, row)| html! {
+                                <tr class="hover:bg-gray-50">
+                                    <td class="px-6 py-4">{row.name.clone()}</td>
+                                    {
+                                        pivot.values[i].iter().map(|cell| html! {
+                                            <td class="px-6 py-4">
+                                                {cell.first().copied().flatten().map(format_value).unwrap_or_default()}
+                                            </td>
+                                        }).collect::<Html>()
+                                    }
+                                    if with_cols {
+                                        <td class="px-6 py-4 font-bold">
+                                            {pivot.row_totals[i].first().copied().map(format_value).unwrap_or_default()}
+                                        </td>
+                                    }
+                                </tr>
+                            }).collect::<Html>()
+                        }
+                        if !pivot.rows.is_empty() {
+                            <tr class="font-bold">
+                                <td class="px-6 py-4">{"Итого"}</td>
+                                {
+                                    pivot.col_totals.iter().map(|totals| html! {
+                                        <td class="px-6 py-4">{totals.first().copied().map(format_value).unwrap_or_default()}</td>
+                                    }).collect::<Html>()
+                                }
+                                if with_cols {
+                                    <td class="px-6 py-4">{pivot.total.first().copied().map(format_value).unwrap_or_default()}</td>
+                                }
+                            </tr>
+                        }
+                    </tbody>
+                </table>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,225 @@
+use crate::{
+    components::{
+        dashboard::{Dashboard, Output, Top},
+        footer::Footer,
+        header::component::HeaderComponent,
+    },
+    time_zone,
+};
+
+use gloo::{
+    net::http,
+    storage::{LocalStorage, Storage},
+};
+
+use yew::prelude::*;
+
+fn sparkline(values: &[i64]) -> Html {
+    // Линия выпуска по дням без осей, масштаб по максимуму
+
+    if values.len() < 2 {
+        return html! {};
+    }
+
+    let max = values.iter().copied().max().unwrap_or(0).max(1) as f64;
+    let step = 100.0 / (values.len() - 1) as f64;
+    let points = values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| {
+            format!(
+                "{:.1},{:.1}",
+                i as f64 * step,
+                28.0 - *v as f64 / max * 26.0
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    html! {
+        <svg viewBox="0 0 100 30" preserveAspectRatio="none" class="w-full h-10 mt-2">
+            <polyline
+                points={points}
+                fill="none"
+                stroke="#3b82f6"
+                stroke-width="1.5"
+                vector-effect="non-scaling-stroke"
+            />
+        </svg>
+    }
+}
+
+fn delta(value: i64, base: i64) -> Html {
+    // Изменение к предыдущему периоду
+
+    let (class, text) = match value.cmp(&base) {
+        std::cmp::Ordering::Greater => ("text-green-600", format!("+{}", value - base)),
+        std::cmp::Ordering::Less => ("text-red-600", format!("{}", value - base)),
+        std::cmp::Ordering::Equal => ("text-gray-500", "0".to_string()),
+    };
+    let percent = if base != 0 {
+        format!(" ({:+.1}%)", (value - base) as f64 / base as f64 * 100.0)
+    } else {
+        "".to_string()
+    };
+
+    html! { <span class={classes!("text-sm", class)}>{text}{percent}</span> }
+}
+
+fn card(title: &str, value: i64, footer: Html, values: &[i64]) -> Html {
+    html! {
+        <div class="rounded-lg border border-gray-200 shadow-md bg-white p-4">
+            <div class="text-sm text-gray-500 uppercase">{title}</div>
+            <div class="text-2xl font-bold text-gray-900">{value}</div>
+            {footer}
+            {sparkline(values)}
+        </div>
+    }
+}
+
+fn top_table(title: &str, items: &[Top]) -> Html {
+    html! {
+        <div class="overflow-auto rounded-lg border border-gray-200 shadow-md">
+            <table class="w-full border-collapse bg-white text-left text-sm text-gray-500 table-auto">
+                <thead class="bg-gray-50">
+                    <tr>
+                        <th scope="col" class="px-6 py-4 font-medium text-gray-900 uppercase">{title}</th>
+                        <th scope="col" class="px-6 py-4 font-medium text-gray-900 uppercase">{"Кол-во"}</th>
+                    </tr>
+                </thead>
+                <tbody class="divide-y divide-gray-100 border-t border-gray-100">
+                {
+                    items.iter().map(|top| html! {
+                        <tr class="hover:bg-gray-50">
+                            <td class="px-6 py-4">{top.item.name.clone()}</td>
+                            <td class="px-6 py-4">{format!("{} {}", top.cnt, top.measure)}</td>
+                        </tr>
+                    }).collect::<Html>()
+                }
+                if items.is_empty() {
+                    <tr>
+                        <td class="px-6 py-4" colspan="2">{"Нет выпуска за неделю"}</td>
+                    </tr>
+                }
+                </tbody>
+            </table>
+        </div>
+    }
+}
+
+fn output_cards(output: &Output) -> Html {
+    // Выпуск за сегодня и неделю в одной единице измерения
+
+    let daily = output.daily.iter().map(|p| p.cnt).collect::<Vec<i64>>();
+    let last_week = &daily[daily.len().saturating_sub(7)..];
+    let title = |title: &str| {
+        if output.measure.is_empty() {
+            title.to_string()
+        } else {
+            format!("{}, {}", title, output.measure)
+        }
+    };
+
+    html! {
+        <>
+        {card(
+            &title("Сегодня"),
+            output.today,
+            html! { <div>{delta(output.today, output.yesterday)}{" ко вчера"}</div> },
+            last_week,
+        )}
+        {card(
+            &title("Неделя"),
+            output.week,
+            html! { <div>{delta(output.week, output.previous_week)}{" к прошлой неделе"}</div> },
+            &daily,
+        )}
+        </>
+    }
+}
+
+#[function_component(DashboardComponent)]
+pub fn dashboard() -> Html {
+    // Компонент сводки: выпуск за сегодня и неделю, лидеры недели и записи, требующие внимания
+
+    let dashboard: UseStateHandle<Dashboard> = use_state_eq(Dashboard::default);
+    {
+        let dashboard = dashboard.clone();
+        use_effect_with((), move |_| {
+            let dashboard = dashboard.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let mut header_bearer = String::from("Bearer ");
+                let token: Option<String> = LocalStorage::get("token").unwrap_or(None);
+                if let Some(t) = token {
+                    header_bearer.push_str(&t);
+                }
+
+                let tz = time_zone().unwrap_or_default();
+                let response = http::Request::get("/api/dashboard")
+                    .header("Content-Type", "application/json")
+                    .header("Authorization", &header_bearer)
+                    .query([("tz", tz.as_str())])
+                    .send()
+                    .await
+                    .unwrap()
+                    .json::<Dashboard>()
+                    .await
+                    .unwrap_or_default();
+
+                dashboard.set(response);
+            });
+        });
+    }
+
+    // Выпуск в разных единицах измерения не складывается, карточки выводятся по каждой
+    let output = if dashboard.output.is_empty() {
+        vec![Output::default()]
+    } else {
+        dashboard.output.clone()
+    };
+    let attention = &dashboard.attention;
+
+    html! {
+        <>
+        <HeaderComponent />
+        <div class="grid gap-4 mx-5 my-4 sm:grid-cols-2 lg:grid-cols-4">
+            {output.iter().map(output_cards).collect::<Html>()}
+            {card(
+                "Ждут подтверждения",
+                attention.submitted,
+                html! { <div class="text-sm text-gray-500">{format!("Отклонено: {}", attention.rejected)}</div> },
+                &[],
+            )}
+            {card(
+                "Корректировки",
+                attention.pending_adjustments,
+                html! {
+                    <div class="text-sm text-gray-500">
+                        {format!(
+                            "На утверждении. За неделю одобрено: {} на {} ед.",
+                            dashboard.adjustments_cnt,
+                            dashboard.adjustments_volume,
+                        )}
+                    </div>
+                },
+                &[],
+            )}
+            if attention.open_downtimes > 0 {
+                {card(
+                    "Открытые простои",
+                    attention.open_downtimes,
+                    html! {},
+                    &[],
+                )}
+            }
+        </div>
+        <div class="grid gap-4 mx-5 my-2 md:grid-cols-2">
+            {top_table("Лучшие продукты недели", &dashboard.top_products)}
+            {top_table("Лучшие работники недели", &dashboard.top_workers)}
+        </div>
+
+        <Footer />
+
+        </>
+    }
+}
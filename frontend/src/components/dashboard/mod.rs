@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Select;
+
+pub mod component;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct Point {
+    pub date: chrono::NaiveDate,
+    pub cnt: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct Output {
+    pub measure: String,
+    pub today: i64,
+    pub yesterday: i64,
+    pub week: i64,
+    pub previous_week: i64,
+    pub daily: Vec<Point>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Top {
+    pub item: Select,
+    pub measure: String,
+    pub cnt: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct Attention {
+    pub submitted: i64,
+    pub rejected: i64,
+    pub pending_adjustments: i64,
+    pub open_downtimes: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct Dashboard {
+    pub output: Vec<Output>,
+    pub top_products: Vec<Top>,
+    pub top_workers: Vec<Top>,
+    pub attention: Attention,
+    pub adjustments_cnt: i64,
+    pub adjustments_volume: i64,
+}
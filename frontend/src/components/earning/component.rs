@@ -0,0 +1,251 @@
+use crate::{
+    check_is_admin,
+    components::{earning::Earning, footer::Footer, header::component::HeaderComponent},
+    time_zone, AppContext, Role, User,
+};
+
+use gloo::{
+    net::http,
+    storage::{LocalStorage, Storage},
+};
+
+use wasm_bindgen::JsCast;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+fn header_bearer() -> String {
+    let mut header_bearer = String::from("Bearer ");
+    let token: Option<String> = LocalStorage::get("token").unwrap_or(None);
+    if let Some(t) = token {
+        header_bearer.push_str(&t);
+    }
+
+    header_bearer
+}
+
+async fn download_report(date_one: chrono::NaiveDate, date_two: chrono::NaiveDate, format: &str) {
+    // Скачивание ведомости сдельной оплаты
+
+    let from = date_one.to_string();
+    let to = date_two.to_string();
+    let tz = time_zone().unwrap_or_default();
+    let q = vec![
+        ("date_one", from.as_str()),
+        ("date_two", to.as_str()),
+        ("tz", tz.as_str()),
+        ("format", format),
+    ];
+
+    let resp = http::Request::post("/api/upload-payroll-report")
+        .header("Authorization", &header_bearer())
+        .query(q)
+        .send()
+        .await
+        .unwrap();
+
+    if !resp.ok() {
+        return;
+    }
+
+    let filename = format!(
+        "payroll_report_{}_{}.{}",
+        date_one.format("%d.%m.%Y"),
+        date_two.format("%d.%m.%Y"),
+        format
+    );
+    let bytes = resp.binary().await.unwrap();
+
+    let u8_array = js_sys::Uint8Array::from(&bytes[..]);
+    let array = js_sys::Array::new_with_length(1);
+    array.set(0, u8_array.into());
+
+    let blob = web_sys::Blob::new_with_u8_array_sequence_and_options(
+        &array,
+        web_sys::BlobPropertyBag::new().type_("application/octet-stream"),
+    )
+    .unwrap();
+    let url = web_sys::Url::create_object_url_with_blob(&blob).unwrap();
+    let document = web_sys::window().unwrap().document().unwrap();
+
+    let a = document.create_element("a").unwrap();
+    let a = a.dyn_into::<web_sys::HtmlAnchorElement>().unwrap();
+
+    a.set_href(&url);
+    a.set_download(&filename);
+    a.click();
+
+    web_sys::Url::revoke_object_url(&url).unwrap();
+}
+
+#[function_component(EarningComponent)]
+pub fn earning() -> Html {
+    // Компонент сдельной оплаты: работник видит свои начисления,
+    // Директор и администратор - ведомость по всем работникам с выгрузкой
+
+    let ctx = use_context::<AppContext>();
+    let current_user: Option<User> = ctx.and_then(|ctx| ctx.0.clone());
+    let is_manager = current_user
+        .as_ref()
+        .is_some_and(|u| u.role == Role::Director || check_is_admin(u.role));
+
+    let date_naive = chrono::Utc::now().date_naive();
+    // По умолчанию текущий месяц
+    let date_one = use_state_eq(|| {
+        chrono::NaiveDate::from_ymd_opt(
+            chrono::Datelike::year(&date_naive),
+            chrono::Datelike::month(&date_naive),
+            1,
+        )
+        .unwrap_or(date_naive)
+    });
+    let date_two = use_state_eq(|| date_naive);
+
+    let items: UseStateHandle<Vec<Earning>> = use_state_eq(Vec::new);
+    {
+        let items = items.clone();
+        use_effect_with(
+            (*date_one, *date_two, is_manager),
+            move |(date_one, date_two, is_manager)| {
+                let items = items.clone();
+                let from = date_one.to_string();
+                let to = date_two.to_string();
+                let tz = time_zone().unwrap_or_default();
+                let url = if *is_manager {
+                    "/api/payroll"
+                } else {
+                    "/api/payroll/my"
+                };
+                wasm_bindgen_futures::spawn_local(async move {
+                    let response = http::Request::get(url)
+                        .header("Content-Type", "application/json")
+                        .header("Authorization", &header_bearer())
+                        .query([
+                            ("date_one", from.as_str()),
+                            ("date_two", to.as_str()),
+                            ("tz", tz.as_str()),
+                        ])
+                        .send()
+                        .await
+                        .unwrap()
+                        .json::<Vec<Earning>>()
+                        .await
+                        .unwrap_or_default();
+
+                    items.set(response);
+                });
+            },
+        );
+    }
+
+    let onchange_date_one = {
+        let date_one = date_one.clone();
+        Callback::from(move |event: Event| {
+            let value = event
+                .target()
+                .unwrap()
+                .unchecked_into::<HtmlInputElement>()
+                .value();
+
+            if let Ok(value) = chrono::NaiveDate::parse_from_str(value.as_str(), "%Y-%m-%d") {
+                date_one.set(value);
+            }
+        })
+    };
+
+    let onchange_date_two = {
+        let date_two = date_two.clone();
+        Callback::from(move |event: Event| {
+            let value = event
+                .target()
+                .unwrap()
+                .unchecked_into::<HtmlInputElement>()
+                .value();
+
+            if let Ok(value) = chrono::NaiveDate::parse_from_str(value.as_str(), "%Y-%m-%d") {
+                date_two.set(value);
+            }
+        })
+    };
+
+    let onclick_download = |format: &'static str| {
+        let date_one = *date_one;
+        let date_two = *date_two;
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+
+            wasm_bindgen_futures::spawn_local(async move {
+                download_report(date_one, date_two, format).await;
+            });
+        })
+    };
+
+    let total: f64 = items.iter().map(|item| item.amount).sum();
+    let class = "px-4 py-2 text-gray-600 rounded-md font-normal text-sm border border-gray-300 focus:border-indigo-700 focus:outline-none";
+    let button_class =
+        "min-w-[151px] px-4 py-2 bg-blue-500 text-white rounded-md hover:bg-blue-700 mt-2 mx-2";
+
+    html! {
+        <>
+        <HeaderComponent />
+        if is_manager {
+            <div class="flex justify-end mx-3">
+                <button onclick={onclick_download("xlsx")} class={button_class}>
+                    {"Ведомость Excel"}
+                </button>
+                <button onclick={onclick_download("csv")} class={button_class}>
+                    {"Ведомость CSV"}
+                </button>
+            </div>
+        }
+        <div class="flex gap-2 mx-5 mt-2">
+            <input type="date" onchange={onchange_date_one} {class} value={date_one.to_string()} />
+            <input type="date" onchange={onchange_date_two} {class} value={date_two.to_string()} />
+        </div>
+        <div class="overflow-auto rounded-lg border border-gray-200 shadow-md mx-5 my-2 max-h-[68%]">
+            <table class="w-full border-collapse bg-white text-left text-sm text-gray-500 table-auto">
+                <thead class="bg-gray-50 sticky top-0">
+                    <tr>
+                    if is_manager {
+                        <th scope="col" class="px-6 py-4 font-medium text-gray-900 uppercase">{"Работник"}</th>
+                    }
+                    <th scope="col" class="px-6 py-4 font-medium text-gray-900 uppercase">{"Продукт"}</th>
+                    <th scope="col" class="px-6 py-4 font-medium text-gray-900 uppercase">{"Eдиница Измерения"}</th>
+                    <th scope="col" class="px-6 py-4 font-medium text-gray-900 uppercase">{"Кол-во"}</th>
+                    <th scope="col" class="px-6 py-4 font-medium text-gray-900 uppercase">{"Расценка, руб."}</th>
+                    <th scope="col" class="px-6 py-4 font-medium text-gray-900 uppercase">{"Сумма, руб."}</th>
+                    </tr>
+                </thead>
+                <tbody class="divide-y divide-gray-100 border-t border-gray-100">
+                {
+                    items.iter().map(|item| {
+                        html! {
+                            <tr class="hover:bg-gray-50">
+                                if is_manager {
+                                    <td class="px-6 py-4">{item.user.name.clone()}</td>
+                                }
+                                <td class="px-6 py-4">{item.product.name.clone()}</td>
+                                <td class="px-6 py-4">{item.measure.clone()}</td>
+                                <td class="px-6 py-4">{item.cnt}</td>
+                                <td class="px-6 py-4">
+                                    {item.rate.map_or("не задана".to_string(), |rate| format!("{:.2}", rate))}
+                                </td>
+                                <td class="px-6 py-4">{format!("{:.2}", item.amount)}</td>
+                            </tr>
+                        }
+                    }).collect::<Html>()
+                }
+                if !items.is_empty() {
+                    <tr class="font-bold">
+                        <td class="px-6 py-4" colspan={if is_manager { "5" } else { "4" }}>{"Итого"}</td>
+                        <td class="px-6 py-4">{format!("{:.2}", total)}</td>
+                    </tr>
+                }
+                </tbody>
+            </table>
+        </div>
+
+        <Footer />
+
+        </>
+    }
+}
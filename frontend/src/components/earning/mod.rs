@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Select;
+
+pub mod component;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Earning {
+    pub measure: String,
+    pub rate: Option<f64>,
+    pub cnt: i64,
+    pub amount: f64,
+
+    pub user: Select,
+    pub product: Select,
+}
@@ -0,0 +1,277 @@
+use gloo::{
+    net::http,
+    storage::{LocalStorage, Storage},
+};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::Select;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Attachment {
+    pub id: i64,
+    pub file_name: String,
+    pub content_type: String,
+    pub size: i64,
+    pub has_thumbnail: bool,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub user: Select,
+}
+
+fn header_bearer() -> String {
+    let mut header_bearer = String::from("Bearer ");
+    let token: Option<String> = LocalStorage::get("token").unwrap_or(None);
+    if let Some(t) = token {
+        header_bearer.push_str(&t);
+    }
+
+    header_bearer
+}
+
+async fn fetch_object_url(url: &str) -> Option<String> {
+    // Файлы отдаются только с токеном, поэтому загружаем их в Blob и даем ссылку на него
+
+    let resp = http::Request::get(url)
+        .header("Authorization", &header_bearer())
+        .send()
+        .await
+        .ok()?;
+
+    if !resp.ok() {
+        return None;
+    }
+
+    let content_type = resp.headers().get("Content-Type").unwrap_or_default();
+    let bytes = resp.binary().await.ok()?;
+
+    let u8_array = js_sys::Uint8Array::from(&bytes[..]);
+    let array = js_sys::Array::new_with_length(1);
+    array.set(0, u8_array.into());
+
+    let blob = web_sys::Blob::new_with_u8_array_sequence_and_options(
+        &array,
+        web_sys::BlobPropertyBag::new().type_(&content_type),
+    )
+    .ok()?;
+
+    web_sys::Url::create_object_url_with_blob(&blob).ok()
+}
+
+#[derive(Properties, PartialEq)]
+struct ThumbnailProps {
+    id: i64,
+}
+
+#[function_component(Thumbnail)]
+fn thumbnail(ThumbnailProps { id }: &ThumbnailProps) -> Html {
+    // Превью изображения
+
+    let src: UseStateHandle<Option<String>> = use_state_eq(|| None);
+    // Ссылка на Blob освобождается при удалении превью
+    let object_url = use_mut_ref(|| None::<String>);
+    {
+        let src = src.clone();
+        let object_url = object_url.clone();
+        use_effect_with(*id, move |id| {
+            let id = *id;
+            let cloned_object_url = object_url.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let url = fetch_object_url(&format!("/api/attachments/{}/thumbnail", id)).await;
+
+                *cloned_object_url.borrow_mut() = url.clone();
+                src.set(url);
+            });
+
+            move || {
+                if let Some(url) = object_url.borrow_mut().take() {
+                    let _ = web_sys::Url::revoke_object_url(&url);
+                }
+            }
+        });
+    }
+
+    html! {
+        if let Some(src) = (*src).clone() {
+            <img src={src} class="w-20 h-20 object-cover rounded border border-gray-200" />
+        } else {
+            <div class="w-20 h-20 rounded border border-gray-200 bg-gray-100" />
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    // Адрес списка вложений записи, например /api/products/1/attachments
+    pub url: String,
+    #[prop_or("image/jpeg,image/png,image/webp".to_string())]
+    pub accept: String,
+    #[prop_or(true)]
+    pub editable: bool,
+}
+
+#[function_component(Attachments)]
+pub fn attachments(
+    Props {
+        url,
+        accept,
+        editable,
+    }: &Props,
+) -> Html {
+    // Компонент списка вложений записи с загрузкой, скачиванием и удалением
+
+    let items: UseStateHandle<Vec<Attachment>> = use_state_eq(Vec::new);
+    let rendered = use_state_eq(|| true);
+    let error: UseStateHandle<Option<String>> = use_state_eq(|| None);
+
+    {
+        let items = items.clone();
+        let rendered = rendered.clone();
+        use_effect_with((url.clone(), *rendered), move |(url, _)| {
+            let url = url.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let response = http::Request::get(&url)
+                    .header("Content-Type", "application/json")
+                    .header("Authorization", &header_bearer())
+                    .send()
+                    .await
+                    .unwrap()
+                    .json::<Vec<Attachment>>()
+                    .await
+                    .unwrap_or_default();
+
+                items.set(response);
+            });
+        });
+    }
+
+    let onchange_file = {
+        let url = url.clone();
+        let rendered = rendered.clone();
+        let error = error.clone();
+        Callback::from(move |event: Event| {
+            let input = event.target().unwrap().unchecked_into::<HtmlInputElement>();
+            let Some(file) = input.files().and_then(|files| files.get(0)) else {
+                return;
+            };
+            input.set_value("");
+
+            let url = url.clone();
+            let rendered = rendered.clone();
+            let error = error.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let form = web_sys::FormData::new().unwrap();
+                form.append_with_blob_and_filename("file", &file, &file.name())
+                    .unwrap();
+
+                let resp = http::Request::post(&url)
+                    .header("Authorization", &header_bearer())
+                    .body(form)
+                    .unwrap()
+                    .send()
+                    .await
+                    .unwrap();
+
+                if resp.ok() {
+                    error.set(None);
+                } else {
+                    let detail = resp
+                        .json::<crate::ResponseMsg>()
+                        .await
+                        .map(|msg| msg.detail)
+                        .unwrap_or("Не удалось загрузить файл".to_string());
+
+                    error.set(Some(detail));
+                }
+
+                rendered.set(!*rendered);
+            });
+        })
+    };
+
+    let on_download = Callback::from(move |item: Attachment| {
+        wasm_bindgen_futures::spawn_local(async move {
+            if let Some(url) = fetch_object_url(&format!("/api/attachments/{}", item.id)).await {
+                let document = web_sys::window().unwrap().document().unwrap();
+                let a = document.create_element("a").unwrap();
+                let a = a.dyn_into::<web_sys::HtmlAnchorElement>().unwrap();
+
+                a.set_href(&url);
+                a.set_download(&item.file_name);
+                a.click();
+
+                web_sys::Url::revoke_object_url(&url).unwrap();
+            }
+        });
+    });
+
+    let on_delete = {
+        let rendered = rendered.clone();
+        Callback::from(move |id: i64| {
+            let rendered = rendered.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let _ = http::Request::delete(&format!("/api/attachments/{}", id))
+                    .header("Content-Type", "application/json")
+                    .header("Authorization", &header_bearer())
+                    .send()
+                    .await;
+
+                rendered.set(!*rendered);
+            });
+        })
+    };
+
+    html! {
+        <div class="mb-5">
+            <label class="text-gray-800 text-sm font-bold leading-tight tracking-normal">{"Файлы"}</label>
+            <div class="flex flex-wrap gap-2 mt-2">
+            {
+                items.iter().map(|item| {
+                    let cloned_item = item.clone();
+                    let on_download = on_download.clone();
+                    let onclick_download = Callback::from(move |_: MouseEvent| on_download.emit(cloned_item.clone()));
+
+                    let id = item.id;
+                    let on_delete = on_delete.clone();
+                    let onclick_delete = Callback::from(move |_: MouseEvent| on_delete.emit(id));
+
+                    html! {
+                        <div class="relative w-20 text-xs text-gray-600" title={item.file_name.clone()}>
+                            <button type="button" onclick={onclick_download} class="block">
+                                if item.has_thumbnail {
+                                    <Thumbnail id={item.id} />
+                                } else {
+                                    <div class="w-20 h-20 flex items-center justify-center rounded border border-gray-200 bg-gray-50 uppercase">
+                                        {item.file_name.rsplit('.').next().unwrap_or("file").to_string()}
+                                    </div>
+                                }
+                            </button>
+                            <p class="truncate">{item.file_name.clone()}</p>
+                            if *editable {
+                                <button
+                                    type="button"
+                                    onclick={onclick_delete}
+                                    class="absolute top-0 right-0 px-1 bg-white rounded text-red-500 hover:text-red-700">
+                                    {"×"}
+                                </button>
+                            }
+                        </div>
+                    }
+                }).collect::<Html>()
+            }
+            </div>
+            if *editable {
+                <input
+                    type="file"
+                    accept={accept.clone()}
+                    onchange={onchange_file}
+                    class="mt-2 text-sm text-gray-600"
+                />
+            }
+            if let Some(error) = (*error).clone() {
+                <p class="text-sm text-red-600">{error}</p>
+            }
+        </div>
+    }
+}
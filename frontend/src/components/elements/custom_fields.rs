@@ -0,0 +1,212 @@
+use gloo::{
+    net::http,
+    storage::{LocalStorage, Storage},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use crate::ResponseItems;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CustomField {
+    pub id: i64,
+    pub entity: String,
+    pub code: String,
+    pub name: String,
+    pub kind: String,
+    pub options: Vec<String>,
+    pub required: bool,
+}
+
+pub async fn fetch_custom_fields(entity: &str, organization_id: Option<i64>) -> Vec<CustomField> {
+    // Описания дополнительных полей организации
+
+    let mut header_bearer = String::from("Bearer ");
+    let token: Option<String> = LocalStorage::get("token").unwrap_or(None);
+    if let Some(t) = token {
+        header_bearer.push_str(&t);
+    }
+
+    let organization_id = organization_id.map(|o| o.to_string());
+    let mut q = vec![("page", "1"), ("per_page", "10000"), ("entity", entity)];
+    if let Some(organization_id) = &organization_id {
+        q.push(("organization_id", organization_id));
+    }
+
+    match http::Request::get("/api/custom-fields")
+        .header("Content-Type", "application/json")
+        .header("Authorization", &header_bearer)
+        .query(q)
+        .send()
+        .await
+    {
+        Ok(resp) => resp
+            .json::<ResponseItems<CustomField>>()
+            .await
+            .map(|r| r.items)
+            .unwrap_or_default(),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn value_to_string(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Null) | None => "".to_string(),
+        Some(v) => v.to_string(),
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    // Product или ProducedGood
+    pub entity: String,
+    pub organization_id: Option<i64>,
+    pub values: Value,
+    pub on_change: Callback<Value>,
+    #[prop_or(false)]
+    pub disabled: bool,
+}
+
+#[function_component(CustomFields)]
+pub fn custom_fields(
+    Props {
+        entity,
+        organization_id,
+        values,
+        on_change,
+        disabled,
+    }: &Props,
+) -> Html {
+    // Поля формы по описаниям дополнительных полей организации
+
+    let fields: UseStateHandle<Vec<CustomField>> = use_state_eq(Vec::new);
+    {
+        let fields = fields.clone();
+        use_effect_with((entity.clone(), *organization_id), move |(entity, org)| {
+            let entity = entity.clone();
+            let org = *org;
+            wasm_bindgen_futures::spawn_local(async move {
+                fields.set(fetch_custom_fields(&entity, org).await);
+            });
+        });
+    }
+
+    let on_value = {
+        let values = values.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |(code, value): (String, Value)| {
+            let mut values = match values.clone() {
+                Value::Object(values) => values,
+                _ => serde_json::Map::new(),
+            };
+            values.insert(code, value);
+
+            on_change.emit(Value::Object(values));
+        })
+    };
+
+    let class = "mb-5 mt-2 text-gray-600 focus:outline-none focus:border focus:border-indigo-700 font-normal w-full h-10 flex items-center pl-3 text-sm border-gray-300 rounded border";
+
+    html! {
+        <>
+        {
+            fields.iter().map(|field| {
+                let value = values.get(&field.code);
+                let code = field.code.clone();
+                let on_value = on_value.clone();
+
+                let input = match field.kind.as_str() {
+                    "Boolean" => {
+                        let onchange = Callback::from(move |event: Event| {
+                            let checked = event
+                                .target()
+                                .unwrap()
+                                .unchecked_into::<HtmlInputElement>()
+                                .checked();
+
+                            on_value.emit((code.clone(), Value::Bool(checked)));
+                        });
+
+                        html! {
+                            <input
+                                type="checkbox"
+                                class="mb-5 mt-2 block"
+                                disabled={*disabled}
+                                checked={value.and_then(Value::as_bool).unwrap_or(false)}
+                                {onchange}
+                            />
+                        }
+                    }
+                    "Enum" => {
+                        let onchange = Callback::from(move |event: Event| {
+                            let value = event
+                                .target()
+                                .unwrap()
+                                .unchecked_into::<HtmlSelectElement>()
+                                .value();
+
+                            on_value.emit((code.clone(), Value::String(value)));
+                        });
+                        let current = value_to_string(value);
+
+                        html! {
+                            <select {class} disabled={*disabled} required={field.required} {onchange}>
+                                <option selected={current.is_empty()} value="">{"—"}</option>
+                                {
+                                    field.options.iter().map(|o| html! {
+                                        <option selected={current == *o} value={o.clone()}>{o.clone()}</option>
+                                    }).collect::<Html>()
+                                }
+                            </select>
+                        }
+                    }
+                    kind => {
+                        let onchange = Callback::from(move |event: Event| {
+                            let value = event
+                                .target()
+                                .unwrap()
+                                .unchecked_into::<HtmlInputElement>()
+                                .value();
+
+                            on_value.emit((code.clone(), Value::String(value)));
+                        });
+                        let input_type = match kind {
+                            "Number" => "number",
+                            "Date" => "date",
+                            _ => "text",
+                        };
+
+                        html! {
+                            <input
+                                type={input_type}
+                                step="any"
+                                {class}
+                                disabled={*disabled}
+                                required={field.required}
+                                value={value_to_string(value)}
+                                {onchange}
+                            />
+                        }
+                    }
+                };
+
+                html! {
+                    <>
+                    <label class="text-gray-800 text-sm font-bold leading-tight tracking-normal">
+                        {field.name.clone()}
+                        if field.required {
+                            {" *"}
+                        }
+                    </label>
+                    {input}
+                    </>
+                }
+            }).collect::<Html>()
+        }
+        </>
+    }
+}
@@ -1,7 +1,10 @@
+pub mod attachments;
+pub mod custom_fields;
 pub mod error;
 pub mod input;
 pub mod loader;
 pub mod modal;
 pub mod multiselect;
 pub mod paginate;
+pub mod prices;
 pub mod select;
@@ -0,0 +1,209 @@
+use gloo::{
+    net::http,
+    storage::{LocalStorage, Storage},
+};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::Select;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Price {
+    pub id: i64,
+    pub valid_from: chrono::NaiveDate,
+    pub cost: f64,
+    pub price: f64,
+    pub user: Select,
+}
+
+fn header_bearer() -> String {
+    let mut header_bearer = String::from("Bearer ");
+    let token: Option<String> = LocalStorage::get("token").unwrap_or(None);
+    if let Some(t) = token {
+        header_bearer.push_str(&t);
+    }
+
+    header_bearer
+}
+
+fn input_value(event: Event) -> String {
+    event
+        .target()
+        .unwrap()
+        .unchecked_into::<HtmlInputElement>()
+        .value()
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub product_id: i64,
+}
+
+#[function_component(Prices)]
+pub fn prices(Props { product_id }: &Props) -> Html {
+    // Компонент истории себестоимости и цен продукта
+
+    let items: UseStateHandle<Vec<Price>> = use_state_eq(Vec::new);
+    let rendered = use_state_eq(|| true);
+    let error: UseStateHandle<Option<String>> = use_state_eq(|| None);
+
+    let valid_from = use_state_eq(|| chrono::Utc::now().date_naive().to_string());
+    let cost = use_state_eq(String::new);
+    let price = use_state_eq(String::new);
+
+    {
+        let items = items.clone();
+        let rendered = rendered.clone();
+        use_effect_with((*product_id, *rendered), move |(product_id, _)| {
+            let product_id = *product_id;
+            wasm_bindgen_futures::spawn_local(async move {
+                let response = http::Request::get(&format!("/api/products/{}/prices", product_id))
+                    .header("Content-Type", "application/json")
+                    .header("Authorization", &header_bearer())
+                    .send()
+                    .await
+                    .unwrap()
+                    .json::<Vec<Price>>()
+                    .await
+                    .unwrap_or_default();
+
+                items.set(response);
+            });
+        });
+    }
+
+    let onchange_valid_from = {
+        let valid_from = valid_from.clone();
+        Callback::from(move |event: Event| valid_from.set(input_value(event)))
+    };
+    let onchange_cost = {
+        let cost = cost.clone();
+        Callback::from(move |event: Event| cost.set(input_value(event)))
+    };
+    let onchange_price = {
+        let price = price.clone();
+        Callback::from(move |event: Event| price.set(input_value(event)))
+    };
+
+    let onclick_add = {
+        let product_id = *product_id;
+        let rendered = rendered.clone();
+        let error = error.clone();
+        let valid_from = valid_from.clone();
+        let cost = cost.clone();
+        let price = price.clone();
+        Callback::from(move |_: MouseEvent| {
+            let body = serde_json::json!({
+                "valid_from": *valid_from,
+                "cost": cost.parse::<f64>().unwrap_or(0.0),
+                "price": price.parse::<f64>().unwrap_or(0.0),
+            });
+
+            let rendered = rendered.clone();
+            let error = error.clone();
+            let cost = cost.clone();
+            let price = price.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let resp = http::Request::post(&format!("/api/products/{}/prices", product_id))
+                    .header("Content-Type", "application/json")
+                    .header("Authorization", &header_bearer())
+                    .json(&body)
+                    .unwrap()
+                    .send()
+                    .await
+                    .unwrap();
+
+                if resp.ok() {
+                    error.set(None);
+                    cost.set(String::new());
+                    price.set(String::new());
+                } else {
+                    let detail = resp
+                        .json::<crate::ResponseMsg>()
+                        .await
+                        .map(|msg| msg.detail)
+                        .unwrap_or("Не удалось сохранить цену".to_string());
+
+                    error.set(Some(detail));
+                }
+
+                rendered.set(!*rendered);
+            });
+        })
+    };
+
+    let on_delete = {
+        let rendered = rendered.clone();
+        Callback::from(move |id: i64| {
+            let rendered = rendered.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let _ = http::Request::delete(&format!("/api/product-prices/{}", id))
+                    .header("Content-Type", "application/json")
+                    .header("Authorization", &header_bearer())
+                    .send()
+                    .await;
+
+                rendered.set(!*rendered);
+            });
+        })
+    };
+
+    let class = "mt-2 text-gray-600 focus:outline-none focus:border focus:border-indigo-700 font-normal w-full h-10 flex items-center pl-3 text-sm border-gray-300 rounded border";
+
+    html! {
+        <div class="mb-5">
+            <label class="text-gray-800 text-sm font-bold leading-tight tracking-normal">{"Себестоимость и цена"}</label>
+            <table class="w-full mt-2 text-sm text-left text-gray-600">
+                <thead>
+                    <tr>
+                        <th class="py-1">{"Действует с"}</th>
+                        <th class="py-1">{"Себестоимость"}</th>
+                        <th class="py-1">{"Цена"}</th>
+                        <th class="py-1"></th>
+                    </tr>
+                </thead>
+                <tbody>
+                {
+                    items.iter().map(|item| {
+                        let id = item.id;
+                        let on_delete = on_delete.clone();
+                        let onclick_delete = Callback::from(move |_: MouseEvent| on_delete.emit(id));
+
+                        html! {
+                            <tr title={item.user.name.clone()}>
+                                <td class="py-1">{item.valid_from.format("%d.%m.%Y").to_string()}</td>
+                                <td class="py-1">{format!("{:.2}", item.cost)}</td>
+                                <td class="py-1">{format!("{:.2}", item.price)}</td>
+                                <td class="py-1 text-right">
+                                    <button
+                                        type="button"
+                                        onclick={onclick_delete}
+                                        class="px-1 text-red-500 hover:text-red-700">
+                                        {"×"}
+                                    </button>
+                                </td>
+                            </tr>
+                        }
+                    }).collect::<Html>()
+                }
+                </tbody>
+            </table>
+            <div class="flex gap-2 items-end">
+                <input type="date" {class} value={(*valid_from).clone()} onchange={onchange_valid_from} />
+                <input type="number" step="0.01" min="0" {class} placeholder="Себестоимость" value={(*cost).clone()} onchange={onchange_cost} />
+                <input type="number" step="0.01" min="0" {class} placeholder="Цена" value={(*price).clone()} onchange={onchange_price} />
+                <button
+                    type="button"
+                    onclick={onclick_add}
+                    class="h-10 bg-blue-500 hover:bg-blue-700 rounded text-white px-4 text-sm">
+                    {"+"}
+                </button>
+            </div>
+            if let Some(error) = (*error).clone() {
+                <p class="text-sm text-red-600">{error}</p>
+            }
+        </div>
+    }
+}
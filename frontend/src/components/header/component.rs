@@ -29,11 +29,15 @@ pub fn header() -> Html {
 
     let mut menus: Vec<(Route, String)> = vec![(Route::Home, String::from("Производство"))];
     if let Some(u) = current_user.clone() {
+        menus.push((Route::Dashboard, String::from("Сводка")));
+        menus.push((Route::Earning, String::from("Заработок")));
+
         if u.role == Role::Director {
             menus.extend(vec![
                 (Route::Product, String::from("Товары")),
                 (Route::MeasureUnit, String::from("Единицы измерения")),
                 (Route::User, String::from("Пользователи")),
+                (Route::WorkOrder, String::from("Заказы")),
                 (Route::Analitic, String::from("Аналитика")),
             ])
         }
@@ -44,6 +48,7 @@ pub fn header() -> Html {
                 (Route::MeasureUnit, String::from("Единицы измерения")),
                 (Route::User, String::from("Пользователи")),
                 (Route::Organization, String::from("Организации")),
+                (Route::WorkOrder, String::from("Заказы")),
                 (Route::Analitic, String::from("Аналитика")),
             ])
         }
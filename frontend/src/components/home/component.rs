@@ -17,21 +17,66 @@ use crate::{
         },
         footer::Footer,
         header::component::HeaderComponent,
-        home::{list::ProducedGoodList, modal::Modal, ProducedGood},
+        home::{
+            confirm::ModalConfirm, list::ProducedGoodList, modal::Modal, ProducedGood,
+            ProducedGoodForm,
+        },
         PER_PAGE,
     },
-    AppContext, ResponseId, ResponseItems, ResponseMsg, Route, User,
+    AppContext, ResponseItems, ResponseMsg, Role, Route, User,
 };
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct RequestData {
     product_id: i64,
     cnt: i64,
+    work_order_id: Option<i64>,
+    defects: Vec<RequestDataDefect>,
+    lot: Option<String>,
+    custom_fields: serde_json::Value,
+    machine_id: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct ResponseCreated {
+    id: i64,
+    lot: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RequestDataDefect {
+    defect_reason_id: i64,
+    cnt: i64,
+}
+
+impl RequestDataDefect {
+    fn from_pairs(defects: Vec<(i64, i64)>) -> Vec<Self> {
+        defects
+            .into_iter()
+            .map(|(defect_reason_id, cnt)| Self {
+                defect_reason_id,
+                cnt,
+            })
+            .collect()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct RequestDataStatus {
+    status: String,
+    comment: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct RequestDataConfirm {
+    ids: Vec<i64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 struct RequestDataAdj {
     cnt: i64,
+    reason_id: i64,
+    comment: String,
 }
 
 #[function_component(HomeComponent)]
@@ -48,8 +93,15 @@ pub fn home() -> Html {
     let is_visible = use_state_eq(|| false);
     let is_visible_del = use_state_eq(|| false);
     let is_adj = use_state_eq(|| false);
+    // Очередь записей, ожидающих подтверждения Директором
+    let is_queue = use_state_eq(|| false);
+    let can_review = current_user
+        .as_ref()
+        .is_some_and(|u| check_is_admin(u.role) || u.role == Role::Director);
 
     let item: UseStateHandle<Option<ProducedGood>> = use_state_eq(|| None);
+    // Созданная запись для подтверждения с номером партии: (партия, кол-во)
+    let created: UseStateHandle<Option<(String, i64)>> = use_state_eq(|| None);
     let items: UseStateHandle<ResponseItems<ProducedGood>> = use_state_eq(|| ResponseItems {
         cnt: 0,
         items: vec![],
@@ -57,6 +109,8 @@ pub fn home() -> Html {
 
     {
         let items = items.clone();
+        // Переключение очереди перерисовывает список через rendered
+        let queue = *is_queue;
         use_effect_with((page, rendered.clone()), move |(page, rendered)| {
             let items = items.clone();
             let page = *page;
@@ -68,13 +122,18 @@ pub fn home() -> Html {
                     header_bearer.push_str(&t);
                 }
 
-                let response = http::Request::get("/api/produced-goods")
+                let mut request = http::Request::get("/api/produced-goods")
                     .header("Content-Type", "application/json")
                     .header("Authorization", &header_bearer)
                     .query([
                         ("page", page.clone().to_string().as_str()),
                         ("per_page", PER_PAGE.to_string().as_str()),
-                    ])
+                    ]);
+                if queue {
+                    request = request.query([("status", "Submitted")]);
+                }
+
+                let response = request
                     .send()
                     .await
                     .unwrap()
@@ -129,7 +188,7 @@ pub fn home() -> Html {
         let cloned_rendered = rendered.clone();
         let cloned_is_adj = is_adj.clone();
         let navigator = use_navigator();
-        Callback::from(move |cnt| {
+        Callback::from(move |(cnt, reason_id, comment)| {
             let mut header_bearer = String::from("Bearer ");
             let token: Option<String> = LocalStorage::get("token").unwrap_or(None);
             if let Some(t) = token.clone() {
@@ -142,7 +201,11 @@ pub fn home() -> Html {
             let cloned_is_adj = cloned_is_adj.clone();
             let navigator = navigator.clone();
             wasm_bindgen_futures::spawn_local(async move {
-                let req_data = RequestDataAdj { cnt };
+                let req_data = RequestDataAdj {
+                    cnt,
+                    reason_id,
+                    comment,
+                };
                 // Хак для Home
                 let path = "/api/produced-goods";
 
@@ -176,8 +239,9 @@ pub fn home() -> Html {
         let cloned_is_visible = is_visible.clone();
         let cloned_item = item.clone();
         let cloned_rendered = rendered.clone();
+        let cloned_created = created.clone();
         let navigator = use_navigator();
-        Callback::from(move |(product_id, cnt)| {
+        Callback::from(move |form: ProducedGoodForm| {
             // e.prevent_default();
 
             let mut header_bearer = String::from("Bearer ");
@@ -189,9 +253,18 @@ pub fn home() -> Html {
             let cloned_is_visible = cloned_is_visible.clone();
             let cloned_item = cloned_item.clone();
             let cloned_rendered = cloned_rendered.clone();
+            let cloned_created = cloned_created.clone();
             let navigator = navigator.clone();
             wasm_bindgen_futures::spawn_local(async move {
-                let req_data = RequestData { product_id, cnt };
+                let req_data = RequestData {
+                    product_id: form.product_id,
+                    cnt: form.cnt,
+                    work_order_id: form.work_order_id,
+                    defects: RequestDataDefect::from_pairs(form.defects),
+                    lot: form.lot,
+                    custom_fields: form.custom_fields,
+                    machine_id: form.machine_id,
+                };
                 // Хак для Home
                 let path = "/api/produced-goods";
 
@@ -208,7 +281,7 @@ pub fn home() -> Html {
                         .await
                         .unwrap();
                 } else {
-                    let _: ResponseId = http::Request::post(path)
+                    let response: ResponseCreated = http::Request::post(path)
                         .header("Content-Type", "application/json")
                         .header("Authorization", &header_bearer)
                         .json(&req_data)
@@ -219,6 +292,8 @@ pub fn home() -> Html {
                         .json()
                         .await
                         .unwrap();
+
+                    cloned_created.set(Some((response.lot, form.cnt)));
                 }
 
                 cloned_is_visible.set(!*cloned_is_visible);
@@ -231,6 +306,115 @@ pub fn home() -> Html {
         })
     };
 
+    let toggle_queue = {
+        let cloned_is_queue = is_queue.clone();
+        let cloned_rendered = rendered.clone();
+        let navigator = use_navigator();
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+
+            cloned_is_queue.set(!*cloned_is_queue);
+            cloned_rendered.set(true);
+            // Очередь открывается с первой страницы
+            if let Some(navigator) = navigator.clone() {
+                navigator.push(&Route::Home);
+            }
+        })
+    };
+
+    let on_review = {
+        let cloned_rendered = rendered.clone();
+        Callback::from(move |(item, confirmed): (ProducedGood, bool)| {
+            // При отклонении Директор указывает причину
+            let comment = if confirmed {
+                None
+            } else {
+                match web_sys::window()
+                    .and_then(|w| w.prompt_with_message("Причина отклонения").ok())
+                    .flatten()
+                    .filter(|c| !c.trim().is_empty())
+                {
+                    Some(comment) => Some(comment),
+                    None => return,
+                }
+            };
+
+            let mut header_bearer = String::from("Bearer ");
+            let token: Option<String> = LocalStorage::get("token").unwrap_or(None);
+            if let Some(t) = token.clone() {
+                header_bearer.push_str(&t);
+            }
+
+            let cloned_rendered = cloned_rendered.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let req_data = RequestDataStatus {
+                    status: if confirmed { "Confirmed" } else { "Rejected" }.to_string(),
+                    comment,
+                };
+
+                let _: ResponseMsg =
+                    http::Request::patch(&format!("/api/produced-goods/{}/status", item.id))
+                        .header("Content-Type", "application/json")
+                        .header("Authorization", &header_bearer)
+                        .json(&req_data)
+                        .unwrap()
+                        .send()
+                        .await
+                        .unwrap()
+                        .json()
+                        .await
+                        .unwrap();
+
+                cloned_rendered.set(true); // для перерисовки списка после действий.
+            });
+        })
+    };
+
+    let on_confirm_all = {
+        let cloned_items = items.clone();
+        let cloned_rendered = rendered.clone();
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+
+            let mut header_bearer = String::from("Bearer ");
+            let token: Option<String> = LocalStorage::get("token").unwrap_or(None);
+            if let Some(t) = token.clone() {
+                header_bearer.push_str(&t);
+            }
+
+            // Подтверждаются записи текущей страницы очереди
+            let req_data = RequestDataConfirm {
+                ids: cloned_items.items.iter().map(|it| it.id).collect(),
+            };
+            if req_data.ids.is_empty() {
+                return;
+            }
+
+            let cloned_rendered = cloned_rendered.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let _ = http::Request::post("/api/produced-goods/confirm")
+                    .header("Content-Type", "application/json")
+                    .header("Authorization", &header_bearer)
+                    .json(&req_data)
+                    .unwrap()
+                    .send()
+                    .await
+                    .unwrap();
+
+                cloned_rendered.set(true); // для перерисовки списка после действий.
+            });
+        })
+    };
+
+    let toggle_confirm = {
+        let cloned_created = created.clone();
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+
+            cloned_created.set(None);
+        })
+    };
+
     let cloned_is_visible_del = is_visible_del.clone();
     let toggle_modal_del = {
         let cloned_item = item.clone();
@@ -299,6 +483,20 @@ pub fn home() -> Html {
         <HeaderComponent />
 
         <div class="flex justify-end mb-5">
+            if can_review {
+                if *is_queue {
+                    <button
+                        onclick={on_confirm_all}
+                        class="px-4 py-2 bg-green-500 text-white rounded-md mr-5 mt-5 hover:bg-green-700">
+                        {"Подтвердить все"}
+                    </button>
+                }
+                <button
+                    onclick={toggle_queue}
+                    class="px-4 py-2 bg-gray-100 text-gray-700 border rounded-md mr-5 mt-5 hover:bg-gray-300">
+                    {if *is_queue {"Все записи"} else {"Ожидают подтверждения"}}
+                </button>
+            }
             <button
                 onclick={toggle_modal.clone()}
                 class="px-4 py-2 bg-blue-500 text-white rounded-md mr-5 mt-5 hover:bg-blue-700">
@@ -317,6 +515,7 @@ pub fn home() -> Html {
                         }
                         <th scope="col" class="px-6 py-4 font-medium text-gray-900 uppercase">{"Корректировки"}</th>
                         <th scope="col" class="px-6 py-4 font-medium text-gray-900 uppercase">{"Кол-во"}</th>
+                        <th scope="col" class="px-6 py-4 font-medium text-gray-900 uppercase">{"Статус"}</th>
                         <th scope="col" class="px-6 py-4 font-medium text-gray-900 uppercase">{"Дата создания"}</th>
                         <th scope="col" class="px-6 py-4 font-medium text-gray-900 uppercase"></th>
                     </tr>
@@ -328,6 +527,7 @@ pub fn home() -> Html {
                         {on_edit}
                         {on_add_adj}
                         on_delete={on_delete_modal}
+                        {on_review}
                     />
                 </tbody>
             </table>
@@ -359,6 +559,12 @@ pub fn home() -> Html {
             {on_save_adj}
         />
 
+        <ModalConfirm
+            lot={created.as_ref().map(|(lot, _)| lot.clone())}
+            cnt={created.as_ref().map_or(0, |(_, cnt)| *cnt)}
+            toggle={toggle_confirm}
+        />
+
         <Footer />
 
         </>
@@ -0,0 +1,55 @@
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq, Default)]
+pub struct Props {
+    pub lot: Option<String>,
+    pub cnt: i64,
+    pub toggle: Callback<MouseEvent>,
+}
+
+#[function_component(ModalConfirm)]
+pub fn modal_confirm(props: &Props) -> Html {
+    // Подтверждение внесения выпуска с номером партии для печати
+
+    let on_print = Callback::from(move |e: MouseEvent| {
+        e.prevent_default();
+
+        if let Some(window) = web_sys::window() {
+            let _ = window.print();
+        }
+    });
+
+    html! {
+        <div class={format!("relative z-10 {}", if props.lot.is_none() {"hidden"} else {""})} aria-labelledby="modal-title" role="dialog" aria-modal="true">
+            <div class="fixed inset-0 bg-gray-500 bg-opacity-75 transition-opacity print:hidden"></div>
+            <div class="fixed inset-0 z-10 w-screen overflow-y-auto">
+            <div class="flex min-h-full items-end justify-center p-4 text-center sm:items-center sm:p-0">
+                <div class="relative transform overflow-hidden rounded-lg bg-white text-left shadow-xl transition-all sm:my-8 sm:w-full sm:max-w-lg print:shadow-none">
+                <div class="bg-white px-4 pb-4 pt-5 sm:p-6 sm:pb-4">
+                    <h3 class="text-base font-semibold leading-6 text-gray-900 print:hidden" id="modal-title">{"Выпуск сохранен"}</h3>
+                    <div class="mt-4 text-center">
+                        <p class="text-sm text-gray-500">{format!("Кол-во: {}", props.cnt)}</p>
+                        <p class="mt-2 text-xs uppercase text-gray-500">{"Партия"}</p>
+                        <p class="text-3xl font-bold tracking-wider text-gray-900">{props.lot.clone().unwrap_or_default()}</p>
+                    </div>
+                </div>
+                <div class="bg-gray-50 px-4 py-3 sm:flex sm:flex-row-reverse sm:px-6 print:hidden">
+                    <button
+                        onclick={on_print}
+                        type="button" class="inline-flex w-full justify-center rounded-md bg-blue-500 px-3 py-2 text-sm font-semibold text-white shadow-sm hover:bg-blue-700 sm:ml-3 sm:w-auto">
+                        {"Печать"}
+                    </button>
+                    <button
+                        onclick={props.toggle.clone()}
+                        type="button"
+                        class="mt-3 inline-flex w-full justify-center rounded-md bg-white px-3 py-2 text-sm font-semibold text-gray-900 shadow-sm ring-1 ring-inset ring-gray-300 hover:bg-gray-50 sm:mt-0 sm:w-auto"
+                    >
+                        {"Закрыть"}
+                    </button>
+                </div>
+                </div>
+            </div>
+            </div>
+        </div>
+    }
+}
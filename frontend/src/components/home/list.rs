@@ -1,4 +1,4 @@
-use super::ProducedGood;
+use super::{ProducedGood, Status};
 use crate::{check_is_admin, Role, User};
 use chrono::Local;
 use yew::prelude::*;
@@ -10,6 +10,8 @@ pub struct Props {
     pub on_edit: Callback<ProducedGood>,
     pub on_add_adj: Callback<ProducedGood>,
     pub on_delete: Callback<ProducedGood>,
+    // Подтверждение (true) или отклонение (false) записи Директором
+    pub on_review: Callback<(ProducedGood, bool)>,
 }
 
 #[function_component(ProducedGoodList)]
@@ -20,6 +22,7 @@ pub fn produced_good_lists(
         on_edit,
         on_add_adj,
         on_delete,
+        on_review,
     }: &Props,
 ) -> Html {
     // Компонент списка данных для домвшней страницы
@@ -60,6 +63,34 @@ pub fn produced_good_lists(
                 })
             };
 
+            let on_confirm = {
+                let on_review = on_review.clone();
+                let cloned_item = item.clone();
+                Callback::from(move |e: MouseEvent| {
+                    e.prevent_default();
+
+                    on_review.emit((cloned_item.clone(), true));
+                })
+            };
+
+            let on_reject = {
+                let on_review = on_review.clone();
+                let cloned_item = item.clone();
+                Callback::from(move |e: MouseEvent| {
+                    e.prevent_default();
+
+                    on_review.emit((cloned_item.clone(), false));
+                })
+            };
+
+            let (status, color_status) = match item.status {
+                Status::Submitted => ("Ожидает", "text-yellow-600 bg-yellow-50"),
+                Status::Confirmed => ("Подтверждено", "text-green-600 bg-green-50"),
+                Status::Rejected => ("Отклонено", "text-red-600 bg-red-50"),
+            };
+            let can_review = item.status == Status::Submitted
+                && current_user.as_ref().is_some_and(|u| check_is_admin(u.role) || u.role == Role::Director);
+
             // Color adj
             let color_adj = if item.adj >= 0 {"text-green-600 text-green-50"} else {"text-red-600 text-red-50"};
             html! {
@@ -69,6 +100,9 @@ pub fn produced_good_lists(
                         <div class="text-sm">
                         <div class="font-medium text-gray-700">{item.product.name.clone()}</div>
                             <div class="text-gray-400">{item.product.measure_unit.name.clone()}</div>
+                            if let Some(lot) = item.lot.clone() {
+                                <div class="text-xs text-gray-400">{format!("Партия: {}", lot)}</div>
+                            }
                         </div>
                     </th>
                     <td class="px-6 py-4">{item.user.fio.clone()}</td>
@@ -83,9 +117,35 @@ pub fn produced_good_lists(
                     </span>
                     </td>
                     <td class="px-6 py-4">{item.cnt + item.adj}</td>
+                    <td class="px-6 py-4">
+                    <span
+                        title={item.review_comment.clone().unwrap_or_default()}
+                        class={format!("inline-flex items-center gap-1 rounded-full px-2 py-1 text-xs font-semibold {}", color_status)}
+                    >
+                        {status}
+                    </span>
+                    </td>
                     <td class="px-6 py-4">{item.created_at.with_timezone(&Local).format("%d.%m.%Y %H:%M").to_string()}</td>
                     <td class="px-6 py-4">
                     <div class="flex justify-end gap-4">
+                        if can_review {
+                            <a
+                            onclick={on_confirm}
+                            title="Подтвердить"
+                            href="#">
+                            <svg xmlns="http://www.w3.org/2000/svg" fill="none" viewBox="0 0 24 24" stroke-width="1.5" stroke="currentColor" class="w-6 h-6 text-green-600">
+                                <path stroke-linecap="round" stroke-linejoin="round" d="M4.5 12.75l6 6 9-13.5" />
+                            </svg>
+                            </a>
+                            <a
+                            onclick={on_reject}
+                            title="Отклонить"
+                            href="#">
+                            <svg xmlns="http://www.w3.org/2000/svg" fill="none" viewBox="0 0 24 24" stroke-width="1.5" stroke="currentColor" class="w-6 h-6 text-red-600">
+                                <path stroke-linecap="round" stroke-linejoin="round" d="M6 18L18 6M6 6l12 12" />
+                            </svg>
+                            </a>
+                        }
                         <a
                         onclick={on_add_adj}
                         x-data="{ tooltip: 'Delete' }" href="#">
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use crate::Select;
 
 pub mod component;
+pub mod confirm;
 pub mod list;
 pub mod modal;
 
@@ -19,13 +20,59 @@ pub struct ItemProduct {
     pub measure_unit: Select,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Defect {
+    pub id: i64,
+    pub name: String,
+    pub cnt: i64,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub enum Status {
+    #[default]
+    Submitted,
+    Confirmed,
+    Rejected,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct ProducedGood {
     pub id: i64,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub cnt: i64,
     pub adj: i64,
+    pub defect: i64,
+    pub defects: Vec<Defect>,
+    pub work_order_id: Option<i64>,
+    #[serde(default)]
+    pub machine_id: Option<i64>,
+    pub lot: Option<String>,
+    pub status: Status,
+    pub review_comment: Option<String>,
+    #[serde(default)]
+    pub custom_fields: serde_json::Value,
     pub user: USelect,
     pub product: ItemProduct,
     pub organization: Select,
 }
+
+// Данные формы записи производства для создания и редактирования
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ProducedGoodForm {
+    pub product_id: i64,
+    pub cnt: i64,
+    pub work_order_id: Option<i64>,
+    // Брак по причинам: (id причины, кол-во)
+    pub defects: Vec<(i64, i64)>,
+    pub lot: Option<String>,
+    pub custom_fields: serde_json::Value,
+    pub machine_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Machine {
+    pub id: i64,
+    pub name: String,
+    pub active: bool,
+    pub organization: Select,
+}
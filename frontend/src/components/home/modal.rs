@@ -1,5 +1,13 @@
-use super::ProducedGood;
-use crate::{check_is_admin, components::rbs::product::Product, ResponseItems, User};
+use super::{Machine, ProducedGood, ProducedGoodForm};
+use crate::{
+    check_is_admin,
+    components::{
+        elements::{attachments::Attachments, custom_fields::CustomFields},
+        rbs::product::Product,
+        work_order::WorkOrder,
+    },
+    ResponseItems, Select, User,
+};
 use gloo::{
     net::http,
     storage::{LocalStorage, Storage},
@@ -16,8 +24,8 @@ pub struct Props {
     pub item: Option<ProducedGood>,
 
     pub toggle_modal: Callback<MouseEvent>,
-    pub on_save: Callback<(i64, i64)>,
-    pub on_save_adj: Callback<i64>,
+    pub on_save: Callback<ProducedGoodForm>,
+    pub on_save_adj: Callback<(i64, i64, String)>,
 }
 
 #[function_component(Modal)]
@@ -37,14 +45,37 @@ pub fn modal(
     let product_id = use_state_eq(|| 0);
     let cnt = use_state_eq(|| 0);
     let adj = use_state_eq(|| 0);
+    let adj_reason_id = use_state_eq(|| 0);
+    let adj_comment = use_state_eq(|| "".to_string());
+    let work_order_id: UseStateHandle<Option<i64>> = use_state_eq(|| None);
+    let machine_id: UseStateHandle<Option<i64>> = use_state_eq(|| None);
+    let lot = use_state_eq(|| "".to_string());
+    // Брак по причинам: (id причины, кол-во)
+    let defects: UseStateHandle<Vec<(i64, i64)>> = use_state_eq(Vec::new);
+    let custom_fields = use_state_eq(|| serde_json::Value::Null);
 
     let products: UseStateHandle<Vec<Product>> = use_state_eq(Vec::new);
+    let work_orders: UseStateHandle<Vec<WorkOrder>> = use_state_eq(Vec::new);
+    let machines: UseStateHandle<Vec<Machine>> = use_state_eq(Vec::new);
+    let defect_reasons: UseStateHandle<Vec<Select>> = use_state_eq(Vec::new);
+    let adj_reasons: UseStateHandle<Vec<Select>> = use_state_eq(Vec::new);
 
     {
         let cloned_products = products.clone();
         let cloned_item = item.clone();
         let cloned_product_id = product_id.clone();
         let cloned_cnt = cnt.clone();
+        let cloned_work_orders = work_orders.clone();
+        let cloned_work_order_id = work_order_id.clone();
+        let cloned_machines = machines.clone();
+        let cloned_machine_id = machine_id.clone();
+        let cloned_defects = defects.clone();
+        let cloned_defect_reasons = defect_reasons.clone();
+        let cloned_lot = lot.clone();
+        let cloned_custom_fields = custom_fields.clone();
+        let cloned_adj_reasons = adj_reasons.clone();
+        let cloned_adj_reason_id = adj_reason_id.clone();
+        let cloned_adj_comment = adj_comment.clone();
         use_effect_with(*is_visible, move |visible| {
             if *visible {
                 wasm_bindgen_futures::spawn_local(async move {
@@ -71,16 +102,88 @@ pub fn modal(
                     if let Some(item) = cloned_item.clone() {
                         cloned_product_id.set(item.product.id);
                         cloned_cnt.set(item.cnt);
+                        cloned_work_order_id.set(item.work_order_id);
+                        cloned_machine_id.set(item.machine_id);
+                        cloned_defects.set(item.defects.iter().map(|d| (d.id, d.cnt)).collect());
+                        cloned_lot.set(item.lot.unwrap_or_default());
+                        cloned_custom_fields.set(item.custom_fields);
                     } else {
                         cloned_product_id.set(response.items.last().map_or(0, |it| it.id));
                         cloned_cnt.set(0);
+                        cloned_work_order_id.set(None);
+                        cloned_machine_id.set(None);
+                        cloned_defects.set(Vec::new());
+                        cloned_lot.set("".to_string());
+                        cloned_custom_fields.set(serde_json::Value::Null);
                     }
+
+                    // Открытые заказы для привязки производства
+                    let response = http::Request::get("/api/work-orders")
+                        .header("Content-Type", "application/json")
+                        .header("Authorization", &header_bearer)
+                        .query([("page", "1"), ("per_page", "10000")])
+                        .send()
+                        .await
+                        .unwrap()
+                        .json::<ResponseItems<WorkOrder>>()
+                        .await
+                        .unwrap();
+
+                    cloned_work_orders.set(response.items);
+
+                    // Оборудование организации
+                    let response = http::Request::get("/api/machines")
+                        .header("Content-Type", "application/json")
+                        .header("Authorization", &header_bearer)
+                        .query([("page", "1"), ("per_page", "10000")])
+                        .send()
+                        .await
+                        .unwrap()
+                        .json::<ResponseItems<Machine>>()
+                        .await
+                        .unwrap_or(ResponseItems {
+                            items: vec![],
+                            cnt: 0,
+                        });
+
+                    cloned_machines.set(response.items);
+
+                    // Причины брака организации
+                    let response = http::Request::get("/api/defect-reasons")
+                        .header("Content-Type", "application/json")
+                        .header("Authorization", &header_bearer)
+                        .query([("page", "1"), ("per_page", "10000")])
+                        .send()
+                        .await
+                        .unwrap()
+                        .json::<ResponseItems<Select>>()
+                        .await
+                        .unwrap();
+
+                    cloned_defect_reasons.set(response.items);
+
+                    // Причины корректировки организации
+                    let response = http::Request::get("/api/adjustment-reasons")
+                        .header("Content-Type", "application/json")
+                        .header("Authorization", &header_bearer)
+                        .query([("page", "1"), ("per_page", "10000")])
+                        .send()
+                        .await
+                        .unwrap()
+                        .json::<ResponseItems<Select>>()
+                        .await
+                        .unwrap();
+
+                    cloned_adj_reason_id.set(response.items.first().map_or(0, |it| it.id));
+                    cloned_adj_comment.set("".to_string());
+                    cloned_adj_reasons.set(response.items);
                 })
             }
         });
     }
 
     let cloned_product_id = product_id.clone();
+    let cloned_work_order_id = work_order_id.clone();
     let onchange_product = Callback::from(move |event: Event| {
         let value = event
             .target()
@@ -89,8 +192,54 @@ pub fn modal(
             .value();
 
         cloned_product_id.set(value.parse::<i64>().ok().unwrap_or(0));
+        cloned_work_order_id.set(None); // Заказ привязан к продукту
+    });
+
+    let cloned_work_order_id = work_order_id.clone();
+    let onchange_work_order = Callback::from(move |event: Event| {
+        let value = event
+            .target()
+            .unwrap()
+            .unchecked_into::<HtmlSelectElement>()
+            .value();
+
+        cloned_work_order_id.set(value.parse::<i64>().ok());
+    });
+
+    let cloned_machine_id = machine_id.clone();
+    let onchange_machine = Callback::from(move |event: Event| {
+        let value = event
+            .target()
+            .unwrap()
+            .unchecked_into::<HtmlSelectElement>()
+            .value();
+
+        cloned_machine_id.set(value.parse::<i64>().ok());
     });
 
+    // Действующее оборудование организации выбранного продукта
+    let product_organization_id = products
+        .iter()
+        .find(|p| p.id == *product_id)
+        .map(|p| p.organization.id);
+    let available_machines = (*machines)
+        .iter()
+        .filter(|m| {
+            Some(m.organization.id) == product_organization_id
+                && (m.active || Some(m.id) == *machine_id)
+        })
+        .cloned()
+        .collect::<Vec<Machine>>();
+
+    // Заказы, к которым можно привязать выбранный продукт
+    let open_work_orders = (*work_orders)
+        .iter()
+        .filter(|wo| {
+            wo.product.id == *product_id && (wo.status.is_open() || Some(wo.id) == *work_order_id)
+        })
+        .cloned()
+        .collect::<Vec<WorkOrder>>();
+
     let cloned_cnt = cnt.clone();
     let onchange_cnt = Callback::from(move |event: Event| {
         let value = event
@@ -113,10 +262,72 @@ pub fn modal(
         cloned_adj.set(value.parse::<i64>().ok().unwrap_or(0));
     });
 
+    let cloned_adj_reason_id = adj_reason_id.clone();
+    let onchange_adj_reason = Callback::from(move |event: Event| {
+        let value = event
+            .target()
+            .unwrap()
+            .unchecked_into::<HtmlSelectElement>()
+            .value();
+
+        cloned_adj_reason_id.set(value.parse::<i64>().ok().unwrap_or(0));
+    });
+
+    let cloned_adj_comment = adj_comment.clone();
+    let onchange_adj_comment = Callback::from(move |event: Event| {
+        let value = event
+            .target()
+            .unwrap()
+            .unchecked_into::<HtmlInputElement>()
+            .value();
+
+        cloned_adj_comment.set(value.trim().to_string());
+    });
+
+    let cloned_lot = lot.clone();
+    let onchange_lot = Callback::from(move |event: Event| {
+        let value = event
+            .target()
+            .unwrap()
+            .unchecked_into::<HtmlInputElement>()
+            .value();
+
+        cloned_lot.set(value.trim().to_string());
+    });
+
+    let onchange_defect = {
+        let cloned_defects = defects.clone();
+        Callback::from(move |(reason_id, event): (i64, Event)| {
+            let value = event
+                .target()
+                .unwrap()
+                .unchecked_into::<HtmlInputElement>()
+                .value();
+            let value = value.parse::<i64>().ok().unwrap_or(0);
+
+            let mut items = (*cloned_defects)
+                .iter()
+                .filter(|(id, _)| *id != reason_id)
+                .cloned()
+                .collect::<Vec<(i64, i64)>>();
+            if value > 0 {
+                items.push((reason_id, value));
+            }
+            cloned_defects.set(items);
+        })
+    };
+
     let on_save = {
         let cloned_product_id = product_id.clone();
         let cloned_cnt = cnt.clone();
         let cloned_adj = adj.clone();
+        let cloned_adj_reason_id = adj_reason_id.clone();
+        let cloned_adj_comment = adj_comment.clone();
+        let cloned_work_order_id = work_order_id.clone();
+        let cloned_machine_id = machine_id.clone();
+        let cloned_defects = defects.clone();
+        let cloned_lot = lot.clone();
+        let cloned_custom_fields = custom_fields.clone();
         let cloned_on_save = on_save.clone();
         let cloned_on_save_adj = on_save_adj.clone();
         let cloned_is_adj = *is_adj;
@@ -124,13 +335,37 @@ pub fn modal(
             e.prevent_default();
 
             if cloned_is_adj {
-                cloned_on_save_adj.emit(*cloned_adj);
+                cloned_on_save_adj.emit((
+                    *cloned_adj,
+                    *cloned_adj_reason_id,
+                    (*cloned_adj_comment).clone(),
+                ));
             } else {
-                cloned_on_save.emit((*cloned_product_id, *cloned_cnt));
+                cloned_on_save.emit(ProducedGoodForm {
+                    product_id: *cloned_product_id,
+                    cnt: *cloned_cnt,
+                    work_order_id: *cloned_work_order_id,
+                    defects: (*cloned_defects).clone(),
+                    lot: Some((*cloned_lot).clone()).filter(|l| !l.is_empty()),
+                    custom_fields: (*cloned_custom_fields).clone(),
+                    machine_id: *cloned_machine_id,
+                });
             }
         })
     };
 
+    let cloned_custom_fields = custom_fields.clone();
+    let onchange_custom_fields = Callback::from(move |values: serde_json::Value| {
+        cloned_custom_fields.set(values);
+    });
+
+    // Администратор видит дополнительные поля организации выбранного продукта
+    let custom_fields_organization_id = current_user
+        .as_ref()
+        .filter(|u| check_is_admin(u.role))
+        .and_then(|_| products.iter().find(|p| p.id == *product_id))
+        .map(|p| p.organization.id);
+
     html! {
         <div>
             <div
@@ -176,6 +411,52 @@ pub fn modal(
                                         }).collect::<Html>()
                                     }
                                 </select>
+                                if !open_work_orders.is_empty() {
+                                    <label for="work_order" class="text-gray-800 text-sm font-bold leading-tight tracking-normal">{"Заказ"}</label>
+                                    <select
+                                        disabled={*is_adj}
+                                        onchange={onchange_work_order}
+                                        id="work_order"
+                                        class="mb-5 mt-2 text-gray-600 focus:outline-none focus:border focus:border-indigo-700 font-normal w-full h-10 flex items-center pl-3 text-sm border-gray-300 rounded border"
+                                        placeholder="Выберите заказ">
+                                        <option selected={work_order_id.is_none()} value="">{"Без заказа"}</option>
+                                        {
+                                            open_work_orders.iter().map(|wo| {
+                                                html! {
+                                                    <option
+                                                        selected={Some(wo.id) == *work_order_id}
+                                                        value={wo.id.to_string()}
+                                                    >
+                                                        {format!("#{} ({} / {}, до {})", wo.id, wo.done, wo.cnt, wo.due_date.format("%d.%m.%Y"))}
+                                                    </option>
+                                                }
+                                            }).collect::<Html>()
+                                        }
+                                    </select>
+                                }
+                                if !available_machines.is_empty() {
+                                    <label for="machine" class="text-gray-800 text-sm font-bold leading-tight tracking-normal">{"Оборудование"}</label>
+                                    <select
+                                        disabled={*is_adj}
+                                        onchange={onchange_machine}
+                                        id="machine"
+                                        class="mb-5 mt-2 text-gray-600 focus:outline-none focus:border focus:border-indigo-700 font-normal w-full h-10 flex items-center pl-3 text-sm border-gray-300 rounded border"
+                                        placeholder="Выберите оборудование">
+                                        <option selected={machine_id.is_none()} value="">{"Не указано"}</option>
+                                        {
+                                            available_machines.iter().map(|m| {
+                                                html! {
+                                                    <option
+                                                        selected={Some(m.id) == *machine_id}
+                                                        value={m.id.to_string()}
+                                                    >
+                                                        {m.name.clone()}
+                                                    </option>
+                                                }
+                                            }).collect::<Html>()
+                                        }
+                                    </select>
+                                }
                                 <label for="cnt" class="text-gray-800 text-sm font-bold leading-tight tracking-normal">
                                     {"Кол-во"}
                                 </label>
@@ -197,6 +478,48 @@ pub fn modal(
                                         }
                                     }
                                 />
+                                <label for="lot" class="text-gray-800 text-sm font-bold leading-tight tracking-normal">
+                                    {"Партия"}
+                                </label>
+                                <input
+                                    disabled={*is_adj}
+                                    onchange={onchange_lot}
+                                    type="text"
+                                    id="lot"
+                                    class="mb-5 mt-2 text-gray-600 focus:outline-none focus:border focus:border-indigo-700 font-normal w-full h-10 flex items-center pl-3 text-sm border-gray-300 rounded border"
+                                    placeholder="Сформируется автоматически"
+                                    value={(*lot).clone()}
+                                />
+                                if !defect_reasons.is_empty() && !*is_adj {
+                                    <label class="text-gray-800 text-sm font-bold leading-tight tracking-normal">{"Брак"}</label>
+                                    <div class="grid grid-cols-2 gap-x-4 mt-2 mb-2">
+                                    {
+                                        (*defect_reasons).iter().map(|reason| {
+                                            let reason_id = reason.id;
+                                            let onchange = onchange_defect.reform(move |e: Event| (reason_id, e));
+                                            let value = defects
+                                                .iter()
+                                                .find(|(id, _)| *id == reason_id)
+                                                .map_or("".to_string(), |(_, cnt)| cnt.to_string());
+
+                                            html! {
+                                                <div>
+                                                    <label for={format!("defect_{}", reason_id)} class="text-gray-600 text-xs">{reason.name.clone()}</label>
+                                                    <input
+                                                        {onchange}
+                                                        type="number"
+                                                        min=0
+                                                        id={format!("defect_{}", reason_id)}
+                                                        class="mb-2 mt-1 text-gray-600 focus:outline-none focus:border focus:border-indigo-700 font-normal w-full h-8 flex items-center pl-3 text-sm border-gray-300 rounded border"
+                                                        placeholder="0"
+                                                        {value}
+                                                    />
+                                                </div>
+                                            }
+                                        }).collect::<Html>()
+                                    }
+                                    </div>
+                                }
                                 if let Some(it) = item.clone() {
                                     <p
                                         class="flex items-center gap-1 mb-5 font-sans text-sm antialiased font-normal leading-normal text-gray-700"
@@ -229,6 +552,43 @@ pub fn modal(
                                         class="mb-5 mt-2 text-gray-600 focus:outline-none focus:border focus:border-indigo-700 font-normal w-full h-10 flex items-center pl-3 text-sm border-gray-300 rounded border"
                                         placeholder="Введите корректировку"
                                     />
+                                    <label for="adj_reason" class="text-gray-800 text-sm font-bold leading-tight tracking-normal">{"Причина"}</label>
+                                    <select
+                                        onchange={onchange_adj_reason}
+                                        required={true}
+                                        id="adj_reason"
+                                        class="mb-5 mt-2 text-gray-600 focus:outline-none focus:border focus:border-indigo-700 font-normal w-full h-10 flex items-center pl-3 text-sm border-gray-300 rounded border"
+                                    >
+                                    {
+                                        (*adj_reasons).iter().map(|reason| html! {
+                                            <option value={reason.id.to_string()} selected={reason.id == *adj_reason_id}>{reason.name.clone()}</option>
+                                        }).collect::<Html>()
+                                    }
+                                    </select>
+                                    <label for="adj_comment" class="text-gray-800 text-sm font-bold leading-tight tracking-normal">{"Комментарий"}</label>
+                                    <input
+                                        onchange={onchange_adj_comment}
+                                        required={true}
+                                        type="text"
+                                        id="adj_comment"
+                                        class="mb-5 mt-2 text-gray-600 focus:outline-none focus:border focus:border-indigo-700 font-normal w-full h-10 flex items-center pl-3 text-sm border-gray-300 rounded border"
+                                        placeholder="Опишите причину корректировки"
+                                        value={(*adj_comment).clone()}
+                                    />
+                                }
+                                if !*is_adj {
+                                    <CustomFields
+                                        entity="ProducedGood"
+                                        organization_id={custom_fields_organization_id}
+                                        values={(*custom_fields).clone()}
+                                        on_change={onchange_custom_fields}
+                                    />
+                                }
+                                if let Some(it) = item.as_ref().filter(|_| !*is_adj) {
+                                    <Attachments
+                                        url={format!("/api/produced-goods/{}/attachments", it.id)}
+                                        accept="image/jpeg,image/png,image/webp,application/pdf"
+                                    />
                                 }
                                 <div class="flex items-center justify-center w-full">
                                     <button
@@ -5,6 +5,8 @@ use yew::prelude::*;
 
 pub mod analitic;
 pub mod auth;
+pub mod dashboard;
+pub mod earning;
 pub mod elements;
 pub mod footer;
 pub mod header;
@@ -13,6 +15,7 @@ pub mod not_found;
 pub mod organization;
 pub mod rbs;
 pub mod user;
+pub mod work_order;
 
 // Для пагинации
 const PER_PAGE: i64 = 8;
@@ -2,7 +2,6 @@ use gloo::{
     net::http,
     storage::{LocalStorage, Storage},
 };
-use serde::{Deserialize, Serialize};
 use std::ops::Deref;
 use yew::prelude::*;
 use yew_router::hooks::{use_location, use_navigator};
@@ -16,20 +15,13 @@ use crate::{
         },
         footer::Footer,
         header::component::HeaderComponent,
-        rbs::product::Product,
         rbs::product::{list::ProductList, modal::Modal},
+        rbs::product::{Product, ProductForm},
         PER_PAGE,
     },
     AppContext, ResponseId, ResponseItems, ResponseMsg, Route, User,
 };
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct RequestData {
-    organization_id: i64,
-    measure_unit_id: i64,
-    name: String,
-}
-
 #[function_component(ProductComponent)]
 pub fn product() -> Html {
     // Компонент домашней страницы
@@ -130,7 +122,7 @@ pub fn product() -> Html {
         let cloned_item = item.clone();
         let cloned_rendered = rendered.clone();
         let navigator = use_navigator();
-        Callback::from(move |(measure_unit_id, name, organization_id)| {
+        Callback::from(move |req_data: ProductForm| {
             let mut header_bearer = String::from("Bearer ");
             let token: Option<String> = LocalStorage::get("token").unwrap_or(None);
             if let Some(t) = token.clone() {
@@ -142,11 +134,6 @@ pub fn product() -> Html {
             let cloned_rendered = cloned_rendered.clone();
             let navigator = navigator.clone();
             wasm_bindgen_futures::spawn_local(async move {
-                let req_data = RequestData {
-                    organization_id,
-                    measure_unit_id,
-                    name,
-                };
                 // Хак для Home
                 let path = "/api/products";
 
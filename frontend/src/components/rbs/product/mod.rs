@@ -10,8 +10,39 @@ pub mod modal;
 pub struct Product {
     pub id: i64,
     pub name: String,
+    pub lot_pattern: Option<String>,
+    pub sku: Option<String>,
+    pub barcode: Option<String>,
+    pub barcode_type: Option<String>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub custom_fields: serde_json::Value,
     pub created_at: chrono::DateTime<chrono::Utc>,
 
     pub organization: Select,
     pub measure_unit: Select,
+    pub category: Option<Select>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ProductCategory {
+    pub id: i64,
+    pub parent_id: Option<i64>,
+    pub name: String,
+    pub path: String,
+}
+
+// Данные формы продукта для создания и редактирования
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct ProductForm {
+    pub organization_id: i64,
+    pub measure_unit_id: i64,
+    pub name: String,
+    pub lot_pattern: Option<String>,
+    pub sku: Option<String>,
+    pub barcode: Option<String>,
+    pub barcode_type: Option<String>,
+    pub description: Option<String>,
+    pub category_id: Option<i64>,
+    pub custom_fields: serde_json::Value,
 }
@@ -1,11 +1,18 @@
-use super::Product;
-use crate::{check_is_admin, components::rbs::measure::MeasureUnit, ResponseItems, Select, User};
+use super::{Product, ProductCategory, ProductForm};
+use crate::{
+    check_is_admin,
+    components::{
+        elements::{attachments::Attachments, custom_fields::CustomFields, prices::Prices},
+        rbs::measure::MeasureUnit,
+    },
+    ResponseItems, Select, User,
+};
 use gloo::{
     net::http,
     storage::{LocalStorage, Storage},
 };
 use wasm_bindgen::JsCast;
-use web_sys::{HtmlInputElement, HtmlSelectElement};
+use web_sys::{HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement};
 use yew::prelude::*;
 
 #[derive(Properties, PartialEq, Default)]
@@ -15,7 +22,7 @@ pub struct Props {
     pub item: Option<Product>,
 
     pub toggle_modal: Callback<MouseEvent>,
-    pub on_save: Callback<(i64, String, i64)>,
+    pub on_save: Callback<ProductForm>,
 }
 
 #[function_component(Modal)]
@@ -31,6 +38,15 @@ pub fn modal(
     // Заполнение данными
 
     let name = use_state_eq(|| "".to_string());
+    let lot_pattern = use_state_eq(|| "".to_string());
+    let sku = use_state_eq(|| "".to_string());
+    let barcode = use_state_eq(|| "".to_string());
+    let barcode_type = use_state_eq(|| "EAN13".to_string());
+    let description = use_state_eq(|| "".to_string());
+
+    let category_id: UseStateHandle<Option<i64>> = use_state_eq(|| None);
+    let categories: UseStateHandle<Vec<ProductCategory>> = use_state_eq(Vec::new);
+    let custom_fields = use_state_eq(|| serde_json::Value::Null);
 
     let organization_id = use_state_eq(|| 0);
     let organizations: UseStateHandle<Vec<Select>> = use_state(Vec::new);
@@ -43,6 +59,14 @@ pub fn modal(
         let cloned_item = item.clone();
         let cloned_mu_id = mu_id.clone();
         let cloned_name = name.clone();
+        let cloned_lot_pattern = lot_pattern.clone();
+        let cloned_sku = sku.clone();
+        let cloned_barcode = barcode.clone();
+        let cloned_barcode_type = barcode_type.clone();
+        let cloned_description = description.clone();
+        let cloned_category_id = category_id.clone();
+        let cloned_categories = categories.clone();
+        let cloned_custom_fields = custom_fields.clone();
         let cloned_organization_id = organization_id.clone();
         let cloned_organizations = organizations.clone();
         let cloned_current_user = current_user.clone();
@@ -79,13 +103,40 @@ pub fn modal(
 
                         cloned_measure_units.set(response.items.clone());
 
+                        let categories = http::Request::get("/api/product-categories")
+                            .header("Content-Type", "application/json")
+                            .header("Authorization", &header_bearer)
+                            .query([("page", "1"), ("per_page", "10000")])
+                            .send()
+                            .await
+                            .unwrap()
+                            .json::<ResponseItems<ProductCategory>>()
+                            .await
+                            .unwrap();
+
+                        cloned_categories.set(categories.items);
+
                         if let Some(item) = cloned_item.clone() {
                             cloned_mu_id.set(item.measure_unit.id);
                             cloned_name.set(item.name);
+                            cloned_lot_pattern.set(item.lot_pattern.unwrap_or_default());
+                            cloned_sku.set(item.sku.unwrap_or_default());
+                            cloned_barcode.set(item.barcode.unwrap_or_default());
+                            cloned_barcode_type.set(item.barcode_type.unwrap_or("EAN13".into()));
+                            cloned_description.set(item.description.unwrap_or_default());
+                            cloned_category_id.set(item.category.map(|c| c.id));
+                            cloned_custom_fields.set(item.custom_fields);
                             cloned_organization_id.set(item.organization.id);
                         } else {
                             cloned_mu_id.set(response.items.last().map_or(0, |it| it.id));
                             cloned_name.set("".to_string());
+                            cloned_lot_pattern.set("".to_string());
+                            cloned_sku.set("".to_string());
+                            cloned_barcode.set("".to_string());
+                            cloned_barcode_type.set("EAN13".to_string());
+                            cloned_description.set("".to_string());
+                            cloned_category_id.set(None);
+                            cloned_custom_fields.set(serde_json::Value::Null);
                             cloned_organization_id.set(cloned_current_user.map_or(0, |i| i.id));
                         }
 
@@ -136,6 +187,77 @@ pub fn modal(
         cloned_name.set(value);
     });
 
+    let cloned_lot_pattern = lot_pattern.clone();
+    let onchange_lot_pattern = Callback::from(move |event: Event| {
+        let value = event
+            .target()
+            .unwrap()
+            .unchecked_into::<HtmlInputElement>()
+            .value();
+
+        cloned_lot_pattern.set(value);
+    });
+
+    let cloned_sku = sku.clone();
+    let onchange_sku = Callback::from(move |event: Event| {
+        let value = event
+            .target()
+            .unwrap()
+            .unchecked_into::<HtmlInputElement>()
+            .value();
+
+        cloned_sku.set(value);
+    });
+
+    let cloned_barcode = barcode.clone();
+    let onchange_barcode = Callback::from(move |event: Event| {
+        let value = event
+            .target()
+            .unwrap()
+            .unchecked_into::<HtmlInputElement>()
+            .value();
+
+        cloned_barcode.set(value);
+    });
+
+    let cloned_barcode_type = barcode_type.clone();
+    let onchange_barcode_type = Callback::from(move |event: Event| {
+        let value = event
+            .target()
+            .unwrap()
+            .unchecked_into::<HtmlSelectElement>()
+            .value();
+
+        cloned_barcode_type.set(value);
+    });
+
+    let cloned_description = description.clone();
+    let onchange_description = Callback::from(move |event: Event| {
+        let value = event
+            .target()
+            .unwrap()
+            .unchecked_into::<HtmlTextAreaElement>()
+            .value();
+
+        cloned_description.set(value);
+    });
+
+    let cloned_category_id = category_id.clone();
+    let onchange_category = Callback::from(move |event: Event| {
+        let value = event
+            .target()
+            .unwrap()
+            .unchecked_into::<HtmlSelectElement>()
+            .value();
+
+        cloned_category_id.set(value.parse::<i64>().ok());
+    });
+
+    let cloned_custom_fields = custom_fields.clone();
+    let onchange_custom_fields = Callback::from(move |values: serde_json::Value| {
+        cloned_custom_fields.set(values);
+    });
+
     let onchange_organization = {
         let cloned_o = organization_id.clone();
         Callback::from(move |event: Event| {
@@ -152,16 +274,35 @@ pub fn modal(
     let on_save = {
         let cloned_mu_id = mu_id.clone();
         let cloned_name = name.clone();
+        let cloned_lot_pattern = lot_pattern.clone();
+        let cloned_sku = sku.clone();
+        let cloned_barcode = barcode.clone();
+        let cloned_barcode_type = barcode_type.clone();
+        let cloned_description = description.clone();
+        let cloned_category_id = category_id.clone();
+        let cloned_custom_fields = custom_fields.clone();
         let cloned_organization_id = organization_id.clone();
         let cloned_on_save = on_save.clone();
         Callback::from(move |e: MouseEvent| {
             e.prevent_default();
 
-            cloned_on_save.emit((
-                *cloned_mu_id,
-                (*cloned_name).clone(),
-                *cloned_organization_id,
-            ));
+            // Пустые поля формы не отправляются
+            let not_empty =
+                |v: &UseStateHandle<String>| Some((**v).clone()).filter(|v| !v.is_empty());
+            let barcode = not_empty(&cloned_barcode);
+
+            cloned_on_save.emit(ProductForm {
+                organization_id: *cloned_organization_id,
+                measure_unit_id: *cloned_mu_id,
+                name: (*cloned_name).clone(),
+                lot_pattern: not_empty(&cloned_lot_pattern),
+                sku: not_empty(&cloned_sku),
+                barcode_type: barcode.as_ref().map(|_| (*cloned_barcode_type).clone()),
+                barcode,
+                description: not_empty(&cloned_description),
+                category_id: *cloned_category_id,
+                custom_fields: (*cloned_custom_fields).clone(),
+            });
         })
     };
 
@@ -199,6 +340,77 @@ pub fn modal(
                                     placeholder="Введите наименование"
                                     value={(*name).clone()}
                                 />
+                                <label for="sku" class="text-gray-800 text-sm font-bold leading-tight tracking-normal">
+                                    {"Артикул"}
+                                </label>
+                                <input
+                                    onchange={onchange_sku}
+                                    type="text"
+                                    id="sku"
+                                    class="mb-5 mt-2 text-gray-600 focus:outline-none focus:border focus:border-indigo-700 font-normal w-full h-10 flex items-center pl-3 text-sm border-gray-300 rounded border"
+                                    placeholder="Введите артикул"
+                                    value={(*sku).clone()}
+                                />
+                                <label for="barcode" class="text-gray-800 text-sm font-bold leading-tight tracking-normal">
+                                    {"Штрихкод"}
+                                </label>
+                                <div class="flex gap-2 mb-5 mt-2">
+                                    <select
+                                        onchange={onchange_barcode_type}
+                                        id="barcode_type"
+                                        class="text-gray-600 focus:outline-none focus:border focus:border-indigo-700 font-normal w-1/3 h-10 flex items-center pl-3 text-sm border-gray-300 rounded border">
+                                        {
+                                            ["EAN13", "Code128"].iter().map(|t| html! {
+                                                <option selected={*barcode_type == *t} value={*t}>{*t}</option>
+                                            }).collect::<Html>()
+                                        }
+                                    </select>
+                                    <input
+                                        onchange={onchange_barcode}
+                                        type="text"
+                                        id="barcode"
+                                        class="text-gray-600 focus:outline-none focus:border focus:border-indigo-700 font-normal w-2/3 h-10 flex items-center pl-3 text-sm border-gray-300 rounded border"
+                                        placeholder="Введите штрихкод"
+                                        value={(*barcode).clone()}
+                                    />
+                                </div>
+                                <label for="category" class="text-gray-800 text-sm font-bold leading-tight tracking-normal">{"Категория"}</label>
+                                <select
+                                    onchange={onchange_category}
+                                    id="category"
+                                    class="mb-5 mt-2 text-gray-600 focus:outline-none focus:border focus:border-indigo-700 font-normal w-full h-10 flex items-center pl-3 text-sm border-gray-300 rounded border">
+                                    <option selected={category_id.is_none()} value="">{"Без категории"}</option>
+                                    {
+                                        (*categories).iter().map(|c| html! {
+                                            <option selected={*category_id == Some(c.id)} value={c.id.to_string()}>{c.path.clone()}</option>
+                                        }).collect::<Html>()
+                                    }
+                                </select>
+                                <label for="description" class="text-gray-800 text-sm font-bold leading-tight tracking-normal">
+                                    {"Описание"}
+                                </label>
+                                <textarea
+                                    onchange={onchange_description}
+                                    id="description"
+                                    rows="3"
+                                    class="mb-5 mt-2 text-gray-600 focus:outline-none focus:border focus:border-indigo-700 font-normal w-full flex items-center pl-3 text-sm border-gray-300 rounded border"
+                                    placeholder="Введите описание"
+                                    value={(*description).clone()}
+                                />
+                                <label for="lot_pattern" class="text-gray-800 text-sm font-bold leading-tight tracking-normal">
+                                    {"Шаблон номера партии"}
+                                </label>
+                                <input
+                                    onchange={onchange_lot_pattern}
+                                    type="text"
+                                    id="lot_pattern"
+                                    class="mb-1 mt-2 text-gray-600 focus:outline-none focus:border focus:border-indigo-700 font-normal w-full h-10 flex items-center pl-3 text-sm border-gray-300 rounded border"
+                                    placeholder="{ID}-{YYYY}{MM}{DD}-{SEQ}"
+                                    value={(*lot_pattern).clone()}
+                                />
+                                <p class="mb-5 text-xs text-gray-500">
+                                    {"Поля: {ID}, {YYYY}, {YY}, {MM}, {DD}, {SEQ} - порядковый номер за день"}
+                                </p>
                                 <label for="measure" class="text-gray-800 text-sm font-bold leading-tight tracking-normal">{"Ед. измерения"}</label>
                                 <select
                                     onchange={onchange_measure}
@@ -237,6 +449,21 @@ pub fn modal(
                                         }
                                     </select>
                                 }
+                                <CustomFields
+                                    entity="Product"
+                                    organization_id={
+                                        current_user
+                                            .as_ref()
+                                            .filter(|u| check_is_admin(u.role))
+                                            .map(|_| *organization_id)
+                                    }
+                                    values={(*custom_fields).clone()}
+                                    on_change={onchange_custom_fields}
+                                />
+                                if let Some(it) = item.as_ref() {
+                                    <Prices product_id={it.id} />
+                                    <Attachments url={format!("/api/products/{}/attachments", it.id)} />
+                                }
                                 <div class="flex items-center justify-center w-full">
                                     <button
                                         onclick={toggle_modal.clone()}
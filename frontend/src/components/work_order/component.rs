@@ -0,0 +1,285 @@
+use gloo::{
+    net::http,
+    storage::{LocalStorage, Storage},
+};
+use serde::{Deserialize, Serialize};
+use yew::prelude::*;
+use yew_router::hooks::use_navigator;
+
+use crate::{
+    check_is_admin,
+    components::{
+        elements::modal::ModalDelete,
+        footer::Footer,
+        header::component::HeaderComponent,
+        work_order::{list::WorkOrderList, modal::Modal, WorkOrder, WorkOrderStatus},
+    },
+    AppContext, ResponseId, ResponseItems, ResponseMsg, Role, Route, User,
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RequestData {
+    product_id: i64,
+    cnt: i64,
+    due_date: chrono::NaiveDate,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct RequestDataStatus {
+    status: WorkOrderStatus,
+}
+
+#[function_component(WorkOrderComponent)]
+pub fn work_order() -> Html {
+    // Компонент доски заказов на производство
+
+    let ctx = use_context::<AppContext>();
+    let current_user: Option<User> = ctx.and_then(|ctx| ctx.0.clone());
+
+    let rendered = use_state_eq(|| false);
+    let is_visible = use_state_eq(|| false);
+    let is_visible_del = use_state_eq(|| false);
+
+    let item: UseStateHandle<Option<WorkOrder>> = use_state_eq(|| None);
+    let items: UseStateHandle<Vec<WorkOrder>> = use_state_eq(Vec::new);
+
+    {
+        let items = items.clone();
+        use_effect_with(rendered.clone(), move |rendered| {
+            let items = items.clone();
+            let cloned_rendered = rendered.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let mut header_bearer = String::from("Bearer ");
+                let token: Option<String> = LocalStorage::get("token").unwrap_or(None);
+                if let Some(t) = token.clone() {
+                    header_bearer.push_str(&t);
+                }
+
+                // Доска показывает все заказы без пагинации
+                let response = http::Request::get("/api/work-orders")
+                    .header("Content-Type", "application/json")
+                    .header("Authorization", &header_bearer)
+                    .query([("page", "1"), ("per_page", "10000")])
+                    .send()
+                    .await
+                    .unwrap()
+                    .json::<ResponseItems<WorkOrder>>()
+                    .await
+                    .unwrap();
+
+                items.set(response.items);
+                cloned_rendered.set(false);
+            });
+        });
+    }
+
+    let cloned_is_visible = is_visible.clone();
+    let toggle_modal = {
+        let cloned_item = item.clone();
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+
+            cloned_is_visible.set(!*cloned_is_visible);
+            cloned_item.set(None); //Сбросим state для Item редактирование
+        })
+    };
+
+    let on_edit = {
+        let cloned_item = item.clone();
+        let cloned_is_visible = is_visible.clone();
+        Callback::from(move |item: WorkOrder| {
+            cloned_item.set(Some(item));
+            // Toggle modal
+            cloned_is_visible.set(!*cloned_is_visible);
+        })
+    };
+
+    let on_save = {
+        let cloned_is_visible = is_visible.clone();
+        let cloned_item = item.clone();
+        let cloned_rendered = rendered.clone();
+        let navigator = use_navigator();
+        Callback::from(move |(product_id, cnt, due_date)| {
+            let mut header_bearer = String::from("Bearer ");
+            let token: Option<String> = LocalStorage::get("token").unwrap_or(None);
+            if let Some(t) = token.clone() {
+                header_bearer.push_str(&t);
+            }
+
+            let cloned_is_visible = cloned_is_visible.clone();
+            let cloned_item = cloned_item.clone();
+            let cloned_rendered = cloned_rendered.clone();
+            let navigator = navigator.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let req_data = RequestData {
+                    product_id,
+                    cnt,
+                    due_date,
+                };
+                let path = "/api/work-orders";
+
+                if let Some(item) = (*cloned_item).clone() {
+                    let _: ResponseMsg = http::Request::patch(&format!("{}/{}", path, item.id))
+                        .header("Content-Type", "application/json")
+                        .header("Authorization", &header_bearer)
+                        .json(&req_data)
+                        .unwrap()
+                        .send()
+                        .await
+                        .unwrap()
+                        .json()
+                        .await
+                        .unwrap();
+                } else {
+                    let _: ResponseId = http::Request::post(path)
+                        .header("Content-Type", "application/json")
+                        .header("Authorization", &header_bearer)
+                        .json(&req_data)
+                        .unwrap()
+                        .send()
+                        .await
+                        .unwrap()
+                        .json()
+                        .await
+                        .unwrap();
+                }
+
+                cloned_is_visible.set(!*cloned_is_visible);
+                cloned_rendered.set(true); // для перерисовки списка после действий.
+
+                if let Some(navigator) = navigator {
+                    navigator.push(&Route::WorkOrder);
+                }
+            });
+        })
+    };
+
+    let on_move = {
+        let cloned_rendered = rendered.clone();
+        Callback::from(move |(item, status): (WorkOrder, WorkOrderStatus)| {
+            let mut header_bearer = String::from("Bearer ");
+            let token: Option<String> = LocalStorage::get("token").unwrap_or(None);
+            if let Some(t) = token.clone() {
+                header_bearer.push_str(&t);
+            }
+
+            let cloned_rendered = cloned_rendered.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let req_data = RequestDataStatus { status };
+
+                let _ = http::Request::patch(&format!("/api/work-orders/{}/status", item.id))
+                    .header("Content-Type", "application/json")
+                    .header("Authorization", &header_bearer)
+                    .json(&req_data)
+                    .unwrap()
+                    .send()
+                    .await;
+
+                cloned_rendered.set(true); // для перерисовки списка после действий.
+            });
+        })
+    };
+
+    let cloned_is_visible_del = is_visible_del.clone();
+    let toggle_modal_del = {
+        let cloned_item = item.clone();
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+
+            cloned_is_visible_del.set(!*cloned_is_visible_del);
+            cloned_item.set(None); //Сбросим state для Item редактирование
+        })
+    };
+
+    let on_delete_modal = {
+        let cloned_item = item.clone();
+        let cloned_is_visible_del = is_visible_del.clone();
+        Callback::from(move |item: WorkOrder| {
+            cloned_item.set(Some(item));
+            // Toggle modal
+            cloned_is_visible_del.set(!*cloned_is_visible_del);
+        })
+    };
+
+    let on_delete = {
+        let cloned_is_visible_del = is_visible_del.clone();
+        let cloned_item = item.clone();
+        let cloned_rendered = rendered.clone();
+        Callback::from(move |_| {
+            let mut header_bearer = String::from("Bearer ");
+            let token: Option<String> = LocalStorage::get("token").unwrap_or(None);
+            if let Some(t) = token.clone() {
+                header_bearer.push_str(&t);
+            }
+
+            let cloned_is_visible_del = cloned_is_visible_del.clone();
+            let cloned_item = cloned_item.clone();
+            let cloned_rendered = cloned_rendered.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let path = "/api/work-orders";
+
+                if let Some(item) = (*cloned_item).clone() {
+                    let _: ResponseMsg = http::Request::delete(&format!("{}/{}", path, item.id))
+                        .header("Content-Type", "application/json")
+                        .header("Authorization", &header_bearer)
+                        .send()
+                        .await
+                        .unwrap()
+                        .json()
+                        .await
+                        .unwrap();
+                }
+
+                cloned_is_visible_del.set(!*cloned_is_visible_del);
+                cloned_rendered.set(true); // для перерисовки списка после действий.
+            });
+        })
+    };
+
+    html! {
+        <>
+        <HeaderComponent />
+
+        if current_user.as_ref().is_some_and(|u| check_is_admin(u.role) || u.role == Role::Director) {
+            <div class="flex justify-end mb-5">
+                <button
+                    onclick={toggle_modal.clone()}
+                    class="px-4 py-2 bg-blue-500 text-white rounded-md mr-5 mt-5 hover:bg-blue-700">
+                    {"Добавить"}
+                </button>
+            </div>
+        }
+        <div class="flex gap-4 overflow-auto mx-5 my-2 max-h-[68%]">
+            {WorkOrderStatus::all().into_iter().map(|status| {
+                html! {
+                    <WorkOrderList
+                        {status}
+                        items={items.iter().filter(|it| it.status == status).cloned().collect::<Vec<WorkOrder>>()}
+                        current_user={current_user.clone()}
+                        on_edit={on_edit.clone()}
+                        on_move={on_move.clone()}
+                        on_delete={on_delete_modal.clone()}
+                    />
+                }
+            }).collect::<Html>()}
+        </div>
+
+        <ModalDelete
+            is_visible={*is_visible_del}
+            toggle={toggle_modal_del}
+            {on_delete}
+        />
+
+        <Modal
+            current_user={current_user}
+            is_visible={*is_visible}
+            item={(*item).clone()}
+            {toggle_modal}
+            {on_save}
+        />
+
+        <Footer />
+
+        </>
+    }
+}
@@ -0,0 +1,120 @@
+use super::{WorkOrder, WorkOrderStatus};
+use crate::{check_is_admin, Role, User};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub status: WorkOrderStatus,
+    pub items: Vec<WorkOrder>,
+    pub current_user: Option<User>,
+    pub on_edit: Callback<WorkOrder>,
+    pub on_move: Callback<(WorkOrder, WorkOrderStatus)>,
+    pub on_delete: Callback<WorkOrder>,
+}
+
+#[function_component(WorkOrderList)]
+pub fn work_order_list(
+    Props {
+        status,
+        current_user,
+        items,
+        on_edit,
+        on_move,
+        on_delete,
+    }: &Props,
+) -> Html {
+    // Компонент колонки доски заказов
+
+    let can_manage = current_user
+        .as_ref()
+        .is_some_and(|u| check_is_admin(u.role) || u.role == Role::Director);
+    let today = chrono::Local::now().date_naive();
+
+    html! {
+        <div class="flex flex-col min-w-[250px] w-full rounded-lg border border-gray-200 bg-gray-50 shadow-md">
+            <div class="flex justify-between px-4 py-3 border-b border-gray-200">
+                <span class="font-medium text-gray-900 uppercase text-sm">{status.to_string()}</span>
+                <span class="rounded-full bg-gray-200 px-2 text-xs font-semibold text-gray-700">{items.len()}</span>
+            </div>
+            <div class="flex flex-col gap-2 p-2 overflow-auto">
+            {items.iter().map(|item| {
+
+                // Generate onclick
+                let on_edit = {
+                    let on_edit = on_edit.clone();
+                    let cloned_item = item.clone();
+                    Callback::from(move |e: MouseEvent| {
+                        e.prevent_default();
+
+                        on_edit.emit(cloned_item.clone());
+                    })
+                };
+
+                let on_move = {
+                    let on_move = on_move.clone();
+                    let cloned_item = item.clone();
+                    Callback::from(move |e: MouseEvent| {
+                        e.prevent_default();
+
+                        if let Some(next) = cloned_item.status.next() {
+                            on_move.emit((cloned_item.clone(), next));
+                        }
+                    })
+                };
+
+                let on_delete = {
+                    let on_delete = on_delete.clone();
+                    let cloned_item = item.clone();
+                    Callback::from(move |e: MouseEvent| {
+                        e.prevent_default();
+
+                        on_delete.emit(cloned_item.clone());
+                    })
+                };
+
+                // Прогресс выполнения заказа
+                let progress = if item.cnt > 0 {
+                    (item.done * 100 / item.cnt).clamp(0, 100)
+                } else {
+                    0
+                };
+                let overdue = item.due_date < today
+                    && !matches!(item.status, WorkOrderStatus::Completed | WorkOrderStatus::Closed);
+
+                html! {
+                    <div class="rounded-md border border-gray-200 bg-white p-3 text-sm text-gray-500">
+                        <div class="flex justify-between">
+                            <span class="font-medium text-gray-700">{format!("#{} {}", item.id, item.product.name)}</span>
+                            if current_user.as_ref().is_some_and(|u| check_is_admin(u.role)) {
+                                <span class="text-gray-400">{item.organization.name.clone()}</span>
+                            }
+                        </div>
+                        <div class="mt-1">
+                            {format!("{} / {} {}", item.done, item.cnt, item.measure_unit.name)}
+                        </div>
+                        <div class="mt-1 h-2 w-full rounded-full bg-gray-200">
+                            <div class="h-2 rounded-full bg-blue-500" style={format!("width: {}%", progress)}></div>
+                        </div>
+                        <div class={classes!("mt-1", if overdue {"text-red-600"} else {""})}>
+                            {format!("Срок: {}", item.due_date.format("%d.%m.%Y"))}
+                        </div>
+                        if can_manage {
+                            <div class="flex justify-end gap-4 mt-2">
+                                if item.status == WorkOrderStatus::Draft {
+                                    <a onclick={on_delete} href="#" class="text-red-600 hover:text-red-800">{"Удалить"}</a>
+                                    <a onclick={on_edit} href="#" class="text-gray-600 hover:text-gray-900">{"Изменить"}</a>
+                                }
+                                if let Some(next) = item.status.next() {
+                                    <a onclick={on_move} href="#" class="text-blue-500 hover:text-blue-700">
+                                        {format!("→ {}", next)}
+                                    </a>
+                                }
+                            </div>
+                        }
+                    </div>
+                }
+            }).collect::<Html>()}
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,78 @@
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Select;
+
+pub mod component;
+pub mod list;
+pub mod modal;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub enum WorkOrderStatus {
+    #[default]
+    Draft,
+    Released,
+    InProgress,
+    Completed,
+    Closed,
+}
+
+impl fmt::Display for WorkOrderStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            WorkOrderStatus::Draft => "Черновик",
+            WorkOrderStatus::Released => "Выпущен",
+            WorkOrderStatus::InProgress => "В работе",
+            WorkOrderStatus::Completed => "Выполнен",
+            WorkOrderStatus::Closed => "Закрыт",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+impl WorkOrderStatus {
+    pub fn all() -> [WorkOrderStatus; 5] {
+        // Колонки доски в порядке жизненного цикла
+        [
+            WorkOrderStatus::Draft,
+            WorkOrderStatus::Released,
+            WorkOrderStatus::InProgress,
+            WorkOrderStatus::Completed,
+            WorkOrderStatus::Closed,
+        ]
+    }
+
+    pub fn next(&self) -> Option<WorkOrderStatus> {
+        // Следующий статус для кнопки на карточке
+        match self {
+            WorkOrderStatus::Draft => Some(WorkOrderStatus::Released),
+            WorkOrderStatus::Released => Some(WorkOrderStatus::InProgress),
+            WorkOrderStatus::InProgress => Some(WorkOrderStatus::Completed),
+            WorkOrderStatus::Completed => Some(WorkOrderStatus::Closed),
+            WorkOrderStatus::Closed => None,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        // Заказ открыт для привязки производства
+        matches!(
+            self,
+            WorkOrderStatus::Released | WorkOrderStatus::InProgress
+        )
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct WorkOrder {
+    pub id: i64,
+    pub cnt: i64,
+    pub done: i64,
+    pub due_date: chrono::NaiveDate,
+    pub status: WorkOrderStatus,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+
+    pub product: Select,
+    pub measure_unit: Select,
+    pub organization: Select,
+}
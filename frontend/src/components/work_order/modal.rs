@@ -0,0 +1,233 @@
+use super::WorkOrder;
+use crate::{check_is_admin, components::rbs::product::Product, ResponseItems, User};
+use gloo::{
+    net::http,
+    storage::{LocalStorage, Storage},
+};
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq, Default)]
+pub struct Props {
+    pub current_user: Option<User>,
+    pub is_visible: bool,
+    pub item: Option<WorkOrder>,
+
+    pub toggle_modal: Callback<MouseEvent>,
+    pub on_save: Callback<(i64, i64, chrono::NaiveDate)>,
+}
+
+#[function_component(Modal)]
+pub fn modal(
+    Props {
+        current_user,
+        is_visible,
+        item,
+        toggle_modal,
+        on_save,
+    }: &Props,
+) -> Html {
+    // Заполнение данными
+
+    let product_id = use_state_eq(|| 0);
+    let cnt = use_state_eq(|| 0);
+    let due_date = use_state_eq(|| chrono::Local::now().date_naive());
+
+    let products: UseStateHandle<Vec<Product>> = use_state_eq(Vec::new);
+
+    {
+        let cloned_products = products.clone();
+        let cloned_item = item.clone();
+        let cloned_product_id = product_id.clone();
+        let cloned_cnt = cnt.clone();
+        let cloned_due_date = due_date.clone();
+        use_effect_with(*is_visible, move |visible| {
+            if *visible {
+                wasm_bindgen_futures::spawn_local(async move {
+                    let mut header_bearer = String::from("Bearer ");
+                    let token: Option<String> = LocalStorage::get("token").unwrap_or(None);
+                    if let Some(t) = token.clone() {
+                        header_bearer.push_str(&t);
+                    }
+
+                    let response =
+                        http::Request::get("/api/products") // todo helpers
+                            .header("Content-Type", "application/json")
+                            .header("Authorization", &header_bearer)
+                            .query([("page", "1"), ("per_page", "10000")])
+                            .send()
+                            .await
+                            .unwrap()
+                            .json::<ResponseItems<Product>>()
+                            .await
+                            .unwrap();
+
+                    cloned_products.set(response.items.clone());
+
+                    if let Some(item) = cloned_item.clone() {
+                        cloned_product_id.set(item.product.id);
+                        cloned_cnt.set(item.cnt);
+                        cloned_due_date.set(item.due_date);
+                    } else {
+                        cloned_product_id.set(response.items.last().map_or(0, |it| it.id));
+                        cloned_cnt.set(0);
+                        cloned_due_date.set(chrono::Local::now().date_naive());
+                    }
+                })
+            }
+        });
+    }
+
+    let cloned_product_id = product_id.clone();
+    let onchange_product = Callback::from(move |event: Event| {
+        let value = event
+            .target()
+            .unwrap()
+            .unchecked_into::<HtmlSelectElement>()
+            .value();
+
+        cloned_product_id.set(value.parse::<i64>().ok().unwrap_or(0));
+    });
+
+    let cloned_cnt = cnt.clone();
+    let onchange_cnt = Callback::from(move |event: Event| {
+        let value = event
+            .target()
+            .unwrap()
+            .unchecked_into::<HtmlInputElement>()
+            .value();
+
+        cloned_cnt.set(value.parse::<i64>().ok().unwrap_or(0));
+    });
+
+    let cloned_due_date = due_date.clone();
+    let onchange_due_date = Callback::from(move |event: Event| {
+        let value = event
+            .target()
+            .unwrap()
+            .unchecked_into::<HtmlInputElement>()
+            .value();
+
+        if let Ok(dt) = chrono::NaiveDate::parse_from_str(value.as_str(), "%Y-%m-%d") {
+            cloned_due_date.set(dt);
+        }
+    });
+
+    let on_save = {
+        let cloned_product_id = product_id.clone();
+        let cloned_cnt = cnt.clone();
+        let cloned_due_date = due_date.clone();
+        let cloned_on_save = on_save.clone();
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+
+            cloned_on_save.emit((*cloned_product_id, *cloned_cnt, *cloned_due_date));
+        })
+    };
+
+    html! {
+        <div>
+            <div
+                class={format!("py-12 bg-gray-700 transition duration-150 ease-in-out z-10 absolute top-0 right-0 bottom-0 left-0 {}", if *is_visible {""} else {"hidden"})}
+                    id="modal"
+                >
+                    <div
+                        role="alert"
+                        class="container mx-auto w-11/12 md:w-2/3 max-w-lg"
+                    >
+                        <div
+                            class="relative py-8 px-5 md:px-10 bg-white shadow-md rounded border border-gray-400"
+                        >
+                            <h1
+                                class="text-gray-800 font-lg font-bold tracking-normal leading-tight mb-4"
+                            >
+                                {"Создание/Редактирование"}
+                            </h1>
+                            <form
+                                class="group"
+                            >
+                                <label for="product" class="text-gray-800 text-sm font-bold leading-tight tracking-normal">{"Продукт"}</label>
+                                <select
+                                    onchange={onchange_product}
+                                    id="product"
+                                    class="mb-5 mt-2 text-gray-600 focus:outline-none focus:border focus:border-indigo-700 font-normal w-full h-10 flex items-center pl-3 text-sm border-gray-300 rounded border"
+                                    placeholder="Выберите продукт">
+                                    {
+                                        (*products).iter().map(|item| {
+                                            html! {
+                                                <option
+                                                    selected={item.id == *product_id}
+                                                    value={item.id.to_string()}
+                                                >
+                                                if current_user.as_ref().is_some_and(|u| check_is_admin(u.role)) {
+                                                    {format!("{} ({}) [{}]", &item.name, &item.measure_unit.name.clone(), &item.organization.name.clone())}
+                                                } else {
+                                                    {format!("{} ({})", &item.name, &item.measure_unit.name.clone())}
+                                                }
+                                                </option>
+                                            }
+                                        }).collect::<Html>()
+                                    }
+                                </select>
+                                <label for="cnt" class="text-gray-800 text-sm font-bold leading-tight tracking-normal">
+                                    {"Кол-во"}
+                                </label>
+                                <input
+                                    onchange={onchange_cnt}
+                                    required={true}
+                                    type="number"
+                                    min=1
+                                    id="cnt"
+                                    class="mb-5 mt-2 text-gray-600 focus:outline-none focus:border focus:border-indigo-700 font-normal w-full h-10 flex items-center pl-3 text-sm border-gray-300 rounded border"
+                                    placeholder="Введите кол-во"
+                                    value={
+                                        let cnt = *cnt;
+                                        if cnt > 0 {
+                                            cnt.to_string()
+                                        } else {
+                                            "".to_string()
+                                        }
+                                    }
+                                />
+                                <label for="due_date" class="text-gray-800 text-sm font-bold leading-tight tracking-normal">
+                                    {"Срок"}
+                                </label>
+                                <input
+                                    onchange={onchange_due_date}
+                                    required={true}
+                                    type="date"
+                                    id="due_date"
+                                    class="mb-5 mt-2 text-gray-600 focus:outline-none focus:border focus:border-indigo-700 font-normal w-full h-10 flex items-center pl-3 text-sm border-gray-300 rounded border"
+                                    value={due_date.to_string()}
+                                />
+                                <div class="flex items-center justify-center w-full">
+                                    <button
+                                        onclick={toggle_modal.clone()}
+                                        class="focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-gray-400 ml-3 bg-gray-100 transition duration-150 text-gray-600 ease-in-out hover:border-gray-400 hover:bg-gray-300 border rounded px-8 py-2 text-sm mr-5" >
+                                        {"Отменить"}
+                                    </button>
+                                    <button
+                                    onclick={on_save}
+                                        class="group-invalid:pointer-events-none group-invalid:opacity-30 focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-blue-500 transition duration-150 ease-in-out hover:bg-blue-700 bg-blue-500 rounded text-white px-8 py-2 text-sm">
+                                        {"Сохранить"}
+                                    </button>
+                                </div>
+                                <button
+                                    onclick={toggle_modal.clone()}
+                                    class="cursor-pointer absolute top-0 right-0 mt-4 mr-5 text-gray-400 hover:text-gray-600 transition duration-150 ease-in-out rounded focus:ring-2 focus:outline-none focus:ring-gray-600"
+                                    aria-label="close modal"
+                                    role="button">
+                                    <svg  xmlns="http://www.w3.org/2000/svg" class="icon icon-tabler icon-tabler-x" width="20" height="20" viewBox="0 0 24 24" stroke-width="2.5" stroke="currentColor" fill="none" stroke-linecap="round" stroke-linejoin="round">
+                                        <path stroke="none" d="M0 0h24v24H0z" />
+                                        <line x1="18" y1="6" x2="6" y2="18" />
+                                        <line x1="6" y1="6" x2="18" y2="18" />
+                                    </svg>
+                                </button>
+                            </form>
+                        </div>
+                    </div>
+            </div>
+        </div>
+    }
+}
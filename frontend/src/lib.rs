@@ -49,6 +49,17 @@ pub fn check_is_admin(role: Role) -> bool {
     matches!(role, Role::Developer | Role::Admin)
 }
 
+pub fn time_zone() -> Option<String> {
+    // Часовой пояс браузера, по нему считаются границы дней и периодов в отчетах
+
+    let options = js_sys::Intl::DateTimeFormat::new(&js_sys::Array::new(), &js_sys::Object::new())
+        .resolved_options();
+
+    js_sys::Reflect::get(&options, &"timeZone".into())
+        .ok()
+        .and_then(|tz| tz.as_string())
+}
+
 #[derive(Routable, PartialEq, Eq, Clone, Debug)]
 pub enum Route {
     #[at("/auth")]
@@ -65,6 +76,12 @@ pub enum Route {
     Analitic,
     #[at("/organizations")]
     Organization,
+    #[at("/work-orders")]
+    WorkOrder,
+    #[at("/earnings")]
+    Earning,
+    #[at("/dashboard")]
+    Dashboard,
     #[not_found]
     #[at("/404")]
     NotFound,
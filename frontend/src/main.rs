@@ -2,12 +2,15 @@ use frontend::{
     components::{
         analitic::component::AnaliticComponent,
         auth::AuthComponent,
+        dashboard::component::DashboardComponent,
+        earning::component::EarningComponent,
         elements::loader::Loader,
         home::component::HomeComponent,
         not_found::NotFound,
         organization::component::OrganizationComponent,
         rbs::{measure::component::MeasureUnitComponent, product::component::ProductComponent},
         user::component::UserComponent,
+        work_order::component::WorkOrderComponent,
     },
     AppContext, AppStateContext, Route, User,
 };
@@ -91,6 +94,9 @@ fn switch(route: Route) -> Html {
             Route::User => html! {<UserComponent /> },
             Route::Analitic => html! {<AnaliticComponent /> },
             Route::Organization => html! {<OrganizationComponent /> },
+            Route::WorkOrder => html! {<WorkOrderComponent /> },
+            Route::Earning => html! {<EarningComponent /> },
+            Route::Dashboard => html! {<DashboardComponent /> },
             Route::NotFound => html! {<NotFound /> },
         },
         None => match route {